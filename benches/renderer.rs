@@ -0,0 +1,24 @@
+use criterion::{black_box, criterion_group, criterion_main, Criterion};
+use modular_game_engine::renderer_2d::{Color, Renderer2D};
+
+/// Large opaque fill: `draw_rect`'s row fill against the per-pixel `set_pixel` loop
+fn fill_rect(c: &mut Criterion) {
+    let mut renderer = Renderer2D::new(800, 600);
+    let mut group = c.benchmark_group("fill_rect_400x300");
+    group.bench_function("draw_rect", |b| {
+        b.iter(|| renderer.draw_rect(black_box(100), black_box(100), 400, 300, Color::RED))
+    });
+    group.bench_function("set_pixel_loop", |b| {
+        b.iter(|| {
+            for y in 100..black_box(400) {
+                for x in 100..black_box(500) {
+                    renderer.set_pixel(x, y, Color::RED);
+                }
+            }
+        })
+    });
+    group.finish();
+}
+
+criterion_group!(benches, fill_rect);
+criterion_main!(benches);
@@ -45,15 +45,15 @@ fn main() {
         match event {
             Event::WindowEvent { event, window_id } if window_id == window.id() => match event {
                 WindowEvent::CloseRequested => *control_flow = ControlFlow::Exit,
-                WindowEvent::KeyboardInput { input, .. } => {
-                    if input.state == ElementState::Pressed {
-                        if let Some(VirtualKeyCode::Space) = input.virtual_keycode {
-                                                        audio_manager.play_sound("assets/audio/click.wav").unwrap();
-                            println!("Playing click sound!");
-                        }
-                        if let Some(VirtualKeyCode::Escape) = input.virtual_keycode {
-                            *control_flow = ControlFlow::Exit;
-                        }
+                WindowEvent::KeyboardInput { input, .. }
+                    if input.state == ElementState::Pressed =>
+                {
+                    if let Some(VirtualKeyCode::Space) = input.virtual_keycode {
+                        audio_manager.play_sound("assets/audio/click.wav").unwrap();
+                        println!("Playing click sound!");
+                    }
+                    if let Some(VirtualKeyCode::Escape) = input.virtual_keycode {
+                        *control_flow = ControlFlow::Exit;
                     }
                 }
                 _ => {}
@@ -5,6 +5,7 @@
 //! scoring, and trail systems.
 
 use difficulty::DifficultySystem;
+use game_state::GamePhase;
 use menu::MenuSystem;
 use modular_game_engine::*;
 use particles::ParticleSystem;
@@ -22,61 +23,42 @@ const BALL_SIZE: f32 = 12.0;
 const BRICK_WIDTH: f32 = 60.0;
 const BRICK_HEIGHT: f32 = 20.0;
 const BRICK_ROWS: usize = 6;
-const BRICK_COLS: usize = 13;
+/// `=` takes one hit, `-` two and `#` three
+const LEVEL: &str = "\
+=============
+=============
+-------------
+-------------
+#############
+#############";
 #[allow(dead_code)]
 const PADDLE_SPEED: f32 = 400.0;
 const BALL_SPEED: f32 = 300.0;
 
-// Game entities
-#[derive(Component, Debug, Clone)]
-#[storage(VecStorage)]
-pub struct Paddle;
-
+// Game entities; paddles, bricks and power-ups use the engine's components
 #[derive(Component, Debug, Clone)]
 #[storage(VecStorage)]
 pub struct Ball {
     pub attached_to_paddle: bool,
 }
 
+/// How a brick looks and scores; its remaining hits are its `Health`
 #[derive(Component, Debug, Clone)]
 #[storage(VecStorage)]
-pub struct Brick {
-    pub hits_required: i32,
+pub struct BrickStyle {
     pub points: i32,
     pub color: [f32; 4],
 }
 
-#[derive(Component, Debug, Clone)]
-#[storage(VecStorage)]
-pub struct PowerUp {
-    pub power_type: PowerUpType,
-    pub velocity: Vec2,
-}
-
-#[derive(Debug, Clone, Copy)]
-pub enum PowerUpType {
-    ExtraLife,
-    MultiBall,
-    LargerPaddle,
-    SmallerPaddle,
-    FasterBall,
-    SlowerBall,
-}
-
 // Game state
-#[derive(Debug, Clone)]
-pub enum BreakoutGameState {
-    Menu,
-    Playing,
-    Paused,
-    GameOver { won: bool },
-    LevelComplete,
-}
+/// Custom phase shown between levels
+const LEVEL_COMPLETE: &str = "level_complete";
 
 pub struct BreakoutGame {
     world: World,
     dispatcher: specs::Dispatcher<'static, 'static>,
-    game_state: BreakoutGameState,
+    collision_handlers: physics::CollisionHandlers,
+    game_state: GamePhase,
     last_update: std::time::Instant,
 
     // Extracted systems
@@ -90,39 +72,23 @@ pub struct BreakoutGame {
     // Game data
     level: i32,
     lives: i32,
-    balls: Vec<specs::Entity>,
-    bricks_remaining: usize,
     paddle_entity: Option<specs::Entity>,
 }
 
+impl Default for BreakoutGame {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 impl BreakoutGame {
     pub fn new() -> Self {
-        let mut world = specs::World::new();
-
-        // Register core components
-        world.register::<Position>();
-        world.register::<Velocity>();
-        world.register::<Acceleration>();
-        world.register::<Renderable>();
-        world.register::<Player>();
-        world.register::<Enemy>();
-        world.register::<Health>();
-        world.register::<Collider>();
-        world.register::<Camera>();
-        world.register::<MarkedForRemoval>();
-        world.register::<Score>();
-        world.register::<Animation>();
+        let mut world = modular_game_engine::init().expect("engine world initialization failed");
 
         // Register game-specific components
-        world.register::<Paddle>();
         world.register::<Ball>();
-        world.register::<Brick>();
-        world.register::<PowerUp>();
+        world.register::<BrickStyle>();
 
-        // Add core resources
-        world.insert(Time::default());
-        world.insert(Score::default());
-        
         // Insert required resources
         world.insert(crate::input_window::WindowInputState::default());
 
@@ -138,18 +104,38 @@ impl BreakoutGame {
         trail_system.create_trail_with_config("ball", trail_presets::pong_ball_trail());
 
         // Set up systems dispatcher
-        let dispatcher = specs::DispatcherBuilder::new()
+        let mut dispatcher = specs::DispatcherBuilder::new()
             .with(BreakoutInputSystem, "input", &[])
-            .with(BreakoutPhysicsSystem, "physics", &["input"])
-            .with(BreakoutCollisionSystem, "collision", &["physics"])
+            .with(HealthSystem, "health", &[])
+            .with(PhysicsSystem, "physics", &["input"])
+            .with(BreakoutWallSystem, "walls", &["physics"])
+            .with(physics::CollisionSystem::new(), "collision", &["walls"])
+            .with(PowerUpSystem::new(), "power_ups", &["collision", "health"])
+            .with(CleanupSystem, "cleanup", &["power_ups"])
+            .with(LiveSetSystem::<Ball>::new(), "live_balls", &["cleanup"])
+            .with(LiveSetSystem::<Brick>::new(), "live_bricks", &["cleanup"])
             .with(BreakoutGameLogicSystem, "game_logic", &["collision"])
             .with(BreakoutRenderingSystem, "rendering", &["game_logic"])
             .build();
+        dispatcher.setup(&mut world);
+
+        // Ball responses, run after each dispatch
+        let mut collision_handlers = physics::CollisionHandlers::new();
+        collision_handlers
+            .on_collision::<Ball, Paddle, _>(bounce_off_paddle)
+            .on_collision::<Ball, Brick, _>(|_, brick, _, world| {
+                // The collision system already bounced the ball off the brick. Dead
+                // bricks are removed, and may drop a power-up, on the next dispatch.
+                if let Some(health) = world.write_storage::<Health>().get_mut(brick) {
+                    health.take_damage(1.0);
+                }
+            });
 
         let mut game = Self {
             world,
             dispatcher,
-            game_state: BreakoutGameState::Menu,
+            collision_handlers,
+            game_state: GamePhase::Menu,
             last_update: std::time::Instant::now(),
             difficulty_system,
             particle_system,
@@ -159,8 +145,6 @@ impl BreakoutGame {
             trail_system,
             level: 1,
             lives: 3,
-            balls: Vec::new(),
-            bricks_remaining: 0,
             paddle_entity: None,
         };
 
@@ -172,44 +156,48 @@ impl BreakoutGame {
         // Clear existing entities
         self.clear_level();
 
-        // Create paddle
+        // Create paddle; positions are centers, like the colliders on them
         let paddle_entity = self
             .world
             .create_entity_with_components()
             .with(Position::new(
-                WINDOW_WIDTH as f32 / 2.0 - PADDLE_WIDTH / 2.0,
-                WINDOW_HEIGHT as f32 - 60.0,
+                WINDOW_WIDTH as f32 / 2.0,
+                WINDOW_HEIGHT as f32 - 60.0 + PADDLE_HEIGHT / 2.0,
             ))
-            .with(Velocity::new(0.0, 0.0))
             .with(Renderable::new("paddle".to_string()))
-            .with(Paddle)
+            .with(Paddle {
+                player_controlled: true,
+            })
             .with(Collider::new_rectangle(PADDLE_WIDTH, PADDLE_HEIGHT))
             .build();
         self.paddle_entity = Some(paddle_entity);
 
         // Create ball attached to paddle
-        let ball_entity = self
-            .world
-            .create_entity_with_components()
-            .with(Position::new(
-                WINDOW_WIDTH as f32 / 2.0 - BALL_SIZE / 2.0,
-                WINDOW_HEIGHT as f32 - 80.0,
-            ))
-            .with(Velocity::new(0.0, 0.0))
-            .with(Renderable::new("ball".to_string()))
-            .with(Ball {
-                attached_to_paddle: true,
-            })
-            .with(Collider::new_circle(BALL_SIZE / 2.0))
-            .build();
-        self.balls.push(ball_entity);
+        self.spawn_ball(
+            Position::new(
+                WINDOW_WIDTH as f32 / 2.0,
+                WINDOW_HEIGHT as f32 - 80.0 + BALL_SIZE / 2.0,
+            ),
+            Vec2::zeros(),
+            true,
+        );
 
         // Create bricks
         self.create_bricks();
 
         // Reset scoring for new level
         self.scoring_system.reset();
-        self.bricks_remaining = self.count_bricks();
+    }
+
+    fn spawn_ball(&mut self, position: Position, velocity: Vec2, attached_to_paddle: bool) {
+        self.world
+            .create_entity_with_components()
+            .with(position)
+            .with(Velocity::new(velocity.x, velocity.y))
+            .with(Renderable::new("ball".to_string()))
+            .with(Ball { attached_to_paddle })
+            .with(Collider::new_circle(BALL_SIZE / 2.0))
+            .build();
     }
 
     fn create_bricks(&mut self) {
@@ -224,73 +212,36 @@ impl BreakoutGame {
             [0.5, 0.0, 1.0, 1.0], // Purple
         ];
 
-        for row in 0..BRICK_ROWS {
-            for col in 0..BRICK_COLS {
-                let x = start_x + col as f32 * (BRICK_WIDTH + 5.0);
-                let y = start_y + row as f32 * (BRICK_HEIGHT + 5.0);
-
-                let hits_required = if row < 2 {
-                    1
-                } else if row < 4 {
-                    2
-                } else {
-                    3
-                };
-                let points = (BRICK_ROWS - row) * 10;
-
-                self.world
-                    .create_entity_with_components()
-                    .with(Position::new(x, y))
-                    .with(Velocity::new(0.0, 0.0))
-                    .with(Renderable::new("brick".to_string()))
-                    .with(Brick {
-                        hits_required,
-                        points: points as i32,
-                        color: colors[row % colors.len()],
-                    })
-                    .with(Collider::new_rectangle(BRICK_WIDTH, BRICK_HEIGHT))
-                    .build();
-            }
+        let bricks = resources::LevelLoader::new().with_tile('-', 2).parse(
+            LEVEL,
+            Vec2::new(BRICK_WIDTH + 5.0, BRICK_HEIGHT + 5.0),
+            Vec2::new(start_x, start_y),
+        );
+        for spec in bricks {
+            let points = BRICK_ROWS.saturating_sub(spec.row) * 10;
+
+            self.world
+                .create_entity_with_components()
+                .with(Position::new(
+                    spec.position.x + BRICK_WIDTH / 2.0,
+                    spec.position.y + BRICK_HEIGHT / 2.0,
+                ))
+                .with(Renderable::new("brick".to_string()))
+                .with(Brick)
+                .with(Health::new(spec.hits as f32))
+                .with(BrickStyle {
+                    points: points as i32,
+                    color: colors[spec.row % colors.len()],
+                })
+                .with(Collider::new_rectangle(BRICK_WIDTH, BRICK_HEIGHT))
+                .build();
         }
     }
 
-    fn count_bricks(&self) -> usize {
-        let bricks = self.world.read_storage::<Brick>();
-        bricks.join().count()
-    }
-
     fn clear_level(&mut self) {
-        // Remove all game entities
-        let mut to_remove = Vec::new();
+        // Every entity belongs to the level, so remove them all
+        self.world.clear_all();
 
-        // Remove balls
-        let balls = self.world.read_storage::<Ball>();
-        for (entity, _) in (&self.world.entities(), &balls).join() {
-            to_remove.push(entity);
-        }
-
-        // Remove bricks
-        let bricks = self.world.read_storage::<Brick>();
-        for (entity, _) in (&self.world.entities(), &bricks).join() {
-            to_remove.push(entity);
-        }
-
-        // Remove power-ups
-        let powerups = self.world.read_storage::<PowerUp>();
-        for (entity, _) in (&self.world.entities(), &powerups).join() {
-            to_remove.push(entity);
-        }
-
-        // Remove paddle
-        if let Some(paddle) = self.paddle_entity {
-            to_remove.push(paddle);
-        }
-
-        for entity in to_remove {
-            let _ = self.world.entities().delete(entity);
-        }
-
-        self.balls.clear();
         self.paddle_entity = None;
     }
 
@@ -299,9 +250,13 @@ impl BreakoutGame {
         let actual_delta = current_time.duration_since(self.last_update).as_secs_f32();
         self.last_update = current_time;
 
-        // Update Time resource for physics
-        self.world.write_resource::<Time>().delta = actual_delta;
-        self.world.write_resource::<Time>().elapsed += actual_delta;
+        // Gameplay and effects run on scaled time, so pausing freezes them together
+        let scaled_delta = {
+            let mut time = self.world.write_resource::<Time>();
+            time.set_paused(matches!(self.game_state, GamePhase::Paused));
+            time.advance(actual_delta);
+            time.delta
+        };
 
         // Update input state resource
         *self
@@ -309,131 +264,165 @@ impl BreakoutGame {
             .write_resource::<input_window::WindowInputState>() = input_state.clone();
 
         // Update extracted systems
-        self.particle_system.update(actual_delta);
-        self.visual_effects.update(actual_delta);
-        self.scoring_system.update_time(actual_delta);
+        self.particle_system.update(scaled_delta);
+        self.visual_effects.update(scaled_delta);
+        self.scoring_system.update_time(scaled_delta);
 
         match &self.game_state {
-            BreakoutGameState::Menu => {
+            GamePhase::Menu => {
                 // Handle menu input
                 // For now, just start the game
                 if self.menu_system.get_selected_item().is_some() {
-                    self.game_state = BreakoutGameState::Playing;
+                    self.game_state = GamePhase::Playing;
                 }
             }
-            BreakoutGameState::Playing => {
+            GamePhase::Playing => {
                 // Update game systems
-                self.dispatcher.dispatch(&mut self.world);
+                self.dispatcher.dispatch(&self.world);
+                self.collision_handlers.invoke(&mut self.world);
+                self.apply_power_ups();
 
                 // Update ball trails
-                for ball_entity in &self.balls {
-                    if let Some(position) = self.world.read_storage::<Position>().get(*ball_entity)
-                    {
-                        if let Some(velocity) =
-                            self.world.read_storage::<Velocity>().get(*ball_entity)
-                        {
-                            self.trail_system.update_trail(
-                                "ball",
-                                actual_delta,
-                                Vec2::new(position.x, position.y),
-                                Vec2::new(velocity.x, velocity.y),
-                            );
-                        }
-                    }
+                for (position, velocity, _) in (
+                    &self.world.read_storage::<Position>(),
+                    &self.world.read_storage::<Velocity>(),
+                    &self.world.read_storage::<Ball>(),
+                )
+                    .join()
+                {
+                    self.trail_system.update_trail(
+                        "ball",
+                        scaled_delta,
+                        Vec2::new(position.x, position.y),
+                        Vec2::new(velocity.x, velocity.y),
+                    );
                 }
 
-                self.world.maintain();
+                self.world.apply_commands();
 
                 // Check win/lose conditions
-                if self.bricks_remaining == 0 {
-                    self.game_state = BreakoutGameState::LevelComplete;
+                let cleared = !self
+                    .world
+                    .write_resource::<LiveSet<Brick>>()
+                    .drain_events()
+                    .is_empty();
+                if cleared {
+                    self.game_state = GamePhase::custom(LEVEL_COMPLETE);
                 }
 
-                // Check if all balls are lost
-                let active_balls = self
-                    .balls
-                    .iter()
-                    .filter(|&&entity| self.world.entities().is_alive(entity))
-                    .count();
-
-                if active_balls == 0 {
+                // The life ends once the last ball in play is lost
+                let balls_lost = !self
+                    .world
+                    .write_resource::<LiveSet<Ball>>()
+                    .drain_events()
+                    .is_empty();
+                if balls_lost {
                     self.lives -= 1;
                     if self.lives <= 0 {
-                        self.game_state = BreakoutGameState::GameOver { won: false };
+                        self.game_state = GamePhase::GameOver;
                     } else {
                         // Reset ball
                         self.reset_ball();
                     }
                 }
             }
-            BreakoutGameState::Paused => {
+            GamePhase::Paused => {
                 // Handle pause menu
             }
-            BreakoutGameState::GameOver { .. } => {
+            GamePhase::GameOver => {
                 // Handle game over
             }
-            BreakoutGameState::LevelComplete => {
+            GamePhase::Custom(name) if name == LEVEL_COMPLETE => {
                 // Handle level complete
                 self.level += 1;
                 self.initialize_level();
-                self.game_state = BreakoutGameState::Playing;
+                self.game_state = GamePhase::Playing;
             }
+            GamePhase::Custom(_) => {}
         }
     }
 
-    fn reset_ball(&mut self) {
-        // Reset ball position and attach to paddle
-        if let Some(ball_entity) = self.balls.first() {
-            if let Some(positions) =
-                self.world.write_storage::<Position>().get_mut(*ball_entity)
-            {
-                if let Some(velocities) =
-                    self.world.write_storage::<Velocity>().get_mut(*ball_entity)
-                {
-                    if let Some(paddle_entity) = self.paddle_entity {
-                        if let Some(paddle_pos) =
-                            self.world.read_storage::<Position>().get(paddle_entity)
-                        {
-                            positions.x = paddle_pos.x + PADDLE_WIDTH / 2.0 - BALL_SIZE / 2.0;
-                            positions.y = paddle_pos.y - BALL_SIZE;
-                            velocities.x = 0.0;
-                            velocities.y = 0.0;
-
-                            if let Some(balls) =
-                                self.world.write_storage::<Ball>().get_mut(*ball_entity)
-                            {
-                                balls.attached_to_paddle = true;
-                            }
-                        }
-                    }
-                }
+    /// Apply the pickups the engine leaves to the game; paddle width
+    /// effects are handled by `PowerUpSystem`
+    fn apply_power_ups(&mut self) {
+        let collected =
+            std::mem::take(&mut self.world.write_resource::<CollectedPowerUps>().collected);
+        for (_, power_type) in collected {
+            match power_type {
+                PowerUpType::ExtraLife => self.lives += 1,
+                PowerUpType::MultiBall => self.split_balls(),
+                PowerUpType::LargerPaddle | PowerUpType::SmallerPaddle => {}
             }
         }
     }
 
+    /// Add two balls next to the first one in play, fanned out 30 degrees either side
+    fn split_balls(&mut self) {
+        let source = (
+            &self.world.read_storage::<Position>(),
+            &self.world.read_storage::<Velocity>(),
+            &self.world.read_storage::<Ball>(),
+        )
+            .join()
+            .find(|(_, _, ball)| !ball.attached_to_paddle)
+            .map(|(position, velocity, _)| (*position, velocity.as_vec2()));
+        let Some((position, velocity)) = source else {
+            return;
+        };
+        for angle in [-std::f32::consts::FRAC_PI_6, std::f32::consts::FRAC_PI_6] {
+            let (sin, cos) = angle.sin_cos();
+            let split = Vec2::new(
+                velocity.x * cos - velocity.y * sin,
+                velocity.x * sin + velocity.y * cos,
+            );
+            self.spawn_ball(position, split, false);
+        }
+    }
+
+    /// Serve a new ball from the paddle after the last one was lost
+    fn reset_ball(&mut self) {
+        let Some(paddle_entity) = self.paddle_entity else {
+            return;
+        };
+        let Some(paddle_pos) = self
+            .world
+            .read_storage::<Position>()
+            .get(paddle_entity)
+            .copied()
+        else {
+            return;
+        };
+        self.spawn_ball(
+            Position::new(paddle_pos.x, paddle_pos.y - PADDLE_HEIGHT / 2.0 - BALL_SIZE),
+            Vec2::zeros(),
+            true,
+        );
+    }
+
     pub fn render(&self, renderer: &mut renderer_2d::Renderer2D) {
         // Clear screen
         renderer.clear(renderer_2d::Color::rgb(20, 20, 40));
 
         match &self.game_state {
-            BreakoutGameState::Menu => {
+            GamePhase::Menu => {
                 self.render_menu(renderer);
             }
-            BreakoutGameState::Playing | BreakoutGameState::Paused => {
+            GamePhase::Playing | GamePhase::Paused => {
                 self.render_gameplay(renderer);
 
-                if let BreakoutGameState::Paused = self.game_state {
+                if let GamePhase::Paused = self.game_state {
                     self.render_pause_overlay(renderer);
                 }
             }
-            BreakoutGameState::GameOver { won } => {
+            GamePhase::GameOver => {
                 self.render_gameplay(renderer);
-                self.render_game_over(renderer, *won);
+                self.render_game_over(renderer, self.lives > 0);
             }
-            BreakoutGameState::LevelComplete => {
+            GamePhase::Custom(name) if name == LEVEL_COMPLETE => {
                 self.render_gameplay(renderer);
                 self.render_level_complete(renderer);
             }
+            GamePhase::Custom(_) => {}
         }
 
         // Render particles on top
@@ -479,11 +468,14 @@ impl BreakoutGame {
         let positions = self.world.read_storage::<Position>();
         let paddles = self.world.read_storage::<Paddle>();
 
-        for (pos, _) in (&positions, &paddles).join() {
+        let colliders = self.world.read_storage::<Collider>();
+        for (pos, _, collider) in (&positions, &paddles, &colliders).join() {
+            // Power-ups resize the collider, so draw from it
+            let width = paddle_width(collider);
             renderer.draw_rect(
-                pos.x as i32,
-                pos.y as i32,
-                PADDLE_WIDTH as i32,
+                (pos.x - width / 2.0) as i32,
+                (pos.y - PADDLE_HEIGHT / 2.0) as i32,
+                width as i32,
                 PADDLE_HEIGHT as i32,
                 renderer_2d::Color::rgb(100, 200, 100),
             );
@@ -493,15 +485,15 @@ impl BreakoutGame {
         let balls = self.world.read_storage::<Ball>();
         for (pos, _) in (&positions, &balls).join() {
             renderer.draw_circle_filled(
-                (pos.x + BALL_SIZE / 2.0) as i32,
-                (pos.y + BALL_SIZE / 2.0) as i32,
+                pos.x as i32,
+                pos.y as i32,
                 (BALL_SIZE / 2.0) as i32,
                 renderer_2d::Color::WHITE,
             );
         }
 
         // Render bricks
-        let bricks = self.world.read_storage::<Brick>();
+        let bricks = self.world.read_storage::<BrickStyle>();
         for (pos, brick) in (&positions, &bricks).join() {
             let color = renderer_2d::Color::rgba(
                 (brick.color[0] * 255.0) as u8,
@@ -511,8 +503,8 @@ impl BreakoutGame {
             );
 
             renderer.draw_rect(
-                pos.x as i32,
-                pos.y as i32,
+                (pos.x - BRICK_WIDTH / 2.0) as i32,
+                (pos.y - BRICK_HEIGHT / 2.0) as i32,
                 BRICK_WIDTH as i32,
                 BRICK_HEIGHT as i32,
                 color,
@@ -527,11 +519,9 @@ impl BreakoutGame {
                 PowerUpType::MultiBall => renderer_2d::Color::BLUE,
                 PowerUpType::LargerPaddle => renderer_2d::Color::YELLOW,
                 PowerUpType::SmallerPaddle => renderer_2d::Color::RED,
-                PowerUpType::FasterBall => renderer_2d::Color::rgb(128, 0, 128),
-                PowerUpType::SlowerBall => renderer_2d::Color::CYAN,
             };
 
-            renderer.draw_circle_filled((pos.x + 10.0) as i32, (pos.y + 10.0) as i32, 8, color);
+            renderer.draw_circle_filled(pos.x as i32, pos.y as i32, 8, color);
         }
 
         // Render UI
@@ -561,7 +551,10 @@ impl BreakoutGame {
         );
 
         // Bricks remaining
-        let bricks_text = format!("Bricks: {}", self.bricks_remaining);
+        let bricks_text = format!(
+            "Bricks: {}",
+            self.world.read_resource::<LiveSet<Brick>>().count_alive()
+        );
         renderer.draw_text(
             &bricks_text,
             WINDOW_WIDTH - 100,
@@ -575,17 +568,7 @@ impl BreakoutGame {
         // This would integrate with the particle system rendering
         // For now, just render ball trails
         if let Some(trail) = self.trail_system.get_trail("ball") {
-            for segment in trail.get_segments() {
-                let alpha = (segment.alpha() * 255.0) as u8;
-                let color = renderer_2d::Color::rgba(100, 100, 255, alpha);
-
-                renderer.draw_circle_filled(
-                    segment.position.x as i32,
-                    segment.position.y as i32,
-                    segment.size as i32,
-                    color,
-                );
-            }
+            trail.render(renderer, None);
         }
     }
 
@@ -689,61 +672,45 @@ impl BreakoutGame {
         }
 
         match &self.game_state {
-            BreakoutGameState::Menu => {
+            GamePhase::Menu => {
                 if input_state.is_key_just_pressed(Key::Space) {
-                    self.game_state = BreakoutGameState::Playing;
+                    self.game_state = GamePhase::Playing;
                     println!("Game started! Press SPACE to launch the ball.");
                 }
             }
-            BreakoutGameState::Playing => {
+            GamePhase::Playing => {
                 if input_state.is_key_just_pressed(Key::Escape) {
-                    self.game_state = BreakoutGameState::Paused;
+                    self.game_state = GamePhase::Paused;
                 }
 
                 // Launch ball if attached to paddle
                 if input_state.is_key_just_pressed(Key::Space) {
-                    if let Some(ball_entity) = self.balls.first() {
-                        // Check if ball is attached to paddle and launch it
-                        let should_launch = {
-                            let balls = self.world.read_storage::<Ball>();
-                            balls.get(*ball_entity).map_or(false, |ball| ball.attached_to_paddle)
-                        };
-                        
-                        if should_launch {
-                            // Launch the ball - separate scopes to avoid borrowing conflicts
-                            {
-                                let mut velocities = self.world.write_storage::<Velocity>();
-                                if let Some(velocity) = velocities.get_mut(*ball_entity) {
-                                    velocity.x = BALL_SPEED
-                                        * self.difficulty_system.ball_speed_multiplier();
-                                    velocity.y = -BALL_SPEED
-                                        * self.difficulty_system.ball_speed_multiplier();
-                                }
-                            }
-
-                            {
-                                let mut balls = self.world.write_storage::<Ball>();
-                                if let Some(ball_component) = balls.get_mut(*ball_entity) {
-                                    ball_component.attached_to_paddle = false;
-                                }
-                            }
+                    let speed = BALL_SPEED * self.difficulty_system.ball_speed_multiplier();
+                    let mut velocities = self.world.write_storage::<Velocity>();
+                    let mut balls = self.world.write_storage::<Ball>();
+                    for (velocity, ball) in (&mut velocities, &mut balls).join() {
+                        if ball.attached_to_paddle {
+                            ball.attached_to_paddle = false;
+                            velocity.x = speed;
+                            velocity.y = -speed;
                         }
                     }
                 }
             }
-            BreakoutGameState::Paused => {
+            GamePhase::Paused => {
                 if input_state.is_key_just_pressed(Key::Escape) {
-                    self.game_state = BreakoutGameState::Playing;
+                    self.game_state = GamePhase::Playing;
                 }
             }
-            BreakoutGameState::GameOver { .. } => {
+            GamePhase::GameOver => {
                 // R key restart is handled globally above
             }
-            BreakoutGameState::LevelComplete => {
+            GamePhase::Custom(name) if name == LEVEL_COMPLETE => {
                 if input_state.is_key_just_pressed(Key::Space) {
-                    self.game_state = BreakoutGameState::Playing;
+                    self.game_state = GamePhase::Playing;
                 }
             }
+            GamePhase::Custom(_) => {}
         }
     }
 
@@ -753,13 +720,13 @@ impl BreakoutGame {
         self.lives = 3;
         self.scoring_system.reset();
         self.initialize_level();
-        self.game_state = BreakoutGameState::Playing;
+        self.game_state = GamePhase::Playing;
         println!("✅ Game restarted! Press SPACE to launch the ball.");
     }
 }
 
 // Game systems
-use specs::{Entities, Join, Read, ReadStorage, System, WriteStorage};
+use specs::{Entities, Join, ReadStorage, System, WriteStorage};
 
 pub struct BreakoutInputSystem;
 
@@ -777,112 +744,87 @@ impl<'a> System<'a> for BreakoutInputSystem {
     }
 }
 
-pub struct BreakoutPhysicsSystem;
-
-impl<'a> System<'a> for BreakoutPhysicsSystem {
-    type SystemData = (
-        WriteStorage<'a, Position>,
-        WriteStorage<'a, Velocity>,
-        ReadStorage<'a, Ball>,
-        Read<'a, Time>,
-    );
-
-    fn run(&mut self, (mut positions, mut velocities, balls, time): Self::SystemData) {
-        for (pos, vel, _) in (&mut positions, &mut velocities, &balls).join() {
-            pos.x += vel.x * time.delta;
-            pos.y += vel.y * time.delta;
-        }
-    }
-}
-
-pub struct BreakoutCollisionSystem;
+/// Bounces balls off the side and top walls and removes balls and power-ups
+/// that fall out the bottom. Ball, paddle and brick contacts go through
+/// `physics::CollisionSystem`.
+pub struct BreakoutWallSystem;
 
-impl<'a> System<'a> for BreakoutCollisionSystem {
+impl<'a> System<'a> for BreakoutWallSystem {
     type SystemData = (
         Entities<'a>,
         WriteStorage<'a, Position>,
         WriteStorage<'a, Velocity>,
         ReadStorage<'a, Ball>,
-        ReadStorage<'a, Paddle>,
-        ReadStorage<'a, Brick>,
         ReadStorage<'a, PowerUp>,
     );
 
     fn run(
         &mut self,
-        (entities, mut positions, mut velocities, balls, paddles, bricks, _powerups): Self::SystemData,
+        (entities, mut positions, mut velocities, balls, powerups): Self::SystemData,
     ) {
-        // Ball-wall collisions
+        // Missed power-ups fall out of play
+        for (entity, pos, _) in (&entities, &positions, &powerups).join() {
+            if pos.y >= WINDOW_HEIGHT as f32 {
+                let _ = entities.delete(entity);
+            }
+        }
+
+        let radius = BALL_SIZE / 2.0;
         for (entity, pos, vel, _) in (&entities, &mut positions, &mut velocities, &balls).join() {
             // Left and right walls
-            if pos.x <= 0.0 || pos.x >= WINDOW_WIDTH as f32 - BALL_SIZE {
+            if pos.x <= radius || pos.x >= WINDOW_WIDTH as f32 - radius {
                 vel.x = -vel.x;
-                pos.x = pos.x.clamp(0.0, WINDOW_WIDTH as f32 - BALL_SIZE);
+                pos.x = pos.x.clamp(radius, WINDOW_WIDTH as f32 - radius);
             }
 
             // Top wall
-            if pos.y <= 0.0 {
+            if pos.y <= radius {
                 vel.y = -vel.y;
-                pos.y = 0.0;
+                pos.y = radius;
             }
 
             // Bottom (ball lost)
-            if pos.y >= WINDOW_HEIGHT as f32 {
+            if pos.y - radius >= WINDOW_HEIGHT as f32 {
                 let _ = entities.delete(entity);
             }
         }
+    }
+}
 
-        // Ball-paddle collisions
-        for (_ball_entity, ball_pos, ball_vel, _) in
-            (&entities, &positions, &mut velocities, &balls).join()
-        {
-            for (paddle_pos, _) in (&positions, &paddles).join() {
-                if ball_pos.x < paddle_pos.x + PADDLE_WIDTH
-                    && ball_pos.x + BALL_SIZE > paddle_pos.x
-                    && ball_pos.y < paddle_pos.y + PADDLE_HEIGHT
-                    && ball_pos.y + BALL_SIZE > paddle_pos.y
-                    && ball_vel.y > 0.0
-                {
-                    // Only if ball is moving down
-
-                    ball_vel.y = -ball_vel.y;
-
-                    // Add some angle based on where ball hits paddle
-                    let hit_pos = (ball_pos.x + BALL_SIZE / 2.0 - paddle_pos.x) / PADDLE_WIDTH;
-                    let angle = (hit_pos - 0.5) * std::f32::consts::PI / 3.0; // Max 60 degrees
-                    let speed = (ball_vel.x * ball_vel.x + ball_vel.y * ball_vel.y).sqrt();
-                    ball_vel.x = angle.sin() * speed;
-                    ball_vel.y = -angle.cos().abs() * speed;
-                }
-            }
-        }
-
-        // Ball-brick collisions
-        let mut bricks_to_remove = Vec::new();
-
-        for (_ball_entity, ball_pos, ball_vel, _) in
-            (&entities, &positions, &mut velocities, &balls).join()
-        {
-            for (brick_entity, brick_pos, _brick) in (&entities, &positions, &bricks).join() {
-                if ball_pos.x < brick_pos.x + BRICK_WIDTH
-                    && ball_pos.x + BALL_SIZE > brick_pos.x
-                    && ball_pos.y < brick_pos.y + BRICK_HEIGHT
-                    && ball_pos.y + BALL_SIZE > brick_pos.y
-                {
-                    // Ball collision with brick
-                    ball_vel.y = -ball_vel.y;
-
-                    // Damage brick
-                    // In a full implementation, we'd track brick health
-                    bricks_to_remove.push(brick_entity);
-                }
-            }
-        }
+/// Width of a paddle's rectangle collider
+fn paddle_width(collider: &Collider) -> f32 {
+    match collider.shape {
+        CollisionShape::Rectangle { width, .. } => width,
+        _ => PADDLE_WIDTH,
+    }
+}
 
-        // Remove destroyed bricks
-        for brick_entity in bricks_to_remove {
-            let _ = entities.delete(brick_entity);
-        }
+/// Aim a ball that hit the top of the paddle by where it landed
+fn bounce_off_paddle(
+    ball: specs::Entity,
+    paddle: specs::Entity,
+    event: &physics::CollisionEvent,
+    world: &mut World,
+) {
+    // Only the top face steers; the collision system already reflected the ball
+    if event.normal.y <= 0.0 {
+        return;
+    }
+    let positions = world.read_storage::<Position>();
+    let (Some(ball_pos), Some(paddle_pos)) = (positions.get(ball), positions.get(paddle)) else {
+        return;
+    };
+    let width = world
+        .read_storage::<Collider>()
+        .get(paddle)
+        .map_or(PADDLE_WIDTH, paddle_width);
+    // Up to 30 degrees either side of straight up
+    let hit_pos = ((ball_pos.x - paddle_pos.x) / width).clamp(-0.5, 0.5);
+    let angle = hit_pos * std::f32::consts::PI / 3.0;
+    if let Some(velocity) = world.write_storage::<Velocity>().get_mut(ball) {
+        let speed = velocity.as_vec2().norm();
+        velocity.x = angle.sin() * speed;
+        velocity.y = -angle.cos() * speed;
     }
 }
 
@@ -944,7 +886,8 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
         width: WINDOW_WIDTH,
         height: WINDOW_HEIGHT,
         resizable: false,
-        vsync: true,
+        present_mode: window::PresentMode::Fifo,
+        fullscreen: false,
     };
 
     let mut render_context = renderer_2d::RenderContext::new(window_config).unwrap();
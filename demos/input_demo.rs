@@ -24,7 +24,8 @@ fn main() {
         width: WINDOW_WIDTH,
         height: WINDOW_HEIGHT,
         resizable: false,
-        vsync: true,
+        present_mode: window::PresentMode::Fifo,
+        fullscreen: false,
     })
     .unwrap();
 
@@ -159,7 +160,6 @@ fn main() {
             renderer_2d::Color::WHITE,
             1,
         );
-        y_offset += line_height;
 
         if let Err(e) = render_context.present() {
             eprintln!("Error presenting frame: {}", e);
@@ -168,7 +168,7 @@ fn main() {
 
         // Diagnostic: print window open state each frame (throttled)
         frame_counter += 1;
-        if frame_counter % 60 == 0 {
+        if frame_counter.is_multiple_of(60) {
             println!(
                 "Frame {} - window.is_open: {}",
                 frame_counter,
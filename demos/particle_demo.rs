@@ -101,7 +101,8 @@ fn main() {
         width: WINDOW_WIDTH,
         height: WINDOW_HEIGHT,
         resizable: false,
-        vsync: true,
+        present_mode: window::PresentMode::Fifo,
+        fullscreen: false,
     }).unwrap();
 
     let mut input_manager = input_window::WindowInputManager::new();
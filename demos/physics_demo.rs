@@ -144,8 +144,7 @@ fn print_physics_status(world: &World, frame: u64) {
 
     ball_positions.sort_by(|a, b| a.0.cmp(&b.0));
 
-    let ball1_pos = ball_positions
-        .get(0)
+    let ball1_pos = ball_positions.first()
         .map(|(_, x, y)| format!("({:6.1},{:6.1})", x, y))
         .unwrap_or("N/A".to_string());
 
@@ -186,7 +185,7 @@ impl<'a> System<'a> for CollisionSystem {
         for (entity, position, collider, velocity) in
             (&entities, &positions, &colliders, &velocities).join()
         {
-            dynamic_entities.push((entity, position.clone(), collider.clone(), velocity.clone()));
+            dynamic_entities.push((entity, *position, collider.clone(), *velocity));
         }
 
         // Check collisions between dynamic entities
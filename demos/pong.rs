@@ -19,6 +19,12 @@
 //! The game will automatically use high-quality fonts for all text rendering.
 //! If no fonts are loaded, it falls back to the built-in bitmap font.
 
+use modular_game_engine::difficulty::DifficultyLevel;
+use modular_game_engine::game_state::GamePhase;
+use modular_game_engine::games::pong::{PongConfig, PongGame, PongSide};
+use modular_game_engine::input::{key_name, ActionMap};
+use modular_game_engine::menu::{menu_items, MenuAction, MenuSystem};
+use modular_game_engine::resources::Settings;
 use modular_game_engine::*;
 
 // Game constants
@@ -30,6 +36,13 @@ const BALL_SIZE: f32 = 15.0;
 const PADDLE_SPEED: f32 = 350.0;
 const BALL_SPEED: f32 = 450.0;
 const MAX_SCORE: u32 = 5;
+/// Key bindings file, next to the settings
+const BINDINGS_FILE: &str = "pong_bindings.cfg";
+/// Paddle key schemes offered in the settings menu: (name, up, down)
+const CONTROL_SCHEMES: [(&str, minifb::Key, minifb::Key); 2] = [
+    ("W / S", minifb::Key::W, minifb::Key::S),
+    ("Up / Down", minifb::Key::Up, minifb::Key::Down),
+];
 
 // Particle system for visual effects
 struct Particle {
@@ -108,15 +121,18 @@ impl ParticleSystem {
 
 // Enhanced Pong game state
 struct ImprovedPongGame {
-    world: World,
-    dispatcher: specs::Dispatcher<'static, 'static>,
-    game_state: GameState,
+    pong: PongGame,
+    game_state: GamePhase,
     last_update: std::time::Instant,
     score: (u32, u32),
     particle_system: ParticleSystem,
-    ball_trail: Vec<(f32, f32, f32)>, // (x, y, alpha)
     game_time: f32,
     difficulty: Difficulty,
+    settings_menu: MenuSystem,
+    bindings: ActionMap,
+    /// Slow motion played on the winning point before the game-over screen
+    slow_motion: visual_effects::TimeScaleController,
+    winning_point: bool,
 }
 
 #[derive(Clone, Copy, PartialEq)]
@@ -127,6 +143,22 @@ enum Difficulty {
 }
 
 impl Difficulty {
+    fn from_level(level: DifficultyLevel) -> Self {
+        match level {
+            DifficultyLevel::VeryEasy | DifficultyLevel::Easy => Difficulty::Easy,
+            DifficultyLevel::Hard | DifficultyLevel::VeryHard => Difficulty::Hard,
+            DifficultyLevel::Normal | DifficultyLevel::Custom => Difficulty::Normal,
+        }
+    }
+
+    fn level(self) -> DifficultyLevel {
+        match self {
+            Difficulty::Easy => DifficultyLevel::Easy,
+            Difficulty::Normal => DifficultyLevel::Normal,
+            Difficulty::Hard => DifficultyLevel::Hard,
+        }
+    }
+
         #[allow(dead_code)]
     fn ai_speed_multiplier(&self) -> f32 {
         match self {
@@ -145,79 +177,168 @@ impl Difficulty {
     }
 }
 
-#[derive(Debug, Clone, PartialEq)]
-enum GameState {
-    Menu,
-    DifficultySelect,
-    Playing,
-        Paused,
-    #[allow(dead_code)]
-    Scored { points: u32, is_player: bool },
-    GameOver { winner: String },
-}
+/// Custom phase between the menu and play
+const DIFFICULTY_SELECT: &str = "difficulty_select";
+/// Custom phase for the settings screen, reached from the menu
+const SETTINGS: &str = "settings";
 
 impl ImprovedPongGame {
     fn new() -> Self {
-        let mut world = init().unwrap();
-
-        // Register game-specific components
-        world.register::<Paddle>();
-        world.register::<Ball>();
-        world.register::<Score>();
-
-        // Create game entities
-        create_pong_entities(&mut world);
-
-        // Insert input state resource
-        world.insert(crate::input_window::WindowInputState::default());
+        let config = PongConfig::new()
+            .with_size(WINDOW_WIDTH as f32, WINDOW_HEIGHT as f32)
+            .with_paddle_speed(PADDLE_SPEED)
+            .with_ball_speed(BALL_SPEED)
+            .with_max_score(MAX_SCORE);
+        let config = PongConfig {
+            paddle_width: PADDLE_WIDTH,
+            paddle_height: PADDLE_HEIGHT,
+            ball_size: BALL_SIZE,
+            ..config
+        };
 
-        // Set up systems
-        let dispatcher = specs::DispatcherBuilder::new()
-            .with(ImprovedPongInputSystem, "input", &[])
-            .with(ImprovedPongAISystem, "ai", &["input"])
-            .with(PhysicsSystem, "physics", &["ai"])
-            .with(ImprovedPongCollisionSystem, "collision", &["physics"])
-            .with(ImprovedPongGameLogicSystem, "game_logic", &["collision"])
-            .build();
+        let mut pong = PongGame::new(config);
+        let settings = Settings::load();
+        let difficulty = Difficulty::from_level(settings.difficulty);
+        let settings_menu = MenuSystem::create_settings_menu_from(&settings);
+        pong.world.insert(settings);
 
-        Self {
-            world,
-            dispatcher,
-            game_state: GameState::Menu,
+        let mut game = Self {
+            pong,
+            game_state: GamePhase::Menu,
             last_update: std::time::Instant::now(),
             score: (0, 0),
             particle_system: ParticleSystem::new(),
-            ball_trail: Vec::new(),
             game_time: 0.0,
-            difficulty: Difficulty::Normal,
+            difficulty,
+            settings_menu,
+            bindings: Self::load_bindings(),
+            slow_motion: visual_effects::TimeScaleController::new(),
+            winning_point: false,
+        };
+        game.apply_bindings();
+        game
+    }
+
+    /// Saved key bindings, or W/S when there are none yet
+    fn load_bindings() -> ActionMap {
+        let (_, up, down) = CONTROL_SCHEMES[0];
+        let mut defaults = ActionMap::new();
+        defaults.bind("move_up", up);
+        defaults.bind("move_down", down);
+
+        match Settings::config_path(BINDINGS_FILE) {
+            Some(path) if path.exists() => ActionMap::load_from_file(&path).unwrap_or_else(|e| {
+                eprintln!("Warning: could not load key bindings: {}", e);
+                defaults
+            }),
+            _ => defaults,
+        }
+    }
+
+    fn save_bindings(&self) {
+        let Some(path) = Settings::config_path(BINDINGS_FILE) else {
+            return;
+        };
+        if let Err(e) = self.bindings.save_to_file(&path) {
+            eprintln!("Warning: could not save key bindings: {}", e);
+        }
+    }
+
+    /// Point the paddle keys at the first key bound to each move action
+    fn apply_bindings(&mut self) {
+        let mut config = self.pong.world.write_resource::<PongConfig>();
+        if let Some(key) = self.bindings.keys("move_up").first() {
+            config.up_key = *key;
+        }
+        if let Some(key) = self.bindings.keys("move_down").first() {
+            config.down_key = *key;
+        }
+    }
+
+    /// Settings menu plus a controls selector, placed above Back
+    fn open_settings(&mut self) {
+        let mut menu = MenuSystem::create_settings_menu_from(&self.settings());
+        let config = self.pong.config();
+        let scheme = CONTROL_SCHEMES
+            .iter()
+            .position(|(_, up, down)| (*up, *down) == (config.up_key, config.down_key))
+            .unwrap_or(0);
+        let controls = menu_items::selector(
+            "controls",
+            "Controls",
+            CONTROL_SCHEMES
+                .iter()
+                .map(|(name, _, _)| name.to_string())
+                .collect(),
+            scheme,
+            MenuAction::SelectOption("controls".to_string(), scheme),
+        );
+        menu.items.insert(menu.items.len() - 1, controls);
+        menu.update_layout();
+
+        self.settings_menu = menu;
+        self.game_state = GamePhase::custom(SETTINGS);
+    }
+
+    /// Leave the settings menu, writing the key bindings
+    fn close_settings(&mut self) {
+        self.save_bindings();
+        self.game_state = GamePhase::Menu;
+    }
+
+    fn settings(&self) -> Settings {
+        (*self.pong.world.read_resource::<Settings>()).clone()
+    }
+
+    /// Apply a settings change and persist it
+    fn apply_setting(&mut self, action: &MenuAction) {
+        let mut settings = self.pong.world.write_resource::<Settings>();
+        if settings.apply_menu_action(action) {
+            self.difficulty = Difficulty::from_level(settings.difficulty);
+            if let Err(e) = settings.save() {
+                eprintln!("Warning: could not save settings: {}", e);
+            }
         }
     }
 
     fn update(&mut self, delta_time: f32, input: &input_window::WindowInputState) {
         self.game_time += delta_time;
 
-        // Update time resource
-        self.world.write_resource::<Time>().delta = delta_time;
-        self.world.write_resource::<Time>().elapsed = self.game_time;
-
-        // Update input state resource
-        *self
-            .world
-            .write_resource::<crate::input_window::WindowInputState>() = input.clone();
+        // Effects run on the match clock, so pausing freezes them with the game
+        let scaled_delta = {
+            let mut time = self.pong.world.write_resource::<Time>();
+            time.set_paused(matches!(self.game_state, GamePhase::Paused));
+            delta_time * time.effective_scale()
+        };
 
         // Update particle system
-        self.particle_system.update(delta_time);
-
-        // Update ball trail
-        self.update_ball_trail(delta_time);
+        self.particle_system.update(scaled_delta);
 
         match &self.game_state {
-            GameState::Menu => {
+            GamePhase::Menu => {
                 if input.is_key_just_pressed(minifb::Key::Space) {
-                    self.game_state = GameState::DifficultySelect;
+                    self.game_state = GamePhase::custom(DIFFICULTY_SELECT);
+                } else if input.is_key_just_pressed(minifb::Key::Tab) {
+                    self.open_settings();
                 }
             }
-            GameState::DifficultySelect => {
+            GamePhase::Custom(name) if name == SETTINGS => {
+                match self.settings_menu.handle_input(input) {
+                    Some(MenuAction::Back) => self.close_settings(),
+                    Some(MenuAction::SelectOption(name, index)) if name == "controls" => {
+                        let (_, up, down) = CONTROL_SCHEMES[index];
+                        self.bindings.set_bindings("move_up", vec![up]);
+                        self.bindings.set_bindings("move_down", vec![down]);
+                        self.apply_bindings();
+                    }
+                    Some(action) => self.apply_setting(&action),
+                    None if input.is_key_just_pressed(minifb::Key::Escape) => {
+                        self.close_settings();
+                    }
+                    None => {}
+                }
+            }
+            GamePhase::Custom(name) if name == DIFFICULTY_SELECT => {
                 // Navigation keys (change selection without starting)
                 if input.is_key_just_pressed(minifb::Key::Down) {
                     self.difficulty = match self.difficulty {
@@ -256,141 +377,114 @@ impl ImprovedPongGame {
                 {
                     self.start_game();
                 } else if input.is_key_just_pressed(minifb::Key::Escape) {
-                    self.game_state = GameState::Menu;
+                    self.game_state = GamePhase::Menu;
                 }
             }
-            GameState::Playing => {
+            GamePhase::Playing => {
                 // Run game systems
-                self.dispatcher.dispatch(&mut self.world);
-                self.world.maintain();
-
-                // Update score from world
-                let score_resource = self.world.read_resource::<Score>();
-                self.score = (score_resource.player_score, score_resource.ai_score);
-
-                // Check for game end
-                if self.score.0 >= MAX_SCORE {
-                    self.game_state = GameState::GameOver {
-                        winner: "Player".to_string(),
-                    };
-                } else if self.score.1 >= MAX_SCORE {
-                    self.game_state = GameState::GameOver {
-                        winner: "AI".to_string(),
-                    };
+                self.pong.update(delta_time, input);
+                self.slow_motion
+                    .apply(&mut self.pong.world.write_resource::<Time>());
+                self.score = self.pong.score();
+
+                // The winning point plays out in slow motion, then the game ends
+                if self.pong.winner().is_some() {
+                    if !self.winning_point {
+                        self.winning_point = true;
+                        self.slow_motion
+                            .slow_to(0.2, 1.5, visual_effects::Easing::EaseInOut);
+                    } else if !self.slow_motion.is_active() {
+                        self.game_state = GamePhase::GameOver;
+                    }
                 }
 
                 // Handle pause
                 if input.is_key_just_pressed(minifb::Key::Escape) {
-                    self.game_state = GameState::Paused;
+                    self.game_state = GamePhase::Paused;
                 }
             }
-            GameState::Paused => {
+            GamePhase::Paused => {
                 if input.is_key_just_pressed(minifb::Key::Escape) {
-                    self.game_state = GameState::Playing;
+                    self.game_state = GamePhase::Playing;
                 } else if input.is_key_just_pressed(minifb::Key::Q) {
-                    self.game_state = GameState::Menu;
+                    self.game_state = GamePhase::Menu;
                     self.reset_game();
                 }
             }
-            GameState::Scored { .. } => {
-                // Wait a moment before continuing
-                if self.game_time % 1.5 < delta_time {
-                    self.game_state = GameState::Playing;
-                }
-            }
-            GameState::GameOver { .. } => {
+            GamePhase::GameOver => {
                 if input.is_key_just_pressed(minifb::Key::Space) {
                     self.reset_game();
-                    self.game_state = GameState::Menu;
+                    self.game_state = GamePhase::Menu;
                 }
             }
+            GamePhase::Custom(_) => {}
         }
     }
 
     fn start_game(&mut self) {
-        self.game_state = GameState::Playing;
+        // Remember the chosen difficulty for next time
+        let index = Settings::DIFFICULTIES
+            .iter()
+            .position(|level| *level == self.difficulty.level())
+            .unwrap_or(1);
+        self.apply_setting(&MenuAction::SelectOption("difficulty".to_string(), index));
+
+        self.game_state = GamePhase::Playing;
         self.score = (0, 0);
         self.game_time = 0.0;
 
-        // Reset score in world
-        {
-            let mut score_resource = self.world.write_resource::<Score>();
-            score_resource.player_score = 0;
-            score_resource.ai_score = 0;
-        }
+        // Reset score and ball
+        self.pong.reset(self.difficulty.ball_speed_multiplier());
+        self.reset_slow_motion();
 
-        // Reset ball
-        reset_ball(&mut self.world, self.difficulty.ball_speed_multiplier());
-
-        // Clear particles and trail
+        // Clear particles
         self.particle_system.particles.clear();
-        self.ball_trail.clear();
     }
 
     fn reset_game(&mut self) {
         self.score = (0, 0);
         self.game_time = 0.0;
-        reset_ball(&mut self.world, 1.0);
+        self.pong.reset(1.0);
+        self.reset_slow_motion();
         self.particle_system.particles.clear();
-        self.ball_trail.clear();
     }
 
-    fn update_ball_trail(&mut self, delta_time: f32) {
-        // Add current ball position to trail
-        let ball_pos = self
-            .world
-            .read_storage::<Position>()
-            .join()
-            .find(|_| true) // Get first ball
-            .map(|pos| (pos.x, pos.y));
-
-        if let Some((x, y)) = ball_pos {
-            self.ball_trail.push((x, y, 1.0));
-
-            // Limit trail length
-            if self.ball_trail.len() > 20 {
-                self.ball_trail.remove(0);
-            }
-        }
-
-        // Update trail alpha
-        for (_, _, alpha) in &mut self.ball_trail {
-            *alpha -= delta_time * 2.0;
-            if *alpha < 0.0 {
-                *alpha = 0.0;
-            }
-        }
-
-        // Remove faded trail points
-        self.ball_trail.retain(|(_, _, alpha)| *alpha > 0.0);
+    fn reset_slow_motion(&mut self) {
+        self.slow_motion = visual_effects::TimeScaleController::new();
+        self.winning_point = false;
+        self.pong.world.write_resource::<Time>().time_scale = 1.0;
     }
 
     fn render(&self, renderer: &mut renderer_2d::Renderer2D) {
         // Clear screen with dark background
         renderer.clear(renderer_2d::Color::rgb(20, 20, 30));
 
-        match self.game_state {
-            GameState::Menu => {
+        match &self.game_state {
+            GamePhase::Menu => {
                 self.render_menu(renderer);
             }
-            GameState::DifficultySelect => {
+            GamePhase::Custom(name) if name == DIFFICULTY_SELECT => {
                 self.render_difficulty_select(renderer);
             }
-            GameState::Playing | GameState::Paused => {
+            GamePhase::Custom(name) if name == SETTINGS => {
+                self.render_settings(renderer);
+            }
+            GamePhase::Playing | GamePhase::Paused => {
                 self.render_gameplay(renderer);
 
-                if let GameState::Paused = self.game_state {
+                if let GamePhase::Paused = self.game_state {
                     self.render_pause_overlay(renderer);
                 }
             }
-                                                            GameState::Scored { points, is_player } => {
-                self.render_gameplay(renderer);
-                self.render_score_effect(renderer, points, is_player);
-            }
-            GameState::GameOver { ref winner } => {
+            GamePhase::GameOver => {
+                let winner = match self.pong.winner() {
+                    Some(PongSide::Ai) => "AI",
+                    _ => "Player",
+                };
                 self.render_gameplay(renderer);
                 self.render_game_over(renderer, winner);
             }
+            GamePhase::Custom(_) => {}
         }
 
         // Render particles on top of everything
@@ -424,8 +518,13 @@ impl ImprovedPongGame {
             renderer_2d::Color::GREEN,
             2,
         );
+        let config = self.pong.config();
         renderer.draw_text_centered(
-            "W/S: Move Paddle",
+            &format!(
+                "{}/{}: Move Paddle",
+                key_name(config.up_key),
+                key_name(config.down_key)
+            ),
             WINDOW_WIDTH / 2,
             350,
             renderer_2d::Color::WHITE,
@@ -445,6 +544,13 @@ impl ImprovedPongGame {
             renderer_2d::Color::WHITE,
             1,
         );
+        renderer.draw_text_centered(
+            "TAB: Settings",
+            WINDOW_WIDTH / 2,
+            440,
+            renderer_2d::Color::WHITE,
+            1,
+        );
 
         // Version info
         renderer.draw_text_centered(
@@ -515,31 +621,61 @@ impl ImprovedPongGame {
         );
     }
 
-    fn render_gameplay(&self, renderer: &mut renderer_2d::Renderer2D) {
-        // Draw ball trail
-                for (_i, (x, y, alpha)) in self.ball_trail.iter().enumerate() {
-            let trail_color = renderer_2d::Color::rgba(
-                (255.0 * alpha) as u8,
-                (255.0 * alpha) as u8,
-                (100.0 * alpha) as u8,
-                (alpha * 255.0) as u8,
-            );
-            renderer.draw_circle_filled(
-                *x as i32,
-                *y as i32,
-                (BALL_SIZE * alpha * 0.5) as i32,
-                trail_color,
+    fn render_settings(&self, renderer: &mut renderer_2d::Renderer2D) {
+        renderer.draw_text_centered(
+            &self.settings_menu.config.title,
+            WINDOW_WIDTH / 2,
+            150,
+            renderer_2d::Color::WHITE,
+            2,
+        );
+
+        for (index, item) in self.settings_menu.items.iter().enumerate() {
+            let color = if index == self.settings_menu.selected_index {
+                renderer_2d::Color::GREEN
+            } else {
+                renderer_2d::Color::WHITE
+            };
+            renderer.draw_text_centered(
+                &item.display_text(),
+                WINDOW_WIDTH / 2,
+                230 + index * 40,
+                color,
+                2,
             );
         }
 
+        renderer.draw_text_centered(
+            "UP/DOWN to choose, LEFT/RIGHT to change, ESC to go back",
+            WINDOW_WIDTH / 2,
+            510,
+            renderer_2d::Color::rgb(150, 150, 150),
+            1,
+        );
+    }
+
+    fn render_gameplay(&self, renderer: &mut renderer_2d::Renderer2D) {
         // Draw game objects
-        let positions = self.world.read_storage::<Position>();
-        let renderables = self.world.read_storage::<Renderable>();
-        let paddles = self.world.read_storage::<Paddle>();
-        let balls = self.world.read_storage::<Ball>();
+        let positions = self.pong.world.read_storage::<Position>();
+
+        // Motion blur streak behind the ball
+        let velocities = self.pong.world.read_storage::<Velocity>();
+        let ball = self.pong.entities.ball;
+        if let (Some(pos), Some(vel)) = (positions.get(ball), velocities.get(ball)) {
+            visual_effects::draw_motion_blur(
+                renderer,
+                Vec2::new(pos.x, pos.y),
+                Vec2::new(vel.x, vel.y),
+                1.0 / 20.0,
+                BALL_SIZE * 0.5,
+                renderer_2d::Color::rgba(255, 255, 100, 160),
+            );
+        }
+        let paddles = self.pong.world.read_storage::<Paddle>();
+        let balls = self.pong.world.read_storage::<Ball>();
 
         // Draw paddles with glow effect
-        for (pos, _, paddle) in (&positions, &renderables, &paddles).join() {
+        for (pos, paddle) in (&positions, &paddles).join() {
             let base_color = if paddle.player_controlled {
                 renderer_2d::Color::rgb(0, 150, 0) // Green for player
             } else {
@@ -547,9 +683,10 @@ impl ImprovedPongGame {
             };
 
             // Glow effect
+            let (left, top) = (pos.x - PADDLE_WIDTH / 2.0, pos.y - PADDLE_HEIGHT / 2.0);
             renderer.draw_rect(
-                pos.x as i32 - 3,
-                pos.y as i32 - 3,
+                left as i32 - 3,
+                top as i32 - 3,
                 (PADDLE_WIDTH + 6.0) as i32,
                 (PADDLE_HEIGHT + 6.0) as i32,
                 renderer_2d::Color::rgba(255, 255, 255, 50),
@@ -557,8 +694,8 @@ impl ImprovedPongGame {
 
             // Main paddle
             renderer.draw_rect(
-                pos.x as i32,
-                pos.y as i32,
+                left as i32,
+                top as i32,
                 PADDLE_WIDTH as i32,
                 PADDLE_HEIGHT as i32,
                 base_color,
@@ -566,7 +703,7 @@ impl ImprovedPongGame {
         }
 
         // Draw ball with glow
-        for (pos, _, _) in (&positions, &renderables, &balls).join() {
+        for (pos, _) in (&positions, &balls).join() {
             // Glow effect
             renderer.draw_circle_filled(
                 pos.x as i32,
@@ -756,21 +893,26 @@ fn main() {
     println!("- Smooth animations and transitions");
     println!();
     println!("Controls:");
-    println!("  W/S: Move paddle");
+    println!("  W/S: Move paddle (rebind in Settings)");
     println!("  SPACE: Start game / Menu navigation");
     println!("  ESC: Pause / Resume");
     println!("  Q: Quit to menu");
     println!("  1/2/3: Select difficulty (in menu)");
+    println!("  TAB: Settings (in menu)");
     println!();
 
+    let mut pong_game = ImprovedPongGame::new();
+
     // Initialize window and rendering
-    let window_config = window::WindowConfig {
+    let mut window_config = window::WindowConfig {
         title: "Improved Pong - Modular Game Engine".to_string(),
         width: WINDOW_WIDTH,
         height: WINDOW_HEIGHT,
         resizable: false,
-        vsync: true,
+        present_mode: window::PresentMode::Fifo,
+        fullscreen: false,
     };
+    pong_game.settings().apply_to_window(&mut window_config);
 
         let mut render_context = renderer_2d::RenderContext::new(window_config).unwrap();
 
@@ -778,7 +920,6 @@ fn main() {
     render_context.renderer.load_font("game_font", "assets/fonts/DejaVuSans.ttf").unwrap();
     render_context.renderer.set_default_font("game_font");
     let mut input_manager = input_window::WindowInputManager::new();
-    let mut pong_game = ImprovedPongGame::new();
 
     // Main game loop
     while !render_context.should_close() {
@@ -794,6 +935,21 @@ fn main() {
         // Update game
         pong_game.update(delta_time, input_manager.state());
 
+        // Follow display changes made in the settings menu
+        let settings = pong_game.settings();
+        if settings.present_mode != render_context.window.present_mode() {
+            render_context
+                .window
+                .set_present_mode(settings.present_mode);
+        }
+        if settings.fullscreen != render_context.window.is_fullscreen() {
+            if let Err(e) = render_context.window.toggle_fullscreen() {
+                eprintln!("Warning: could not switch fullscreen: {}", e);
+                pong_game.pong.world.write_resource::<Settings>().fullscreen =
+                    render_context.window.is_fullscreen();
+            }
+        }
+
         // Render
         pong_game.render(&mut render_context.renderer);
         render_context.present().unwrap();
@@ -807,259 +963,3 @@ fn main() {
 
     println!("Game closed. Thanks for playing Improved Pong!");
 }
-
-// Game systems (enhanced versions)
-use specs::{Entities, Join, Read, ReadStorage, System, Write, WriteStorage};
-
-pub struct ImprovedPongInputSystem;
-impl<'a> System<'a> for ImprovedPongInputSystem {
-    type SystemData = (
-        WriteStorage<'a, Velocity>,
-        ReadStorage<'a, Paddle>,
-        Read<'a, crate::input_window::WindowInputState>,
-    );
-
-    fn run(&mut self, (mut velocities, paddles, input_state): Self::SystemData) {
-        for (velocity, paddle) in (&mut velocities, &paddles).join() {
-            if paddle.player_controlled {
-                velocity.y = 0.0;
-                if input_state.keys_pressed.contains(&minifb::Key::W) {
-                    velocity.y = -PADDLE_SPEED;
-                }
-                if input_state.keys_pressed.contains(&minifb::Key::S) {
-                    velocity.y = PADDLE_SPEED;
-                }
-            }
-        }
-    }
-}
-
-pub struct ImprovedPongAISystem;
-impl<'a> System<'a> for ImprovedPongAISystem {
-    type SystemData = (
-        ReadStorage<'a, Position>,
-        WriteStorage<'a, Velocity>,
-        ReadStorage<'a, Paddle>,
-        ReadStorage<'a, Ball>,
-        Read<'a, Time>,
-        Read<'a, Score>,
-    );
-
-    fn run(&mut self, (positions, mut velocities, paddles, balls, time, score): Self::SystemData) {
-        let ball_pos = balls
-            .join()
-            .next()
-            .and_then(|_| positions.join().next())
-            .map(|pos| pos.as_vec2())
-            .unwrap_or(Vec2::new(
-                WINDOW_WIDTH as f32 / 2.0,
-                WINDOW_HEIGHT as f32 / 2.0,
-            ));
-
-        for (position, velocity, paddle) in (&positions, &mut velocities, &paddles).join() {
-            if !paddle.player_controlled {
-                let paddle_center = position.y + PADDLE_HEIGHT / 2.0;
-                let ball_center = ball_pos.y;
-                let diff = ball_center - paddle_center;
-
-                // Adjust AI speed based on score difference
-                let score_diff = score.player_score as i32 - score.ai_score as i32;
-                let ai_multiplier = match score_diff {
-                    -2..=2 => 0.8, // Normal speed
-                    3..=5 => 1.0,  // Faster when losing
-                    _ => 0.6,      // Slower when winning
-                };
-
-                let ai_error = (time.elapsed * 3.0).sin() * 15.0;
-                let target_diff = diff + ai_error;
-
-                if target_diff.abs() > 15.0 {
-                    velocity.y = target_diff.signum() * PADDLE_SPEED * ai_multiplier;
-                } else {
-                    velocity.y = 0.0;
-                }
-            }
-        }
-    }
-}
-
-pub struct ImprovedPongCollisionSystem;
-impl<'a> System<'a> for ImprovedPongCollisionSystem {
-    type SystemData = (
-        Entities<'a>,
-        WriteStorage<'a, Position>,
-        WriteStorage<'a, Velocity>,
-        ReadStorage<'a, Ball>,
-        ReadStorage<'a, Paddle>,
-        Write<'a, Score>,
-    );
-
-    fn run(
-        &mut self,
-        (entities, mut positions, mut velocities, balls, paddles, mut score): Self::SystemData,
-    ) {
-        // Get collision data first to avoid borrowing conflicts
-        let ball_positions: Vec<(specs::Entity, Position)> = (&entities, &positions, &balls)
-            .join()
-            .map(|(entity, pos, _)| (entity, pos.clone()))
-            .collect();
-
-        let paddle_positions: Vec<(specs::Entity, Position)> = (&entities, &positions, &paddles)
-            .join()
-            .map(|(entity, pos, _)| (entity, pos.clone()))
-            .collect();
-
-        // Process collisions
-        let mut scored_this_frame = false;
-        for (ball_entity, ball_pos) in &ball_positions {
-            if scored_this_frame {
-                break; // Skip processing other balls if we've already scored
-            }
-
-            // Check wall collisions
-            if ball_pos.y <= 0.0 || ball_pos.y >= WINDOW_HEIGHT as f32 - BALL_SIZE {
-                if let Some(vel) = velocities.get_mut(*ball_entity) {
-                    vel.y = -vel.y;
-                }
-            }
-
-            // Check paddle collisions
-                        for (_paddle_entity, paddle_pos) in &paddle_positions {
-                if check_paddle_ball_collision(ball_pos, paddle_pos) {
-                    if let Some(vel) = velocities.get_mut(*ball_entity) {
-                        vel.x = -vel.x;
-
-                        // Add minimal spin based on hit position
-                        let paddle_center = paddle_pos.y + PADDLE_HEIGHT / 2.0;
-                        let hit_pos = ball_pos.y + BALL_SIZE / 2.0;
-                        let spin_factor = (hit_pos - paddle_center) / (PADDLE_HEIGHT / 2.0);
-                        vel.y += spin_factor * 50.0; // Minimal spin for better control
-
-                        // Ensure ball doesn't get too fast
-                        let speed = (vel.x * vel.x + vel.y * vel.y).sqrt();
-                        if speed > BALL_SPEED * 1.5 {
-                            vel.x = vel.x / speed * BALL_SPEED * 1.0;
-                            vel.y = vel.y / speed * BALL_SPEED * 1.0;
-                        }
-                    }
-                    break; // Only handle first collision
-                }
-            }
-
-            // Check for scoring
-            if ball_pos.x < -BALL_SIZE {
-                score.ai_score += 1;
-                reset_ball_positions(&mut positions, &mut velocities, &balls);
-                scored_this_frame = true;
-            } else if ball_pos.x > WINDOW_WIDTH as f32 {
-                score.player_score += 1;
-                reset_ball_positions(&mut positions, &mut velocities, &balls);
-                scored_this_frame = true;
-            }
-        }
-    }
-}
-
-fn check_paddle_ball_collision(ball_pos: &Position, paddle_pos: &Position) -> bool {
-    ball_pos.x < paddle_pos.x + PADDLE_WIDTH
-        && ball_pos.x + BALL_SIZE > paddle_pos.x
-        && ball_pos.y < paddle_pos.y + PADDLE_HEIGHT
-        && ball_pos.y + BALL_SIZE > paddle_pos.y
-}
-
-fn reset_ball_positions(
-    positions: &mut WriteStorage<Position>,
-    velocities: &mut WriteStorage<Velocity>,
-    balls: &ReadStorage<Ball>,
-) {
-    for (pos, vel, _) in (positions, velocities, balls).join() {
-        pos.x = WINDOW_WIDTH as f32 / 2.0 - BALL_SIZE / 2.0;
-        pos.y = WINDOW_HEIGHT as f32 / 2.0 - BALL_SIZE / 2.0;
-        // Always start towards player (left) after reset
-        vel.x = -BALL_SPEED;
-        vel.y = (rand::random::<f32>() - 0.5) * BALL_SPEED * 0.8;
-    }
-}
-
-pub struct ImprovedPongGameLogicSystem;
-impl<'a> System<'a> for ImprovedPongGameLogicSystem {
-    type SystemData = (
-        WriteStorage<'a, Position>,
-        WriteStorage<'a, Velocity>,
-        ReadStorage<'a, Ball>,
-        Read<'a, Time>,
-    );
-
-        fn run(&mut self, (_positions, _velocities, _balls, _time): Self::SystemData) {
-        // Simple game logic - ball reset is handled in collision system
-    }
-}
-
-// Helper functions
-fn create_pong_entities(world: &mut World) {
-    // Create player paddle (left side)
-    world
-        .create_entity_with_components()
-        .with(Position::new(
-            50.0,
-            WINDOW_HEIGHT as f32 / 2.0 - PADDLE_HEIGHT / 2.0,
-        ))
-        .with(Velocity::new(0.0, 0.0))
-        .with(Renderable::new("player_paddle".to_string()))
-        .with(Paddle {
-            player_controlled: true,
-        })
-        .with(Collider::new_rectangle(PADDLE_WIDTH, PADDLE_HEIGHT))
-        .build();
-
-    // Create AI paddle (right side)
-    world
-        .create_entity_with_components()
-        .with(Position::new(
-            WINDOW_WIDTH as f32 - 50.0 - PADDLE_WIDTH,
-            WINDOW_HEIGHT as f32 / 2.0 - PADDLE_HEIGHT / 2.0,
-        ))
-        .with(Velocity::new(0.0, 0.0))
-        .with(Renderable::new("ai_paddle".to_string()))
-        .with(Paddle {
-            player_controlled: false,
-        })
-        .with(Collider::new_rectangle(PADDLE_WIDTH, PADDLE_HEIGHT))
-        .build();
-
-    // Create ball (center) - start moving towards player (left)
-    world
-        .create_entity_with_components()
-        .with(Position::new(
-            WINDOW_WIDTH as f32 / 2.0 - BALL_SIZE / 2.0,
-            WINDOW_HEIGHT as f32 / 2.0 - BALL_SIZE / 2.0,
-        ))
-        .with(Velocity::new(
-            -BALL_SPEED,
-            (rand::random::<f32>() - 0.5) * BALL_SPEED * 0.5,
-        ))
-        .with(Renderable::new("ball".to_string()))
-        .with(Ball)
-        .with(Collider::new_circle(BALL_SIZE / 2.0))
-        .build();
-
-    // Create score entity
-    world
-        .create_entity_with_components()
-        .with(Score::default())
-        .build();
-}
-
-fn reset_ball(world: &mut World, speed_multiplier: f32) {
-    let mut positions = world.write_storage::<Position>();
-    let mut velocities = world.write_storage::<Velocity>();
-    let balls = world.read_storage::<Ball>();
-
-    for (pos, vel, _) in (&mut positions, &mut velocities, &balls).join() {
-        pos.x = WINDOW_WIDTH as f32 / 2.0 - BALL_SIZE / 2.0;
-        pos.y = WINDOW_HEIGHT as f32 / 2.0 - BALL_SIZE / 2.0;
-        // Always start towards player (left) after reset
-        vel.x = -BALL_SPEED * speed_multiplier;
-        vel.y = (rand::random::<f32>() - 0.5) * BALL_SPEED * speed_multiplier * 0.8;
-    }
-}
@@ -9,7 +9,7 @@ use enhanced_ai::{AIDifficulty, AISystem};
 use menu::{menu_items, MenuAction, MenuSystem};
 use modular_game_engine::*;
 use particles::ParticleSystem;
-use scoring::{presets as scoring_presets, ScoreType, ScoringSystem};
+use scoring::{presets as scoring_presets, ScoreType};
 use trail_system::{presets as trail_presets, TrailSystem};
 use visual_effects::VisualEffectsSystem;
 
@@ -43,14 +43,14 @@ fn main() {
     let mut particle_system = ParticleSystem::new();
 
     // Create explosion effect
-    let explosion_id = particle_system.create_explosion(Vec2::new(100.0, 100.0), 1.5);
+    let _explosion_id = particle_system.create_explosion(Vec2::new(100.0, 100.0), 1.5);
     println!(
         "  Created explosion with {} particles",
         particle_system.total_particle_count()
     );
 
     // Create spark effect
-    let spark_id = particle_system.create_sparks(Vec2::new(200.0, 200.0), Vec2::new(0.0, -1.0));
+    let _spark_id = particle_system.create_sparks(Vec2::new(200.0, 200.0), Vec2::new(0.0, -1.0));
     println!("  Created spark effect");
 
     // Update particles
@@ -90,12 +90,12 @@ fn main() {
     println!("  Added screen shake effect");
 
     // Add color transition
-    let transition_id =
+    let _transition_id =
         visual_system.add_color_transition(visual_effects::effects::warning_flash());
     println!("  Added warning flash transition");
 
     // Add UI pulse effect
-    let pulse_id = visual_system.create_ui_pulse(1.0);
+    let _pulse_id = visual_system.create_ui_pulse(1.0);
     println!("  Added UI pulse effect");
 
     // 5. ENHANCED AI SYSTEM - Smart AI behaviors
@@ -194,10 +194,10 @@ fn main() {
     // Create all systems
     let mut game_difficulty = DifficultySystem::with_pong_defaults();
     let mut game_particles = ParticleSystem::new();
-    let mut game_menu = MenuSystem::create_difficulty_menu();
+    let game_menu = MenuSystem::create_difficulty_menu();
     let mut game_visuals = VisualEffectsSystem::new();
     let mut game_ai = AISystem::new();
-    let mut game_scoring = scoring_presets::pong_scoring(5);
+    let _game_scoring = scoring_presets::pong_scoring(5);
     let mut game_trails = TrailSystem::new();
 
     // Configure for a specific game mode
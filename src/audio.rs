@@ -3,6 +3,7 @@
 //! Sound and music playback with spatial audio.
 
 use specs::{Component, VecStorage};
+use rodio::source::ChannelVolume;
 use rodio::{OutputStream, OutputStreamHandle, Sink, Source};
 use std::collections::HashMap;
 use std::fs::File;
@@ -17,13 +18,143 @@ pub struct AudioSource {
     pub loop_sound: bool,
 }
 
-/// Audio manager
+/// Left/right gains for `pan` in `[-1, 1]` (left to right) using an
+/// equal-power law, so loudness stays constant across the stereo field
+pub fn pan_gains(pan: f32) -> (f32, f32) {
+    let pan = if pan.is_nan() {
+        0.0
+    } else {
+        pan.clamp(-1.0, 1.0)
+    };
+    let angle = (pan + 1.0) * std::f32::consts::FRAC_PI_4;
+    (angle.cos(), angle.sin())
+}
+
+/// Pan value for a world x coordinate on a screen `width` pixels wide
+pub fn pan_for_x(x: f32, width: f32) -> f32 {
+    if width <= 0.0 {
+        return 0.0;
+    }
+    (x / width * 2.0 - 1.0).clamp(-1.0, 1.0)
+}
+
+/// Linear volume ramp towards a target, clamped to `[0, 1]`
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct VolumeRamp {
+    pub volume: f32,
+    target: f32,
+    /// Volume change per second
+    speed: f32,
+}
+
+impl VolumeRamp {
+    pub fn new(volume: f32) -> Self {
+        let volume = volume.clamp(0.0, 1.0);
+        Self {
+            volume,
+            target: volume,
+            speed: 0.0,
+        }
+    }
+
+    /// Ramp from the current volume to `target` over `duration` seconds
+    /// (immediately when `duration` is not positive)
+    pub fn ramp_to(&mut self, target: f32, duration: f32) {
+        self.target = target.clamp(0.0, 1.0);
+        if duration > 0.0 {
+            self.speed = (self.target - self.volume).abs() / duration;
+        } else {
+            self.volume = self.target;
+            self.speed = 0.0;
+        }
+    }
+
+    /// Advance the ramp and return the new volume
+    pub fn update(&mut self, dt: f32) -> f32 {
+        let step = self.speed * dt;
+        if (self.target - self.volume).abs() <= step {
+            self.volume = self.target;
+        } else {
+            self.volume += step.copysign(self.target - self.volume);
+        }
+        self.volume
+    }
+
+    pub fn target(&self) -> f32 {
+        self.target
+    }
+
+    pub fn is_done(&self) -> bool {
+        self.volume == self.target
+    }
+}
+
+/// Ordered list of music tracks, advanced when the current one finishes
+#[derive(Debug, Clone, PartialEq)]
+pub struct Playlist {
+    pub tracks: Vec<String>,
+    /// Start over after the last track
+    pub looping: bool,
+    /// Crossfade duration between tracks, in seconds
+    pub crossfade: f32,
+    current: usize,
+}
+
+impl Playlist {
+    pub fn new(tracks: Vec<String>) -> Self {
+        Self {
+            tracks,
+            looping: true,
+            crossfade: 1.0,
+            current: 0,
+        }
+    }
+
+    pub fn with_looping(mut self, looping: bool) -> Self {
+        self.looping = looping;
+        self
+    }
+
+    pub fn with_crossfade(mut self, crossfade: f32) -> Self {
+        self.crossfade = crossfade;
+        self
+    }
+
+    pub fn current(&self) -> Option<&str> {
+        self.tracks.get(self.current).map(String::as_str)
+    }
+
+    /// Move to the following track, wrapping if looping
+    pub fn advance(&mut self) -> Option<&str> {
+        if self.current + 1 < self.tracks.len() {
+            self.current += 1;
+        } else if self.looping && !self.tracks.is_empty() {
+            self.current = 0;
+        } else {
+            self.current = self.tracks.len();
+        }
+        self.current()
+    }
+}
+
+/// A playing (or fading out) music track
+struct MusicTrack {
+    name: String,
+    ramp: VolumeRamp,
+    /// `None` on the silent backend
+    sink: Option<Sink>,
+}
+
+/// Audio manager. Without an output device it runs as a silent no-op backend.
 pub struct AudioManager {
-    _stream: OutputStream,
-    stream_handle: OutputStreamHandle,
+    _stream: Option<OutputStream>,
+    stream_handle: Option<OutputStreamHandle>,
         #[allow(dead_code)]
     sinks: HashMap<String, Sink>,
     master_volume: f32,
+    /// Current music track last, older ones fading out before it
+    music: Vec<MusicTrack>,
+    playlist: Option<Playlist>,
 }
 
 impl Default for AudioManager {
@@ -33,21 +164,46 @@ impl Default for AudioManager {
 }
 
 impl AudioManager {
+    /// Open the default output device, falling back to silence if there is none
     pub fn new() -> Self {
-        let (_stream, stream_handle) = OutputStream::try_default().unwrap();
+        match OutputStream::try_default() {
+            Ok((stream, stream_handle)) => Self {
+                _stream: Some(stream),
+                stream_handle: Some(stream_handle),
+                sinks: HashMap::new(),
+                master_volume: 1.0,
+                music: Vec::new(),
+                playlist: None,
+            },
+            Err(_) => Self::new_silent(),
+        }
+    }
+
+    /// Manager that decodes sounds but never outputs them (tests, headless runs)
+    pub fn new_silent() -> Self {
         Self {
-            _stream,
-            stream_handle,
+            _stream: None,
+            stream_handle: None,
             sinks: HashMap::new(),
             master_volume: 1.0,
+            music: Vec::new(),
+            playlist: None,
         }
     }
 
+    /// Whether this manager has no output device
+    pub fn is_silent(&self) -> bool {
+        self.stream_handle.is_none()
+    }
+
         pub fn load_sound(&self, _id: &str, path: &str) -> Result<(), String> {
         let file = BufReader::new(File::open(path).map_err(|e| e.to_string())?);
         let source = rodio::Decoder::new(file).map_err(|e| e.to_string())?;
+        let Some(stream_handle) = &self.stream_handle else {
+            return Ok(());
+        };
 
-        let sink = Sink::try_new(&self.stream_handle).map_err(|e| e.to_string())?;
+        let sink = Sink::try_new(stream_handle).map_err(|e| e.to_string())?;
         sink.append(source);
         sink.pause(); // Pause initially, play on demand
 
@@ -69,15 +225,193 @@ impl AudioManager {
     pub fn play_sound(&self, path: &str) -> Result<(), String> {
         let file = BufReader::new(File::open(path).map_err(|e| e.to_string())?);
         let source = rodio::Decoder::new(file).map_err(|e| e.to_string())?;
+        let Some(stream_handle) = &self.stream_handle else {
+            return Ok(());
+        };
 
-        let sink = Sink::try_new(&self.stream_handle).map_err(|e| e.to_string())?;
+        let sink = Sink::try_new(stream_handle).map_err(|e| e.to_string())?;
         sink.append(source.amplify(self.master_volume));
         sink.play();
         sink.detach(); // Detach to play in background
         Ok(())
     }
 
+    /// Play a sound mixed down to mono and placed in the stereo field by
+    /// `pan` (-1 left, 0 center, +1 right; clamped). See `pan_gains`.
+    pub fn play_panned(&self, path: &str, pan: f32) -> Result<(), String> {
+        let file = BufReader::new(File::open(path).map_err(|e| e.to_string())?);
+        let source = rodio::Decoder::new(file).map_err(|e| e.to_string())?;
+        let Some(stream_handle) = &self.stream_handle else {
+            return Ok(());
+        };
+
+        let (left, right) = pan_gains(pan);
+        let panned = ChannelVolume::new(source, vec![left, right]);
+        let sink = Sink::try_new(stream_handle).map_err(|e| e.to_string())?;
+        sink.append(panned.amplify(self.master_volume));
+        sink.play();
+        sink.detach();
+        Ok(())
+    }
+
     pub fn set_master_volume(&mut self, volume: f32) {
         self.master_volume = volume;
     }
+
+    /// Start a music track, crossfading: the current track fades out while the
+    /// new one fades in, both linearly over `fade_in` seconds. Call `update` every frame.
+    pub fn play_music(&mut self, path: &str, fade_in: f32) -> Result<(), String> {
+        let file = BufReader::new(File::open(path).map_err(|e| e.to_string())?);
+        let source = rodio::Decoder::new(file).map_err(|e| e.to_string())?;
+        let sink = match &self.stream_handle {
+            Some(stream_handle) => {
+                let sink = Sink::try_new(stream_handle).map_err(|e| e.to_string())?;
+                sink.set_volume(0.0);
+                sink.append(source);
+                Some(sink)
+            }
+            None => None,
+        };
+        self.start_music(path, sink, fade_in);
+        Ok(())
+    }
+
+    fn start_music(&mut self, name: &str, sink: Option<Sink>, fade: f32) {
+        for track in &mut self.music {
+            track.ramp.ramp_to(0.0, fade);
+        }
+        let mut ramp = VolumeRamp::new(0.0);
+        ramp.ramp_to(1.0, fade);
+        self.music.push(MusicTrack {
+            name: name.to_string(),
+            ramp,
+            sink,
+        });
+        self.apply_music_volumes();
+    }
+
+    /// Fade out all music over `fade_out` seconds
+    pub fn stop_music(&mut self, fade_out: f32) {
+        self.playlist = None;
+        for track in &mut self.music {
+            track.ramp.ramp_to(0.0, fade_out);
+        }
+    }
+
+    /// Play `playlist` from its current track, advancing when tracks finish
+    pub fn set_playlist(&mut self, playlist: Playlist) -> Result<(), String> {
+        let first = playlist.current().map(str::to_string);
+        let crossfade = playlist.crossfade;
+        self.playlist = Some(playlist);
+        match first {
+            Some(track) => self.play_music(&track, crossfade),
+            None => Ok(()),
+        }
+    }
+
+    pub fn playlist(&self) -> Option<&Playlist> {
+        self.playlist.as_ref()
+    }
+
+    /// Name and volume of each music track, the current one last
+    pub fn music_volumes(&self) -> Vec<(&str, f32)> {
+        self.music
+            .iter()
+            .map(|track| (track.name.as_str(), track.ramp.volume))
+            .collect()
+    }
+
+    /// Advance fades, drop silent outgoing tracks and move the playlist on
+    pub fn update(&mut self, dt: f32) -> Result<(), String> {
+        for track in &mut self.music {
+            track.ramp.update(dt);
+        }
+        let last = self.music.len().saturating_sub(1);
+        let mut index = 0;
+        self.music.retain(|track| {
+            let keep = index == last || track.ramp.volume > 0.0 || track.ramp.target() > 0.0;
+            index += 1;
+            keep
+        });
+        self.apply_music_volumes();
+
+        let finished = self
+            .music
+            .last()
+            .and_then(|track| track.sink.as_ref())
+            .is_some_and(|sink| sink.empty());
+        if finished {
+            if let Some(playlist) = &mut self.playlist {
+                let crossfade = playlist.crossfade;
+                if let Some(next) = playlist.advance().map(str::to_string) {
+                    return self.play_music(&next, crossfade);
+                }
+            }
+        }
+        Ok(())
+    }
+
+    fn apply_music_volumes(&self) {
+        for track in &self.music {
+            if let Some(sink) = &track.sink {
+                sink.set_volume(track.ramp.volume * self.master_volume);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_equal_power_pan_gains() {
+        let close = |(l, r): (f32, f32), (el, er): (f32, f32)| {
+            (l - el).abs() < 1e-3 && (r - er).abs() < 1e-3
+        };
+        assert!(close(pan_gains(-1.0), (1.0, 0.0)));
+        assert!(close(pan_gains(0.0), (0.707, 0.707)));
+        assert!(close(pan_gains(1.0), (0.0, 1.0)));
+        assert!(close(pan_gains(5.0), (0.0, 1.0)));
+
+        assert_eq!(pan_for_x(0.0, 800.0), -1.0);
+        assert_eq!(pan_for_x(400.0, 800.0), 0.0);
+    }
+
+    #[test]
+    fn test_crossfade_midpoint_is_half_gain() {
+        let mut audio = AudioManager::new_silent();
+        audio.start_music("menu", None, 0.0);
+        assert_eq!(audio.music_volumes(), vec![("menu", 1.0)]);
+
+        audio.start_music("gameplay", None, 1.0);
+        audio.update(0.5).unwrap();
+        let volumes = audio.music_volumes();
+        assert_eq!(volumes.len(), 2);
+        assert!((volumes[0].1 - 0.5).abs() < 1e-4);
+        assert!((volumes[1].1 - 0.5).abs() < 1e-4);
+
+        // the outgoing track is dropped once silent
+        audio.update(0.6).unwrap();
+        assert_eq!(audio.music_volumes(), vec![("gameplay", 1.0)]);
+    }
+
+    #[test]
+    fn test_playlist_advances_and_loops() {
+        let mut playlist = Playlist::new(vec!["a".to_string(), "b".to_string()]);
+        assert_eq!(playlist.current(), Some("a"));
+        assert_eq!(playlist.advance(), Some("b"));
+        assert_eq!(playlist.advance(), Some("a"));
+
+        let mut once = playlist.clone().with_looping(false);
+        once.advance();
+        assert_eq!(once.advance(), None);
+    }
+
+    #[test]
+    fn test_silent_backend_still_reports_missing_files() {
+        let audio = AudioManager::new_silent();
+        assert!(audio.is_silent());
+        assert!(audio.play_panned("does/not/exist.wav", 0.5).is_err());
+    }
 }
@@ -3,16 +3,20 @@
 //! This module defines all the core components used in the game.
 
 use crate::Vec2;
-use specs::{Component, DenseVecStorage, VecStorage};
+use specs::{Component, DenseVecStorage, Entity, FlaggedStorage, VecStorage};
 
-/// Position component for 2D positioning
-#[derive(Component, Debug, Clone, Copy)]
-#[storage(VecStorage)]
+/// Position component for 2D positioning. Flagged, so changes can be read
+/// with `GameWorldExt::changed_entities`.
+#[derive(Debug, Clone, Copy)]
 pub struct Position {
     pub x: f32,
     pub y: f32,
 }
 
+impl Component for Position {
+    type Storage = FlaggedStorage<Self, VecStorage<Self>>;
+}
+
 impl Position {
     pub fn new(x: f32, y: f32) -> Self {
         Self { x, y }
@@ -120,14 +124,18 @@ pub enum EnemyType {
     Boss,
 }
 
-/// Health component for damageable entities
-#[derive(Component, Debug, Clone, Copy)]
-#[storage(VecStorage)]
+/// Health component for damageable entities. Flagged, so UI can react to
+/// damage through `GameWorldExt::changed_entities`.
+#[derive(Debug, Clone, Copy)]
 pub struct Health {
     pub current: f32,
     pub maximum: f32,
 }
 
+impl Component for Health {
+    type Storage = FlaggedStorage<Self, VecStorage<Self>>;
+}
+
 impl Health {
     pub fn new(maximum: f32) -> Self {
         Self {
@@ -171,6 +179,58 @@ impl Collider {
             is_trigger: false,
         }
     }
+
+    /// Make this a trigger: overlaps are reported but never resolved
+    pub fn as_trigger(mut self) -> Self {
+        self.is_trigger = true;
+        self
+    }
+
+    /// Whether this collider at `position` overlaps `other` at `other_position`.
+    /// Colliders are centered on their positions; touching edges count as overlap,
+    /// so a zero-size rectangle works as a point probe.
+    pub fn intersects(&self, position: Vec2, other: &Collider, other_position: Vec2) -> bool {
+        match (&self.shape, &other.shape) {
+            (CollisionShape::Circle { radius: r1 }, CollisionShape::Circle { radius: r2 }) => {
+                (position - other_position).norm_squared() <= (r1 + r2) * (r1 + r2)
+            }
+            (
+                CollisionShape::Rectangle {
+                    width: w1,
+                    height: h1,
+                },
+                CollisionShape::Rectangle {
+                    width: w2,
+                    height: h2,
+                },
+            ) => {
+                let d = position - other_position;
+                d.x.abs() <= (w1 + w2) / 2.0 && d.y.abs() <= (h1 + h2) / 2.0
+            }
+            (CollisionShape::Circle { radius }, CollisionShape::Rectangle { width, height }) => {
+                circle_rect_overlap(position, *radius, other_position, *width, *height)
+            }
+            (CollisionShape::Rectangle { width, height }, CollisionShape::Circle { radius }) => {
+                circle_rect_overlap(other_position, *radius, position, *width, *height)
+            }
+        }
+    }
+}
+
+fn circle_rect_overlap(
+    center: Vec2,
+    radius: f32,
+    rect_center: Vec2,
+    width: f32,
+    height: f32,
+) -> bool {
+    let half = Vec2::new(width / 2.0, height / 2.0);
+    let offset = center - rect_center;
+    let closest = Vec2::new(
+        offset.x.clamp(-half.x, half.x),
+        offset.y.clamp(-half.y, half.y),
+    );
+    (offset - closest).norm_squared() <= radius * radius
 }
 
 /// Collision shapes
@@ -180,6 +240,11 @@ pub enum CollisionShape {
     Rectangle { width: f32, height: f32 },
 }
 
+/// Draw/pick order; higher values are on top. Entities without one count as 0.
+#[derive(Component, Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord)]
+#[storage(VecStorage)]
+pub struct ZOrder(pub i32);
+
 /// Camera component for rendering
 #[derive(Component, Debug, Clone)]
 #[storage(DenseVecStorage)]
@@ -204,6 +269,73 @@ impl Camera {
 #[storage(DenseVecStorage)]
 pub struct MarkedForRemoval;
 
+/// Marker component for pooled entities that are currently parked; systems skip them
+#[derive(Component, Debug, Clone, Default)]
+#[storage(DenseVecStorage)]
+pub struct Inactive;
+
+/// Put on a `Camera2D` entity to have `CameraFollowSystem` track another entity
+#[derive(Component, Debug, Clone, Copy, PartialEq, Eq)]
+#[storage(DenseVecStorage)]
+pub struct CameraTarget(pub Entity);
+
+/// Side an entity fights for; how teams treat each other is set by `TeamRelations`
+#[derive(Component, Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[storage(DenseVecStorage)]
+pub struct Team(pub u8);
+
+/// Damage dealt to a hostile entity's `Health` on each solid collision
+#[derive(Component, Debug, Clone, Copy, PartialEq)]
+#[storage(DenseVecStorage)]
+pub struct ContactDamage(pub f32);
+
+/// Marks the entity for removal once `remaining` seconds have passed
+#[derive(Component, Debug, Clone, Copy, PartialEq)]
+#[storage(VecStorage)]
+pub struct Lifetime {
+    pub remaining: f32,
+}
+
+impl Lifetime {
+    pub fn new(seconds: f32) -> Self {
+        Self { remaining: seconds }
+    }
+}
+
+/// Ability cooldown (dash, shoot) counted down by `CooldownSystem`.
+/// Check `is_ready` before using the ability and `trigger` it when used.
+#[derive(Component, Debug, Clone, Copy, PartialEq)]
+#[storage(DenseVecStorage)]
+pub struct Cooldown {
+    /// Seconds until the ability can be used again
+    pub remaining: f32,
+    /// Seconds the ability is unavailable after each use
+    pub duration: f32,
+}
+
+impl Cooldown {
+    /// A cooldown of `duration` seconds that starts out ready
+    pub fn new(duration: f32) -> Self {
+        Self {
+            remaining: 0.0,
+            duration,
+        }
+    }
+
+    pub fn is_ready(&self) -> bool {
+        self.remaining <= 0.0
+    }
+
+    /// Start the cooldown after using the ability
+    pub fn trigger(&mut self) {
+        self.remaining = self.duration;
+    }
+
+    pub fn update(&mut self, delta_time: f32) {
+        self.remaining = (self.remaining - delta_time).max(0.0);
+    }
+}
+
 /// Animation component for animated sprites
 #[derive(Component, Debug, Clone)]
 #[storage(VecStorage)]
@@ -267,3 +399,45 @@ pub struct Paddle {
 #[derive(Component, Debug, Clone, Default)]
 #[storage(DenseVecStorage)]
 pub struct Ball;
+
+/// Brick marker for Breakout-style games
+#[derive(Component, Debug, Clone, Default)]
+#[storage(DenseVecStorage)]
+pub struct Brick;
+
+/// Keeps an entity inside the `WorldBounds` resource, stopping it at the edge
+#[derive(Component, Debug, Clone, Default)]
+#[storage(DenseVecStorage)]
+pub struct ClampToBounds;
+
+/// Reflects an entity's velocity off the `WorldBounds` edges, like a ball off walls
+#[derive(Component, Debug, Clone, Default)]
+#[storage(DenseVecStorage)]
+pub struct BounceOnBounds;
+
+/// Kinds of power-up a brick can drop
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PowerUpType {
+    ExtraLife,
+    MultiBall,
+    LargerPaddle,
+    SmallerPaddle,
+}
+
+/// Falling power-up collected by touching a paddle
+#[derive(Component, Debug, Clone)]
+#[storage(DenseVecStorage)]
+pub struct PowerUp {
+    pub power_type: PowerUpType,
+}
+
+/// Temporary paddle width change that reverts once `remaining` runs out
+#[derive(Component, Debug, Clone)]
+#[storage(DenseVecStorage)]
+pub struct TimedEffect {
+    pub power_type: PowerUpType,
+    /// Seconds left before the effect is reverted
+    pub remaining: f32,
+    /// Collider width before the effect was applied
+    pub base_width: f32,
+}
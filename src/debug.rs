@@ -0,0 +1,45 @@
+//! Debug visualization helpers.
+
+use crate::renderer_2d::Color;
+use specs::Entity;
+
+/// Hue in degrees (`0.0..360.0`) derived from an entity id. The id is run
+/// through an integer hash so neighbouring ids land far apart on the wheel.
+pub fn debug_hue(id: u32) -> f32 {
+    // lowbias32 integer hash
+    let mut h = id;
+    h ^= h >> 16;
+    h = h.wrapping_mul(0x7feb_352d);
+    h ^= h >> 15;
+    h = h.wrapping_mul(0x846c_a68b);
+    h ^= h >> 16;
+    (h >> 8) as f32 / (1 << 24) as f32 * 360.0
+}
+
+/// Stable, distinct color for drawing an entity's debug shapes. Depends only
+/// on the entity's id, so it stays the same every frame.
+pub fn entity_debug_color(entity: Entity) -> Color {
+    Color::from_hsv(debug_hue(entity.id()), 0.75, 1.0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use specs::{Builder, WorldExt};
+
+    #[test]
+    fn test_entity_debug_color_is_stable_and_distinct() {
+        let mut world = specs::World::new();
+        let a = world.create_entity().build();
+        let b = world.create_entity().build();
+
+        assert_eq!(entity_debug_color(a), entity_debug_color(a));
+        assert_ne!(entity_debug_color(a), entity_debug_color(b));
+        assert!((debug_hue(a.id()) - debug_hue(b.id())).abs() > 30.0);
+
+        let hue = debug_hue(1234);
+        assert!((0.0..360.0).contains(&hue));
+        assert_eq!(Color::from_hsv(240.0, 1.0, 1.0), Color::BLUE);
+        assert_eq!(Color::from_hsv(-240.0, 1.0, 1.0), Color::GREEN);
+    }
+}
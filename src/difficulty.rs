@@ -6,7 +6,9 @@
 use std::collections::HashMap;
 
 /// Represents different difficulty levels
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+#[derive(
+    Debug, Clone, Copy, PartialEq, Eq, Hash, Default, serde::Serialize, serde::Deserialize,
+)]
 pub enum DifficultyLevel {
     VeryEasy,
     Easy,
@@ -471,7 +473,7 @@ mod tests {
 
         assert_eq!(system.get_float("test_float"), 2.5);
         assert_eq!(system.get_int("test_int"), 42);
-        assert_eq!(system.get_bool("test_bool"), true);
+        assert!(system.get_bool("test_bool"));
     }
 
     #[test]
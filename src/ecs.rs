@@ -2,13 +2,160 @@
 //!
 //! This module provides the core ECS functionality using the Specs crate.
 
-use specs::{Builder, Component, Entity, World, WorldExt};
+use crate::components::{
+    Ball, Brick, Collider, Health, Inactive, Paddle, Position, Velocity, ZOrder,
+};
+use crate::physics::Mass;
+use crate::resources::EntityBudget;
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+use serde_json::Value;
+use specs::hibitset::BitSet;
+use specs::storage::ComponentEvent;
+use specs::{
+    Builder, Component, Entities, Entity, Join, ReadStorage, ReaderId, Tracked, World, WorldExt,
+};
+use std::marker::PhantomData;
+use std::sync::Arc;
 
-/// Core time resource
-#[derive(Debug, Clone, Default)]
+/// Core time resource.
+///
+/// Gameplay (physics, particles, trails, timers, animations) should read the
+/// scaled `delta`, so `set_paused` (or a `time_scale` of 0) freezes everything
+/// at once. Pausing is separate from `time_scale`, so a slow-motion scale
+/// survives a pause. Menus and other UI that must keep animating read
+/// `unscaled_delta`.
+///
+/// `delta` is also capped at `max_delta`, so a long stall (debugger pause,
+/// window drag) can't fling or tunnel anything in a single step.
+#[derive(Debug, Clone)]
 pub struct Time {
+    /// Frame time, capped at `max_delta` and multiplied by `time_scale`
     pub delta: f32,
+    /// Real frame time, unaffected by `time_scale`
+    pub unscaled_delta: f32,
+    /// Scaled game time since start
     pub elapsed: f32,
+    /// Multiplier applied to `delta`; 0 pauses, 1 is real time
+    pub time_scale: f32,
+    /// Freezes `delta` on top of `time_scale`; see `set_paused`
+    pub paused: bool,
+    /// Number of frames advanced so far
+    pub frame_count: u64,
+    /// Smoothed frames per second
+    pub fps: f32,
+    /// Longest real frame time gameplay will see in one step
+    pub max_delta: f32,
+}
+
+impl Default for Time {
+    fn default() -> Self {
+        Self {
+            delta: 0.0,
+            unscaled_delta: 0.0,
+            elapsed: 0.0,
+            time_scale: 1.0,
+            paused: false,
+            frame_count: 0,
+            fps: 0.0,
+            max_delta: 0.1,
+        }
+    }
+}
+
+impl Time {
+    /// Advance by one frame of `delta` real seconds, updating the scaled delta,
+    /// elapsed time, frame count and FPS
+    pub fn advance(&mut self, delta: f32) {
+        self.unscaled_delta = delta;
+        self.delta = delta.min(self.max_delta) * self.effective_scale();
+        self.elapsed += self.delta;
+        self.frame_count += 1;
+
+        if delta > 0.0 {
+            let instant_fps = 1.0 / delta;
+            // exponential smoothing keeps the readout stable
+            self.fps = if self.fps > 0.0 {
+                self.fps * 0.9 + instant_fps * 0.1
+            } else {
+                instant_fps
+            };
+        }
+    }
+}
+
+impl Time {
+    /// Whether gameplay time is frozen
+    pub fn is_paused(&self) -> bool {
+        self.paused || self.time_scale == 0.0
+    }
+
+    /// Freeze or resume gameplay time, leaving `time_scale` untouched
+    pub fn set_paused(&mut self, paused: bool) {
+        self.paused = paused;
+    }
+
+    /// The multiplier `advance` applies: 0 while paused, else `time_scale`
+    pub fn effective_scale(&self) -> f32 {
+        if self.paused {
+            0.0
+        } else {
+            self.time_scale
+        }
+    }
+
+    /// Cap the real frame time fed to gameplay each step
+    pub fn set_max_delta(&mut self, max_delta: f32) {
+        self.max_delta = max_delta.max(0.0);
+    }
+}
+
+/// Seeded random number generator resource.
+///
+/// Gameplay randomness drawn from here is reproducible for a given seed,
+/// unlike `rand::random`.
+#[derive(Debug, Clone)]
+pub struct RngResource {
+    seed: u64,
+    rng: StdRng,
+}
+
+impl Default for RngResource {
+    fn default() -> Self {
+        Self::new(0)
+    }
+}
+
+impl RngResource {
+    pub fn new(seed: u64) -> Self {
+        Self {
+            seed,
+            rng: StdRng::seed_from_u64(seed),
+        }
+    }
+
+    /// Seed this generator was created or last reseeded with
+    pub fn seed(&self) -> u64 {
+        self.seed
+    }
+
+    /// Restart the sequence from `seed`
+    pub fn reseed(&mut self, seed: u64) {
+        *self = Self::new(seed);
+    }
+
+    /// Uniform value in `[0, 1)`
+    pub fn next_f32(&mut self) -> f32 {
+        self.rng.gen()
+    }
+
+    /// Uniform value in `[min, max)`; returns `min` for an empty range
+    pub fn range(&mut self, min: f32, max: f32) -> f32 {
+        if max <= min {
+            return min;
+        }
+        self.rng.gen_range(min..max)
+    }
 }
 
 /// Input state resource
@@ -22,6 +169,39 @@ pub struct InputState {
 /// ECS World extension methods
 pub trait GameWorldExt {
     fn create_entity_with_components(&mut self) -> EntityBuilder<'_>;
+
+    /// Start recording changes to the flagged component `C`
+    fn track_changes<C>(&mut self)
+    where
+        C: Component + Send + Sync,
+        C::Storage: Tracked;
+
+    /// Spawn a new entity with a copy of every component in `prefab`
+    fn spawn_prefab(&mut self, prefab: &Prefab) -> Entity;
+
+    /// Spawn from `prefab`, overriding its position
+    fn spawn_prefab_at(&mut self, prefab: &Prefab, position: Position) -> Entity;
+
+    /// Entities whose `C` was inserted or modified since the previous call.
+    /// The first call starts tracking and returns nothing.
+    fn changed_entities<C>(&mut self) -> Vec<Entity>
+    where
+        C: Component + Send + Sync,
+        C::Storage: Tracked;
+
+    /// Apply everything queued in the `CommandBuffer` resource, in order,
+    /// then `maintain` so despawns take effect. Call this instead of
+    /// `maintain` after dispatching.
+    fn apply_commands(&mut self);
+
+    /// Delete every live entity except those in `keep` (e.g. a persistent
+    /// camera), for resetting between levels. Resources are left untouched.
+    fn clear_all_except(&mut self, keep: &[Entity]);
+
+    /// Delete every live entity; resources are left untouched
+    fn clear_all(&mut self) {
+        self.clear_all_except(&[]);
+    }
 }
 
 impl GameWorldExt for World {
@@ -32,6 +212,329 @@ impl GameWorldExt for World {
             entity,
         }
     }
+
+    fn spawn_prefab(&mut self, prefab: &Prefab) -> Entity {
+        let entity = self.create_entity().build();
+        for (_, insert) in &prefab.components {
+            insert(self, entity);
+        }
+        entity
+    }
+
+    fn spawn_prefab_at(&mut self, prefab: &Prefab, position: Position) -> Entity {
+        let entity = self.spawn_prefab(prefab);
+        self.write_storage::<Position>()
+            .insert(entity, position)
+            .unwrap();
+        entity
+    }
+
+    fn track_changes<C>(&mut self)
+    where
+        C: Component + Send + Sync,
+        C::Storage: Tracked,
+    {
+        if !self.has_value::<ChangeTracker<C>>() {
+            let tracker = ChangeTracker::<C>::new(self);
+            self.insert(tracker);
+        }
+    }
+
+    fn changed_entities<C>(&mut self) -> Vec<Entity>
+    where
+        C: Component + Send + Sync,
+        C::Storage: Tracked,
+    {
+        if !self.has_value::<ChangeTracker<C>>() {
+            self.track_changes::<C>();
+            return Vec::new();
+        }
+        let mut tracker = self.write_resource::<ChangeTracker<C>>();
+        tracker.changed(&self.entities(), &self.read_storage::<C>())
+    }
+
+    fn apply_commands(&mut self) {
+        let commands = match self.try_fetch_mut::<CommandBuffer>() {
+            Some(mut buffer) => std::mem::take(&mut buffer.commands),
+            None => Vec::new(),
+        };
+        for command in commands {
+            command(self);
+        }
+        self.maintain();
+        if self.has_value::<EntityBudget>() {
+            let live = self.entities().join().count();
+            self.write_resource::<EntityBudget>().set_live(live);
+        }
+    }
+
+    fn clear_all_except(&mut self, keep: &[Entity]) {
+        {
+            let entities = self.entities();
+            for entity in (&entities).join().filter(|e| !keep.contains(e)) {
+                let _ = entities.delete(entity);
+            }
+        }
+        self.maintain();
+    }
+}
+
+/// Delete every entity that has a `C` component and return how many were removed
+pub fn clear_entities_with<C: Component>(world: &mut World) -> usize {
+    let doomed: Vec<Entity> = (&world.entities(), &world.read_storage::<C>())
+        .join()
+        .map(|(entity, _)| entity)
+        .collect();
+    world.delete_entities(&doomed).ok();
+    world.maintain();
+    doomed.len()
+}
+
+type WorldCommand = Box<dyn FnOnce(&mut World) + Send + Sync>;
+
+/// Structural changes queued by systems while they iterate, applied together
+/// by `GameWorldExt::apply_commands` once dispatch is over.
+///
+/// Systems take it as `Commands<'a>` in their `SystemData`, so despawning
+/// or spawning never touches the entity sets being joined.
+#[derive(Default)]
+pub struct CommandBuffer {
+    commands: Vec<WorldCommand>,
+}
+
+/// `SystemData` handle for queuing commands
+pub type Commands<'a> = specs::Write<'a, CommandBuffer>;
+
+impl CommandBuffer {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Queue an arbitrary change to the world
+    pub fn push<F>(&mut self, command: F)
+    where
+        F: FnOnce(&mut World) + Send + Sync + 'static,
+    {
+        self.commands.push(Box::new(command));
+    }
+
+    /// Queue spawning an entity from `prefab`
+    pub fn spawn(&mut self, prefab: Prefab) {
+        self.push(move |world| {
+            world.spawn_prefab(&prefab);
+        });
+    }
+
+    /// Queue spawning from `prefab` if `budget` has room; over the cap the
+    /// spawn is dropped and an error returned when the budget refuses spawns
+    pub fn try_spawn(&mut self, prefab: Prefab, budget: &mut EntityBudget) -> Result<(), String> {
+        budget.reserve()?;
+        self.spawn(prefab);
+        Ok(())
+    }
+
+    /// Queue deleting `entity`; already-dead entities are ignored
+    pub fn despawn(&mut self, entity: Entity) {
+        self.push(move |world| {
+            let _ = world.delete_entity(entity);
+        });
+    }
+
+    /// Queue adding (or replacing) a component on `entity`
+    pub fn insert_component<C: Component + Send + Sync>(&mut self, entity: Entity, component: C) {
+        self.push(move |world| {
+            let _ = world.write_storage::<C>().insert(entity, component);
+        });
+    }
+
+    /// Queue removing a component from `entity`
+    pub fn remove_component<C: Component + Send + Sync>(&mut self, entity: Entity) {
+        self.push(move |world| {
+            world.write_storage::<C>().remove(entity);
+        });
+    }
+
+    /// Number of queued commands
+    pub fn len(&self) -> usize {
+        self.commands.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.commands.is_empty()
+    }
+}
+
+impl std::fmt::Debug for CommandBuffer {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("CommandBuffer")
+            .field("len", &self.commands.len())
+            .finish()
+    }
+}
+
+/// Reads insert/modify events from a component stored in a `FlaggedStorage`.
+///
+/// Mutable joins flag every visited component as modified, whether or not it
+/// was written. A tracker must be read regularly, since unread events are kept.
+pub struct ChangeTracker<C: Component> {
+    reader: ReaderId<ComponentEvent>,
+    _marker: PhantomData<fn() -> C>,
+}
+
+impl<C> ChangeTracker<C>
+where
+    C: Component + Send + Sync,
+    C::Storage: Tracked,
+{
+    pub fn new(world: &World) -> Self {
+        Self {
+            reader: world.write_storage::<C>().register_reader(),
+            _marker: PhantomData,
+        }
+    }
+
+    /// Entities whose component changed since the last call, in id order.
+    /// Entities that lost the component in the meantime are left out.
+    pub fn changed(&mut self, entities: &Entities, storage: &ReadStorage<C>) -> Vec<Entity> {
+        let mut dirty = BitSet::new();
+        for event in storage.channel().read(&mut self.reader) {
+            match event {
+                ComponentEvent::Inserted(id) | ComponentEvent::Modified(id) => {
+                    dirty.add(*id);
+                }
+                ComponentEvent::Removed(id) => {
+                    dirty.remove(*id);
+                }
+            }
+        }
+        (entities, &dirty, storage)
+            .join()
+            .map(|(entity, _, _)| entity)
+            .collect()
+    }
+}
+
+type ComponentInserter = Arc<dyn Fn(&World, Entity) + Send + Sync>;
+
+/// Template of components spawned together with `GameWorldExt::spawn_prefab`.
+///
+/// Each spawn gets its own clone of every component. Component types must be
+/// registered in the world (see `crate::init`).
+#[derive(Clone, Default)]
+pub struct Prefab {
+    components: Vec<(&'static str, ComponentInserter)>,
+}
+
+impl Prefab {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add `component` to the template, replacing any existing one of the same type
+    pub fn with<C: Component + Clone + Send + Sync>(mut self, component: C) -> Self {
+        let name = std::any::type_name::<C>();
+        let insert: ComponentInserter = Arc::new(move |world, entity| {
+            world
+                .write_storage::<C>()
+                .insert(entity, component.clone())
+                .unwrap();
+        });
+        match self.components.iter_mut().find(|(n, _)| *n == name) {
+            Some(existing) => existing.1 = insert,
+            None => self.components.push((name, insert)),
+        }
+        self
+    }
+
+    /// Number of component types in the template
+    pub fn len(&self) -> usize {
+        self.components.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.components.is_empty()
+    }
+
+    /// Build a prefab from a JSON object keyed by component name, e.g.
+    /// `{"Position": [0, 0], "Velocity": [200, -150], "Collider": {"circle": 5}, "Ball": null}`.
+    ///
+    /// Supported: `Position`, `Velocity` (`[x, y]`), `Collider` (`{"circle": r}` or
+    /// `{"rectangle": [w, h]}`, optional `"trigger": true`), `Health` (maximum),
+    /// `Mass`, `ZOrder`, `Paddle` (`{"player_controlled": bool}`), `Ball` and `Brick`.
+    pub fn from_json(json: &str) -> Result<Self, String> {
+        let value: Value =
+            serde_json::from_str(json).map_err(|e| format!("Invalid prefab JSON: {}", e))?;
+        let fields = value
+            .as_object()
+            .ok_or("Prefab must be a JSON object of components")?;
+
+        let mut prefab = Self::new();
+        for (name, data) in fields {
+            prefab = match name.as_str() {
+                "Position" => {
+                    let (x, y) = json_pair(name, data)?;
+                    prefab.with(Position::new(x, y))
+                }
+                "Velocity" => {
+                    let (x, y) = json_pair(name, data)?;
+                    prefab.with(Velocity::new(x, y))
+                }
+                "Collider" => prefab.with(json_collider(data)?),
+                "Health" => prefab.with(Health::new(json_f32(name, data)?)),
+                "Mass" => prefab.with(Mass(json_f32(name, data)?)),
+                "ZOrder" => prefab.with(ZOrder(json_f32(name, data)? as i32)),
+                "Paddle" => prefab.with(Paddle {
+                    player_controlled: data
+                        .get("player_controlled")
+                        .and_then(Value::as_bool)
+                        .unwrap_or(false),
+                }),
+                "Ball" => prefab.with(Ball),
+                "Brick" => prefab.with(Brick),
+                other => return Err(format!("Unknown prefab component '{}'", other)),
+            };
+        }
+        Ok(prefab)
+    }
+}
+
+impl std::fmt::Debug for Prefab {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_list()
+            .entries(self.components.iter().map(|(name, _)| name))
+            .finish()
+    }
+}
+
+fn json_f32(name: &str, value: &Value) -> Result<f32, String> {
+    value
+        .as_f64()
+        .map(|v| v as f32)
+        .ok_or_else(|| format!("{} expects a number", name))
+}
+
+fn json_pair(name: &str, value: &Value) -> Result<(f32, f32), String> {
+    match value.as_array().map(Vec::as_slice) {
+        Some([x, y]) => Ok((json_f32(name, x)?, json_f32(name, y)?)),
+        _ => Err(format!("{} expects [x, y]", name)),
+    }
+}
+
+fn json_collider(value: &Value) -> Result<Collider, String> {
+    let collider = if let Some(radius) = value.get("circle") {
+        Collider::new_circle(json_f32("Collider circle", radius)?)
+    } else if let Some(size) = value.get("rectangle") {
+        let (width, height) = json_pair("Collider rectangle", size)?;
+        Collider::new_rectangle(width, height)
+    } else {
+        return Err("Collider expects \"circle\" or \"rectangle\"".to_string());
+    };
+    let trigger = value.get("trigger").and_then(Value::as_bool) == Some(true);
+    Ok(if trigger {
+        collider.as_trigger()
+    } else {
+        collider
+    })
 }
 
 /// Entity builder for fluent component addition
@@ -67,3 +570,346 @@ where
         std::any::type_name::<T>()
     }
 }
+
+/// In-use vs free counts of an `EntityPool`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PoolStats {
+    pub in_use: usize,
+    pub free: usize,
+}
+
+/// Pool of pre-spawned entities for things that are spawned and despawned
+/// constantly (bullets, power-ups, extra balls).
+///
+/// Released entities are not deleted; they get the `Inactive` marker so
+/// systems skip them, and `acquire` hands the same entity out again.
+pub struct EntityPool {
+    template: Box<dyn Fn(&mut World) -> Entity + Send + Sync>,
+    free: Vec<Entity>,
+    in_use: Vec<Entity>,
+}
+
+impl EntityPool {
+    /// Pre-spawn `size` inactive entities using `template` to create each one
+    pub fn new<F>(world: &mut World, size: usize, template: F) -> Self
+    where
+        F: Fn(&mut World) -> Entity + Send + Sync + 'static,
+    {
+        let mut pool = Self {
+            template: Box::new(template),
+            free: Vec::with_capacity(size),
+            in_use: Vec::new(),
+        };
+        for _ in 0..size {
+            let entity = pool.spawn(world);
+            pool.free.push(entity);
+        }
+        pool
+    }
+
+    /// Take an entity from the pool, growing it by one if no entity is free
+    pub fn acquire(&mut self, world: &mut World) -> Entity {
+        let entity = match self.free.pop() {
+            Some(entity) => entity,
+            None => self.spawn(world),
+        };
+        world.write_storage::<Inactive>().remove(entity);
+        self.in_use.push(entity);
+        entity
+    }
+
+    /// Return an entity to the pool. Returns `false` if it wasn't acquired from this pool.
+    pub fn release(&mut self, world: &mut World, entity: Entity) -> bool {
+        let Some(index) = self.in_use.iter().position(|&e| e == entity) else {
+            return false;
+        };
+        self.in_use.swap_remove(index);
+        world
+            .write_storage::<Inactive>()
+            .insert(entity, Inactive)
+            .unwrap();
+        self.free.push(entity);
+        true
+    }
+
+    /// Whether `entity` is currently handed out by this pool
+    pub fn is_in_use(&self, entity: Entity) -> bool {
+        self.in_use.contains(&entity)
+    }
+
+    /// Current in-use and free counts
+    pub fn stats(&self) -> PoolStats {
+        PoolStats {
+            in_use: self.in_use.len(),
+            free: self.free.len(),
+        }
+    }
+
+    fn spawn(&self, world: &mut World) -> Entity {
+        let entity = (self.template)(world);
+        world
+            .write_storage::<Inactive>()
+            .insert(entity, Inactive)
+            .unwrap();
+        entity
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::components::{Position, Velocity};
+
+    #[test]
+    fn test_prefab_spawns_independent_components() {
+        let mut world = crate::init().unwrap();
+        let prefab = Prefab::new()
+            .with(Position::new(1.0, 2.0))
+            .with(Health::new(50.0))
+            .with(Health::new(100.0));
+        assert_eq!(prefab.len(), 2);
+
+        let first = world.spawn_prefab(&prefab);
+        let second = world.spawn_prefab_at(&prefab, Position::new(30.0, 40.0));
+        world
+            .write_storage::<Health>()
+            .get_mut(first)
+            .unwrap()
+            .current = 10.0;
+
+        let health = world.read_storage::<Health>();
+        assert_eq!(health.get(first).unwrap().current, 10.0);
+        assert_eq!(health.get(second).unwrap().current, 100.0);
+        let positions = world.read_storage::<Position>();
+        assert_eq!(positions.get(first).unwrap().x, 1.0);
+        assert_eq!(positions.get(second).unwrap().x, 30.0);
+    }
+
+    #[test]
+    fn test_prefab_from_json() {
+        let mut world = crate::init().unwrap();
+        let prefab = Prefab::from_json(
+            r#"{"Velocity": [200, -150], "Collider": {"circle": 5, "trigger": true}, "Ball": null}"#,
+        )
+        .unwrap();
+        let ball = world.spawn_prefab_at(&prefab, Position::new(0.0, 0.0));
+        assert!(world.read_storage::<Ball>().get(ball).is_some());
+        assert!(
+            world
+                .read_storage::<Collider>()
+                .get(ball)
+                .unwrap()
+                .is_trigger
+        );
+        assert_eq!(
+            world.read_storage::<Velocity>().get(ball).unwrap().y,
+            -150.0
+        );
+
+        assert!(Prefab::from_json(r#"{"Sprite": 1}"#).is_err());
+        assert!(Prefab::from_json(r#"{"Position": [1]}"#).is_err());
+    }
+
+    #[test]
+    fn test_changed_entities_reports_only_modified() {
+        let mut world = crate::init().unwrap();
+        let moved = world.create_entity().with(Position::new(0.0, 0.0)).build();
+        let still = world.create_entity().with(Position::new(5.0, 5.0)).build();
+        assert!(world.changed_entities::<Position>().is_empty());
+
+        world.write_storage::<Position>().get_mut(moved).unwrap().x = 3.0;
+        assert_eq!(world.changed_entities::<Position>(), vec![moved]);
+        assert!(world.changed_entities::<Position>().is_empty());
+
+        // re-inserting counts as a change; a removed component does not
+        world
+            .write_storage::<Position>()
+            .insert(still, Position::new(6.0, 6.0))
+            .unwrap();
+        world.write_storage::<Position>().get_mut(moved).unwrap().y = 1.0;
+        world.write_storage::<Position>().remove(moved);
+        assert_eq!(world.changed_entities::<Position>(), vec![still]);
+    }
+
+    fn ball_pool(world: &mut World, size: usize) -> EntityPool {
+        EntityPool::new(world, size, |world| {
+            world
+                .create_entity_with_components()
+                .with(Position::new(0.0, 0.0))
+                .with(Velocity::new(0.0, 0.0))
+                .build()
+        })
+    }
+
+    #[test]
+    fn test_pool_grows_when_exhausted() {
+        let mut world = crate::init().unwrap();
+        let mut pool = ball_pool(&mut world, 2);
+        assert_eq!(pool.stats(), PoolStats { in_use: 0, free: 2 });
+
+        let a = pool.acquire(&mut world);
+        let b = pool.acquire(&mut world);
+        let c = pool.acquire(&mut world);
+        assert_ne!(a, c);
+        assert_ne!(b, c);
+        assert_eq!(pool.stats(), PoolStats { in_use: 3, free: 0 });
+        assert!(world.read_storage::<Inactive>().get(c).is_none());
+    }
+
+    #[test]
+    fn test_released_entities_are_reused() {
+        let mut world = crate::init().unwrap();
+        let mut pool = ball_pool(&mut world, 1);
+
+        let first = pool.acquire(&mut world);
+        assert!(pool.release(&mut world, first));
+        assert!(world.read_storage::<Inactive>().get(first).is_some());
+        assert!(world.is_alive(first));
+        assert!(!pool.release(&mut world, first));
+
+        let again = pool.acquire(&mut world);
+        assert_eq!(again, first);
+        assert_eq!(again.id(), first.id());
+        assert_eq!(pool.stats(), PoolStats { in_use: 1, free: 0 });
+    }
+
+    #[test]
+    fn test_commands_queued_during_iteration_apply_on_flush() {
+        use specs::{Builder, RunNow, System};
+
+        struct ClearBricks;
+        impl<'a> System<'a> for ClearBricks {
+            type SystemData = (Entities<'a>, ReadStorage<'a, Brick>, Commands<'a>);
+
+            fn run(&mut self, (entities, bricks, mut commands): Self::SystemData) {
+                for (entity, _) in (&entities, &bricks).join() {
+                    commands.despawn(entity);
+                    commands.despawn(entity);
+                }
+                commands.spawn(Prefab::new().with(Ball));
+            }
+        }
+
+        let mut world = crate::init().unwrap();
+        let a = world.create_entity().with(Brick).build();
+        let b = world.create_entity().with(Brick).build();
+        let keep = world.create_entity().with(Position::new(0.0, 0.0)).build();
+
+        ClearBricks.run_now(&world);
+        assert!(world.is_alive(a) && world.is_alive(b));
+        assert_eq!(world.read_resource::<CommandBuffer>().len(), 5);
+
+        world.apply_commands();
+        assert!(!world.is_alive(a));
+        assert!(!world.is_alive(b));
+        assert!(world.is_alive(keep));
+        assert_eq!(world.read_storage::<Ball>().join().count(), 1);
+        assert!(world.read_resource::<CommandBuffer>().is_empty());
+    }
+
+    #[test]
+    fn test_spawns_over_entity_budget_are_refused() {
+        let mut world = crate::init().unwrap();
+        for _ in 0..3 {
+            world.create_entity().with(Brick).build();
+        }
+        world.insert(EntityBudget::new(5).refusing());
+        world.apply_commands();
+        assert_eq!(world.read_resource::<EntityBudget>().used(), 3);
+
+        // a runaway multiball keeps asking for more
+        let spawn_burst = |world: &mut World| -> Vec<bool> {
+            let results = (0..4)
+                .map(|_| {
+                    let mut budget = world.write_resource::<EntityBudget>();
+                    world
+                        .write_resource::<CommandBuffer>()
+                        .try_spawn(Prefab::new().with(Ball), &mut budget)
+                        .is_ok()
+                })
+                .collect();
+            world.apply_commands();
+            results
+        };
+        assert_eq!(spawn_burst(&mut world), vec![true, true, false, false]);
+        assert_eq!(spawn_burst(&mut world), vec![false; 4]);
+
+        assert_eq!(world.entities().join().count(), 5);
+        assert_eq!(world.read_storage::<Ball>().join().count(), 2);
+    }
+
+    #[test]
+    fn test_clear_all_removes_entities_and_keeps_resources() {
+        let mut world = crate::init().unwrap();
+        let paddle = world
+            .create_entity()
+            .with(Paddle {
+                player_controlled: true,
+            })
+            .with(Position::new(0.0, 0.0))
+            .build();
+        for i in 0..3 {
+            world
+                .create_entity()
+                .with(Brick)
+                .with(Health::new(1.0))
+                .with(Position::new(i as f32 * 10.0, 0.0))
+                .build();
+        }
+        world
+            .create_entity()
+            .with(Ball)
+            .with(Velocity::new(1.0, 0.0))
+            .build();
+        world.write_resource::<Time>().advance(0.05);
+
+        assert_eq!(clear_entities_with::<Brick>(&mut world), 3);
+        assert_eq!(world.entities().join().count(), 2);
+
+        world.clear_all_except(&[paddle]);
+        assert!(world.is_alive(paddle));
+        assert_eq!(world.entities().join().count(), 1);
+
+        world.clear_all();
+        assert_eq!(world.entities().join().count(), 0);
+        assert_eq!(world.read_resource::<Time>().elapsed, 0.05);
+        assert!(world.has_value::<CommandBuffer>());
+    }
+
+    #[test]
+    fn test_long_stall_is_clamped_to_max_delta() {
+        let mut time = Time::default();
+        time.set_max_delta(0.05);
+        time.advance(3.0);
+        assert_eq!(time.delta, 0.05);
+        assert_eq!(time.elapsed, 0.05);
+        assert_eq!(time.unscaled_delta, 3.0);
+
+        time.time_scale = 0.5;
+        time.advance(3.0);
+        assert_eq!(time.delta, 0.025);
+
+        // short frames pass through untouched
+        time.advance(0.01);
+        assert_eq!(time.delta, 0.005);
+    }
+
+    #[test]
+    fn test_pause_keeps_time_scale() {
+        let mut time = Time {
+            time_scale: 0.5,
+            ..Time::default()
+        };
+
+        time.set_paused(true);
+        time.advance(0.1);
+        assert!(time.is_paused());
+        assert_eq!(time.delta, 0.0);
+
+        // resuming every frame must not clobber slow motion
+        time.set_paused(false);
+        time.advance(0.1);
+        assert_eq!(time.time_scale, 0.5);
+        assert_eq!(time.delta, 0.05);
+    }
+}
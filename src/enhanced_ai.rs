@@ -3,8 +3,9 @@
 //! A flexible AI system with behavior trees, state machines, and difficulty scaling.
 //! Extracted and enhanced from the Pong game AI.
 
-use crate::Vec2;
-use std::collections::HashMap;
+use crate::{Position, RngResource, Team, TeamRelations, Vec2, Velocity};
+use specs::{Entity, Join, World, WorldExt};
+use std::collections::{HashMap, VecDeque};
 
 /// AI behavior types
 #[derive(Debug, Clone, PartialEq)]
@@ -69,6 +70,29 @@ impl AIDifficulty {
         }
     }
 
+    /// How many seconds late the AI sees its target (`ReactionBuffer`), which
+    /// is also how often it re-aims (`AIErrorModel`)
+    pub fn reaction_delay(&self) -> f32 {
+        match self {
+            AIDifficulty::VeryEasy => 0.3,
+            AIDifficulty::Easy => 0.2,
+            AIDifficulty::Normal => 0.12,
+            AIDifficulty::Hard => 0.06,
+            AIDifficulty::VeryHard => 0.02,
+        }
+    }
+
+    /// Largest aiming error in pixels
+    pub fn error_magnitude(&self) -> f32 {
+        match self {
+            AIDifficulty::VeryEasy => 40.0,
+            AIDifficulty::Easy => 30.0,
+            AIDifficulty::Normal => 20.0,
+            AIDifficulty::Hard => 10.0,
+            AIDifficulty::VeryHard => 4.0,
+        }
+    }
+
     /// Get prediction ability for this difficulty
     pub fn prediction_ability(&self) -> f32 {
         match self {
@@ -81,6 +105,132 @@ impl AIDifficulty {
     }
 }
 
+/// Deterministic aiming imperfection.
+///
+/// The AI only re-reads its target every `reaction_delay` seconds and adds an
+/// error drawn from an `RngResource`, so behavior depends on the seed and the
+/// accumulated frame time rather than on wall-clock `elapsed`.
+#[derive(Debug, Clone)]
+pub struct AIErrorModel {
+    pub reaction_delay: f32,
+    /// Errors are drawn uniformly from `[-error_magnitude, error_magnitude]`
+    pub error_magnitude: f32,
+    /// Time since the target was last read
+    timer: f32,
+    /// The position currently being aimed at
+    aim: Option<f32>,
+}
+
+impl Default for AIErrorModel {
+    fn default() -> Self {
+        Self::new(AIDifficulty::Normal)
+    }
+}
+
+impl AIErrorModel {
+    pub fn new(difficulty: AIDifficulty) -> Self {
+        Self {
+            reaction_delay: difficulty.reaction_delay(),
+            error_magnitude: difficulty.error_magnitude(),
+            timer: 0.0,
+            aim: None,
+        }
+    }
+
+    pub fn with_reaction_delay(mut self, reaction_delay: f32) -> Self {
+        self.reaction_delay = reaction_delay;
+        self
+    }
+
+    pub fn with_error_magnitude(mut self, error_magnitude: f32) -> Self {
+        self.error_magnitude = error_magnitude;
+        self
+    }
+
+    /// Advance by `delta_time` and return the position to aim at. `actual` is
+    /// the true target; it is only sampled when the reaction delay has elapsed.
+    pub fn update(&mut self, delta_time: f32, actual: f32, rng: &mut RngResource) -> f32 {
+        self.timer += delta_time;
+        match self.aim {
+            Some(aim) if self.timer < self.reaction_delay => aim,
+            _ => {
+                self.timer = 0.0;
+                let aim = actual + rng.range(-self.error_magnitude, self.error_magnitude);
+                self.aim = Some(aim);
+                aim
+            }
+        }
+    }
+
+    /// Forget the latched target, e.g. after a serve
+    pub fn reset(&mut self) {
+        self.timer = 0.0;
+        self.aim = None;
+    }
+}
+
+/// Ring buffer of recent target positions, so an AI acts on where the target
+/// was `delay` seconds ago instead of where it is now. The delay is the
+/// difficulty's `reaction_delay`; `clear` it when the target is re-spawned.
+#[derive(Debug, Clone)]
+pub struct ReactionBuffer {
+    pub delay: f32,
+    /// `(time, position)` samples, oldest first
+    samples: VecDeque<(f32, Vec2)>,
+    elapsed: f32,
+}
+
+impl Default for ReactionBuffer {
+    fn default() -> Self {
+        Self::new(AIDifficulty::Normal.reaction_delay())
+    }
+}
+
+impl ReactionBuffer {
+    pub fn new(delay: f32) -> Self {
+        Self {
+            delay,
+            samples: VecDeque::new(),
+            elapsed: 0.0,
+        }
+    }
+
+    pub fn for_difficulty(difficulty: AIDifficulty) -> Self {
+        Self::new(difficulty.reaction_delay())
+    }
+
+    /// Advance by `delta_time` and record the target's current position.
+    /// Samples older than needed for `delay` are dropped.
+    pub fn push(&mut self, delta_time: f32, position: Vec2) {
+        self.elapsed += delta_time;
+        self.samples.push_back((self.elapsed, position));
+
+        let cutoff = self.elapsed - self.delay;
+        while self.samples.len() > 1 && self.samples[1].0 <= cutoff {
+            self.samples.pop_front();
+        }
+    }
+
+    /// The target position `delay` seconds ago, interpolated between samples.
+    /// Until that much history exists this is the oldest recorded position.
+    pub fn delayed(&self) -> Option<Vec2> {
+        let &(time, position) = self.samples.front()?;
+        let cutoff = self.elapsed - self.delay;
+        match self.samples.get(1) {
+            Some(&(next_time, next_position)) if time < cutoff => {
+                let t = (cutoff - time) / (next_time - time);
+                Some(position + (next_position - position) * t)
+            }
+            _ => Some(position),
+        }
+    }
+
+    /// Forget the recorded history, e.g. after the target teleports
+    pub fn clear(&mut self) {
+        self.samples.clear();
+    }
+}
+
 /// AI state for state machine
 #[derive(Debug, Clone, PartialEq)]
 pub enum AIState {
@@ -122,6 +272,33 @@ impl AITarget {
     }
 }
 
+/// Closest other entity with a `Team` that `seeker` is hostile to under the
+/// world's `TeamRelations`, as an `AITarget`
+pub fn nearest_hostile(world: &World, seeker: Entity) -> Option<(Entity, AITarget)> {
+    let positions = world.read_storage::<Position>();
+    let velocities = world.read_storage::<Velocity>();
+    let teams = world.read_storage::<Team>();
+    let relations = world
+        .try_fetch::<TeamRelations>()
+        .map(|relations| (*relations).clone())
+        .unwrap_or_default();
+    let origin = positions.get(seeker)?.as_vec2();
+    let seeker_team = teams.get(seeker);
+
+    (&world.entities(), &positions, &teams)
+        .join()
+        .filter(|(entity, _, team)| {
+            *entity != seeker && relations.entities_hostile(seeker_team, Some(team))
+        })
+        .map(|(entity, position, _)| {
+            let velocity = velocities
+                .get(entity)
+                .map_or_else(Vec2::zeros, Velocity::as_vec2);
+            (entity, AITarget::new(position.as_vec2(), velocity, origin))
+        })
+        .min_by(|a, b| a.1.distance.total_cmp(&b.1.distance))
+}
+
 /// AI decision context
 #[derive(Debug, Clone)]
 pub struct AIContext {
@@ -132,6 +309,8 @@ pub struct AIContext {
     pub delta_time: f32,
     pub difficulty: AIDifficulty,
     pub state: AIState,
+    /// Aiming error to add to the target, e.g. from an `AIErrorModel`
+    pub aim_offset: f32,
     pub custom_data: HashMap<String, f32>,
 }
 
@@ -145,6 +324,7 @@ impl AIContext {
             delta_time: 0.0,
             difficulty,
             state: AIState::Idle,
+            aim_offset: 0.0,
             custom_data: HashMap::new(),
         }
     }
@@ -257,11 +437,8 @@ impl AIBehaviorImpl for PongPaddleAI {
                 };
                 let speed = self.paddle_speed * context.difficulty.reaction_multiplier();
 
-                // Add some imperfection based on difficulty
-                let accuracy = context.difficulty.accuracy_multiplier();
-                let error = (context.time * 2.0).sin() * (1.0 - accuracy) * 20.0;
-
-                let target_y = ball_center + error;
+                // Imperfection comes from the caller's error model
+                let target_y = ball_center + context.aim_offset;
                 let direction_with_error = if target_y > paddle_center { 1.0 } else { -1.0 };
 
                 decision.desired_velocity = Vec2::new(0.0, direction_with_error * speed);
@@ -395,9 +572,14 @@ impl AIBehaviorImpl for PatrolAI {
 }
 
 /// Main AI system manager
+///
+/// Each agent's `AIContext::aim_offset` comes from a per-agent `AIErrorModel`
+/// matching its difficulty, drawn from the system's own `RngResource`.
 pub struct AISystem {
     agents: HashMap<String, Box<dyn AIBehaviorImpl>>,
     contexts: HashMap<String, AIContext>,
+    error_models: HashMap<String, AIErrorModel>,
+    rng: RngResource,
 }
 
 impl AISystem {
@@ -405,9 +587,17 @@ impl AISystem {
         Self {
             agents: HashMap::new(),
             contexts: HashMap::new(),
+            error_models: HashMap::new(),
+            rng: RngResource::default(),
         }
     }
 
+    /// Draw aiming errors from `rng`, e.g. a seeded one for replays
+    pub fn with_rng(mut self, rng: RngResource) -> Self {
+        self.rng = rng;
+        self
+    }
+
     /// Register an AI agent
     pub fn register_agent(&mut self, id: &str, agent: Box<dyn AIBehaviorImpl>) {
         self.agents.insert(id.to_string(), agent);
@@ -417,6 +607,7 @@ impl AISystem {
     pub fn register_pong_ai(&mut self, id: &str, paddle_speed: f32, difficulty: AIDifficulty) {
         let ai = PongPaddleAI::new(paddle_speed).with_difficulty(difficulty);
         self.register_agent(id, Box::new(ai));
+        self.set_agent_difficulty(id, difficulty);
     }
 
     /// Register a chase AI
@@ -461,13 +652,33 @@ impl AISystem {
         context.target = target;
         context.delta_time = 0.016; // Assume 60 FPS
         context.time += context.delta_time;
+
+        let error_model = self
+            .error_models
+            .entry(id.to_string())
+            .or_insert_with(|| AIErrorModel::new(context.difficulty));
+        context.aim_offset = match &context.target {
+            Some(target) => {
+                let actual = target.position.y;
+                error_model.update(context.delta_time, actual, &mut self.rng) - actual
+            }
+            None => 0.0,
+        };
     }
 
-    /// Set difficulty for an agent
+    /// Set difficulty for an agent, including its aiming error
     pub fn set_agent_difficulty(&mut self, id: &str, difficulty: AIDifficulty) {
-        if let Some(context) = self.contexts.get_mut(id) {
-            context.difficulty = difficulty;
-        }
+        self.contexts
+            .entry(id.to_string())
+            .or_insert_with(|| AIContext::new(Vec2::zeros(), Vec2::zeros(), difficulty))
+            .difficulty = difficulty;
+        self.error_models
+            .insert(id.to_string(), AIErrorModel::new(difficulty));
+    }
+
+    /// The context an agent last decided from
+    pub fn context(&self, id: &str) -> Option<&AIContext> {
+        self.contexts.get(id)
     }
 
     /// Get AI decision for an agent
@@ -490,6 +701,7 @@ impl AISystem {
     pub fn remove_agent(&mut self, id: &str) {
         self.agents.remove(id);
         self.contexts.remove(id);
+        self.error_models.remove(id);
     }
 
     /// Get all registered agent IDs
@@ -563,6 +775,53 @@ mod tests {
         assert!(decision.desired_velocity.y < 0.0); // Should move up
     }
 
+    #[test]
+    fn test_error_model_is_deterministic_for_seed() {
+        let run = || {
+            let mut rng = RngResource::new(42);
+            let mut model = AIErrorModel::new(AIDifficulty::Normal);
+            (0..120)
+                .map(|_| model.update(1.0 / 60.0, 300.0, &mut rng))
+                .collect::<Vec<_>>()
+        };
+
+        let first = run();
+        assert_eq!(first, run());
+
+        // Errors stay within the configured magnitude and are re-drawn over time
+        let magnitude = AIDifficulty::Normal.error_magnitude();
+        assert!(first.iter().all(|aim| (aim - 300.0).abs() <= magnitude));
+        assert!(first.windows(2).any(|pair| pair[0] != pair[1]));
+    }
+
+    #[test]
+    fn test_reaction_buffer_reports_past_position() {
+        let delay = AIDifficulty::Easy.reaction_delay();
+        assert!(delay > AIDifficulty::Hard.reaction_delay());
+
+        let mut buffer = ReactionBuffer::for_difficulty(AIDifficulty::Easy);
+        assert_eq!(buffer.delayed(), None);
+
+        // ball moving right at 120 px/s
+        let dt = 1.0 / 60.0;
+        for frame in 0..=60 {
+            let t = frame as f32 * dt;
+            buffer.push(
+                if frame == 0 { 0.0 } else { dt },
+                Vec2::new(t * 120.0, 50.0),
+            );
+        }
+        let expected = (1.0 - delay) * 120.0;
+        let seen = buffer.delayed().unwrap();
+        assert!(
+            (seen.x - expected).abs() < 1e-2,
+            "{} vs {}",
+            seen.x,
+            expected
+        );
+        assert_eq!(seen.y, 50.0);
+    }
+
     #[test]
     fn test_chase_ai_decision() {
         let ai = ChaseAI::new(100.0, 50.0);
@@ -596,4 +855,30 @@ mod tests {
         let decision = system.get_decision("paddle1");
         assert!(decision.is_some());
     }
+
+    #[test]
+    fn test_ai_system_feeds_aim_offset() {
+        let run = |difficulty: AIDifficulty| {
+            let mut system = AISystem::new().with_rng(RngResource::new(7));
+            system.register_pong_ai("paddle", 100.0, difficulty);
+            (0..120)
+                .map(|_| {
+                    let ball = AITarget::new(Vec2::new(350.0, 250.0), Vec2::zeros(), Vec2::zeros());
+                    system.update_context(
+                        "paddle",
+                        Vec2::new(400.0, 300.0),
+                        Vec2::zeros(),
+                        Some(ball),
+                    );
+                    system.context("paddle").unwrap().aim_offset
+                })
+                .collect::<Vec<_>>()
+        };
+
+        let offsets = run(AIDifficulty::Easy);
+        assert_eq!(offsets, run(AIDifficulty::Easy));
+        assert!(offsets.iter().any(|offset| *offset != 0.0));
+        let magnitude = AIDifficulty::Easy.error_magnitude();
+        assert!(offsets.iter().all(|offset| offset.abs() <= magnitude));
+    }
 }
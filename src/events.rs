@@ -2,7 +2,10 @@
 //!
 //! Decoupled communication between systems.
 
+use std::any::{Any, TypeId};
+use std::cell::RefCell;
 use std::collections::HashMap;
+use std::rc::Rc;
 
 /// Event types
 #[derive(Debug, Clone)]
@@ -15,9 +18,65 @@ pub enum GameEvent {
 /// Type alias for event subscriber functions
 type EventSubscriber = Box<dyn Fn(&GameEvent)>;
 
-/// Event bus placeholder
+/// Typed handler registered with `EventBus::on`
+type Handler<T> = Box<dyn FnMut(&T)>;
+
+/// Deferred publish recorded by an `EventSender`
+type PendingEvent = Box<dyn FnOnce(&mut EventBus)>;
+
+/// Upper bound on dispatch rounds so handlers that keep re-publishing cannot hang `dispatch_all`
+const MAX_DISPATCH_ROUNDS: usize = 32;
+
+/// Queue and handlers for a single event type
+struct Channel<T> {
+    queue: Vec<T>,
+    handlers: Vec<Handler<T>>,
+}
+
+/// Type-erased view of a channel so the bus can drain every type in one pass
+trait AnyChannel {
+    fn dispatch(&mut self) -> usize;
+    fn as_any_mut(&mut self) -> &mut dyn Any;
+}
+
+impl<T: 'static> AnyChannel for Channel<T> {
+    fn dispatch(&mut self) -> usize {
+        let events = std::mem::take(&mut self.queue);
+        for event in &events {
+            for handler in &mut self.handlers {
+                handler(event);
+            }
+        }
+        events.len()
+    }
+
+    fn as_any_mut(&mut self) -> &mut dyn Any {
+        self
+    }
+}
+
+/// Cloneable handle for publishing from inside handlers.
+///
+/// Events sent here are buffered and delivered by the next round of `dispatch_all`.
+#[derive(Clone, Default)]
+pub struct EventSender {
+    pending: Rc<RefCell<Vec<PendingEvent>>>,
+}
+
+impl EventSender {
+    /// Queue an event for the owning bus
+    pub fn send<T: 'static>(&self, event: T) {
+        self.pending
+            .borrow_mut()
+            .push(Box::new(move |bus: &mut EventBus| bus.emit(event)));
+    }
+}
+
+/// Event bus with typed channels
 pub struct EventBus {
     pub subscribers: HashMap<String, Vec<EventSubscriber>>,
+    channels: HashMap<TypeId, Box<dyn AnyChannel>>,
+    sender: EventSender,
 }
 
 impl Default for EventBus {
@@ -30,10 +89,111 @@ impl EventBus {
     pub fn new() -> Self {
         Self {
             subscribers: HashMap::new(),
+            channels: HashMap::new(),
+            sender: EventSender::default(),
+        }
+    }
+
+    pub fn publish(&mut self, event: GameEvent) {
+        self.emit(event);
+    }
+
+    /// Queue an event of any type until the next `dispatch_all`
+    pub fn emit<T: 'static>(&mut self, event: T) {
+        self.channel::<T>().queue.push(event);
+    }
+
+    /// Register a handler called for every dispatched event of type `T`
+    pub fn on<T: 'static, F: FnMut(&T) + 'static>(&mut self, handler: F) {
+        self.channel::<T>().handlers.push(Box::new(handler));
+    }
+
+    /// Handle for publishing events from inside handlers
+    pub fn sender(&self) -> EventSender {
+        self.sender.clone()
+    }
+
+    /// Drain every channel, invoking registered handlers.
+    ///
+    /// Events published through an `EventSender` while dispatching are delivered
+    /// in follow-up rounds. Returns the number of events dispatched.
+    pub fn dispatch_all(&mut self) -> usize {
+        let mut dispatched = 0;
+        for _ in 0..MAX_DISPATCH_ROUNDS {
+            self.flush_sender();
+            let mut round = 0;
+            for channel in self.channels.values_mut() {
+                round += channel.dispatch();
+            }
+            dispatched += round;
+            if round == 0 && self.sender.pending.borrow().is_empty() {
+                break;
+            }
+        }
+        dispatched
+    }
+
+    fn flush_sender(&mut self) {
+        let pending = std::mem::take(&mut *self.sender.pending.borrow_mut());
+        for publish in pending {
+            publish(self);
         }
     }
 
-    pub fn publish(&self, _event: GameEvent) {
-        // Publish event
+    fn channel<T: 'static>(&mut self) -> &mut Channel<T> {
+        self.channels
+            .entry(TypeId::of::<T>())
+            .or_insert_with(|| {
+                Box::new(Channel::<T> {
+                    queue: Vec::new(),
+                    handlers: Vec::new(),
+                })
+            })
+            .as_any_mut()
+            .downcast_mut::<Channel<T>>()
+            .expect("event channel stored under the wrong TypeId")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ui::UiEvent;
+    use std::cell::Cell;
+
+    #[test]
+    fn test_click_handler_runs_once_per_event() {
+        let mut bus = EventBus::new();
+        let clicks = Rc::new(Cell::new(0));
+        let counter = clicks.clone();
+        bus.on::<UiEvent, _>(move |event| {
+            if matches!(event, UiEvent::Click(id) if id == "start") {
+                counter.set(counter.get() + 1);
+            }
+        });
+
+        bus.emit(UiEvent::Click("start".to_string()));
+        bus.emit(UiEvent::Click("start".to_string()));
+        assert_eq!(bus.dispatch_all(), 2);
+        assert_eq!(clicks.get(), 2);
+
+        // Drained channels do not fire again
+        bus.dispatch_all();
+        assert_eq!(clicks.get(), 2);
+    }
+
+    #[test]
+    fn test_handler_can_publish_followup_events() {
+        let mut bus = EventBus::new();
+        let sender = bus.sender();
+        bus.on::<UiEvent, _>(move |_| sender.send(GameEvent::EntityCreated));
+
+        let created = Rc::new(Cell::new(0));
+        let counter = created.clone();
+        bus.on::<GameEvent, _>(move |_| counter.set(counter.get() + 1));
+
+        bus.emit(UiEvent::Click("spawn".to_string()));
+        assert_eq!(bus.dispatch_all(), 2);
+        assert_eq!(created.get(), 1);
     }
 }
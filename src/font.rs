@@ -34,7 +34,16 @@ impl FontSystem {
         path: P,
     ) -> Result<(), Box<dyn std::error::Error>> {
         let font_data = fs::read(path)?;
-        let font = Font::try_from_vec(font_data).ok_or("Failed to parse font data")?;
+        self.load_font_from_bytes(name, &font_data)
+    }
+
+    /// Load a TTF font from memory, e.g. one embedded with `include_bytes!`
+    pub fn load_font_from_bytes(
+        &mut self,
+        name: &str,
+        bytes: &[u8],
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        let font = Font::try_from_vec(bytes.to_vec()).ok_or("Failed to parse font data")?;
 
         self.fonts.insert(name.to_string(), font);
 
@@ -167,6 +176,46 @@ impl FontSystem {
         })
     }
 
+    /// Horizontal and size metrics of a single glyph, for custom layout
+    pub fn glyph_metrics(
+        &self,
+        c: char,
+        font_name: Option<&str>,
+        font_size: f32,
+    ) -> Result<GlyphMetrics, Box<dyn std::error::Error>> {
+        let font = self.get_font(font_name).ok_or("Font not found")?;
+
+        let glyph = font.glyph(c).scaled(Scale::uniform(font_size));
+        let h_metrics = glyph.h_metrics();
+        let (width, height) = glyph
+            .exact_bounding_box()
+            .map(|bb| (bb.width(), bb.height()))
+            .unwrap_or((0.0, 0.0));
+
+        Ok(GlyphMetrics {
+            advance: h_metrics.advance_width,
+            bearing: h_metrics.left_side_bearing,
+            width,
+            height,
+        })
+    }
+
+    /// Vertical metrics of a font at a size; `descent` is negative (below the baseline)
+    pub fn font_metrics(
+        &self,
+        font_name: Option<&str>,
+        font_size: f32,
+    ) -> Result<FontMetrics, Box<dyn std::error::Error>> {
+        let font = self.get_font(font_name).ok_or("Font not found")?;
+        let v_metrics = font.v_metrics(Scale::uniform(font_size));
+
+        Ok(FontMetrics {
+            ascent: v_metrics.ascent,
+            descent: v_metrics.descent,
+            line_gap: v_metrics.line_gap,
+        })
+    }
+
     /// Set the default font
     pub fn set_default_font(&mut self, name: &str) {
         self.default_font = Some(name.to_string());
@@ -193,8 +242,250 @@ pub struct TextMetrics {
     pub descent: f32,
 }
 
+/// Metrics of a single glyph
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct GlyphMetrics {
+    /// Horizontal distance to the next glyph's origin
+    pub advance: f32,
+    /// Offset from the origin to the left edge of the glyph
+    pub bearing: f32,
+    pub width: f32,
+    pub height: f32,
+}
+
+/// Vertical metrics of a font at a given size
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct FontMetrics {
+    pub ascent: f32,
+    pub descent: f32,
+    pub line_gap: f32,
+}
+
+impl FontMetrics {
+    /// Distance between consecutive baselines
+    pub fn line_height(&self) -> f32 {
+        self.ascent - self.descent + self.line_gap
+    }
+}
+
 impl Default for FontSystem {
     fn default() -> Self {
         Self::new()
     }
 }
+
+/// Built-in 7x9 bitmap font covering printable ASCII, used when no TTF font is loaded
+#[derive(Debug, Clone, Copy, Default)]
+pub struct BitmapFont;
+
+impl BitmapFont {
+    pub const GLYPH_WIDTH: usize = 7;
+    pub const GLYPH_HEIGHT: usize = 9;
+    /// Horizontal distance between glyph origins, in font pixels
+    pub const ADVANCE: usize = 8;
+
+    pub fn new() -> Self {
+        Self
+    }
+
+    /// Whether `c` has its own glyph rather than the fallback box
+    pub fn has_glyph(&self, c: char) -> bool {
+        (' '..='~').contains(&c)
+    }
+
+    /// Rows of `c` from top to bottom, bit 6 being the leftmost column.
+    /// Characters outside printable ASCII get a hollow box.
+    pub fn glyph(&self, c: char) -> &'static [u8; 9] {
+        if self.has_glyph(c) {
+            &GLYPHS[c as usize - ' ' as usize]
+        } else {
+            &FALLBACK_GLYPH
+        }
+    }
+
+    /// Whether the pixel at `(col, row)` of `c` is set
+    pub fn pixel(&self, c: char, col: usize, row: usize) -> bool {
+        col < Self::GLYPH_WIDTH
+            && row < Self::GLYPH_HEIGHT
+            && self.glyph(c)[row] & (1 << (Self::GLYPH_WIDTH - 1 - col)) != 0
+    }
+
+    /// Call `plot(col, row)` for every set pixel of `c`
+    pub fn draw_glyph(&self, c: char, mut plot: impl FnMut(usize, usize)) {
+        for row in 0..Self::GLYPH_HEIGHT {
+            for col in 0..Self::GLYPH_WIDTH {
+                if self.pixel(c, col, row) {
+                    plot(col, row);
+                }
+            }
+        }
+    }
+}
+
+const FALLBACK_GLYPH: [u8; 9] = [0x7F, 0x41, 0x41, 0x41, 0x41, 0x41, 0x41, 0x41, 0x7F];
+
+/// Glyphs for `' '..='~'` in ASCII order
+#[rustfmt::skip]
+const GLYPHS: [[u8; 9]; 95] = [
+    [0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00], // ' '
+    [0x08, 0x08, 0x08, 0x08, 0x08, 0x08, 0x00, 0x08, 0x08], // '!'
+    [0x14, 0x14, 0x14, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00], // '"'
+    [0x14, 0x14, 0x7F, 0x14, 0x14, 0x14, 0x7F, 0x14, 0x14], // '#'
+    [0x08, 0x3E, 0x48, 0x48, 0x3E, 0x09, 0x09, 0x3E, 0x08], // '$'
+    [0x61, 0x62, 0x04, 0x08, 0x10, 0x20, 0x46, 0x06, 0x00], // '%'
+    [0x38, 0x44, 0x44, 0x38, 0x31, 0x4A, 0x44, 0x46, 0x39], // '&'
+    [0x08, 0x08, 0x08, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00], // '\''
+    [0x04, 0x08, 0x10, 0x10, 0x10, 0x10, 0x10, 0x08, 0x04], // '('
+    [0x10, 0x08, 0x04, 0x04, 0x04, 0x04, 0x04, 0x08, 0x10], // ')'
+    [0x00, 0x08, 0x49, 0x2A, 0x1C, 0x2A, 0x49, 0x08, 0x00], // '*'
+    [0x00, 0x08, 0x08, 0x08, 0x7F, 0x08, 0x08, 0x08, 0x00], // '+'
+    [0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x0C, 0x0C, 0x10], // ','
+    [0x00, 0x00, 0x00, 0x00, 0x7F, 0x00, 0x00, 0x00, 0x00], // '-'
+    [0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x0C, 0x0C], // '.'
+    [0x02, 0x04, 0x08, 0x10, 0x20, 0x40, 0x00, 0x00, 0x00], // '/'
+    [0x3E, 0x41, 0x43, 0x45, 0x49, 0x51, 0x61, 0x41, 0x3E], // '0'
+    [0x08, 0x18, 0x28, 0x08, 0x08, 0x08, 0x08, 0x08, 0x7F], // '1'
+    [0x3E, 0x41, 0x01, 0x02, 0x04, 0x08, 0x10, 0x20, 0x7F], // '2'
+    [0x3E, 0x41, 0x01, 0x06, 0x0C, 0x01, 0x01, 0x41, 0x3E], // '3'
+    [0x04, 0x0C, 0x14, 0x24, 0x44, 0x7F, 0x04, 0x04, 0x04], // '4'
+    [0x7F, 0x40, 0x40, 0x7E, 0x01, 0x01, 0x01, 0x41, 0x3E], // '5'
+    [0x3E, 0x41, 0x40, 0x40, 0x7E, 0x41, 0x41, 0x41, 0x3E], // '6'
+    [0x7F, 0x01, 0x02, 0x04, 0x08, 0x10, 0x20, 0x40, 0x00], // '7'
+    [0x3E, 0x41, 0x41, 0x41, 0x3E, 0x41, 0x41, 0x41, 0x3E], // '8'
+    [0x3E, 0x41, 0x41, 0x41, 0x3F, 0x01, 0x01, 0x41, 0x3E], // '9'
+    [0x00, 0x10, 0x10, 0x00, 0x00, 0x00, 0x10, 0x10, 0x00], // ':'
+    [0x00, 0x0C, 0x0C, 0x00, 0x00, 0x00, 0x0C, 0x0C, 0x10], // ';'
+    [0x02, 0x04, 0x08, 0x10, 0x20, 0x10, 0x08, 0x04, 0x02], // '<'
+    [0x00, 0x00, 0x00, 0x7F, 0x00, 0x7F, 0x00, 0x00, 0x00], // '='
+    [0x20, 0x10, 0x08, 0x04, 0x02, 0x04, 0x08, 0x10, 0x20], // '>'
+    [0x3E, 0x41, 0x01, 0x02, 0x04, 0x08, 0x00, 0x08, 0x08], // '?'
+    [0x3E, 0x41, 0x4F, 0x51, 0x51, 0x4F, 0x40, 0x41, 0x3E], // '@'
+    [0x3E, 0x41, 0x41, 0x41, 0x7F, 0x41, 0x41, 0x41, 0x41], // 'A'
+    [0x7E, 0x41, 0x41, 0x41, 0x7E, 0x41, 0x41, 0x41, 0x7E], // 'B'
+    [0x3E, 0x41, 0x40, 0x40, 0x40, 0x40, 0x40, 0x41, 0x3E], // 'C'
+    [0x7E, 0x41, 0x41, 0x41, 0x41, 0x41, 0x41, 0x41, 0x7E], // 'D'
+    [0x7F, 0x40, 0x40, 0x40, 0x7E, 0x40, 0x40, 0x40, 0x7F], // 'E'
+    [0x7F, 0x40, 0x40, 0x40, 0x7E, 0x40, 0x40, 0x40, 0x40], // 'F'
+    [0x3E, 0x41, 0x40, 0x40, 0x4F, 0x41, 0x41, 0x41, 0x3E], // 'G'
+    [0x41, 0x41, 0x41, 0x41, 0x7F, 0x41, 0x41, 0x41, 0x41], // 'H'
+    [0x7F, 0x08, 0x08, 0x08, 0x08, 0x08, 0x08, 0x08, 0x7F], // 'I'
+    [0x7F, 0x02, 0x02, 0x02, 0x02, 0x02, 0x42, 0x42, 0x3C], // 'J'
+    [0x42, 0x44, 0x48, 0x50, 0x60, 0x50, 0x48, 0x44, 0x42], // 'K'
+    [0x40, 0x40, 0x40, 0x40, 0x40, 0x40, 0x40, 0x40, 0x7F], // 'L'
+    [0x41, 0x63, 0x55, 0x55, 0x49, 0x49, 0x41, 0x41, 0x41], // 'M'
+    [0x41, 0x61, 0x51, 0x51, 0x49, 0x49, 0x45, 0x45, 0x43], // 'N'
+    [0x3E, 0x41, 0x41, 0x41, 0x41, 0x41, 0x41, 0x41, 0x3E], // 'O'
+    [0x7E, 0x41, 0x41, 0x41, 0x7E, 0x40, 0x40, 0x40, 0x40], // 'P'
+    [0x3E, 0x41, 0x41, 0x41, 0x41, 0x45, 0x43, 0x41, 0x3F], // 'Q'
+    [0x7E, 0x41, 0x41, 0x41, 0x7E, 0x44, 0x42, 0x41, 0x41], // 'R'
+    [0x3E, 0x41, 0x40, 0x40, 0x3E, 0x01, 0x01, 0x41, 0x3E], // 'S'
+    [0x7F, 0x08, 0x08, 0x08, 0x08, 0x08, 0x08, 0x08, 0x08], // 'T'
+    [0x41, 0x41, 0x41, 0x41, 0x41, 0x41, 0x41, 0x41, 0x3E], // 'U'
+    [0x41, 0x41, 0x41, 0x41, 0x41, 0x41, 0x22, 0x22, 0x1C], // 'V'
+    [0x41, 0x41, 0x41, 0x41, 0x41, 0x55, 0x55, 0x63, 0x41], // 'W'
+    [0x41, 0x22, 0x22, 0x14, 0x14, 0x08, 0x14, 0x22, 0x41], // 'X'
+    [0x41, 0x22, 0x22, 0x14, 0x14, 0x08, 0x08, 0x08, 0x08], // 'Y'
+    [0x7F, 0x01, 0x02, 0x04, 0x08, 0x10, 0x20, 0x40, 0x7F], // 'Z'
+    [0x1E, 0x10, 0x10, 0x10, 0x10, 0x10, 0x10, 0x10, 0x1E], // '['
+    [0x40, 0x20, 0x10, 0x08, 0x04, 0x02, 0x01, 0x00, 0x00], // '\\'
+    [0x3C, 0x04, 0x04, 0x04, 0x04, 0x04, 0x04, 0x04, 0x3C], // ']'
+    [0x08, 0x14, 0x22, 0x41, 0x00, 0x00, 0x00, 0x00, 0x00], // '^'
+    [0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x7F], // '_'
+    [0x10, 0x08, 0x04, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00], // '`'
+    [0x00, 0x00, 0x1C, 0x42, 0x42, 0x42, 0x42, 0x3C, 0x00], // 'a'
+    [0x40, 0x40, 0x40, 0x7C, 0x42, 0x42, 0x42, 0x7C, 0x00], // 'b'
+    [0x00, 0x00, 0x3C, 0x42, 0x40, 0x40, 0x42, 0x3C, 0x00], // 'c'
+    [0x02, 0x02, 0x02, 0x3E, 0x42, 0x42, 0x42, 0x3E, 0x00], // 'd'
+    [0x00, 0x00, 0x3C, 0x42, 0x7E, 0x40, 0x42, 0x3C, 0x00], // 'e'
+    [0x1C, 0x22, 0x20, 0x7C, 0x20, 0x20, 0x20, 0x20, 0x00], // 'f'
+    [0x00, 0x00, 0x3E, 0x42, 0x42, 0x42, 0x3E, 0x02, 0x3C], // 'g'
+    [0x40, 0x40, 0x40, 0x7C, 0x42, 0x42, 0x42, 0x42, 0x00], // 'h'
+    [0x10, 0x00, 0x38, 0x10, 0x10, 0x10, 0x10, 0x38, 0x00], // 'i'
+    [0x08, 0x00, 0x1C, 0x08, 0x08, 0x08, 0x08, 0x48, 0x30], // 'j'
+    [0x40, 0x40, 0x44, 0x48, 0x70, 0x48, 0x44, 0x42, 0x00], // 'k'
+    [0x38, 0x10, 0x10, 0x10, 0x10, 0x10, 0x10, 0x38, 0x00], // 'l'
+    [0x00, 0x00, 0x6C, 0x54, 0x54, 0x54, 0x54, 0x54, 0x00], // 'm'
+    [0x00, 0x00, 0x7C, 0x44, 0x44, 0x44, 0x44, 0x44, 0x00], // 'n'
+    [0x00, 0x00, 0x3C, 0x42, 0x42, 0x42, 0x42, 0x3C, 0x00], // 'o'
+    [0x00, 0x00, 0x7C, 0x42, 0x42, 0x42, 0x7C, 0x40, 0x40], // 'p'
+    [0x00, 0x00, 0x3E, 0x42, 0x42, 0x42, 0x3E, 0x02, 0x02], // 'q'
+    [0x00, 0x00, 0x7C, 0x42, 0x40, 0x40, 0x40, 0x40, 0x00], // 'r'
+    [0x00, 0x00, 0x3C, 0x42, 0x78, 0x04, 0x42, 0x3C, 0x00], // 's'
+    [0x20, 0x20, 0x7C, 0x20, 0x20, 0x20, 0x20, 0x1C, 0x00], // 't'
+    [0x00, 0x00, 0x42, 0x42, 0x42, 0x42, 0x42, 0x3C, 0x00], // 'u'
+    [0x00, 0x00, 0x42, 0x42, 0x42, 0x42, 0x24, 0x18, 0x00], // 'v'
+    [0x00, 0x00, 0x42, 0x42, 0x42, 0x54, 0x54, 0x22, 0x00], // 'w'
+    [0x00, 0x00, 0x42, 0x24, 0x18, 0x18, 0x24, 0x42, 0x00], // 'x'
+    [0x00, 0x00, 0x42, 0x42, 0x42, 0x24, 0x18, 0x04, 0x08], // 'y'
+    [0x00, 0x00, 0x7E, 0x04, 0x08, 0x10, 0x20, 0x7E, 0x00], // 'z'
+    [0x06, 0x08, 0x08, 0x08, 0x30, 0x08, 0x08, 0x08, 0x06], // '{'
+    [0x08, 0x08, 0x08, 0x08, 0x08, 0x08, 0x08, 0x08, 0x08], // '|'
+    [0x30, 0x08, 0x08, 0x08, 0x06, 0x08, 0x08, 0x08, 0x30], // '}'
+    [0x00, 0x00, 0x00, 0x31, 0x49, 0x46, 0x00, 0x00, 0x00], // '~'
+];
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn dejavu() -> FontSystem {
+        let mut fonts = FontSystem::new();
+        let path = concat!(env!("CARGO_MANIFEST_DIR"), "/assets/fonts/DejaVuSans.ttf");
+        fonts.load_font("dejavu", path).unwrap();
+        fonts
+    }
+
+    #[test]
+    fn test_proportional_glyph_advances() {
+        let fonts = dejavu();
+        let i = fonts.glyph_metrics('i', None, 16.0).unwrap();
+        let m = fonts.glyph_metrics('m', Some("dejavu"), 16.0).unwrap();
+        assert!(i.advance < m.advance);
+        assert!(i.width > 0.0 && i.height > 0.0);
+
+        let metrics = fonts.font_metrics(None, 16.0).unwrap();
+        assert!(metrics.ascent > 0.0 && metrics.descent < 0.0);
+        assert!(metrics.line_height() >= metrics.ascent - metrics.descent);
+        assert!(fonts.glyph_metrics('i', Some("missing"), 16.0).is_err());
+    }
+
+    #[test]
+    fn test_load_font_from_embedded_bytes() {
+        let bytes = include_bytes!("../assets/fonts/DejaVuSans.ttf");
+        let mut fonts = FontSystem::new();
+        fonts.load_font_from_bytes("embedded", bytes).unwrap();
+        assert_eq!(fonts.get_default_font(), Some("embedded"));
+        assert!(fonts.glyph_metrics('m', None, 16.0).is_ok());
+
+        assert!(fonts.load_font_from_bytes("broken", &bytes[..64]).is_err());
+    }
+
+    #[test]
+    fn test_bitmap_font_covers_printable_ascii() {
+        let font = BitmapFont::new();
+        for c in ' '..='~' {
+            assert!(font.has_glyph(c));
+            let mut lit = 0;
+            font.draw_glyph(c, |col, row| {
+                assert!(col < BitmapFont::GLYPH_WIDTH && row < BitmapFont::GLYPH_HEIGHT);
+                lit += 1;
+            });
+            assert_eq!(lit == 0, c == ' ', "glyph for {:?}", c);
+        }
+
+        // no two printable characters may render the same
+        let mut seen = std::collections::HashMap::new();
+        for c in ' '..='~' {
+            if let Some(other) = seen.insert(font.glyph(c), c) {
+                panic!("{:?} and {:?} share a bitmap", other, c);
+            }
+        }
+        assert_ne!(font.glyph('9'), font.glyph('O'));
+        assert_eq!(font.glyph('Z')[BitmapFont::GLYPH_HEIGHT - 1], 0x7F);
+
+        assert!(!font.has_glyph('é'));
+        assert_eq!(font.glyph('é'), &FALLBACK_GLYPH);
+        assert_eq!(font.glyph('\n'), &FALLBACK_GLYPH);
+        assert!(font.pixel('é', 0, 0) && !font.pixel('é', 3, 4));
+    }
+}
@@ -2,12 +2,26 @@
 //!
 //! Main game loop with fixed timestep and frame rate management.
 
+use std::collections::VecDeque;
+use std::fmt;
 use std::time::{Duration, Instant};
 
+/// How the loop treats rendering when it falls behind the simulation
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum FrameSkip {
+    /// Render once per frame, however long it took
+    #[default]
+    Never,
+    /// Skip rendering on frames that needed more than one update,
+    /// at most this many frames in a row. Updates are never skipped.
+    UpTo(u32),
+}
+
 /// Game loop configuration
 pub struct GameLoopConfig {
     pub target_fps: u32,
     pub max_frame_time: Duration,
+    pub frame_skip: FrameSkip,
 }
 
 impl Default for GameLoopConfig {
@@ -15,57 +29,434 @@ impl Default for GameLoopConfig {
         Self {
             target_fps: 60,
             max_frame_time: Duration::from_millis(100),
+            frame_skip: FrameSkip::Never,
         }
     }
 }
 
+/// What a single `GameLoop::tick` did
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct FrameOutcome {
+    /// Fixed-timestep updates that ran
+    pub updates: u32,
+    /// Whether the render callback ran
+    pub rendered: bool,
+}
+
 /// Game loop runner
 pub struct GameLoop {
     config: GameLoopConfig,
     last_time: Instant,
     accumulator: Duration,
     frame_count: u64,
+    stats: FrameStats,
+    consecutive_skips: u32,
+    skipped_renders: u64,
 }
 
 impl GameLoop {
     pub fn new(config: GameLoopConfig) -> Self {
+        // anything slower than two target frames counts as a stutter
+        let stutter_threshold = 2.0 / config.target_fps.max(1) as f32;
         Self {
             config,
             last_time: Instant::now(),
             accumulator: Duration::ZERO,
             frame_count: 0,
+            stats: FrameStats::new(FrameStats::DEFAULT_CAPACITY, stutter_threshold),
+            consecutive_skips: 0,
+            skipped_renders: 0,
         }
     }
 
-    pub fn run<F>(&mut self, mut update_fn: F)
+    /// Fixed-timestep updates run so far
+    pub fn frame_count(&self) -> u64 {
+        self.frame_count
+    }
+
+    /// Renders dropped by the `FrameSkip` policy so far
+    pub fn skipped_renders(&self) -> u64 {
+        self.skipped_renders
+    }
+
+    /// Pacing statistics of recent real (unclamped) frame times
+    pub fn stats(&self) -> &FrameStats {
+        &self.stats
+    }
+
+    pub fn run<F>(&mut self, update_fn: F)
     where
         F: FnMut(f32),
     {
-        let target_frame_time = Duration::from_secs(1) / self.config.target_fps;
+        self.run_with_render(update_fn, |_| {});
+    }
 
+    /// Run forever, calling `render_fn` with the interpolation alpha after the updates
+    /// unless the `FrameSkip` policy drops the frame
+    pub fn run_with_render<F, R>(&mut self, mut update_fn: F, mut render_fn: R)
+    where
+        F: FnMut(f32),
+        R: FnMut(f32),
+    {
         loop {
             let current_time = Instant::now();
-            let mut delta_time = current_time.duration_since(self.last_time);
+            let delta_time = current_time.duration_since(self.last_time);
             self.last_time = current_time;
+            self.tick(delta_time, &mut update_fn, &mut render_fn);
+        }
+    }
 
-            // Prevent spiral of death
-            if delta_time > self.config.max_frame_time {
-                delta_time = self.config.max_frame_time;
-            }
+    /// Advance the loop by `elapsed` real time: run the fixed-timestep updates
+    /// that are due, then render unless the frame-skip policy says otherwise
+    pub fn tick<F, R>(
+        &mut self,
+        elapsed: Duration,
+        mut update_fn: F,
+        mut render_fn: R,
+    ) -> FrameOutcome
+    where
+        F: FnMut(f32),
+        R: FnMut(f32),
+    {
+        let target_frame_time = Duration::from_secs(1) / self.config.target_fps.max(1);
+        self.stats.record(elapsed.as_secs_f32());
+
+        // Prevent spiral of death
+        self.accumulator += elapsed.min(self.config.max_frame_time);
+
+        // Update with fixed timestep
+        let mut updates = 0;
+        while self.accumulator >= target_frame_time {
+            update_fn(target_frame_time.as_secs_f32());
+            self.accumulator -= target_frame_time;
+            self.frame_count += 1;
+            updates += 1;
+        }
+
+        // More than one update means the last frame overran its budget
+        let skip = match self.config.frame_skip {
+            FrameSkip::Never => false,
+            FrameSkip::UpTo(max) => updates > 1 && self.consecutive_skips < max,
+        };
+        if skip {
+            self.consecutive_skips += 1;
+            self.skipped_renders += 1;
+        } else {
+            self.consecutive_skips = 0;
+            render_fn(self.accumulator.as_secs_f32() / target_frame_time.as_secs_f32());
+        }
+
+        FrameOutcome {
+            updates,
+            rendered: !skip,
+        }
+    }
+}
+
+/// Ring buffer of recent frame durations for spotting frame spikes
+#[derive(Debug, Clone)]
+pub struct FrameStats {
+    /// Frame durations in seconds, oldest first
+    frames: VecDeque<f32>,
+    capacity: usize,
+    /// Frames longer than this many seconds count as stutters
+    pub stutter_threshold: f32,
+}
+
+/// Summary produced by `FrameStats::report`; durations in seconds
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct FrameReport {
+    pub frames: usize,
+    pub min: f32,
+    pub max: f32,
+    pub average: f32,
+    pub p99: f32,
+    pub stutters: usize,
+}
+
+impl fmt::Display for FrameReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} frames: min {:.2}ms, avg {:.2}ms, p99 {:.2}ms, max {:.2}ms, {} stutters",
+            self.frames,
+            self.min * 1000.0,
+            self.average * 1000.0,
+            self.p99 * 1000.0,
+            self.max * 1000.0,
+            self.stutters
+        )
+    }
+}
 
-            self.accumulator += delta_time;
+impl FrameStats {
+    /// Five seconds of history at 60 FPS
+    pub const DEFAULT_CAPACITY: usize = 300;
 
-            // Update with fixed timestep
-            while self.accumulator >= target_frame_time {
-                let dt = target_frame_time.as_secs_f32();
-                update_fn(dt);
-                self.accumulator -= target_frame_time;
-                self.frame_count += 1;
+    pub fn new(capacity: usize, stutter_threshold: f32) -> Self {
+        Self {
+            frames: VecDeque::with_capacity(capacity),
+            capacity: capacity.max(1),
+            stutter_threshold,
+        }
+    }
+
+    /// Record one frame, dropping the oldest once full
+    pub fn record(&mut self, duration: f32) {
+        if self.frames.len() == self.capacity {
+            self.frames.pop_front();
+        }
+        self.frames.push_back(duration);
+    }
+
+    pub fn len(&self) -> usize {
+        self.frames.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.frames.is_empty()
+    }
+
+    pub fn clear(&mut self) {
+        self.frames.clear();
+    }
+
+    pub fn min(&self) -> f32 {
+        self.frames.iter().copied().reduce(f32::min).unwrap_or(0.0)
+    }
+
+    pub fn max(&self) -> f32 {
+        self.frames.iter().copied().reduce(f32::max).unwrap_or(0.0)
+    }
+
+    pub fn average(&self) -> f32 {
+        if self.frames.is_empty() {
+            0.0
+        } else {
+            self.frames.iter().sum::<f32>() / self.frames.len() as f32
+        }
+    }
+
+    /// Nearest-rank percentile (`p` in 0..=100) of the recorded durations
+    pub fn percentile(&self, p: f32) -> f32 {
+        if self.frames.is_empty() {
+            return 0.0;
+        }
+        let mut sorted: Vec<f32> = self.frames.iter().copied().collect();
+        sorted.sort_by(f32::total_cmp);
+        let rank = (p.clamp(0.0, 100.0) / 100.0 * sorted.len() as f32).ceil() as usize;
+        sorted[rank.clamp(1, sorted.len()) - 1]
+    }
+
+    /// Recorded frames longer than `stutter_threshold`
+    pub fn stutters(&self) -> usize {
+        self.frames
+            .iter()
+            .filter(|&&duration| duration > self.stutter_threshold)
+            .count()
+    }
+
+    pub fn report(&self) -> FrameReport {
+        FrameReport {
+            frames: self.frames.len(),
+            min: self.min(),
+            max: self.max(),
+            average: self.average(),
+            p99: self.percentile(99.0),
+            stutters: self.stutters(),
+        }
+    }
+}
+
+/// A single step in a scripted `Sequence`
+pub enum SequenceStep {
+    /// Pause for the given number of seconds
+    Wait(f32),
+    /// Run a one-shot callback
+    Action(Box<dyn FnOnce()>),
+}
+
+/// Scripted series of waits and actions, advanced by `update`
+///
+/// Useful for intros and game-over flows: "show title, wait 2s, fade in menu".
+#[derive(Default)]
+pub struct Sequence {
+    steps: VecDeque<SequenceStep>,
+    elapsed: f32,
+}
+
+impl Sequence {
+    pub fn new() -> Self {
+        Self {
+            steps: VecDeque::new(),
+            elapsed: 0.0,
+        }
+    }
+
+    /// Append a step
+    pub fn push(&mut self, step: SequenceStep) {
+        self.steps.push_back(step);
+    }
+
+    /// Append a wait of `duration` seconds
+    pub fn wait(mut self, duration: f32) -> Self {
+        self.push(SequenceStep::Wait(duration));
+        self
+    }
+
+    /// Append an action
+    pub fn then<F: FnOnce() + 'static>(mut self, action: F) -> Self {
+        self.push(SequenceStep::Action(Box::new(action)));
+        self
+    }
+
+    /// Advance the sequence, running every action that becomes due.
+    ///
+    /// Time left over after a wait finishes carries into the following steps.
+    pub fn update(&mut self, dt: f32) {
+        let mut remaining = dt;
+        while let Some(step) = self.steps.front() {
+            match step {
+                SequenceStep::Wait(duration) => {
+                    let left = duration - self.elapsed;
+                    if remaining < left {
+                        self.elapsed += remaining;
+                        return;
+                    }
+                    remaining -= left;
+                    self.elapsed = 0.0;
+                    self.steps.pop_front();
+                }
+                SequenceStep::Action(_) => {
+                    if let Some(SequenceStep::Action(action)) = self.steps.pop_front() {
+                        action();
+                    }
+                }
             }
+        }
+    }
 
-            // Optional: render with interpolation
-            // let alpha = self.accumulator.as_secs_f32() / target_frame_time.as_secs_f32();
-            // render_fn(alpha);
+    /// Whether every step has completed
+    pub fn is_done(&self) -> bool {
+        self.steps.is_empty()
+    }
+
+    /// Number of steps still to run
+    pub fn remaining_steps(&self) -> usize {
+        self.steps.len()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::cell::Cell;
+    use std::rc::Rc;
+
+    #[test]
+    fn test_frame_stats_detect_spike() {
+        let mut stats = FrameStats::new(100, 1.0 / 30.0);
+        for _ in 0..99 {
+            stats.record(0.016);
+        }
+        stats.record(0.050);
+
+        let report = stats.report();
+        assert_eq!(report.frames, 100);
+        assert_eq!(report.stutters, 1);
+        assert_eq!((report.min, report.max), (0.016, 0.050));
+        // a single spike in 100 frames sits above the 99th percentile
+        assert_eq!(report.p99, 0.016);
+        assert!((report.average - 0.01634).abs() < 1e-5);
+
+        // a second spike pushes the oldest 16ms frame out and lands on p99
+        stats.record(0.040);
+        assert_eq!(stats.len(), 100);
+        assert_eq!(stats.stutters(), 2);
+        assert_eq!(stats.percentile(99.0), 0.040);
+        assert_eq!(stats.percentile(100.0), 0.050);
+        assert!(report.to_string().contains("1 stutters"));
+    }
+
+    #[test]
+    fn test_sequence_runs_actions_after_waits() {
+        let first = Rc::new(Cell::new(false));
+        let second = Rc::new(Cell::new(false));
+        let (f, s) = (first.clone(), second.clone());
+        let mut sequence = Sequence::new()
+            .wait(1.0)
+            .then(move || f.set(true))
+            .wait(1.0)
+            .then(move || s.set(true));
+
+        for _ in 0..3 {
+            sequence.update(0.25);
+        }
+        assert!(!first.get());
+
+        sequence.update(0.25);
+        assert!(first.get());
+        assert!(!second.get());
+
+        for _ in 0..3 {
+            sequence.update(0.25);
         }
+        assert!(!second.get());
+        assert!(!sequence.is_done());
+
+        sequence.update(0.25);
+        assert!(second.get());
+        assert!(sequence.is_done());
+    }
+
+    #[test]
+    fn test_frame_skip_drops_renders_not_updates() {
+        let mut game_loop = GameLoop::new(GameLoopConfig {
+            target_fps: 50,
+            frame_skip: FrameSkip::UpTo(1),
+            ..Default::default()
+        });
+        let budget = Duration::from_millis(20);
+        let (mut updates, mut renders) = (0, 0);
+
+        // render took three frame budgets: three updates are due
+        let outcome = game_loop.tick(budget * 3, |_| updates += 1, |_| renders += 1);
+        assert_eq!(
+            outcome,
+            FrameOutcome {
+                updates: 3,
+                rendered: false
+            }
+        );
+
+        // still behind, but only one skip in a row is allowed
+        let outcome = game_loop.tick(budget * 3, |_| updates += 1, |_| renders += 1);
+        assert_eq!(
+            outcome,
+            FrameOutcome {
+                updates: 3,
+                rendered: true
+            }
+        );
+
+        // caught up
+        let outcome = game_loop.tick(budget, |_| updates += 1, |_| renders += 1);
+        assert_eq!(
+            outcome,
+            FrameOutcome {
+                updates: 1,
+                rendered: true
+            }
+        );
+
+        assert_eq!((updates, renders), (7, 2));
+        assert_eq!(game_loop.frame_count(), 7);
+        assert_eq!(game_loop.skipped_renders(), 1);
+
+        let mut always = GameLoop::new(GameLoopConfig {
+            target_fps: 50,
+            ..Default::default()
+        });
+        assert!(always.tick(budget * 3, |_| {}, |_| {}).rendered);
     }
 }
@@ -3,6 +3,7 @@
 //! Provides a framework for managing different game states (menu, gameplay, pause, etc.)
 //! with clean transitions and state-specific logic.
 
+use specs::World;
 use std::collections::HashMap;
 
 /// Unique identifier for game states
@@ -50,6 +51,73 @@ pub enum StateTransition {
     Quit,
 }
 
+/// High-level phase of a game, for games that track their flow as a plain
+/// enum instead of registering `GameState` objects with a `StateManager`.
+///
+/// Game-specific screens (difficulty select, level complete, ...) use `Custom`.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Default)]
+pub enum GamePhase {
+    #[default]
+    Menu,
+    Playing,
+    Paused,
+    GameOver,
+    Custom(String),
+}
+
+impl GamePhase {
+    pub fn custom(name: impl Into<String>) -> Self {
+        GamePhase::Custom(name.into())
+    }
+
+    /// Whether this is the custom phase `name`
+    pub fn is_custom(&self, name: &str) -> bool {
+        matches!(self, GamePhase::Custom(custom) if custom == name)
+    }
+
+    /// Id of the matching built-in state (`MenuState`, `GameplayState`, ...),
+    /// or the custom name
+    pub fn id(&self) -> StateId {
+        match self {
+            GamePhase::Menu => "menu".to_string(),
+            GamePhase::Playing => "gameplay".to_string(),
+            GamePhase::Paused => "pause".to_string(),
+            GamePhase::GameOver => "game_over".to_string(),
+            GamePhase::Custom(name) => name.clone(),
+        }
+    }
+
+    /// Whether moving to `next` is one of the standard transitions:
+    /// menu -> playing, playing <-> paused, playing -> game over,
+    /// paused/game over -> menu and game over -> playing (restart).
+    /// Custom phases may be entered from and left to any phase.
+    pub fn can_transition_to(&self, next: &GamePhase) -> bool {
+        use GamePhase::*;
+        match (self, next) {
+            (Custom(from), Custom(to)) => from != to,
+            (Custom(_), _) | (_, Custom(_)) => true,
+            (Menu, Playing)
+            | (Playing, Paused)
+            | (Playing, GameOver)
+            | (Playing, Menu)
+            | (Paused, Playing)
+            | (Paused, Menu)
+            | (GameOver, Menu)
+            | (GameOver, Playing) => true,
+            _ => false,
+        }
+    }
+
+    /// Move to `next`, refusing transitions `can_transition_to` rejects
+    pub fn transition(&mut self, next: GamePhase) -> Result<(), String> {
+        if !self.can_transition_to(&next) {
+            return Err(format!("Invalid transition from {:?} to {:?}", self, next));
+        }
+        *self = next;
+        Ok(())
+    }
+}
+
 /// Context passed to states during updates
 pub struct StateContext {
     pub delta_time: f32,
@@ -75,11 +143,16 @@ impl StateContext {
     }
 }
 
+/// Callback run against the ECS world when a state is entered or exited
+pub type WorldHook = Box<dyn Fn(&mut World)>;
+
 /// Game state manager
 pub struct StateManager {
     states: HashMap<StateId, Box<dyn GameState>>,
     state_stack: Vec<StateId>,
     context: StateContext,
+    enter_hooks: HashMap<StateId, Vec<WorldHook>>,
+    exit_hooks: HashMap<StateId, Vec<WorldHook>>,
 }
 
 impl StateManager {
@@ -89,6 +162,73 @@ impl StateManager {
             states: HashMap::new(),
             state_stack: Vec::new(),
             context: StateContext::new(window_width, window_height),
+            enter_hooks: HashMap::new(),
+            exit_hooks: HashMap::new(),
+        }
+    }
+
+    /// Register a world hook run whenever `state` is entered via `apply_transition`
+    pub fn on_enter<F>(&mut self, state: impl Into<StateId>, hook: F)
+    where
+        F: Fn(&mut World) + 'static,
+    {
+        self.enter_hooks
+            .entry(state.into())
+            .or_default()
+            .push(Box::new(hook));
+    }
+
+    /// Register a world hook run whenever `state` is exited via `apply_transition`
+    pub fn on_exit<F>(&mut self, state: impl Into<StateId>, hook: F)
+    where
+        F: Fn(&mut World) + 'static,
+    {
+        self.exit_hooks
+            .entry(state.into())
+            .or_default()
+            .push(Box::new(hook));
+    }
+
+    /// Perform a transition and run the matching world hooks.
+    ///
+    /// Pushing a state does not exit the one below it, and popping back to a
+    /// state does not re-enter it. Returns `Ok(false)` on `Quit`.
+    pub fn apply_transition(
+        &mut self,
+        transition: StateTransition,
+        world: &mut World,
+    ) -> Result<bool, String> {
+        match transition {
+            StateTransition::None => {}
+            StateTransition::Switch(state_id) => {
+                let previous = self.state_stack.last().cloned();
+                self.switch_to(state_id.clone())?;
+                if let Some(previous) = previous {
+                    Self::run_hooks(&self.exit_hooks, &previous, world);
+                }
+                Self::run_hooks(&self.enter_hooks, &state_id, world);
+            }
+            StateTransition::Push(state_id) => {
+                self.push_state(state_id.clone())?;
+                Self::run_hooks(&self.enter_hooks, &state_id, world);
+            }
+            StateTransition::Pop => {
+                let popped = self.state_stack.last().cloned();
+                self.pop_state()?;
+                if let Some(popped) = popped {
+                    Self::run_hooks(&self.exit_hooks, &popped, world);
+                }
+            }
+            StateTransition::Quit => return Ok(false),
+        }
+        Ok(true)
+    }
+
+    fn run_hooks(hooks: &HashMap<StateId, Vec<WorldHook>>, state_id: &StateId, world: &mut World) {
+        if let Some(hooks) = hooks.get(state_id) {
+            for hook in hooks {
+                hook(world);
+            }
         }
     }
 
@@ -472,3 +612,80 @@ impl GameState for GameOverState {
         "game_over".to_string()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use specs::WorldExt;
+
+    #[derive(Default)]
+    struct Score(u32);
+
+    #[derive(Default)]
+    struct Resets(u32);
+
+    #[test]
+    fn test_enter_hook_resets_world_once() {
+        let mut world = World::new();
+        world.insert(Score(42));
+        world.insert(Resets(0));
+
+        let mut manager = StateManager::new(800, 600);
+        manager.register_state(Box::new(MenuState::new()));
+        manager.register_state(Box::new(GameplayState::new()));
+        manager.register_state(Box::new(PauseState::new()));
+        manager.switch_to("menu".to_string()).unwrap();
+        manager.on_enter("gameplay", |world: &mut World| {
+            world.write_resource::<Score>().0 = 0;
+            world.write_resource::<Resets>().0 += 1;
+        });
+
+        let running = manager
+            .apply_transition(StateTransition::Switch("gameplay".to_string()), &mut world)
+            .unwrap();
+        assert!(running);
+        assert_eq!(world.read_resource::<Score>().0, 0);
+        assert_eq!(world.read_resource::<Resets>().0, 1);
+
+        // Pausing and resuming does not re-enter gameplay
+        manager
+            .apply_transition(StateTransition::Push("pause".to_string()), &mut world)
+            .unwrap();
+        manager
+            .apply_transition(StateTransition::Pop, &mut world)
+            .unwrap();
+        assert_eq!(world.read_resource::<Resets>().0, 1);
+    }
+
+    #[test]
+    fn test_game_phase_transitions() {
+        let mut phase = GamePhase::default();
+        assert_eq!(phase, GamePhase::Menu);
+
+        for next in [
+            GamePhase::Playing,
+            GamePhase::Paused,
+            GamePhase::Playing,
+            GamePhase::GameOver,
+            GamePhase::Playing,
+            GamePhase::Paused,
+            GamePhase::Menu,
+        ] {
+            phase.transition(next.clone()).unwrap();
+            assert_eq!(phase, next);
+        }
+
+        assert!(phase.transition(GamePhase::Paused).is_err());
+        assert!(phase.transition(GamePhase::GameOver).is_err());
+        assert_eq!(phase, GamePhase::Menu);
+        assert_eq!(GamePhase::Playing.id(), GameplayState::new().id());
+
+        phase.transition(GamePhase::custom("difficulty")).unwrap();
+        assert!(phase.is_custom("difficulty"));
+        assert_eq!(phase.id(), "difficulty");
+        assert!(phase.transition(GamePhase::custom("difficulty")).is_err());
+        phase.transition(GamePhase::Playing).unwrap();
+        phase.transition(GamePhase::custom("level_complete")).unwrap();
+        assert!(!phase.is_custom("difficulty"));
+    }
+}
@@ -0,0 +1,6 @@
+//! Ready-made games
+//!
+//! Complete, configurable game rules built from the engine's components and
+//! systems. Demos wrap these with their own menus and effects.
+
+pub mod pong;
@@ -0,0 +1,714 @@
+//! Pong
+//!
+//! Paddle/ball entities, the input, AI and collision systems, and a `PongConfig`
+//! holding every tunable. Positions are the center of each paddle/ball, like
+//! their colliders; `BoundsSystem` keeps them between the top and bottom walls.
+//!
+//! ```no_run
+//! use modular_game_engine::games::pong::{PongConfig, PongGame};
+//! use modular_game_engine::renderer_2d::RenderContext;
+//!
+//! let config = PongConfig::default();
+//! let mut context = RenderContext::new_headless(config.width as usize, config.height as usize);
+//! let mut game = PongGame::new(config);
+//! while game.winner().is_none() {
+//!     game.update(1.0 / 60.0, &Default::default());
+//!     game.render(&mut context.renderer);
+//!     context.present().unwrap();
+//! }
+//! ```
+
+use crate::ecs::{GameWorldExt, Prefab};
+use crate::enhanced_ai::{AIDifficulty, AIErrorModel, ReactionBuffer};
+use crate::input_window::WindowInputState;
+use crate::renderer_2d::{Color, Renderer2D};
+use crate::{
+    Ball, BounceOnBounds, BoundsSystem, ClampToBounds, Collider, Paddle, PhysicsSystem, Position,
+    RngResource, Score, Time, Vec2, Velocity, WorldBounds,
+};
+use minifb::Key;
+use specs::{
+    Dispatcher, DispatcherBuilder, Entity, Join, Read, ReadStorage, System, World, WorldExt, Write,
+    WriteStorage,
+};
+
+/// Tunables for a Pong match, stored as a world resource
+#[derive(Debug, Clone)]
+pub struct PongConfig {
+    /// Field size in pixels
+    pub width: f32,
+    pub height: f32,
+    pub paddle_width: f32,
+    pub paddle_height: f32,
+    /// Gap between each paddle and its side of the field
+    pub paddle_margin: f32,
+    pub paddle_speed: f32,
+    pub ball_size: f32,
+    pub ball_speed: f32,
+    /// Ball speed cap, as a multiple of `ball_speed`
+    pub max_ball_speed_factor: f32,
+    /// Vertical speed added per unit of off-center paddle hit
+    pub spin: f32,
+    /// Largest serve angle off horizontal, in radians
+    pub max_serve_angle: f32,
+    /// AI paddle speed as a fraction of `paddle_speed`
+    pub ai_speed: f32,
+    /// How far the ball may be from the AI paddle center before it moves
+    pub ai_dead_zone: f32,
+    /// How many seconds late the AI sees the ball, and how often it re-aims
+    pub ai_reaction_delay: f32,
+    /// Largest AI aiming error in pixels
+    pub ai_error: f32,
+    pub max_score: u32,
+    pub up_key: Key,
+    pub down_key: Key,
+}
+
+impl Default for PongConfig {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl PongConfig {
+    pub fn new() -> Self {
+        Self {
+            width: 800.0,
+            height: 600.0,
+            paddle_width: 20.0,
+            paddle_height: 100.0,
+            paddle_margin: 50.0,
+            paddle_speed: 350.0,
+            ball_size: 15.0,
+            ball_speed: 450.0,
+            max_ball_speed_factor: 1.5,
+            spin: 50.0,
+            max_serve_angle: 0.4,
+            ai_speed: 0.8,
+            ai_dead_zone: 15.0,
+            ai_reaction_delay: AIDifficulty::Normal.reaction_delay(),
+            ai_error: AIDifficulty::Normal.error_magnitude(),
+            max_score: 5,
+            up_key: Key::W,
+            down_key: Key::S,
+        }
+    }
+
+    pub fn with_size(mut self, width: f32, height: f32) -> Self {
+        self.width = width;
+        self.height = height;
+        self
+    }
+
+    pub fn with_paddle_speed(mut self, speed: f32) -> Self {
+        self.paddle_speed = speed;
+        self
+    }
+
+    pub fn with_ball_speed(mut self, speed: f32) -> Self {
+        self.ball_speed = speed;
+        self
+    }
+
+    /// Take AI reaction delay and aiming error from a difficulty level
+    pub fn with_ai_difficulty(mut self, difficulty: AIDifficulty) -> Self {
+        self.ai_reaction_delay = difficulty.reaction_delay();
+        self.ai_error = difficulty.error_magnitude();
+        self
+    }
+
+    pub fn with_max_score(mut self, max_score: u32) -> Self {
+        self.max_score = max_score;
+        self
+    }
+
+    /// A ball served from the center of the field
+    pub fn ball_start(&self) -> Position {
+        Position::new(self.width / 2.0, self.height / 2.0)
+    }
+
+    /// Top and bottom walls; open at the sides so the ball can leave to score
+    pub fn bounds(&self) -> WorldBounds {
+        WorldBounds::new(
+            Vec2::new(f32::NEG_INFINITY, 0.0),
+            Vec2::new(f32::INFINITY, self.height),
+        )
+    }
+
+    /// Serve velocity towards `toward`'s side of the field
+    pub fn serve_velocity(
+        &self,
+        toward: PongSide,
+        speed_multiplier: f32,
+        rng: &mut RngResource,
+    ) -> Velocity {
+        serve_ball(
+            toward,
+            self.ball_speed * speed_multiplier,
+            self.max_serve_angle,
+            rng,
+        )
+    }
+}
+
+/// Which side won a point or the match
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PongSide {
+    Player,
+    Ai,
+}
+
+impl PongSide {
+    /// Horizontal direction towards this side: the player defends the left edge
+    pub fn direction(self) -> f32 {
+        match self {
+            PongSide::Player => -1.0,
+            PongSide::Ai => 1.0,
+        }
+    }
+}
+
+/// Steepest serve `serve_ball` allows, so a serve never bounces between the walls
+pub const MAX_SERVE_ANGLE: f32 = 75.0 * std::f32::consts::PI / 180.0;
+
+/// Velocity of `speed` towards `toward` at a random angle of at most `max_angle`
+/// radians off horizontal; `max_angle` is capped at `MAX_SERVE_ANGLE`
+pub fn serve_ball(toward: PongSide, speed: f32, max_angle: f32, rng: &mut RngResource) -> Velocity {
+    let max_angle = max_angle.abs().min(MAX_SERVE_ANGLE);
+    let angle = if max_angle > 0.0 {
+        rng.range(-max_angle, max_angle)
+    } else {
+        0.0
+    };
+    Velocity::new(
+        toward.direction() * speed * angle.cos(),
+        speed * angle.sin(),
+    )
+}
+
+/// Serves so far, bumped whenever the ball is put back in the center so
+/// systems holding ball history know to drop it
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct ServeCount(pub u32);
+
+/// The entities spawned by `spawn_entities`
+#[derive(Debug, Clone, Copy)]
+pub struct PongEntities {
+    pub player_paddle: Entity,
+    pub ai_paddle: Entity,
+    pub ball: Entity,
+}
+
+/// Moves the player paddle from the configured keys
+pub struct PongInputSystem;
+
+impl<'a> System<'a> for PongInputSystem {
+    type SystemData = (
+        WriteStorage<'a, Velocity>,
+        ReadStorage<'a, Paddle>,
+        Read<'a, WindowInputState>,
+        Read<'a, PongConfig>,
+    );
+
+    fn run(&mut self, (mut velocities, paddles, input, config): Self::SystemData) {
+        for (velocity, paddle) in (&mut velocities, &paddles).join() {
+            if paddle.player_controlled {
+                velocity.y = 0.0;
+                if input.keys_pressed.contains(&config.up_key) {
+                    velocity.y -= config.paddle_speed;
+                }
+                if input.keys_pressed.contains(&config.down_key) {
+                    velocity.y += config.paddle_speed;
+                }
+            }
+        }
+    }
+}
+
+/// Moves AI paddles towards where the ball was `ai_reaction_delay` seconds
+/// ago, speeding up when behind on score. Aiming error comes from the world's
+/// `RngResource`, so a seeded match replays exactly. The ball history is
+/// dropped on every serve.
+#[derive(Debug, Clone, Default)]
+pub struct PongAISystem {
+    error: AIErrorModel,
+    seen: ReactionBuffer,
+    /// `ServeCount` the history was recorded under
+    serve: u32,
+}
+
+impl<'a> System<'a> for PongAISystem {
+    type SystemData = (
+        ReadStorage<'a, Position>,
+        WriteStorage<'a, Velocity>,
+        ReadStorage<'a, Paddle>,
+        ReadStorage<'a, Ball>,
+        Read<'a, Time>,
+        Read<'a, Score>,
+        Read<'a, PongConfig>,
+        Write<'a, RngResource>,
+        Read<'a, ServeCount>,
+    );
+
+    fn run(
+        &mut self,
+        (positions, mut velocities, paddles, balls, time, score, config, mut rng, serves): Self::SystemData,
+    ) {
+        if serves.0 != self.serve {
+            self.serve = serves.0;
+            self.seen.clear();
+            self.error.reset();
+        }
+
+        if let Some(position) = ball_position(&positions, &balls) {
+            self.seen.delay = config.ai_reaction_delay;
+            self.seen.push(time.delta, position);
+        }
+        let ball_center = self
+            .seen
+            .delayed()
+            .map_or(config.height / 2.0, |center| center.y);
+
+        // Faster when losing, slower when comfortably ahead
+        let score_diff = score.player_score as i32 - score.ai_score as i32;
+        let multiplier = match score_diff {
+            -2..=2 => config.ai_speed,
+            3..=5 => config.ai_speed * 1.25,
+            _ => config.ai_speed * 0.75,
+        };
+        self.error.reaction_delay = config.ai_reaction_delay;
+        self.error.error_magnitude = config.ai_error;
+        let aim = self.error.update(time.delta, ball_center, &mut rng);
+
+        for (position, velocity, paddle) in (&positions, &mut velocities, &paddles).join() {
+            if paddle.player_controlled {
+                continue;
+            }
+            let diff = aim - position.y;
+            velocity.y = if diff.abs() > config.ai_dead_zone {
+                diff.signum() * config.paddle_speed * multiplier
+            } else {
+                0.0
+            };
+        }
+    }
+}
+
+/// Position of the first `Ball`, joined with its own `Position` so other
+/// entities stored earlier can't be picked up by mistake
+pub fn find_ball_position(world: &World) -> Option<Vec2> {
+    ball_position(&world.read_storage(), &world.read_storage())
+}
+
+fn ball_position(positions: &ReadStorage<Position>, balls: &ReadStorage<Ball>) -> Option<Vec2> {
+    (positions, balls)
+        .join()
+        .next()
+        .map(|(position, _)| position.as_vec2())
+}
+
+/// Ball/paddle bounces plus scoring. Walls and paddle limits are left to
+/// `BoundsSystem`.
+pub struct PongCollisionSystem;
+
+impl<'a> System<'a> for PongCollisionSystem {
+    type SystemData = (
+        WriteStorage<'a, Position>,
+        WriteStorage<'a, Velocity>,
+        ReadStorage<'a, Ball>,
+        ReadStorage<'a, Paddle>,
+        Write<'a, Score>,
+        Read<'a, PongConfig>,
+        Write<'a, RngResource>,
+        Write<'a, ServeCount>,
+    );
+
+    fn run(
+        &mut self,
+        (mut positions, mut velocities, balls, paddles, mut score, config, mut rng, mut serves): Self::SystemData,
+    ) {
+        let paddle_positions: Vec<Position> = (&positions, &paddles)
+            .join()
+            .map(|(position, _)| *position)
+            .collect();
+
+        for (position, velocity, _) in (&mut positions, &mut velocities, &balls).join() {
+            for paddle in &paddle_positions {
+                if !overlaps(position, config.ball_size, paddle, &config) {
+                    continue;
+                }
+                // Always send the ball back towards the field center
+                let on_left = paddle.x < config.width / 2.0;
+                velocity.x = if on_left {
+                    velocity.x.abs()
+                } else {
+                    -velocity.x.abs()
+                };
+
+                // Off-center hits add spin
+                velocity.y += (position.y - paddle.y) / (config.paddle_height / 2.0) * config.spin;
+
+                let speed = (velocity.x * velocity.x + velocity.y * velocity.y).sqrt();
+                let max_speed = config.ball_speed * config.max_ball_speed_factor;
+                if speed > max_speed {
+                    velocity.x *= max_speed / speed;
+                    velocity.y *= max_speed / speed;
+                }
+                break;
+            }
+
+            // Scoring once the ball is fully off the field; serve towards whoever conceded
+            let half_ball = config.ball_size / 2.0;
+            let conceded = if position.x < -half_ball {
+                score.ai_score += 1;
+                Some(PongSide::Player)
+            } else if position.x > config.width + half_ball {
+                score.player_score += 1;
+                Some(PongSide::Ai)
+            } else {
+                None
+            };
+            if let Some(side) = conceded {
+                *position = config.ball_start();
+                *velocity = config.serve_velocity(side, 1.0, &mut rng);
+                serves.0 += 1;
+            }
+        }
+    }
+}
+
+fn overlaps(ball: &Position, ball_size: f32, paddle: &Position, config: &PongConfig) -> bool {
+    (ball.x - paddle.x).abs() < (ball_size + config.paddle_width) / 2.0
+        && (ball.y - paddle.y).abs() < (ball_size + config.paddle_height) / 2.0
+}
+
+/// Template for a freshly served ball; spawn it at `PongConfig::ball_start`
+pub fn ball_prefab(config: &PongConfig, rng: &mut RngResource) -> Prefab {
+    Prefab::new()
+        .with(config.serve_velocity(PongSide::Player, 1.0, rng))
+        .with(Ball)
+        .with(BounceOnBounds)
+        .with(Collider::new_circle(config.ball_size / 2.0))
+}
+
+/// Insert `config` and spawn both paddles and the ball
+pub fn spawn_entities(world: &mut World, config: PongConfig) -> PongEntities {
+    let paddle_y = config.height / 2.0;
+    let paddle_x = config.paddle_margin + config.paddle_width / 2.0;
+    let paddle = Prefab::new()
+        .with(Velocity::new(0.0, 0.0))
+        .with(Paddle {
+            player_controlled: false,
+        })
+        .with(ClampToBounds)
+        .with(Collider::new_rectangle(
+            config.paddle_width,
+            config.paddle_height,
+        ));
+    let player_paddle = world.spawn_prefab_at(
+        &paddle.clone().with(Paddle {
+            player_controlled: true,
+        }),
+        Position::new(paddle_x, paddle_y),
+    );
+    let ai_paddle =
+        world.spawn_prefab_at(&paddle, Position::new(config.width - paddle_x, paddle_y));
+    let ball = ball_prefab(&config, &mut world.write_resource::<RngResource>());
+    let ball = world.spawn_prefab_at(&ball, config.ball_start());
+
+    world.insert(config.bounds());
+    world.insert(config);
+    if !world.has_value::<WindowInputState>() {
+        world.insert(WindowInputState::default());
+    }
+
+    PongEntities {
+        player_paddle,
+        ai_paddle,
+        ball,
+    }
+}
+
+/// Dispatcher running input, AI, physics, bounds and collision in order
+pub fn dispatcher() -> Dispatcher<'static, 'static> {
+    DispatcherBuilder::new()
+        .with(PongInputSystem, "pong_input", &[])
+        .with(PongAISystem::default(), "pong_ai", &["pong_input"])
+        .with(PhysicsSystem, "physics", &["pong_ai"])
+        .with(BoundsSystem, "bounds", &["physics"])
+        .with(PongCollisionSystem, "pong_collision", &["bounds"])
+        .build()
+}
+
+/// Re-serve every ball from the center towards the player
+pub fn reset_ball(world: &mut World, speed_multiplier: f32) {
+    let config = (*world.read_resource::<PongConfig>()).clone();
+    world
+        .entry::<ServeCount>()
+        .or_insert_with(ServeCount::default)
+        .0 += 1;
+    let mut rng = world.write_resource::<RngResource>();
+    let mut positions = world.write_storage::<Position>();
+    let mut velocities = world.write_storage::<Velocity>();
+    let balls = world.read_storage::<Ball>();
+    for (position, velocity, _) in (&mut positions, &mut velocities, &balls).join() {
+        *position = config.ball_start();
+        *velocity = config.serve_velocity(PongSide::Player, speed_multiplier, &mut rng);
+    }
+}
+
+/// A complete Pong match: world, entities and systems
+pub struct PongGame {
+    pub world: World,
+    pub entities: PongEntities,
+    dispatcher: Dispatcher<'static, 'static>,
+}
+
+impl PongGame {
+    pub fn new(config: PongConfig) -> Self {
+        let mut world = crate::init().expect("engine world initialization failed");
+        let entities = spawn_entities(&mut world, config);
+        let mut dispatcher = dispatcher();
+        dispatcher.setup(&mut world);
+
+        Self {
+            world,
+            entities,
+            dispatcher,
+        }
+    }
+
+    pub fn config(&self) -> PongConfig {
+        (*self.world.read_resource::<PongConfig>()).clone()
+    }
+
+    /// Advance the match by `delta_time` using this frame's input
+    pub fn update(&mut self, delta_time: f32, input: &WindowInputState) {
+        self.world.write_resource::<Time>().advance(delta_time);
+        *self.world.write_resource::<WindowInputState>() = input.clone();
+        self.dispatcher.dispatch(&self.world);
+        self.world.apply_commands();
+    }
+
+    /// `(player, ai)` points
+    pub fn score(&self) -> (u32, u32) {
+        let score = self.world.read_resource::<Score>();
+        (score.player_score, score.ai_score)
+    }
+
+    /// The side that reached `max_score`, if any
+    pub fn winner(&self) -> Option<PongSide> {
+        let max_score = self.world.read_resource::<PongConfig>().max_score;
+        match self.score() {
+            (player, _) if player >= max_score => Some(PongSide::Player),
+            (_, ai) if ai >= max_score => Some(PongSide::Ai),
+            _ => None,
+        }
+    }
+
+    /// Zero the score and serve a fresh ball
+    pub fn reset(&mut self, speed_multiplier: f32) {
+        *self.world.write_resource::<Score>() = Score::default();
+        reset_ball(&mut self.world, speed_multiplier);
+    }
+
+    /// Draw the field, paddles, ball and score
+    pub fn render(&self, renderer: &mut Renderer2D) {
+        let config = self.world.read_resource::<PongConfig>();
+        renderer.clear(Color::BLACK);
+        let center_x = (config.width / 2.0) as i32;
+        for y in (0..config.height as i32).step_by(20) {
+            renderer.draw_rect(center_x - 1, y, 2, 10, Color::rgb(128, 128, 128));
+        }
+
+        let positions = self.world.read_storage::<Position>();
+        let paddles = self.world.read_storage::<Paddle>();
+        let balls = self.world.read_storage::<Ball>();
+        for (position, _) in (&positions, &paddles).join() {
+            renderer.draw_rect(
+                (position.x - config.paddle_width / 2.0) as i32,
+                (position.y - config.paddle_height / 2.0) as i32,
+                config.paddle_width as i32,
+                config.paddle_height as i32,
+                Color::WHITE,
+            );
+        }
+        for (position, _) in (&positions, &balls).join() {
+            renderer.draw_rect(
+                (position.x - config.ball_size / 2.0) as i32,
+                (position.y - config.ball_size / 2.0) as i32,
+                config.ball_size as i32,
+                config.ball_size as i32,
+                Color::WHITE,
+            );
+        }
+
+        let (player, ai) = self.score();
+        let center_x = center_x.max(0) as usize;
+        renderer.draw_text(
+            &player.to_string(),
+            center_x.saturating_sub(60),
+            20,
+            Color::WHITE,
+            3,
+        );
+        renderer.draw_text(&ai.to_string(), center_x + 40, 20, Color::WHITE, 3);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_find_ball_position_skips_decoys() {
+        let mut world = crate::init().unwrap();
+        assert_eq!(find_ball_position(&world), None);
+
+        world
+            .create_entity_with_components()
+            .with(Position::new(1.0, 1.0))
+            .build();
+        world
+            .create_entity_with_components()
+            .with(Position::new(300.0, 200.0))
+            .with(Ball)
+            .build();
+        assert_eq!(find_ball_position(&world), Some(Vec2::new(300.0, 200.0)));
+    }
+
+    #[test]
+    fn test_serve_angle_stays_within_max() {
+        let mut rng = RngResource::new(3);
+        let max_angle = 0.3;
+        for toward in [PongSide::Player, PongSide::Ai] {
+            for _ in 0..200 {
+                let v = serve_ball(toward, 400.0, max_angle, &mut rng).as_vec2();
+                assert_eq!(v.x.signum(), toward.direction());
+                assert!((v.norm() - 400.0).abs() < 1e-2);
+                assert!((v.y / v.x).abs().atan() <= max_angle + 1e-5);
+            }
+        }
+
+        // near-vertical requests are capped
+        let v = serve_ball(PongSide::Ai, 1.0, std::f32::consts::FRAC_PI_2, &mut rng);
+        assert!(v.y.atan2(v.x).abs() <= MAX_SERVE_ANGLE + 1e-5);
+    }
+
+    #[test]
+    fn test_ai_forgets_ball_history_on_serve() {
+        let config = PongConfig {
+            ai_error: 0.0,
+            ai_reaction_delay: 0.2,
+            max_serve_angle: 0.0,
+            ..PongConfig::default()
+        };
+        let mut game = PongGame::new(config);
+        let set_position = |world: &World, entity, y| {
+            let mut positions = world.write_storage::<Position>();
+            let position = positions.get_mut(entity).unwrap();
+            position.y = y;
+        };
+
+        // a stationary ball high up fills the AI's history
+        let ball = game.entities.ball;
+        game.world
+            .write_storage::<Velocity>()
+            .insert(ball, Velocity::new(0.0, 0.0))
+            .unwrap();
+        let input = WindowInputState::default();
+        for _ in 0..30 {
+            set_position(&game.world, ball, 100.0);
+            game.update(1.0 / 60.0, &input);
+        }
+
+        // re-served at mid-field (below the paddle), the AI goes for the new
+        // position rather than the one it remembers
+        set_position(&game.world, game.entities.ai_paddle, 150.0);
+        reset_ball(&mut game.world, 1.0);
+        game.update(1.0 / 60.0, &input);
+        let velocities = game.world.read_storage::<Velocity>();
+        assert!(velocities.get(game.entities.ai_paddle).unwrap().y > 0.0);
+    }
+
+    #[test]
+    fn test_headless_pong_ai_scores() {
+        let config = PongConfig {
+            max_serve_angle: 0.0,
+            ..PongConfig::default()
+        };
+        let mut context = crate::renderer_2d::RenderContext::new_headless(
+            config.width as usize,
+            config.height as usize,
+        );
+        let mut game = PongGame::new(config);
+
+        // Park the idle player paddle out of the ball's path
+        game.world
+            .write_storage::<Position>()
+            .get_mut(game.entities.player_paddle)
+            .unwrap()
+            .y = 0.0;
+
+        let input = WindowInputState::default();
+        let mut ticks = 0;
+        while game.score().1 == 0 && ticks < 600 {
+            game.update(1.0 / 60.0, &input);
+            game.render(&mut context.renderer);
+            context.present().unwrap();
+            ticks += 1;
+        }
+
+        assert_eq!(game.score(), (0, 1), "AI should score within 10 seconds");
+        assert_eq!(game.winner(), None);
+    }
+
+    #[test]
+    fn test_paddles_and_ball_stay_between_walls() {
+        let config = PongConfig::default();
+        let mut game = PongGame::new(config.clone());
+        let ball = game.entities.ball;
+        {
+            let mut positions = game.world.write_storage::<Position>();
+            positions.get_mut(ball).unwrap().y = config.height - 10.0;
+            let mut velocities = game.world.write_storage::<Velocity>();
+            *velocities.get_mut(ball).unwrap() = Velocity::new(0.0, 600.0);
+        }
+
+        let mut input = WindowInputState::default();
+        input.keys_pressed.insert(config.up_key);
+        for _ in 0..120 {
+            game.update(1.0 / 60.0, &input);
+        }
+
+        let positions = game.world.read_storage::<Position>();
+        let paddle = positions.get(game.entities.player_paddle).unwrap();
+        assert_eq!(paddle.y, config.paddle_height / 2.0);
+        let ball_y = positions.get(ball).unwrap().y;
+        assert!(
+            ball_y >= config.ball_size / 2.0 && ball_y <= config.height - config.ball_size / 2.0
+        );
+        let velocities = game.world.read_storage::<Velocity>();
+        assert_eq!(velocities.get(game.entities.player_paddle).unwrap().y, 0.0);
+    }
+
+    #[test]
+    fn test_render_narrow_field() {
+        let mut config = PongConfig::new().with_size(100.0, 80.0);
+        config.paddle_margin = 5.0;
+        config.paddle_width = 4.0;
+        config.paddle_height = 20.0;
+        config.ball_size = 6.0;
+        let game = PongGame::new(config);
+        let mut renderer = Renderer2D::new(100, 80);
+        game.render(&mut renderer);
+
+        // both paddles and the ball land inside the buffer
+        assert_eq!(renderer.get_pixel(7, 40), Some(Color::WHITE));
+        assert_eq!(renderer.get_pixel(92, 40), Some(Color::WHITE));
+        assert_eq!(renderer.get_pixel(50, 40), Some(Color::WHITE));
+        assert_eq!(renderer.get_pixel(7, 60), Some(Color::BLACK));
+        // the AI score is drawn at the right edge, clipped to the buffer
+        assert!((90..100).any(|x| (20..47).any(|y| renderer.get_pixel(x, y) == Some(Color::WHITE))));
+    }
+}
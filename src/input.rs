@@ -2,8 +2,11 @@
 //!
 //! User input handling with keyboard, mouse, and gamepad support.
 
+use crate::input_window::WindowInputState;
+use minifb::Key;
 use specs::{Component, DenseVecStorage};
-use std::collections::HashSet;
+use std::collections::{BTreeMap, HashSet};
+use std::path::Path;
 
 /// Input action mapping
 #[derive(Component, Debug, Clone)]
@@ -38,3 +41,649 @@ impl InputManager {
         self.pressed_keys.contains(&key)
     }
 }
+
+/// Every bindable keyboard key, used to parse key names
+pub const ALL_KEYS: &[Key] = &[
+    Key::Key0,
+    Key::Key1,
+    Key::Key2,
+    Key::Key3,
+    Key::Key4,
+    Key::Key5,
+    Key::Key6,
+    Key::Key7,
+    Key::Key8,
+    Key::Key9,
+    Key::A,
+    Key::B,
+    Key::C,
+    Key::D,
+    Key::E,
+    Key::F,
+    Key::G,
+    Key::H,
+    Key::I,
+    Key::J,
+    Key::K,
+    Key::L,
+    Key::M,
+    Key::N,
+    Key::O,
+    Key::P,
+    Key::Q,
+    Key::R,
+    Key::S,
+    Key::T,
+    Key::U,
+    Key::V,
+    Key::W,
+    Key::X,
+    Key::Y,
+    Key::Z,
+    Key::F1,
+    Key::F2,
+    Key::F3,
+    Key::F4,
+    Key::F5,
+    Key::F6,
+    Key::F7,
+    Key::F8,
+    Key::F9,
+    Key::F10,
+    Key::F11,
+    Key::F12,
+    Key::F13,
+    Key::F14,
+    Key::F15,
+    Key::Down,
+    Key::Left,
+    Key::Right,
+    Key::Up,
+    Key::Apostrophe,
+    Key::Backquote,
+    Key::Backslash,
+    Key::Comma,
+    Key::Equal,
+    Key::LeftBracket,
+    Key::Minus,
+    Key::Period,
+    Key::RightBracket,
+    Key::Semicolon,
+    Key::Slash,
+    Key::Backspace,
+    Key::Delete,
+    Key::End,
+    Key::Enter,
+    Key::Escape,
+    Key::Home,
+    Key::Insert,
+    Key::Menu,
+    Key::PageDown,
+    Key::PageUp,
+    Key::Pause,
+    Key::Space,
+    Key::Tab,
+    Key::NumLock,
+    Key::CapsLock,
+    Key::ScrollLock,
+    Key::LeftShift,
+    Key::RightShift,
+    Key::LeftCtrl,
+    Key::RightCtrl,
+    Key::NumPad0,
+    Key::NumPad1,
+    Key::NumPad2,
+    Key::NumPad3,
+    Key::NumPad4,
+    Key::NumPad5,
+    Key::NumPad6,
+    Key::NumPad7,
+    Key::NumPad8,
+    Key::NumPad9,
+    Key::NumPadDot,
+    Key::NumPadSlash,
+    Key::NumPadAsterisk,
+    Key::NumPadMinus,
+    Key::NumPadPlus,
+    Key::NumPadEnter,
+    Key::LeftAlt,
+    Key::RightAlt,
+    Key::LeftSuper,
+    Key::RightSuper,
+];
+
+/// Parse a key name as written by `key_name` (case-insensitive)
+pub fn parse_key(name: &str) -> Option<Key> {
+    ALL_KEYS
+        .iter()
+        .copied()
+        .find(|key| key_name(*key).eq_ignore_ascii_case(name))
+}
+
+/// Display/config name of a key, e.g. `Enter` or `Key1`
+pub fn key_name(key: Key) -> String {
+    format!("{:?}", key)
+}
+
+/// Named actions bound to one or more keys.
+///
+/// Saved as plain text, one action per line:
+///
+/// ```text
+/// # comments and blank lines are ignored
+/// confirm = Enter, Space
+/// move_up = W, Up
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ActionMap {
+    bindings: BTreeMap<String, Vec<Key>>,
+}
+
+impl ActionMap {
+    pub fn new() -> Self {
+        Self {
+            bindings: BTreeMap::new(),
+        }
+    }
+
+    /// Add `key` to `action`'s bindings
+    pub fn bind(&mut self, action: &str, key: Key) {
+        let keys = self.bindings.entry(action.to_string()).or_default();
+        if !keys.contains(&key) {
+            keys.push(key);
+        }
+    }
+
+    /// Replace all of `action`'s bindings
+    pub fn set_bindings(&mut self, action: &str, keys: Vec<Key>) {
+        self.bindings.insert(action.to_string(), keys);
+    }
+
+    /// Remove `key` from `action`
+    pub fn unbind(&mut self, action: &str, key: Key) {
+        if let Some(keys) = self.bindings.get_mut(action) {
+            keys.retain(|bound| *bound != key);
+        }
+    }
+
+    /// Keys bound to `action`
+    pub fn keys(&self, action: &str) -> &[Key] {
+        self.bindings.get(action).map_or(&[], Vec::as_slice)
+    }
+
+    /// All actions, in name order
+    pub fn actions(&self) -> impl Iterator<Item = &str> {
+        self.bindings.keys().map(String::as_str)
+    }
+
+    /// Whether any key bound to `action` is held
+    pub fn is_active(&self, action: &str, input: &WindowInputState) -> bool {
+        self.keys(action)
+            .iter()
+            .any(|key| input.is_key_pressed(*key))
+    }
+
+    /// Whether any key bound to `action` was pressed this frame
+    pub fn is_just_pressed(&self, action: &str, input: &WindowInputState) -> bool {
+        self.keys(action)
+            .iter()
+            .any(|key| input.is_key_just_pressed(*key))
+    }
+
+    /// Serialize to the text format described on the type
+    pub fn to_config_string(&self) -> String {
+        let mut out = String::new();
+        for (action, keys) in &self.bindings {
+            let names: Vec<String> = keys.iter().map(|key| key_name(*key)).collect();
+            out.push_str(&format!("{} = {}\n", action, names.join(", ")));
+        }
+        out
+    }
+
+    /// Parse the text format. Unknown key names and malformed lines are
+    /// skipped and reported in the returned warnings.
+    pub fn from_config_str(text: &str) -> (Self, Vec<String>) {
+        let mut map = Self::new();
+        let mut warnings = Vec::new();
+        for (number, line) in text.lines().enumerate() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            let Some((action, keys)) = line.split_once('=') else {
+                warnings.push(format!("line {}: expected `action = keys`", number + 1));
+                continue;
+            };
+            let action = action.trim();
+            let mut bound = Vec::new();
+            for name in keys
+                .split(',')
+                .map(str::trim)
+                .filter(|name| !name.is_empty())
+            {
+                match parse_key(name) {
+                    Some(key) => bound.push(key),
+                    None => warnings.push(format!(
+                        "line {}: unknown key `{}` for `{}`",
+                        number + 1,
+                        name,
+                        action
+                    )),
+                }
+            }
+            map.set_bindings(action, bound);
+        }
+        (map, warnings)
+    }
+
+    /// Write the bindings to `path`, creating its directory if needed
+    pub fn save_to_file(&self, path: impl AsRef<Path>) -> Result<(), String> {
+        let path = path.as_ref();
+        if let Some(dir) = path.parent() {
+            std::fs::create_dir_all(dir).map_err(|e| e.to_string())?;
+        }
+        std::fs::write(path, self.to_config_string()).map_err(|e| e.to_string())
+    }
+
+    /// Load bindings from `path`; unknown keys are skipped with a warning on stderr
+    pub fn load_from_file(path: impl AsRef<Path>) -> Result<Self, String> {
+        let text = std::fs::read_to_string(path).map_err(|e| e.to_string())?;
+        let (map, warnings) = Self::from_config_str(&text);
+        for warning in warnings {
+            eprintln!("Warning: key bindings {}", warning);
+        }
+        Ok(map)
+    }
+}
+
+/// Named `ActionMap`s for local multiplayer, one per player.
+///
+/// Profiles keep the order they were added in, which is the player index
+/// `for_player` resolves. Saved as one file, each profile's bindings under a
+/// `[name]` header:
+///
+/// ```text
+/// [p1]
+/// move_up = W
+///
+/// [p2]
+/// move_up = Up
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ControlProfiles {
+    profiles: Vec<(String, ActionMap)>,
+}
+
+impl ControlProfiles {
+    pub fn new() -> Self {
+        Self {
+            profiles: Vec::new(),
+        }
+    }
+
+    /// P1 on W/S and P2 on Up/Down, both with `move_up` and `move_down`
+    pub fn two_player() -> Self {
+        let mut profiles = Self::new();
+        for (name, up, down) in [("p1", Key::W, Key::S), ("p2", Key::Up, Key::Down)] {
+            let mut map = ActionMap::new();
+            map.bind("move_up", up);
+            map.bind("move_down", down);
+            profiles.insert(name, map);
+        }
+        profiles
+    }
+
+    /// Add or replace the profile called `name`, returning its player index
+    pub fn insert(&mut self, name: &str, map: ActionMap) -> usize {
+        if let Some(index) = self.index_of(name) {
+            self.profiles[index].1 = map;
+            return index;
+        }
+        self.profiles.push((name.to_string(), map));
+        self.profiles.len() - 1
+    }
+
+    /// Player index of the profile called `name`
+    pub fn index_of(&self, name: &str) -> Option<usize> {
+        self.profiles
+            .iter()
+            .position(|(profile, _)| profile == name)
+    }
+
+    pub fn get(&self, name: &str) -> Option<&ActionMap> {
+        self.index_of(name).map(|index| &self.profiles[index].1)
+    }
+
+    pub fn get_mut(&mut self, name: &str) -> Option<&mut ActionMap> {
+        let index = self.index_of(name)?;
+        Some(&mut self.profiles[index].1)
+    }
+
+    /// Bindings for player `index` (0 is P1)
+    pub fn for_player(&self, index: usize) -> Option<&ActionMap> {
+        self.profiles.get(index).map(|(_, map)| map)
+    }
+
+    /// Profile names in player order
+    pub fn names(&self) -> impl Iterator<Item = &str> {
+        self.profiles.iter().map(|(name, _)| name.as_str())
+    }
+
+    pub fn len(&self) -> usize {
+        self.profiles.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.profiles.is_empty()
+    }
+
+    /// Whether `action` is held for `player`; false for unknown players
+    pub fn is_active(&self, player: usize, action: &str, input: &WindowInputState) -> bool {
+        self.for_player(player)
+            .is_some_and(|map| map.is_active(action, input))
+    }
+
+    /// Whether `action` was pressed this frame for `player`
+    pub fn is_just_pressed(&self, player: usize, action: &str, input: &WindowInputState) -> bool {
+        self.for_player(player)
+            .is_some_and(|map| map.is_just_pressed(action, input))
+    }
+
+    /// Serialize every profile to the text format described on the type
+    pub fn to_config_string(&self) -> String {
+        let sections: Vec<String> = self
+            .profiles
+            .iter()
+            .map(|(name, map)| format!("[{}]\n{}", name, map.to_config_string()))
+            .collect();
+        sections.join("\n")
+    }
+
+    /// Parse the text format. Bindings outside a `[name]` section are skipped,
+    /// and every problem is reported in the returned warnings.
+    pub fn from_config_str(text: &str) -> (Self, Vec<String>) {
+        let mut profiles = Self::new();
+        let mut warnings = Vec::new();
+        // (name, section text padded so `ActionMap` warnings keep file line numbers)
+        let mut section: Option<(String, String)> = None;
+        let mut finish = |section: Option<(String, String)>, profiles: &mut Self| {
+            if let Some((name, body)) = section {
+                let (map, section_warnings) = ActionMap::from_config_str(&body);
+                warnings.extend(
+                    section_warnings
+                        .into_iter()
+                        .map(|warning| format!("[{}] {}", name, warning)),
+                );
+                profiles.insert(&name, map);
+            }
+        };
+        let mut stray = Vec::new();
+        for (number, line) in text.lines().enumerate() {
+            let trimmed = line.trim();
+            if let Some(name) = trimmed
+                .strip_prefix('[')
+                .and_then(|rest| rest.strip_suffix(']'))
+            {
+                finish(section.take(), &mut profiles);
+                section = Some((name.trim().to_string(), "\n".repeat(number + 1)));
+            } else if let Some((_, body)) = &mut section {
+                body.push_str(line);
+                body.push('\n');
+            } else if !trimmed.is_empty() && !trimmed.starts_with('#') {
+                stray.push(format!("line {}: binding outside a [profile]", number + 1));
+            }
+        }
+        finish(section, &mut profiles);
+        stray.extend(warnings);
+        (profiles, stray)
+    }
+
+    pub fn save_to_file(&self, path: impl AsRef<Path>) -> Result<(), String> {
+        std::fs::write(path, self.to_config_string()).map_err(|e| e.to_string())
+    }
+
+    /// Load all profiles from `path`; problems are reported as warnings on stderr
+    pub fn load_from_file(path: impl AsRef<Path>) -> Result<Self, String> {
+        let text = std::fs::read_to_string(path).map_err(|e| e.to_string())?;
+        let (profiles, warnings) = Self::from_config_str(&text);
+        for warning in warnings {
+            eprintln!("Warning: control profiles {}", warning);
+        }
+        Ok(profiles)
+    }
+}
+
+/// Recent action presses, so a press slightly before it can be acted on still counts.
+///
+/// Advance it by the frame delta, `press` (or `record`) actions as they happen,
+/// and `consume` them when the game is ready to act.
+#[derive(Debug, Clone)]
+pub struct InputBuffer {
+    presses: Vec<(String, f32)>,
+    now: f32,
+    /// Presses older than this many seconds are dropped on `advance`
+    pub max_age: f32,
+}
+
+impl Default for InputBuffer {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl InputBuffer {
+    pub fn new() -> Self {
+        Self {
+            presses: Vec::new(),
+            now: 0.0,
+            max_age: 1.0,
+        }
+    }
+
+    /// Current buffer time in seconds
+    pub fn now(&self) -> f32 {
+        self.now
+    }
+
+    /// Move the buffer clock forward and forget presses older than `max_age`
+    pub fn advance(&mut self, delta: f32) {
+        self.now += delta;
+        let (now, max_age) = (self.now, self.max_age);
+        self.presses.retain(|(_, at)| now - at <= max_age);
+    }
+
+    /// Record a press of `action` at the current time
+    pub fn press(&mut self, action: &str) {
+        self.presses.push((action.to_string(), self.now));
+    }
+
+    /// Record every action in `actions` whose key was pressed this frame
+    pub fn record(&mut self, actions: &ActionMap, input: &WindowInputState) {
+        let pressed: Vec<String> = actions
+            .actions()
+            .filter(|action| actions.is_just_pressed(action, input))
+            .map(str::to_string)
+            .collect();
+        for action in pressed {
+            self.press(&action);
+        }
+    }
+
+    /// Whether `action` was pressed within the last `window` seconds; the press is
+    /// removed so it only triggers once
+    pub fn consume(&mut self, action: &str, window: f32) -> bool {
+        let now = self.now;
+        let found = self
+            .presses
+            .iter()
+            .rposition(|(name, at)| name == action && now - at <= window);
+        if let Some(index) = found {
+            self.presses.remove(index);
+        }
+        found.is_some()
+    }
+
+    /// Whether `action` was pressed within `window` seconds, without consuming it
+    pub fn peek(&self, action: &str, window: f32) -> bool {
+        self.presses
+            .iter()
+            .any(|(name, at)| name == action && self.now - at <= window)
+    }
+
+    pub fn clear(&mut self) {
+        self.presses.clear();
+    }
+}
+
+/// Response curve applied to analog axis values after the dead zone
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Curve {
+    Linear,
+    /// Finer control near center
+    Quadratic,
+    /// Even finer control near center
+    Cubic,
+}
+
+impl Curve {
+    /// Map a magnitude in `0.0..=1.0` through the curve
+    pub fn apply(self, value: f32) -> f32 {
+        match self {
+            Curve::Linear => value,
+            Curve::Quadratic => value * value,
+            Curve::Cubic => value * value * value,
+        }
+    }
+}
+
+/// Dead zone and response curve for one analog axis
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct AxisConfig {
+    /// Raw magnitudes at or below this are treated as zero
+    pub dead_zone: f32,
+    pub response_curve: Curve,
+}
+
+impl Default for AxisConfig {
+    fn default() -> Self {
+        Self {
+            dead_zone: 0.1,
+            response_curve: Curve::Linear,
+        }
+    }
+}
+
+impl AxisConfig {
+    pub fn new(dead_zone: f32, response_curve: Curve) -> Self {
+        Self {
+            dead_zone,
+            response_curve,
+        }
+    }
+
+    /// Turn a raw axis value in `-1.0..=1.0` into a game value: zero inside the
+    /// dead zone, with the remaining range rescaled to `0.0..=1.0` and passed
+    /// through the response curve. The sign is preserved.
+    pub fn apply_axis(&self, raw: f32) -> f32 {
+        let dead_zone = self.dead_zone.clamp(0.0, 0.99);
+        let magnitude = raw.abs().min(1.0);
+        if magnitude <= dead_zone {
+            return 0.0;
+        }
+        let remapped = (magnitude - dead_zone) / (1.0 - dead_zone);
+        self.response_curve.apply(remapped).copysign(raw)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_action_map_round_trips_through_file() {
+        let mut map = ActionMap::new();
+        map.bind("confirm", Key::Enter);
+        map.bind("confirm", Key::Space);
+        map.bind("move_up", Key::W);
+        map.bind("pause", Key::Escape);
+
+        let dir = std::env::temp_dir().join(format!("bindings_{}", std::process::id()));
+        let path = dir.join("bindings.txt");
+        map.save_to_file(&path).unwrap();
+        let loaded = ActionMap::load_from_file(&path).unwrap();
+        std::fs::remove_dir_all(&dir).unwrap();
+
+        assert_eq!(loaded, map);
+        assert_eq!(loaded.keys("confirm"), &[Key::Enter, Key::Space]);
+    }
+
+    #[test]
+    fn test_unknown_keys_are_skipped() {
+        let (map, warnings) = ActionMap::from_config_str("jump = Space, Hyperdrive\nbroken line\n");
+        assert_eq!(map.keys("jump"), &[Key::Space]);
+        assert_eq!(warnings.len(), 2);
+    }
+
+    #[test]
+    fn test_input_buffer_window() {
+        let mut buffer = InputBuffer::new();
+        buffer.press("launch");
+        buffer.advance(0.05);
+        assert!(buffer.peek("launch", 0.1));
+        assert!(buffer.consume("launch", 0.1));
+        // consumed presses don't trigger twice
+        assert!(!buffer.consume("launch", 0.1));
+
+        buffer.press("launch");
+        buffer.advance(0.2);
+        assert!(!buffer.consume("launch", 0.1));
+        assert!(!buffer.consume("jump", 1.0));
+    }
+
+    #[test]
+    fn test_dead_zone_zeroes_small_input() {
+        let axis = AxisConfig::new(0.2, Curve::Linear);
+        assert_eq!(axis.apply_axis(0.1), 0.0);
+        assert_eq!(axis.apply_axis(-0.2), 0.0);
+        assert!((axis.apply_axis(0.6) - 0.5).abs() < 1e-6);
+        assert_eq!(axis.apply_axis(-1.0), -1.0);
+    }
+
+    #[test]
+    fn test_quadratic_curve() {
+        let axis = AxisConfig::new(0.0, Curve::Quadratic);
+        assert_eq!(axis.apply_axis(0.5), 0.25);
+        assert_eq!(axis.apply_axis(-0.5), -0.25);
+        assert_eq!(AxisConfig::new(0.0, Curve::Cubic).apply_axis(0.5), 0.125);
+    }
+
+    #[test]
+    fn test_control_profiles_per_player() {
+        let profiles = ControlProfiles::two_player();
+        assert_eq!(profiles.get("p1").unwrap().keys("move_up"), &[Key::W]);
+        assert_eq!(profiles.get("p2").unwrap().keys("move_up"), &[Key::Up]);
+
+        let mut input = WindowInputState::default();
+        input.keys_pressed.insert(Key::Up);
+        assert!(!profiles.is_active(0, "move_up", &input));
+        assert!(profiles.is_active(1, "move_up", &input));
+        assert!(!profiles.is_active(1, "move_down", &input));
+        assert!(!profiles.is_active(2, "move_up", &input));
+
+        let path = std::env::temp_dir().join(format!("profiles_{}.txt", std::process::id()));
+        profiles.save_to_file(&path).unwrap();
+        let loaded = ControlProfiles::load_from_file(&path).unwrap();
+        std::fs::remove_file(&path).unwrap();
+        assert_eq!(loaded, profiles);
+        assert_eq!(loaded.names().collect::<Vec<_>>(), ["p1", "p2"]);
+
+        let (_, warnings) = ControlProfiles::from_config_str("jump = Space\n[p1]\nfire = Laser\n");
+        assert_eq!(
+            warnings,
+            [
+                "line 1: binding outside a [profile]",
+                "[p1] line 3: unknown key `Laser` for `fire`"
+            ]
+        );
+    }
+}
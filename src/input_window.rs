@@ -3,6 +3,9 @@
 //! Enhanced input system that integrates with window management.
 //! Provides keyboard, mouse, and window event handling.
 
+use crate::renderer_2d::RenderContext;
+use crate::rendering::Camera2D;
+use crate::Vec2;
 use minifb::Key;
 use std::collections::HashSet;
 
@@ -18,6 +21,8 @@ pub struct WindowInputState {
     pub mouse_buttons_just_pressed: HashSet<MouseButton>,
     pub window_focused: bool,
     pub window_resized: Option<(usize, usize)>,
+    /// Vertical mouse wheel movement this frame; positive is away from the user
+    pub scroll_delta: f32,
 }
 
 impl Default for WindowInputState {
@@ -32,6 +37,7 @@ impl Default for WindowInputState {
             mouse_buttons_just_pressed: HashSet::new(),
             window_focused: true,
             window_resized: None,
+            scroll_delta: 0.0,
         }
     }
 }
@@ -67,6 +73,19 @@ impl WindowInputState {
         self.mouse_position
     }
 
+    /// Mouse position in the renderer's logical pixels, undoing `ctx`'s integer
+    /// scaling or letterboxing; use this for UI hit-testing
+    pub fn mouse_pos_logical(&self, ctx: &RenderContext) -> (i32, i32) {
+        ctx.window_to_logical(self.mouse_position.0, self.mouse_position.1)
+    }
+
+    /// World-space point under the mouse, seen through `camera` in `ctx`.
+    /// `camera.viewport_size` should be the renderer's logical size.
+    pub fn mouse_world(&self, ctx: &RenderContext, camera: &Camera2D) -> Vec2 {
+        let (x, y) = self.mouse_pos_logical(ctx);
+        camera.screen_to_world(Vec2::new(x as f32, y as f32))
+    }
+
     /// Get mouse delta as tuple
     pub fn mouse_delta(&self) -> (i32, i32) {
         self.mouse_delta
@@ -79,6 +98,7 @@ impl WindowInputState {
         self.mouse_delta = (0, 0);
         self.mouse_buttons_just_pressed.clear();
         self.window_resized = None;
+        self.scroll_delta = 0.0;
     }
 }
 
@@ -90,9 +110,67 @@ pub enum MouseButton {
     Middle,
 }
 
+/// Left-button drag gesture tracking
+#[derive(Debug, Clone)]
+pub struct DragState {
+    /// Where the left button went down, while it is held
+    pub start: Option<(i32, i32)>,
+    /// Latest cursor position while the button is held
+    pub current: (i32, i32),
+    /// Distance in pixels the cursor must travel before a press becomes a drag
+    pub threshold: f32,
+    dragging: bool,
+}
+
+impl Default for DragState {
+    fn default() -> Self {
+        Self {
+            start: None,
+            current: (0, 0),
+            threshold: 4.0,
+            dragging: false,
+        }
+    }
+}
+
+impl DragState {
+    /// Whether a drag is in progress (button held and moved past the threshold)
+    pub fn is_dragging(&self) -> bool {
+        self.dragging
+    }
+
+    /// Offset from the drag start to the cursor, zero when not dragging
+    pub fn drag_delta(&self) -> Vec2 {
+        match (self.dragging, self.start) {
+            (true, Some((sx, sy))) => {
+                Vec2::new((self.current.0 - sx) as f32, (self.current.1 - sy) as f32)
+            }
+            _ => Vec2::zeros(),
+        }
+    }
+
+    /// Feed the cursor position and left button state for this frame
+    pub fn update(&mut self, position: (i32, i32), left_down: bool) {
+        if !left_down {
+            self.start = None;
+            self.dragging = false;
+            return;
+        }
+
+        let start = *self.start.get_or_insert(position);
+        self.current = position;
+        if !self.dragging {
+            let dx = (position.0 - start.0) as f32;
+            let dy = (position.1 - start.1) as f32;
+            self.dragging = (dx * dx + dy * dy).sqrt() > self.threshold;
+        }
+    }
+}
+
 /// Window input manager
 pub struct WindowInputManager {
     current_state: WindowInputState,
+    drag: DragState,
     previous_keys: HashSet<Key>,
     previous_mouse_buttons: HashSet<MouseButton>,
     previous_mouse_pos: (i32, i32),
@@ -109,6 +187,7 @@ impl WindowInputManager {
     pub fn new() -> Self {
         Self {
             current_state: WindowInputState::default(),
+            drag: DragState::default(),
             previous_keys: HashSet::new(),
             previous_mouse_buttons: HashSet::new(),
             previous_mouse_pos: (0, 0),
@@ -153,21 +232,10 @@ impl WindowInputManager {
             }
         }
 
-        // Determine just pressed and just released keys
-        for key in &current_keys {
-            if !self.previous_keys.contains(key) {
-                self.current_state.keys_just_pressed.insert(*key);
-            }
-        }
-
-        for key in &self.previous_keys {
-            if !current_keys.contains(key) {
-                self.current_state.keys_just_released.insert(*key);
-            }
-        }
-
-        self.current_state.keys_pressed = current_keys.clone();
-        self.previous_keys = current_keys;
+        // Key-down events since the last update, including keys that were
+        // already released again before we sampled them
+        let pressed_events = window.get_keys_pressed(minifb::KeyRepeat::No);
+        self.update_keys(current_keys, &pressed_events);
 
         // Update mouse state (simplified - minifb has limited mouse support)
         // In a real implementation, you'd use a more advanced input library
@@ -181,6 +249,7 @@ impl WindowInputManager {
             self.current_state.mouse_position.1 - self.previous_mouse_pos.1,
         );
         self.previous_mouse_pos = self.current_state.mouse_position;
+        self.current_state.scroll_delta = window.get_scroll_wheel().map_or(0.0, |(_, y)| y);
 
         // Update mouse buttons
         let mut current_mouse_buttons = HashSet::new();
@@ -204,11 +273,67 @@ impl WindowInputManager {
 
         self.current_state.mouse_buttons = current_mouse_buttons.clone();
         self.previous_mouse_buttons = current_mouse_buttons;
+        self.update_drag();
 
         // Update window state
         self.current_state.window_focused = true; // Simplified
     }
 
+    /// Derive pressed/just-pressed/just-released from the keys held this frame
+    /// and the window's key-down events.
+    ///
+    /// Sampling held keys alone misses taps: at low frame rates a key can be
+    /// pressed and released between two updates and never be seen as held.
+    /// Such keys still show up in `pressed_events`, so they are reported as
+    /// just pressed (and, since they're no longer held, just released) this frame.
+    fn update_keys(&mut self, current_keys: HashSet<Key>, pressed_events: &[Key]) {
+        // Determine just pressed and just released keys
+        for key in &current_keys {
+            if !self.previous_keys.contains(key) {
+                self.current_state.keys_just_pressed.insert(*key);
+            }
+        }
+
+        for key in &self.previous_keys {
+            if !current_keys.contains(key) {
+                self.current_state.keys_just_released.insert(*key);
+            }
+        }
+
+        for key in pressed_events {
+            self.current_state.keys_just_pressed.insert(*key);
+            if !current_keys.contains(key) {
+                self.current_state.keys_just_released.insert(*key);
+            }
+        }
+
+        self.current_state.keys_pressed = current_keys.clone();
+        self.previous_keys = current_keys;
+    }
+
+    fn update_drag(&mut self) {
+        self.drag.update(
+            self.current_state.mouse_position,
+            self.current_state
+                .is_mouse_button_pressed(MouseButton::Left),
+        );
+    }
+
+    /// Current drag gesture state
+    pub fn drag(&self) -> &DragState {
+        &self.drag
+    }
+
+    /// Whether the left mouse button is being dragged
+    pub fn is_dragging(&self) -> bool {
+        self.drag.is_dragging()
+    }
+
+    /// Offset of the current drag from its start position
+    pub fn drag_delta(&self) -> Vec2 {
+        self.drag.drag_delta()
+    }
+
     /// Handle window resize event
     pub fn handle_resize(&mut self, width: usize, height: usize) {
         self.current_state.window_resized = Some((width, height));
@@ -392,3 +517,89 @@ impl WindowGameController {
         &mut self.input_mapper
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_tap_between_samples_counts_as_just_pressed() {
+        let mut manager = WindowInputManager::new();
+
+        // Space went down and up within one slow frame: never held when sampled
+        manager.current_state.clear_frame_state();
+        manager.update_keys(HashSet::new(), &[Key::Space]);
+        assert!(manager.state().is_key_just_pressed(Key::Space));
+        assert!(manager.state().is_key_just_released(Key::Space));
+        assert!(!manager.state().is_key_pressed(Key::Space));
+
+        // next frame the tap is gone
+        manager.current_state.clear_frame_state();
+        manager.update_keys(HashSet::new(), &[]);
+        assert!(!manager.state().is_key_just_pressed(Key::Space));
+    }
+
+    #[test]
+    fn test_mouse_pos_undoes_integer_scale() {
+        let mut ctx = RenderContext::new_headless(400, 300);
+        ctx.set_integer_scale(2);
+        let state = WindowInputState {
+            mouse_position: (100, 100),
+            ..Default::default()
+        };
+        assert_eq!(state.mouse_pos(), (100, 100));
+        assert_eq!(state.mouse_pos_logical(&ctx), (50, 50));
+
+        let camera = Camera2D {
+            position: Vec2::new(1000.0, 0.0),
+            zoom: 2.0,
+            rotation: 0.0,
+            viewport_size: Vec2::new(200.0, 150.0),
+        };
+        // 50 logical px left and 25 up of the viewport center, at 2x zoom
+        assert_eq!(state.mouse_world(&ctx, &camera), Vec2::new(975.0, -12.5));
+    }
+
+    #[test]
+    fn test_drag_gesture() {
+        let mut manager = WindowInputManager::new();
+
+        manager.set_mouse_position(10, 10);
+        manager.set_mouse_button_pressed(MouseButton::Left);
+        manager.update_drag();
+        assert!(!manager.is_dragging());
+
+        // small jitter stays below the threshold
+        manager.set_mouse_position(12, 11);
+        manager.update_drag();
+        assert!(!manager.is_dragging());
+        assert_eq!(manager.drag_delta(), Vec2::zeros());
+
+        manager.set_mouse_position(30, 15);
+        manager.update_drag();
+        assert!(manager.is_dragging());
+        assert_eq!(manager.drag_delta(), Vec2::new(20.0, 5.0));
+        assert_eq!(manager.drag().start, Some((10, 10)));
+
+        manager.set_mouse_button_released(MouseButton::Left);
+        manager.update_drag();
+        assert!(!manager.is_dragging());
+        assert_eq!(manager.drag_delta(), Vec2::zeros());
+    }
+
+    #[test]
+    fn test_held_key_is_just_pressed_once() {
+        let mut manager = WindowInputManager::new();
+        let held: HashSet<Key> = [Key::W].into_iter().collect();
+
+        manager.current_state.clear_frame_state();
+        manager.update_keys(held.clone(), &[Key::W]);
+        assert!(manager.state().is_key_just_pressed(Key::W));
+        assert!(!manager.state().is_key_just_released(Key::W));
+
+        manager.current_state.clear_frame_state();
+        manager.update_keys(held, &[]);
+        assert!(manager.state().is_key_pressed(Key::W));
+        assert!(!manager.state().is_key_just_pressed(Key::W));
+    }
+}
@@ -14,6 +14,7 @@
 pub mod ai;
 pub mod audio;
 pub mod components;
+pub mod debug;
 pub mod difficulty;
 pub mod ecs;
 pub mod enhanced_ai;
@@ -21,6 +22,7 @@ pub mod events;
 pub mod font;
 pub mod game_loop;
 pub mod game_state;
+pub mod games;
 pub mod input;
 pub mod input_window;
 pub mod menu;
@@ -34,6 +36,7 @@ pub mod scoring;
 pub mod systems;
 pub mod trail_system;
 pub mod ui;
+pub mod util;
 pub mod visual_effects;
 pub mod window;
 
@@ -64,11 +67,23 @@ pub fn init() -> Result<World, Box<dyn std::error::Error>> {
     world.register::<Enemy>();
     world.register::<Health>();
     world.register::<Collider>();
+    world.register::<ZOrder>();
     world.register::<Camera>();
     world.register::<MarkedForRemoval>();
+    world.register::<Inactive>();
+    world.register::<Lifetime>();
+    world.register::<Cooldown>();
+    world.register::<CameraTarget>();
+    world.register::<Team>();
+    world.register::<ContactDamage>();
     world.register::<Score>();
     world.register::<Paddle>();
     world.register::<Ball>();
+    world.register::<Brick>();
+    world.register::<ClampToBounds>();
+    world.register::<BounceOnBounds>();
+    world.register::<PowerUp>();
+    world.register::<TimedEffect>();
 
     // Register physics components
     world.register::<physics::Mass>();
@@ -81,11 +96,21 @@ pub fn init() -> Result<World, Box<dyn std::error::Error>> {
 
     // Register animation components
     world.register::<Animation>();
+    world.register::<visual_effects::Flash>();
 
     // Add core resources
     world.insert(Time::default());
+    world.insert(RngResource::default());
     world.insert(InputState::default());
     world.insert(Score::default());
+    world.insert(physics::CollisionEvents::default());
+    world.insert(WorldBounds::default());
+    world.insert(TeamRelations::default());
+    world.insert(resources::Settings::default());
+    world.insert(DeathEvents::default());
+    world.insert(CollectedPowerUps::default());
+    world.insert(CommandBuffer::default());
+    world.insert(resources::EntityBudget::default());
 
     Ok(world)
 }
@@ -94,6 +119,8 @@ pub fn init() -> Result<World, Box<dyn std::error::Error>> {
 pub struct Game {
     pub world: World,
     pub dispatcher: specs::Dispatcher<'static, 'static>,
+    /// Invoked after every dispatch with that frame's collisions
+    pub collision_handlers: physics::CollisionHandlers,
 }
 
 impl Game {
@@ -101,22 +128,46 @@ impl Game {
         let world = init()?;
 
         // Create dispatcher with core systems
-        let dispatcher = specs::DispatcherBuilder::new()
+        let dispatcher = SystemPlan::new()
             .with(PhysicsSystem, "physics", &[])
+            .with(physics::CollisionSystem::new(), "collision", &["physics"])
+            .with(BoundsSystem, "bounds", &["collision"])
+            .with(ContactDamageSystem, "contact_damage", &["collision"])
+            .with(LifetimeSystem, "lifetime", &[])
+            .with(CooldownSystem, "cooldown", &[])
+            .with(CameraFollowSystem::new(), "camera_follow", &["bounds"])
+            .with(visual_effects::FlashSystem, "flash", &[])
             .with(RenderingSystem, "rendering", &["physics"])
             .with(InputSystem, "input", &[])
-            .build();
+            .build()
+            .map_err(|missing| format!("Unknown system dependencies: {}", missing.join(", ")))?;
+
+        Ok(Self {
+            world,
+            dispatcher,
+            collision_handlers: physics::CollisionHandlers::new(),
+        })
+    }
 
-        Ok(Self { world, dispatcher })
+    /// Respond to collisions between an `A` entity and a `B` entity; see
+    /// `physics::CollisionHandlers::on_collision`
+    pub fn on_collision<A, B, F>(&mut self, handler: F) -> &mut Self
+    where
+        A: specs::Component,
+        B: specs::Component,
+        F: Fn(Entity, Entity, &physics::CollisionEvent, &mut World) + Send + Sync + 'static,
+    {
+        self.collision_handlers.on_collision::<A, B, F>(handler);
+        self
     }
 
     pub fn update(&mut self, delta_time: f32) {
         // Update time
-        self.world.write_resource::<Time>().delta = delta_time;
-        self.world.write_resource::<Time>().elapsed += delta_time;
+        self.world.write_resource::<Time>().advance(delta_time);
 
         // Run systems
         self.dispatcher.dispatch(&self.world);
-        self.world.maintain();
+        self.collision_handlers.invoke(&mut self.world);
+        self.world.apply_commands();
     }
 }
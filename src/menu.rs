@@ -3,6 +3,8 @@
 //! A comprehensive menu system with navigation, selection highlighting,
 //! and various menu types. Builds on the existing game state system.
 
+use crate::resources::Settings;
+use crate::window::PresentMode;
 use crate::Vec2;
 use std::collections::HashMap;
 
@@ -109,6 +111,39 @@ impl MenuItem {
         }
     }
 
+    /// Text with the current value, e.g. `Music Volume: 70%` or `Fullscreen: Off`
+    pub fn display_text(&self) -> String {
+        match &self.item_type {
+            MenuItemType::Toggle { text, value, .. } => {
+                format!("{}: {}", text, if *value { "On" } else { "Off" })
+            }
+            MenuItemType::Slider {
+                text,
+                value,
+                min,
+                max,
+                ..
+            } => {
+                let percent = if max > min {
+                    (value - min) / (max - min) * 100.0
+                } else {
+                    0.0
+                };
+                format!("{}: {:.0}%", text, percent)
+            }
+            MenuItemType::Selector {
+                text,
+                options,
+                selected,
+                ..
+            } => match options.get(*selected) {
+                Some(option) => format!("{}: {}", text, option),
+                None => text.clone(),
+            },
+            _ => self.get_text().to_string(),
+        }
+    }
+
     pub fn is_selectable(&self) -> bool {
         match &self.item_type {
             MenuItemType::Button { .. }
@@ -279,14 +314,84 @@ impl MenuSystem {
         }
     }
 
-    /// Activate the currently selected item
+    /// Activate the currently selected item. Toggles flip their value and
+    /// selectors advance to the next option first.
     pub fn activate_selected(&mut self) -> Option<MenuAction> {
-        if let Some(item) = self.get_selected_item() {
-            if item.enabled {
-                return self.get_item_action(item);
+        match self.get_selected_item().map(|item| &item.item_type) {
+            Some(MenuItemType::Toggle { .. }) | Some(MenuItemType::Selector { .. }) => {
+                self.adjust_selected(1)
+            }
+            Some(_) => {
+                let item = self.get_selected_item()?;
+                if item.enabled {
+                    self.get_item_action(item)
+                } else {
+                    None
+                }
+            }
+            None => None,
+        }
+    }
+
+    /// Change the selected toggle, slider or selector by `steps` (negative
+    /// goes down/left) and return its action carrying the new value:
+    /// `SetSetting` with the slider value, `SelectOption` with the option
+    /// index, or the toggle's own action.
+    pub fn adjust_selected(&mut self, steps: i32) -> Option<MenuAction> {
+        let item = self.get_selected_item_mut()?;
+        if !item.enabled {
+            return None;
+        }
+        match &mut item.item_type {
+            MenuItemType::Toggle { value, action, .. } => {
+                *value = !*value;
+                Some(action.clone())
+            }
+            MenuItemType::Slider {
+                value,
+                min,
+                max,
+                step,
+                action,
+                ..
+            } => {
+                let mut adjusted = *value + *step * steps as f32;
+                if *step > 0.0 {
+                    // snap so repeated steps don't drift off the grid
+                    adjusted = (adjusted / *step).round() * *step;
+                }
+                let adjusted = adjusted.clamp(*min, *max);
+                if adjusted == *value {
+                    return None;
+                }
+                *value = adjusted;
+                match action {
+                    MenuAction::SetSetting(name, _) => {
+                        Some(MenuAction::SetSetting(name.clone(), adjusted))
+                    }
+                    other => Some(other.clone()),
+                }
+            }
+            MenuItemType::Selector {
+                options,
+                selected,
+                action,
+                ..
+            } => {
+                if options.is_empty() {
+                    return None;
+                }
+                let count = options.len() as i32;
+                *selected = (*selected as i32 + steps).rem_euclid(count) as usize;
+                match action {
+                    MenuAction::SelectOption(name, _) => {
+                        Some(MenuAction::SelectOption(name.clone(), *selected))
+                    }
+                    other => Some(other.clone()),
+                }
             }
+            _ => None,
         }
-        None
     }
 
     /// Get the action for a menu item
@@ -350,12 +455,16 @@ impl MenuSystem {
         }
     }
 
-    /// Handle input for menu navigation
-    pub fn handle_input(&mut self, input_state: &crate::input_window::WindowInputState) {
+    /// Handle input for menu navigation, returning the action of any item
+    /// activated (Enter/Space) or adjusted (Left/Right) this frame
+    pub fn handle_input(
+        &mut self,
+        input_state: &crate::input_window::WindowInputState,
+    ) -> Option<MenuAction> {
         use minifb::Key;
 
         if !self.navigation_enabled {
-            return;
+            return None;
         }
 
         // Navigation
@@ -366,10 +475,15 @@ impl MenuSystem {
         }
 
         // Activation
+        let mut action = None;
         if input_state.is_key_just_pressed(Key::Enter)
             || input_state.is_key_just_pressed(Key::Space)
         {
-            self.activate_selected();
+            action = self.activate_selected();
+        } else if input_state.is_key_just_pressed(Key::Left) {
+            action = self.adjust_selected(-1);
+        } else if input_state.is_key_just_pressed(Key::Right) {
+            action = self.adjust_selected(1);
         }
 
         // Direct selection with number keys
@@ -392,6 +506,7 @@ impl MenuSystem {
                 break;
             }
         }
+        action
     }
 
     /// Get a setting value
@@ -541,6 +656,78 @@ impl MenuSystem {
 
         menu
     }
+
+    /// Settings menu showing the current values of `settings`. Its actions
+    /// are named after `Settings` fields, so `Settings::apply_menu_action`
+    /// applies them directly.
+    pub fn create_settings_menu_from(settings: &Settings) -> Self {
+        let config = MenuConfig {
+            title: "SETTINGS".to_string(),
+            ..Default::default()
+        };
+
+        let mut menu = Self::new(config);
+
+        menu.add_item(menu_items::slider(
+            "master_volume",
+            "Master Volume",
+            settings.master_volume,
+            0.0,
+            1.0,
+            0.1,
+            MenuAction::SetSetting("master_volume".to_string(), settings.master_volume),
+        ));
+        menu.add_item(menu_items::slider(
+            "music_volume",
+            "Music Volume",
+            settings.music_volume,
+            0.0,
+            1.0,
+            0.1,
+            MenuAction::SetSetting("music_volume".to_string(), settings.music_volume),
+        ));
+
+        let difficulty = Settings::DIFFICULTIES
+            .iter()
+            .position(|level| *level == settings.difficulty)
+            .unwrap_or(1);
+        menu.add_item(menu_items::selector(
+            "difficulty",
+            "Difficulty",
+            Settings::DIFFICULTIES
+                .iter()
+                .map(|level| format!("{:?}", level))
+                .collect(),
+            difficulty,
+            MenuAction::SelectOption("difficulty".to_string(), difficulty),
+        ));
+
+        let present_mode = PresentMode::ALL
+            .iter()
+            .position(|mode| *mode == settings.present_mode)
+            .unwrap_or(0);
+        menu.add_item(menu_items::selector(
+            "present_mode",
+            "Present Mode",
+            PresentMode::ALL
+                .iter()
+                .map(|mode| format!("{:?}", mode))
+                .collect(),
+            present_mode,
+            MenuAction::SelectOption("present_mode".to_string(), present_mode),
+        ));
+
+        menu.add_item(menu_items::toggle(
+            "fullscreen",
+            "Fullscreen",
+            settings.fullscreen,
+            MenuAction::ToggleSetting("fullscreen".to_string()),
+        ));
+
+        menu.add_item(menu_items::button("back", "Back", MenuAction::Back));
+
+        menu
+    }
 }
 
 impl Default for MenuSystem {
@@ -658,6 +845,41 @@ mod tests {
         assert!(button.is_selectable());
     }
 
+    #[test]
+    fn test_settings_menu_edits_settings() {
+        let mut settings = Settings::default();
+        let mut menu = MenuSystem::create_settings_menu_from(&settings);
+
+        menu.select_by_id("music_volume");
+        let action = menu.adjust_selected(-1).unwrap();
+        assert!(settings.apply_menu_action(&action));
+        assert!((settings.music_volume - 0.6).abs() < 1e-6);
+        assert_eq!(
+            menu.get_selected_item().unwrap().display_text(),
+            "Music Volume: 60%"
+        );
+
+        menu.select_by_id("difficulty");
+        let action = menu.adjust_selected(1).unwrap();
+        assert!(settings.apply_menu_action(&action));
+        assert_eq!(
+            settings.difficulty,
+            crate::difficulty::DifficultyLevel::Hard
+        );
+
+        menu.select_by_id("fullscreen");
+        let action = menu.activate_selected().unwrap();
+        assert!(settings.apply_menu_action(&action));
+        assert!(settings.fullscreen);
+        assert_eq!(
+            menu.get_selected_item().unwrap().display_text(),
+            "Fullscreen: On"
+        );
+
+        menu.select_by_id("back");
+        assert!(matches!(menu.activate_selected(), Some(MenuAction::Back)));
+    }
+
     #[test]
     fn test_menu_item_types() {
         let label = menu_items::label("test", "Test Label");
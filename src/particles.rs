@@ -3,7 +3,9 @@
 //! A flexible particle system for creating visual effects like explosions,
 //! trails, sparks, and other dynamic visual feedback. Extracted from the Pong game.
 
-use crate::Vec2;
+use crate::renderer_2d::{Color, Renderer2D};
+use crate::rendering::Camera2D;
+use crate::{Vec2, WorldBounds};
 
 /// Individual particle with physics and visual properties
 #[derive(Debug, Clone)]
@@ -300,6 +302,51 @@ pub struct ParticleSystem {
     global_gravity: Vec2,
     /// Time scale for slow motion effects
     time_scale: f32,
+    /// Area particles are kept inside, if any
+    bounds: Option<ParticleBounds>,
+}
+
+/// What happens to a particle that leaves the `ParticleSystem` bounds
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BoundsMode {
+    /// Remove the particle
+    Kill,
+    /// Put it back on the edge and reflect its velocity, scaled by the restitution
+    Bounce,
+}
+
+/// Bounds set with `ParticleSystem::set_bounds`
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ParticleBounds {
+    pub rect: WorldBounds,
+    pub mode: BoundsMode,
+    /// Fraction of speed kept by a bounce
+    pub restitution: f32,
+}
+
+impl ParticleBounds {
+    /// Apply the bounds to `particle`; returns false if it should be removed
+    pub fn apply(&self, particle: &mut Particle) -> bool {
+        if self.mode == BoundsMode::Kill {
+            return self.rect.contains(particle.position);
+        }
+        for axis in 0..2 {
+            let (min, max) = (self.rect.min[axis], self.rect.max[axis]);
+            let (position, velocity) = (&mut particle.position[axis], &mut particle.velocity[axis]);
+            if *position < min {
+                *position = min;
+                if *velocity < 0.0 {
+                    *velocity = -*velocity * self.restitution;
+                }
+            } else if *position > max {
+                *position = max;
+                if *velocity > 0.0 {
+                    *velocity = -*velocity * self.restitution;
+                }
+            }
+        }
+        true
+    }
 }
 
 impl ParticleSystem {
@@ -309,6 +356,7 @@ impl ParticleSystem {
             emitters: Vec::new(),
             global_gravity: Vec2::new(0.0, 100.0),
             time_scale: 1.0,
+            bounds: None,
         }
     }
 
@@ -345,6 +393,11 @@ impl ParticleSystem {
                 emitter.config.gravity = self.global_gravity;
             }
             emitter.update(scaled_delta);
+            if let Some(bounds) = &self.bounds {
+                emitter
+                    .particles
+                    .retain_mut(|particle| bounds.apply(particle));
+            }
         }
 
         // Remove empty emitters
@@ -445,6 +498,24 @@ impl ParticleSystem {
         self.time_scale = scale;
     }
 
+    /// Kill or bounce particles that leave `rect`, e.g. so sparks bounce off the floor
+    pub fn set_bounds(&mut self, rect: WorldBounds, mode: BoundsMode, restitution: f32) {
+        self.bounds = Some(ParticleBounds {
+            rect,
+            mode,
+            restitution,
+        });
+    }
+
+    /// Let particles move freely again
+    pub fn clear_bounds(&mut self) {
+        self.bounds = None;
+    }
+
+    pub fn bounds(&self) -> Option<&ParticleBounds> {
+        self.bounds.as_ref()
+    }
+
     /// Get total number of active particles across all emitters
     pub fn total_particle_count(&self) -> usize {
         self.emitters.iter().map(|e| e.particle_count()).sum()
@@ -454,6 +525,23 @@ impl ParticleSystem {
     pub fn clear(&mut self) {
         self.emitters.clear();
     }
+
+    /// Draw every live particle as a filled circle.
+    /// Positions are in world space when a `camera` is given, otherwise screen space.
+    pub fn render(&self, renderer: &mut Renderer2D, camera: Option<&Camera2D>) {
+        let zoom = camera.map_or(1.0, |camera| camera.zoom);
+        for particle in self.emitters.iter().flat_map(|emitter| &emitter.particles) {
+            let screen = camera.map_or(particle.position, |camera| {
+                camera.world_to_screen(particle.position)
+            });
+            renderer.draw_circle_filled(
+                screen.x as i32,
+                screen.y as i32,
+                (particle.size * zoom) as i32,
+                Color::from_f32(particle.color),
+            );
+        }
+    }
 }
 
 impl Default for ParticleSystem {
@@ -499,6 +587,32 @@ mod tests {
         assert_eq!(emitter.particle_count(), 10);
     }
 
+    #[test]
+    fn test_particles_killed_or_bounced_at_floor() {
+        let falling = |mode| {
+            let mut system = ParticleSystem::new();
+            system.set_global_gravity(Vec2::zeros());
+            system.set_bounds(WorldBounds::from_size(100.0, 100.0), mode, 0.5);
+            let mut emitter = ParticleEmitter::new();
+            emitter.config.active = false;
+            let mut particle = Particle::new();
+            particle.position = Vec2::new(50.0, 99.0);
+            particle.velocity = Vec2::new(0.0, 50.0);
+            emitter.particles.push(particle);
+            let index = system.add_emitter(emitter);
+            system.update(0.1);
+            (system, index)
+        };
+
+        let (killed, _) = falling(BoundsMode::Kill);
+        assert_eq!(killed.total_particle_count(), 0);
+
+        let (bounced, index) = falling(BoundsMode::Bounce);
+        let particle = &bounced.get_emitter(index).unwrap().particles[0];
+        assert_eq!(particle.position.y, 100.0);
+        assert_eq!(particle.velocity.y, -25.0);
+    }
+
     #[test]
     fn test_particle_system() {
         let mut system = ParticleSystem::new();
@@ -2,8 +2,15 @@
 //!
 //! Advanced physics simulation with collision detection and response.
 
-use crate::Vec2;
-use specs::{Component, VecStorage};
+use crate::components::{Collider, CollisionShape, Position, Velocity, ZOrder};
+use crate::renderer_2d::{Color, Renderer2D};
+use crate::rendering::Camera2D;
+use crate::{Point2, Vec2, WorldBounds};
+use specs::{
+    Component, Entities, Entity, Join, Read, ReadStorage, System, VecStorage, World, WorldExt,
+    Write, WriteStorage,
+};
+use std::collections::{HashMap, HashSet};
 
 /// Mass component for physics objects
 #[derive(Component, Debug, Clone, Copy)]
@@ -56,3 +63,1320 @@ impl PhysicsWorld {
         // Physics simulation step
     }
 }
+
+/// Two solid colliders touched this frame
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct CollisionEvent {
+    pub entity_a: Entity,
+    pub entity_b: Entity,
+    /// Unit contact normal pointing from `entity_a` towards `entity_b`
+    pub normal: Vec2,
+    /// Closing speed along `normal` before the collision was resolved
+    pub relative_speed: f32,
+}
+
+/// Something overlapped a trigger collider this frame
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TriggerEvent {
+    /// The trigger
+    pub entity: Entity,
+    /// The entity overlapping it
+    pub other: Entity,
+}
+
+/// Events written by `CollisionSystem`; cleared at the start of each run
+#[derive(Debug, Clone, Default)]
+pub struct CollisionEvents {
+    pub collisions: Vec<CollisionEvent>,
+    pub triggers: Vec<TriggerEvent>,
+    /// Pairs already in `collisions` this frame, lower entity id first
+    reported: HashSet<(Entity, Entity)>,
+}
+
+impl CollisionEvents {
+    /// Record a solid collision once per pair and frame. The event is
+    /// normalized so `entity_a` has the lower entity id (flipping the normal
+    /// if needed); returns `false` if the pair was already reported.
+    pub fn push_collision(&mut self, event: CollisionEvent) -> bool {
+        let event = if event.entity_a.id() > event.entity_b.id() {
+            CollisionEvent {
+                entity_a: event.entity_b,
+                entity_b: event.entity_a,
+                normal: -event.normal,
+                ..event
+            }
+        } else {
+            event
+        };
+        if !self.reported.insert((event.entity_a, event.entity_b)) {
+            return false;
+        }
+        self.collisions.push(event);
+        true
+    }
+
+    /// Drop all events, starting a new frame
+    pub fn clear(&mut self) {
+        self.collisions.clear();
+        self.triggers.clear();
+        self.reported.clear();
+    }
+}
+
+/// Contact between two overlapping colliders
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Contact {
+    /// Unit normal pointing from the first collider towards the second
+    pub normal: Vec2,
+    /// Overlap depth along `normal`
+    pub penetration: f32,
+}
+
+/// Compute the contact between two colliders centered on their positions,
+/// or `None` if they don't overlap
+pub fn contact(pos_a: Vec2, a: &Collider, pos_b: Vec2, b: &Collider) -> Option<Contact> {
+    match (&a.shape, &b.shape) {
+        (CollisionShape::Circle { radius: r1 }, CollisionShape::Circle { radius: r2 }) => {
+            let delta = pos_b - pos_a;
+            let distance = delta.norm();
+            let penetration = r1 + r2 - distance;
+            if penetration < 0.0 {
+                return None;
+            }
+            let normal = if distance > f32::EPSILON {
+                delta / distance
+            } else {
+                Vec2::new(1.0, 0.0)
+            };
+            Some(Contact {
+                normal,
+                penetration,
+            })
+        }
+        (
+            CollisionShape::Rectangle {
+                width: w1,
+                height: h1,
+            },
+            CollisionShape::Rectangle {
+                width: w2,
+                height: h2,
+            },
+        ) => {
+            let delta = pos_b - pos_a;
+            let overlap_x = (w1 + w2) / 2.0 - delta.x.abs();
+            let overlap_y = (h1 + h2) / 2.0 - delta.y.abs();
+            if overlap_x < 0.0 || overlap_y < 0.0 {
+                return None;
+            }
+            // separate along the axis of least overlap; an exact corner hit
+            // overlaps equally on both axes and separates along the diagonal
+            if (overlap_x - overlap_y).abs() <= f32::EPSILON * overlap_x.max(1.0) {
+                let sign = |d: f32| if d < 0.0 { -1.0 } else { 1.0 };
+                Some(Contact {
+                    normal: Vec2::new(sign(delta.x), sign(delta.y)).normalize(),
+                    penetration: overlap_x * std::f32::consts::SQRT_2,
+                })
+            } else if overlap_x < overlap_y {
+                Some(Contact {
+                    normal: Vec2::new(if delta.x < 0.0 { -1.0 } else { 1.0 }, 0.0),
+                    penetration: overlap_x,
+                })
+            } else {
+                Some(Contact {
+                    normal: Vec2::new(0.0, if delta.y < 0.0 { -1.0 } else { 1.0 }),
+                    penetration: overlap_y,
+                })
+            }
+        }
+        (CollisionShape::Circle { radius }, CollisionShape::Rectangle { width, height }) => {
+            circle_rect_contact(pos_a, *radius, pos_b, *width, *height).map(|c| Contact {
+                normal: -c.normal,
+                ..c
+            })
+        }
+        (CollisionShape::Rectangle { width, height }, CollisionShape::Circle { radius }) => {
+            circle_rect_contact(pos_b, *radius, pos_a, *width, *height)
+        }
+    }
+}
+
+/// Contact with the normal pointing from the rectangle towards the circle
+fn circle_rect_contact(
+    center: Vec2,
+    radius: f32,
+    rect_center: Vec2,
+    width: f32,
+    height: f32,
+) -> Option<Contact> {
+    let half = Vec2::new(width / 2.0, height / 2.0);
+    let offset = center - rect_center;
+    let closest = Vec2::new(
+        offset.x.clamp(-half.x, half.x),
+        offset.y.clamp(-half.y, half.y),
+    );
+    let delta = offset - closest;
+    let distance = delta.norm();
+    if distance > radius {
+        return None;
+    }
+    if distance > f32::EPSILON {
+        return Some(Contact {
+            normal: delta / distance,
+            penetration: radius - distance,
+        });
+    }
+
+    // circle center inside the rectangle: push out through the nearest face
+    let to_x = half.x - offset.x.abs();
+    let to_y = half.y - offset.y.abs();
+    if to_x < to_y {
+        Some(Contact {
+            normal: Vec2::new(if offset.x < 0.0 { -1.0 } else { 1.0 }, 0.0),
+            penetration: to_x + radius,
+        })
+    } else {
+        Some(Contact {
+            normal: Vec2::new(0.0, if offset.y < 0.0 { -1.0 } else { 1.0 }),
+            penetration: to_y + radius,
+        })
+    }
+}
+
+/// Separate two bodies along the contact normal and reflect any velocity
+/// heading into the contact. Bodies without a velocity are treated as static.
+pub fn resolve_collision(
+    pos_a: &mut Position,
+    vel_a: Option<&mut Velocity>,
+    pos_b: &mut Position,
+    vel_b: Option<&mut Velocity>,
+    contact: &Contact,
+) {
+    let n = contact.normal;
+    let (share_a, share_b) = match (&vel_a, &vel_b) {
+        (Some(_), Some(_)) => (0.5, 0.5),
+        (Some(_), None) => (1.0, 0.0),
+        (None, Some(_)) => (0.0, 1.0),
+        (None, None) => return,
+    };
+
+    pos_a.x -= n.x * contact.penetration * share_a;
+    pos_a.y -= n.y * contact.penetration * share_a;
+    pos_b.x += n.x * contact.penetration * share_b;
+    pos_b.y += n.y * contact.penetration * share_b;
+
+    // a moves towards b along +n, b towards a along -n
+    if let Some(v) = vel_a {
+        if v.as_vec2().dot(&n) > 0.0 {
+            let reflected = reflect(v.as_vec2(), n);
+            *v = Velocity::new(reflected.x, reflected.y);
+        }
+    }
+    if let Some(v) = vel_b {
+        if v.as_vec2().dot(&n) < 0.0 {
+            let reflected = reflect(v.as_vec2(), n);
+            *v = Velocity::new(reflected.x, reflected.y);
+        }
+    }
+}
+
+/// Mirror `velocity` about the surface with unit `normal`: `v - 2 (v . n) n`
+pub fn reflect(velocity: Vec2, normal: Vec2) -> Vec2 {
+    velocity - 2.0 * velocity.dot(&normal) * normal
+}
+
+/// Sweep a circle moving at `vel` for `dt` seconds against the segment
+/// `seg_a..seg_b` (a wall, possibly angled). Returns the time of impact in
+/// `0.0..=dt` and the unit normal pointing from the wall towards the circle,
+/// ready for `reflect`. Circles already touching and moving in report time 0.
+pub fn circle_segment_sweep(
+    center: Vec2,
+    radius: f32,
+    vel: Vec2,
+    seg_a: Vec2,
+    seg_b: Vec2,
+    dt: f32,
+) -> Option<(f32, Vec2)> {
+    let speed = vel.norm();
+    if speed <= f32::EPSILON || dt < 0.0 {
+        return None;
+    }
+    let mut best: Option<(f32, Vec2)> = None;
+    let mut consider = |toi: f32, normal: Vec2| {
+        if toi <= dt && best.is_none_or(|(t, _)| toi < t) {
+            best = Some((toi, normal));
+        }
+    };
+
+    // flat side of the segment, facing the circle
+    let edge = seg_b - seg_a;
+    let length_sq = edge.norm_squared();
+    if length_sq > f32::EPSILON {
+        let mut normal = Vec2::new(-edge.y, edge.x) / length_sq.sqrt();
+        let mut distance = (center - seg_a).dot(&normal);
+        if distance < 0.0 {
+            normal = -normal;
+            distance = -distance;
+        }
+        let approach = -vel.dot(&normal);
+        if approach > 0.0 {
+            let toi = ((distance - radius) / approach).max(0.0);
+            let touch = center + vel * toi - normal * radius;
+            let along = (touch - seg_a).dot(&edge) / length_sq;
+            if (0.0..=1.0).contains(&along) {
+                consider(toi, normal);
+            }
+        }
+    }
+
+    // rounded ends
+    let dir = vel / speed;
+    for end in [seg_a, seg_b] {
+        if (center - end).dot(&vel) >= 0.0 {
+            continue;
+        }
+        if let Some((distance, normal)) = ray_circle(center, dir, end, radius) {
+            consider(distance / speed, normal);
+        }
+    }
+    best
+}
+
+/// Apply an instantaneous impulse (dash, knockback): `dv = impulse / mass`
+pub fn apply_impulse(velocity: &mut Velocity, impulse: Vec2, mass: &Mass) {
+    let mass = if mass.0 > 0.0 { mass.0 } else { 1.0 };
+    velocity.x += impulse.x / mass;
+    velocity.y += impulse.y / mass;
+}
+
+/// Predict the path of a body moving at constant velocity that bounces off
+/// the edges of `bounds`. Returns `steps + 1` points starting at `start`,
+/// suitable for drawing an aiming guide.
+pub fn simulate_trajectory(
+    start: Vec2,
+    vel: Vec2,
+    steps: usize,
+    dt: f32,
+    bounds: &WorldBounds,
+) -> Vec<Vec2> {
+    let mut points = Vec::with_capacity(steps + 1);
+    let mut position = start;
+    let mut velocity = vel;
+    points.push(position);
+
+    for _ in 0..steps {
+        position += velocity * dt;
+        // mirror overshoot back inside so the path stays on the bounce line
+        for axis in 0..2 {
+            if position[axis] < bounds.min[axis] {
+                position[axis] = 2.0 * bounds.min[axis] - position[axis];
+                velocity[axis] = velocity[axis].abs();
+            } else if position[axis] > bounds.max[axis] {
+                position[axis] = 2.0 * bounds.max[axis] - position[axis];
+                velocity[axis] = -velocity[axis].abs();
+            }
+        }
+        points.push(position);
+    }
+    points
+}
+
+/// Response to a solid collision, called with the entities in the order the
+/// handler was registered for
+pub type CollisionCallback = Box<dyn Fn(Entity, Entity, &CollisionEvent, &mut World) + Send + Sync>;
+
+struct CollisionHandler {
+    /// `Some(swapped)` if the pair carries the handler's components, either way round
+    roles: fn(&World, Entity, Entity) -> Option<bool>,
+    callback: CollisionCallback,
+}
+
+fn pair_roles<A: Component, B: Component>(world: &World, a: Entity, b: Entity) -> Option<bool> {
+    let (first, second) = (world.read_storage::<A>(), world.read_storage::<B>());
+    if first.contains(a) && second.contains(b) {
+        Some(false)
+    } else if first.contains(b) && second.contains(a) {
+        Some(true)
+    } else {
+        None
+    }
+}
+
+/// Per-component-pair responses to the solid collisions in `CollisionEvents`.
+///
+/// Handlers need the whole world, so they cannot run inside a dispatcher.
+/// Call `invoke` after dispatching a `CollisionSystem`, as `Game::update` does.
+#[derive(Default)]
+pub struct CollisionHandlers {
+    handlers: Vec<CollisionHandler>,
+}
+
+impl CollisionHandlers {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Call `handler(a, b, event, world)` for each solid collision between an
+    /// entity with `A` and one with `B`, whichever order the pair was detected in.
+    /// Both component types must be registered.
+    pub fn on_collision<A, B, F>(&mut self, handler: F) -> &mut Self
+    where
+        A: Component,
+        B: Component,
+        F: Fn(Entity, Entity, &CollisionEvent, &mut World) + Send + Sync + 'static,
+    {
+        self.handlers.push(CollisionHandler {
+            roles: pair_roles::<A, B>,
+            callback: Box::new(handler),
+        });
+        self
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.handlers.is_empty()
+    }
+
+    /// Run matching handlers for this frame's `CollisionEvents`. Events whose
+    /// entities an earlier handler deleted are skipped.
+    pub fn invoke(&self, world: &mut World) {
+        if self.handlers.is_empty() {
+            return;
+        }
+        let collisions = world.read_resource::<CollisionEvents>().collisions.clone();
+        for event in &collisions {
+            for handler in &self.handlers {
+                let entities = world.entities();
+                if !entities.is_alive(event.entity_a) || !entities.is_alive(event.entity_b) {
+                    break;
+                }
+                drop(entities);
+                let Some(swapped) = (handler.roles)(world, event.entity_a, event.entity_b) else {
+                    continue;
+                };
+                let event = if swapped {
+                    CollisionEvent {
+                        entity_a: event.entity_b,
+                        entity_b: event.entity_a,
+                        normal: -event.normal,
+                        ..*event
+                    }
+                } else {
+                    *event
+                };
+                (handler.callback)(event.entity_a, event.entity_b, &event, world);
+            }
+        }
+    }
+}
+
+/// Detects overlaps between `Position` + `Collider` entities, resolves solid
+/// ones with `resolve_collision` and reports everything in `CollisionEvents`.
+/// Trigger colliders only emit `TriggerEvent`s and never affect movement.
+///
+/// Handlers registered with `on_collision` run from `invoke_handlers` (or
+/// `run_with_callbacks`). Inside a dispatcher, register them on a
+/// `CollisionHandlers` instead and invoke it after each dispatch.
+#[derive(Default)]
+pub struct CollisionSystem {
+    handlers: CollisionHandlers,
+}
+
+impl CollisionSystem {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// See `CollisionHandlers::on_collision`
+    pub fn on_collision<A, B, F>(&mut self, handler: F) -> &mut Self
+    where
+        A: Component,
+        B: Component,
+        F: Fn(Entity, Entity, &CollisionEvent, &mut World) + Send + Sync + 'static,
+    {
+        self.handlers.on_collision::<A, B, F>(handler);
+        self
+    }
+
+    /// Run matching handlers for this frame's `CollisionEvents`
+    pub fn invoke_handlers(&self, world: &mut World) {
+        self.handlers.invoke(world);
+    }
+
+    /// Detect and resolve collisions, then invoke handlers
+    pub fn run_with_callbacks(&mut self, world: &mut World) {
+        specs::RunNow::run_now(self, world);
+        self.invoke_handlers(world);
+    }
+}
+
+impl<'a> System<'a> for CollisionSystem {
+    type SystemData = (
+        Entities<'a>,
+        WriteStorage<'a, Position>,
+        WriteStorage<'a, Velocity>,
+        ReadStorage<'a, Collider>,
+        ReadStorage<'a, crate::components::Inactive>,
+        Write<'a, CollisionEvents>,
+    );
+
+    fn run(
+        &mut self,
+        (entities, mut positions, mut velocities, colliders, inactive, mut events): Self::SystemData,
+    ) {
+        events.clear();
+
+        let bodies: Vec<(Entity, Collider)> = (&entities, &positions, &colliders, !&inactive)
+            .join()
+            .map(|(entity, _, collider, _)| (entity, collider.clone()))
+            .collect();
+
+        for (i, (entity_a, collider_a)) in bodies.iter().enumerate() {
+            for (entity_b, collider_b) in &bodies[i + 1..] {
+                // positions may have moved while resolving earlier pairs
+                let pos_a = *positions.get(*entity_a).unwrap();
+                let pos_b = *positions.get(*entity_b).unwrap();
+                let Some(contact) =
+                    contact(pos_a.as_vec2(), collider_a, pos_b.as_vec2(), collider_b)
+                else {
+                    continue;
+                };
+
+                if collider_a.is_trigger || collider_b.is_trigger {
+                    if collider_a.is_trigger {
+                        events.triggers.push(TriggerEvent {
+                            entity: *entity_a,
+                            other: *entity_b,
+                        });
+                    }
+                    if collider_b.is_trigger {
+                        events.triggers.push(TriggerEvent {
+                            entity: *entity_b,
+                            other: *entity_a,
+                        });
+                    }
+                    continue;
+                }
+
+                let mut new_a = pos_a;
+                let mut new_b = pos_b;
+                let mut vel_a = velocities.get(*entity_a).copied();
+                let mut vel_b = velocities.get(*entity_b).copied();
+
+                let va = vel_a.map(|v| v.as_vec2()).unwrap_or_else(Vec2::zeros);
+                let vb = vel_b.map(|v| v.as_vec2()).unwrap_or_else(Vec2::zeros);
+                let event = CollisionEvent {
+                    entity_a: *entity_a,
+                    entity_b: *entity_b,
+                    normal: contact.normal,
+                    relative_speed: (va - vb).dot(&contact.normal).max(0.0),
+                };
+                if !events.push_collision(event) {
+                    continue;
+                }
+                resolve_collision(
+                    &mut new_a,
+                    vel_a.as_mut(),
+                    &mut new_b,
+                    vel_b.as_mut(),
+                    &contact,
+                );
+                *positions.get_mut(*entity_a).unwrap() = new_a;
+                *positions.get_mut(*entity_b).unwrap() = new_b;
+                if let Some(v) = vel_a {
+                    *velocities.get_mut(*entity_a).unwrap() = v;
+                }
+                if let Some(v) = vel_b {
+                    *velocities.get_mut(*entity_b).unwrap() = v;
+                }
+            }
+        }
+    }
+}
+
+/// Pushes the lighter body of each solid collision away along the contact
+/// normal with an impulse of `strength * relative_speed`; equal masses both
+/// get pushed. Entities without a `Mass` weigh 1.0. Run after `CollisionSystem`.
+pub struct KnockbackSystem {
+    pub strength: f32,
+}
+
+impl Default for KnockbackSystem {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl KnockbackSystem {
+    pub fn new() -> Self {
+        Self { strength: 1.0 }
+    }
+
+    pub fn with_strength(mut self, strength: f32) -> Self {
+        self.strength = strength;
+        self
+    }
+}
+
+impl<'a> System<'a> for KnockbackSystem {
+    type SystemData = (
+        Read<'a, CollisionEvents>,
+        ReadStorage<'a, Mass>,
+        WriteStorage<'a, Velocity>,
+    );
+
+    fn run(&mut self, (events, masses, mut velocities): Self::SystemData) {
+        let mass_of = |entity| masses.get(entity).copied().unwrap_or(Mass(1.0));
+
+        for event in &events.collisions {
+            let impulse = event.normal * event.relative_speed * self.strength;
+            let mass_a = mass_of(event.entity_a);
+            let mass_b = mass_of(event.entity_b);
+
+            if mass_a.0 <= mass_b.0 {
+                if let Some(velocity) = velocities.get_mut(event.entity_a) {
+                    apply_impulse(velocity, -impulse, &mass_a);
+                }
+            }
+            if mass_b.0 <= mass_a.0 {
+                if let Some(velocity) = velocities.get_mut(event.entity_b) {
+                    apply_impulse(velocity, impulse, &mass_b);
+                }
+            }
+        }
+    }
+}
+
+/// Result of a successful `raycast`
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct RayHit {
+    pub entity: Entity,
+    /// World-space point where the ray enters the collider
+    pub point: Vec2,
+    /// Unit surface normal at `point`, facing back towards the ray
+    pub normal: Vec2,
+    /// Distance from the ray origin to `point`
+    pub distance: f32,
+}
+
+/// Uniform grid resource bucketing collider entities by the cells their bounds overlap.
+///
+/// Rebuild it when colliders move (e.g. once per frame); spatial queries such as
+/// `raycast` use it when it is present in the world instead of testing every collider.
+#[derive(Debug, Clone)]
+pub struct SpatialGrid {
+    pub cell_size: f32,
+    cells: HashMap<(i32, i32), Vec<Entity>>,
+    /// Lowest and highest occupied cell, if any
+    bounds: Option<((i32, i32), (i32, i32))>,
+}
+
+impl Default for SpatialGrid {
+    fn default() -> Self {
+        Self::new(64.0)
+    }
+}
+
+impl SpatialGrid {
+    pub fn new(cell_size: f32) -> Self {
+        Self {
+            cell_size: cell_size.max(f32::EPSILON),
+            cells: HashMap::new(),
+            bounds: None,
+        }
+    }
+
+    /// Remove all entities
+    pub fn clear(&mut self) {
+        self.cells.clear();
+        self.bounds = None;
+    }
+
+    /// Cell coordinate containing a world-space point
+    pub fn cell_of(&self, point: Vec2) -> (i32, i32) {
+        (
+            (point.x / self.cell_size).floor() as i32,
+            (point.y / self.cell_size).floor() as i32,
+        )
+    }
+
+    /// Insert an entity covering the axis-aligned box `min..max`
+    pub fn insert(&mut self, entity: Entity, min: Vec2, max: Vec2) {
+        let (x0, y0) = self.cell_of(min);
+        let (x1, y1) = self.cell_of(max);
+        self.bounds = Some(match self.bounds {
+            Some((lo, hi)) => ((lo.0.min(x0), lo.1.min(y0)), (hi.0.max(x1), hi.1.max(y1))),
+            None => ((x0, y0), (x1, y1)),
+        });
+        for cy in y0..=y1 {
+            for cx in x0..=x1 {
+                self.cells.entry((cx, cy)).or_default().push(entity);
+            }
+        }
+    }
+
+    /// Rebuild the grid from every `Position` + `Collider` entity in the world
+    pub fn rebuild(&mut self, world: &World) {
+        self.clear();
+        let entities = world.entities();
+        let positions = world.read_storage::<Position>();
+        let colliders = world.read_storage::<Collider>();
+        for (entity, position, collider) in (&entities, &positions, &colliders).join() {
+            let half = half_extents(&collider.shape);
+            let center = position.as_vec2();
+            self.insert(entity, center - half, center + half);
+        }
+    }
+
+    /// Entities stored in a single cell
+    pub fn entities_in_cell(&self, cell: (i32, i32)) -> &[Entity] {
+        self.cells.get(&cell).map(Vec::as_slice).unwrap_or(&[])
+    }
+
+    /// Clip the segment `from..to` to the box spanned by the occupied cells.
+    ///
+    /// An infinite `to` (e.g. a ray with `max_dist = f32::INFINITY`) keeps
+    /// only its direction, with non-finite components along zero axes
+    /// treated as zero.
+    fn clip_to_bounds(&self, from: Vec2, to: Vec2) -> Option<(Vec2, Vec2)> {
+        let (lo, hi) = self.bounds?;
+        if !(from.x.is_finite() && from.y.is_finite()) {
+            return None;
+        }
+        let min = Vec2::new(lo.0 as f32, lo.1 as f32) * self.cell_size;
+        let max = Vec2::new(hi.0 as f32 + 1.0, hi.1 as f32 + 1.0) * self.cell_size;
+
+        let mut delta = to - from;
+        if delta.iter().any(|d| d.is_infinite()) {
+            // long enough to leave the bounds from anywhere inside them
+            let reach = (from - min).abs().sup(&(from - max).abs()).norm();
+            delta = delta.map(|d| {
+                if d.is_infinite() {
+                    d.signum() * reach
+                } else {
+                    0.0
+                }
+            });
+        } else if delta.iter().any(|d| d.is_nan()) {
+            return None;
+        }
+
+        // Liang-Barsky
+        let (mut t0, mut t1) = (0.0f32, 1.0f32);
+        for axis in 0..2 {
+            if delta[axis] == 0.0 {
+                if from[axis] < min[axis] || from[axis] > max[axis] {
+                    return None;
+                }
+            } else {
+                let a = (min[axis] - from[axis]) / delta[axis];
+                let b = (max[axis] - from[axis]) / delta[axis];
+                t0 = t0.max(a.min(b));
+                t1 = t1.min(a.max(b));
+            }
+        }
+        (t0 <= t1).then(|| (from + delta * t0, from + delta * t1))
+    }
+
+    /// Unique entities in every cell crossed by the segment `from..to`
+    ///
+    /// Only the part of the segment over occupied cells is walked, so long or
+    /// infinite segments cost no more than one spanning the grid.
+    pub fn entities_along_segment(&self, from: Vec2, to: Vec2) -> Vec<Entity> {
+        let Some((from, to)) = self.clip_to_bounds(from, to) else {
+            return Vec::new();
+        };
+        let mut seen = HashSet::new();
+        let mut result = Vec::new();
+        let mut push_cell = |cell: (i32, i32)| {
+            for &entity in self.entities_in_cell(cell) {
+                if seen.insert(entity) {
+                    result.push(entity);
+                }
+            }
+        };
+
+        // Amanatides & Woo grid traversal
+        let (mut cx, mut cy) = self.cell_of(from);
+        let (end_x, end_y) = self.cell_of(to);
+        let delta = to - from;
+        let step_x = if delta.x >= 0.0 { 1 } else { -1 };
+        let step_y = if delta.y >= 0.0 { 1 } else { -1 };
+        let boundary =
+            |c: i32, step: i32| (c as i64 + if step > 0 { 1 } else { 0 }) as f32 * self.cell_size;
+        let mut t_max_x = if delta.x != 0.0 {
+            (boundary(cx, step_x) - from.x) / delta.x
+        } else {
+            f32::INFINITY
+        };
+        let mut t_max_y = if delta.y != 0.0 {
+            (boundary(cy, step_y) - from.y) / delta.y
+        } else {
+            f32::INFINITY
+        };
+        let t_delta_x = if delta.x != 0.0 {
+            self.cell_size / delta.x.abs()
+        } else {
+            f32::INFINITY
+        };
+        let t_delta_y = if delta.y != 0.0 {
+            self.cell_size / delta.y.abs()
+        } else {
+            f32::INFINITY
+        };
+
+        push_cell((cx, cy));
+        let max_steps = (end_x as i64 - cx as i64).abs() + (end_y as i64 - cy as i64).abs();
+        for _ in 0..max_steps {
+            if t_max_x < t_max_y {
+                cx = cx.saturating_add(step_x);
+                t_max_x += t_delta_x;
+            } else {
+                cy = cy.saturating_add(step_y);
+                t_max_y += t_delta_y;
+            }
+            push_cell((cx, cy));
+        }
+        result
+    }
+}
+
+/// Half width/height of a collision shape's bounding box
+pub(crate) fn half_extents(shape: &CollisionShape) -> Vec2 {
+    match shape {
+        CollisionShape::Circle { radius } => Vec2::new(*radius, *radius),
+        CollisionShape::Rectangle { width, height } => Vec2::new(width / 2.0, height / 2.0),
+    }
+}
+
+/// Cast a ray from `origin` along `dir` and return the nearest collider hit
+/// within `max_dist`.
+///
+/// Colliders are centered on their entity's `Position`. A collider that
+/// already contains the origin is reported at distance 0. Uses the world's
+/// `SpatialGrid` resource, if one is inserted, to limit the colliders tested.
+pub fn raycast(world: &World, origin: Vec2, dir: Vec2, max_dist: f32) -> Option<RayHit> {
+    let length = dir.norm();
+    if length <= f32::EPSILON || max_dist < 0.0 {
+        return None;
+    }
+    let dir = dir / length;
+
+    let entities = world.entities();
+    let positions = world.read_storage::<Position>();
+    let colliders = world.read_storage::<Collider>();
+
+    let test = |entity: Entity, position: &Position, collider: &Collider| {
+        let center = position.as_vec2();
+        let (distance, normal) = match collider.shape {
+            CollisionShape::Circle { radius } => ray_circle(origin, dir, center, radius)?,
+            CollisionShape::Rectangle { width, height } => {
+                let half = Vec2::new(width / 2.0, height / 2.0);
+                ray_aabb(origin, dir, center - half, center + half)?
+            }
+        };
+        (distance <= max_dist).then(|| RayHit {
+            entity,
+            point: origin + dir * distance,
+            normal,
+            distance,
+        })
+    };
+
+    let nearest = |best: Option<RayHit>, hit: RayHit| match best {
+        Some(b) if b.distance <= hit.distance => Some(b),
+        _ => Some(hit),
+    };
+
+    match world.try_fetch::<SpatialGrid>() {
+        Some(grid) => grid
+            .entities_along_segment(origin, origin + dir * max_dist)
+            .into_iter()
+            .filter_map(|entity| {
+                let position = positions.get(entity)?;
+                let collider = colliders.get(entity)?;
+                test(entity, position, collider)
+            })
+            .fold(None, nearest),
+        None => (&entities, &positions, &colliders)
+            .join()
+            .filter_map(|(entity, position, collider)| test(entity, position, collider))
+            .fold(None, nearest),
+    }
+}
+
+/// Return the topmost entity whose collider contains `point` (world space).
+///
+/// "Topmost" is the highest `ZOrder` (missing counts as 0); ties go to the
+/// entity created last. Use `Camera2D::screen_to_world` to pick with the mouse.
+pub fn pick_entity(world: &World, point: Vec2) -> Option<Entity> {
+    let entities = world.entities();
+    let positions = world.read_storage::<Position>();
+    let colliders = world.read_storage::<Collider>();
+    let z_orders = world.read_storage::<ZOrder>();
+    let probe = Collider::new_rectangle(0.0, 0.0);
+
+    (&entities, &positions, &colliders)
+        .join()
+        .filter(|(_, position, collider)| collider.intersects(position.as_vec2(), &probe, point))
+        .max_by_key(|(entity, _, _)| {
+            let z = z_orders.get(*entity).copied().unwrap_or_default();
+            (z, entity.id())
+        })
+        .map(|(entity, _, _)| entity)
+}
+
+/// Outline every collider (triggers included) around its entity's `Position`
+/// for debugging. Positions are in world space seen through `camera`, or
+/// used as screen coordinates without one.
+pub fn draw_colliders(
+    world: &World,
+    renderer: &mut Renderer2D,
+    camera: Option<&Camera2D>,
+    color: Color,
+) {
+    let positions = world.read_storage::<Position>();
+    let colliders = world.read_storage::<Collider>();
+    let to_screen = |point: Vec2| camera.map_or(point, |camera| camera.world_to_screen(point));
+    let zoom = camera.map_or(1.0, |camera| camera.zoom);
+
+    for (position, collider) in (&positions, &colliders).join() {
+        let center = position.as_vec2();
+        match collider.shape {
+            CollisionShape::Circle { radius } => {
+                let screen = to_screen(center);
+                renderer.draw_circle(
+                    screen.x.round() as i32,
+                    screen.y.round() as i32,
+                    (radius * zoom).round() as i32,
+                    color,
+                );
+            }
+            CollisionShape::Rectangle { width, height } => {
+                // corners go through the camera so rotated views stay correct
+                let half = Vec2::new(width / 2.0, height / 2.0);
+                let corners: Vec<Point2> = [(-1.0, -1.0), (1.0, -1.0), (1.0, 1.0), (-1.0, 1.0)]
+                    .iter()
+                    .map(|&(sx, sy)| {
+                        let corner = to_screen(center + Vec2::new(sx * half.x, sy * half.y));
+                        Point2::new(corner.x, corner.y)
+                    })
+                    .collect();
+                renderer.draw_polyline(&corners, color, true);
+            }
+        }
+    }
+}
+
+/// Distance along a unit ray to a circle, and the surface normal there
+fn ray_circle(origin: Vec2, dir: Vec2, center: Vec2, radius: f32) -> Option<(f32, Vec2)> {
+    let to_origin = origin - center;
+    let c = to_origin.norm_squared() - radius * radius;
+    if c <= 0.0 {
+        return Some((0.0, -dir));
+    }
+    let b = to_origin.dot(&dir);
+    let discriminant = b * b - c;
+    if b > 0.0 || discriminant < 0.0 {
+        return None;
+    }
+    let t = -b - discriminant.sqrt();
+    let normal = (origin + dir * t - center) / radius;
+    Some((t, normal))
+}
+
+/// Distance along a unit ray to an axis-aligned box (slab method), and the face normal
+fn ray_aabb(origin: Vec2, dir: Vec2, min: Vec2, max: Vec2) -> Option<(f32, Vec2)> {
+    let mut t_enter = f32::NEG_INFINITY;
+    let mut t_exit = f32::INFINITY;
+    let mut normal = Vec2::zeros();
+
+    for axis in 0..2 {
+        if dir[axis].abs() <= f32::EPSILON {
+            if origin[axis] < min[axis] || origin[axis] > max[axis] {
+                return None;
+            }
+            continue;
+        }
+        let t1 = (min[axis] - origin[axis]) / dir[axis];
+        let t2 = (max[axis] - origin[axis]) / dir[axis];
+        let (near, far) = if t1 < t2 { (t1, t2) } else { (t2, t1) };
+        if near > t_enter {
+            t_enter = near;
+            normal = Vec2::zeros();
+            normal[axis] = -dir[axis].signum();
+        }
+        t_exit = t_exit.min(far);
+    }
+
+    if t_enter > t_exit || t_exit < 0.0 {
+        return None;
+    }
+    if t_enter < 0.0 {
+        return Some((0.0, -dir));
+    }
+    Some((t_enter, normal))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ecs::GameWorldExt;
+
+    fn spawn(world: &mut World, x: f32, y: f32, collider: Collider) -> Entity {
+        world
+            .create_entity_with_components()
+            .with(Position::new(x, y))
+            .with(collider)
+            .build()
+    }
+
+    #[test]
+    fn test_raycast_hits_nearest_collider() {
+        let mut world = crate::init().unwrap();
+        let far = spawn(&mut world, 200.0, 0.0, Collider::new_circle(10.0));
+        let near = spawn(&mut world, 100.0, 0.0, Collider::new_rectangle(20.0, 40.0));
+
+        let hit = raycast(&world, Vec2::new(0.0, 0.0), Vec2::new(1.0, 0.0), 500.0).unwrap();
+        assert_eq!(hit.entity, near);
+        assert!((hit.distance - 90.0).abs() < 1e-4);
+        assert!((hit.point - Vec2::new(90.0, 0.0)).norm() < 1e-4);
+        assert_eq!(hit.normal, Vec2::new(-1.0, 0.0));
+
+        // Same answer through the spatial grid, and the far circle is reachable from behind
+        let mut grid = SpatialGrid::new(32.0);
+        grid.rebuild(&world);
+        world.insert(grid);
+        let hit = raycast(&world, Vec2::new(0.0, 0.0), Vec2::new(1.0, 0.0), 500.0).unwrap();
+        assert_eq!(hit.entity, near);
+        let hit = raycast(&world, Vec2::new(300.0, 0.0), Vec2::new(-1.0, 0.0), 500.0).unwrap();
+        assert_eq!(hit.entity, far);
+        assert!((hit.distance - 90.0).abs() < 1e-4);
+        assert!((hit.normal - Vec2::new(1.0, 0.0)).norm() < 1e-4);
+
+        // Unbounded rays only walk the occupied cells
+        for max_dist in [f32::MAX, f32::INFINITY] {
+            let hit = raycast(&world, Vec2::new(0.0, 0.0), Vec2::new(1.0, 0.0), max_dist).unwrap();
+            assert_eq!(hit.entity, near);
+            assert!(raycast(&world, Vec2::new(0.0, 0.0), Vec2::new(-1.0, 0.0), max_dist).is_none());
+        }
+        let grid = world.read_resource::<SpatialGrid>();
+        let cells = grid.entities_along_segment(Vec2::new(0.0, 1.0), Vec2::new(1e30, 1.0));
+        assert_eq!(cells.len(), 2);
+        assert!(SpatialGrid::new(32.0)
+            .entities_along_segment(Vec2::zeros(), Vec2::new(f32::INFINITY, 0.0))
+            .is_empty());
+    }
+
+    #[test]
+    fn test_pick_entity_prefers_higher_z() {
+        let mut world = crate::init().unwrap();
+        let top = spawn(&mut world, 50.0, 50.0, Collider::new_rectangle(40.0, 40.0));
+        world
+            .write_storage::<ZOrder>()
+            .insert(top, ZOrder(5))
+            .unwrap();
+        // created later and overlapping, but on a lower layer
+        let bottom = spawn(&mut world, 60.0, 60.0, Collider::new_circle(30.0));
+
+        assert_eq!(pick_entity(&world, Vec2::new(55.0, 55.0)), Some(top));
+        // only the circle covers this point
+        assert_eq!(pick_entity(&world, Vec2::new(85.0, 60.0)), Some(bottom));
+        assert_eq!(pick_entity(&world, Vec2::new(200.0, 200.0)), None);
+
+        // mouse -> world through a camera centered on the overlap
+        let camera = crate::rendering::Camera2D {
+            position: Vec2::new(55.0, 55.0),
+            zoom: 2.0,
+            rotation: 0.0,
+            viewport_size: Vec2::new(800.0, 600.0),
+        };
+        let world_point = camera.screen_to_world(Vec2::new(400.0, 300.0));
+        assert_eq!(pick_entity(&world, world_point), Some(top));
+        assert_eq!(camera.world_to_screen(world_point), Vec2::new(400.0, 300.0));
+    }
+
+    fn run_collisions(world: &mut World) {
+        specs::RunNow::run_now(&mut CollisionSystem::new(), world);
+        world.maintain();
+    }
+
+    #[test]
+    fn test_collision_handler_gets_normalized_roles() {
+        use crate::{Ball, Brick, Paddle};
+        use std::sync::{Arc, Mutex};
+
+        let mut world = crate::init().unwrap();
+        // the brick is created first, so the pair is detected as (brick, ball)
+        let brick = world
+            .create_entity_with_components()
+            .with(Position::new(0.0, 0.0))
+            .with(Collider::new_rectangle(40.0, 20.0))
+            .with(Brick)
+            .build();
+        let ball = world
+            .create_entity_with_components()
+            .with(Position::new(0.0, 12.0))
+            .with(Velocity::new(0.0, -100.0))
+            .with(Collider::new_circle(5.0))
+            .with(Ball)
+            .build();
+
+        let hits = Arc::new(Mutex::new(Vec::new()));
+        let recorded = hits.clone();
+        let mut system = CollisionSystem::new();
+        system
+            .on_collision::<Ball, Brick, _>(move |ball, brick, event, world| {
+                recorded.lock().unwrap().push((ball, brick, event.normal));
+                world.delete_entity(brick).unwrap();
+            })
+            .on_collision::<Ball, Paddle, _>(|_, _, _, _| panic!("no paddle here"));
+        system.run_with_callbacks(&mut world);
+
+        let hits = hits.lock().unwrap();
+        assert_eq!(hits.len(), 1);
+        assert_eq!((hits[0].0, hits[0].1), (ball, brick));
+        // normal points from the ball towards the brick above it
+        assert_eq!(hits[0].2, Vec2::new(0.0, -1.0));
+        assert!(!world.is_alive(brick));
+    }
+
+    #[test]
+    fn test_game_invokes_collision_handlers() {
+        use crate::{Ball, Brick};
+
+        let mut game = crate::Game::new().unwrap();
+        let brick = game
+            .world
+            .create_entity_with_components()
+            .with(Position::new(0.0, 0.0))
+            .with(Collider::new_rectangle(40.0, 20.0))
+            .with(Brick)
+            .build();
+        let ball = game
+            .world
+            .create_entity_with_components()
+            .with(Position::new(0.0, 12.0))
+            .with(Velocity::new(0.0, -100.0))
+            .with(Collider::new_circle(5.0))
+            .with(Ball)
+            .build();
+
+        game.on_collision::<Brick, Ball, _>(move |hit_brick, hit_ball, _, world| {
+            assert_eq!((hit_brick, hit_ball), (brick, ball));
+            world.delete_entity(hit_brick).unwrap();
+        });
+        game.update(1.0 / 60.0);
+
+        assert!(!game.world.is_alive(brick));
+        assert!(game.world.read_storage::<Velocity>().get(ball).unwrap().y > 0.0);
+    }
+
+    #[test]
+    fn test_trigger_reports_overlap_without_response() {
+        let mut world = crate::init().unwrap();
+        let zone = spawn(
+            &mut world,
+            0.0,
+            0.0,
+            Collider::new_rectangle(100.0, 20.0).as_trigger(),
+        );
+        let ball = world
+            .create_entity_with_components()
+            .with(Position::new(10.0, 5.0))
+            .with(Velocity::new(30.0, 120.0))
+            .with(Collider::new_circle(5.0))
+            .build();
+
+        run_collisions(&mut world);
+
+        let events = world.read_resource::<CollisionEvents>();
+        assert_eq!(
+            events.triggers,
+            vec![TriggerEvent {
+                entity: zone,
+                other: ball
+            }]
+        );
+        assert!(events.collisions.is_empty());
+        let velocity = world.read_storage::<Velocity>().get(ball).copied().unwrap();
+        assert_eq!((velocity.x, velocity.y), (30.0, 120.0));
+        let position = world.read_storage::<Position>().get(ball).copied().unwrap();
+        assert_eq!((position.x, position.y), (10.0, 5.0));
+    }
+
+    #[test]
+    fn test_solid_collision_reflects_velocity() {
+        let mut world = crate::init().unwrap();
+        spawn(&mut world, 0.0, 0.0, Collider::new_rectangle(100.0, 20.0));
+        let ball = world
+            .create_entity_with_components()
+            .with(Position::new(0.0, -12.0))
+            .with(Velocity::new(30.0, 120.0))
+            .with(Collider::new_circle(5.0))
+            .build();
+
+        run_collisions(&mut world);
+
+        assert_eq!(world.read_resource::<CollisionEvents>().collisions.len(), 1);
+        let velocity = world.read_storage::<Velocity>().get(ball).copied().unwrap();
+        assert_eq!((velocity.x, velocity.y), (30.0, -120.0));
+        let position = world.read_storage::<Position>().get(ball).copied().unwrap();
+        assert!((position.y - -15.0).abs() < 1e-4);
+    }
+
+    #[test]
+    fn test_symmetric_pair_reports_one_collision() {
+        let mut world = crate::init().unwrap();
+        let a = spawn(&mut world, 0.0, 0.0, Collider::new_circle(10.0));
+        let b = spawn(&mut world, 15.0, 0.0, Collider::new_circle(10.0));
+
+        run_collisions(&mut world);
+        let mut events = (*world.read_resource::<CollisionEvents>()).clone();
+        assert_eq!(events.collisions.len(), 1);
+        let event = events.collisions[0];
+        assert_eq!((event.entity_a, event.entity_b), (a, b));
+
+        // the same pair reported the other way round is dropped
+        let mirrored = CollisionEvent {
+            entity_a: b,
+            entity_b: a,
+            normal: -event.normal,
+            ..event
+        };
+        assert!(!events.push_collision(mirrored));
+        assert_eq!(events.collisions, vec![event]);
+    }
+
+    #[test]
+    fn test_draw_colliders_outlines_rect_through_camera() {
+        let mut world = crate::init().unwrap();
+        spawn(&mut world, 50.0, 30.0, Collider::new_rectangle(10.0, 10.0));
+
+        let mut renderer = Renderer2D::new(100, 60);
+        draw_colliders(&world, &mut renderer, None, Color::GREEN);
+        for (x, y) in [(45, 25), (55, 25), (45, 35), (55, 35), (50, 25), (45, 30)] {
+            assert_eq!(renderer.get_pixel(x, y), Some(Color::GREEN));
+        }
+        assert_eq!(renderer.get_pixel(50, 30), Some(Color(0)));
+
+        // a camera centered 20px to the right shifts the outline left
+        let camera = Camera2D {
+            position: Vec2::new(70.0, 30.0),
+            zoom: 1.0,
+            rotation: 0.0,
+            viewport_size: Vec2::new(100.0, 60.0),
+        };
+        let mut renderer = Renderer2D::new(100, 60);
+        draw_colliders(&world, &mut renderer, Some(&camera), Color::GREEN);
+        assert_eq!(renderer.get_pixel(25, 25), Some(Color::GREEN));
+        assert_eq!(renderer.get_pixel(45, 25), Some(Color(0)));
+    }
+
+    #[test]
+    fn test_corner_hit_reflects_along_diagonal() {
+        // 10x10 ball overlapping the top-left corner of a 40x20 brick by 1px on both axes
+        let brick = Collider::new_rectangle(40.0, 20.0);
+        let ball = Collider::new_rectangle(10.0, 10.0);
+        let corner = contact(Vec2::new(-24.0, -14.0), &ball, Vec2::zeros(), &brick).unwrap();
+        let diagonal = Vec2::new(1.0, 1.0).normalize();
+        assert!((corner.normal - diagonal).norm() < 1e-5);
+
+        let bounced = reflect(Vec2::new(100.0, 100.0), corner.normal);
+        assert!((bounced - Vec2::new(-100.0, -100.0)).norm() < 1e-3);
+
+        // a face hit still flips only one axis
+        let top = contact(Vec2::new(0.0, -14.0), &ball, Vec2::zeros(), &brick).unwrap();
+        let bounced = reflect(Vec2::new(100.0, 100.0), top.normal);
+        assert!((bounced - Vec2::new(100.0, -100.0)).norm() < 1e-3);
+    }
+
+    #[test]
+    fn test_impulse_scales_with_inverse_mass() {
+        let impulse = Vec2::new(4.0, -2.0);
+        let mut light = Velocity::new(0.0, 0.0);
+        let mut heavy = Velocity::new(0.0, 0.0);
+        apply_impulse(&mut light, impulse, &Mass(1.0));
+        apply_impulse(&mut heavy, impulse, &Mass(2.0));
+        assert_eq!((light.x, light.y), (4.0, -2.0));
+        assert_eq!((heavy.x, heavy.y), (2.0, -1.0));
+
+        // knockback only moves the lighter body, away from the heavier one
+        let mut world = crate::init().unwrap();
+        let boulder = world
+            .create_entity_with_components()
+            .with(Position::new(0.0, 0.0))
+            .with(Velocity::new(0.0, 0.0))
+            .with(Mass(10.0))
+            .with(Collider::new_circle(10.0))
+            .build();
+        let pebble = world
+            .create_entity_with_components()
+            .with(Position::new(15.0, 0.0))
+            .with(Velocity::new(-50.0, 0.0))
+            .with(Collider::new_circle(10.0))
+            .build();
+        run_collisions(&mut world);
+        specs::RunNow::run_now(&mut KnockbackSystem::new(), &world);
+
+        let velocities = world.read_storage::<Velocity>();
+        let boulder_vel = velocities.get(boulder).unwrap();
+        let pebble_vel = velocities.get(pebble).unwrap();
+        assert_eq!((boulder_vel.x, boulder_vel.y), (0.0, 0.0));
+        // reflected to +50, then pushed another 50 away
+        assert!((pebble_vel.x - 100.0).abs() < 1e-4);
+    }
+
+    #[test]
+    fn test_circle_sweep_hits_vertical_wall() {
+        let wall = (Vec2::new(20.0, -10.0), Vec2::new(20.0, 10.0));
+        let vel = Vec2::new(100.0, 0.0);
+
+        let (toi, normal) =
+            circle_segment_sweep(Vec2::zeros(), 5.0, vel, wall.0, wall.1, 1.0).unwrap();
+        assert!((toi - 0.15).abs() < 1e-5);
+        assert_eq!(normal, Vec2::new(-1.0, 0.0));
+        assert_eq!(reflect(vel, normal), Vec2::new(-100.0, 0.0));
+
+        // not reached within this frame, or moving away
+        assert!(circle_segment_sweep(Vec2::zeros(), 5.0, vel, wall.0, wall.1, 0.1).is_none());
+        assert!(circle_segment_sweep(Vec2::zeros(), 5.0, -vel, wall.0, wall.1, 1.0).is_none());
+
+        // grazing past the top end hits its rounded cap instead
+        let (toi, normal) =
+            circle_segment_sweep(Vec2::new(0.0, -13.0), 5.0, vel, wall.0, wall.1, 1.0).unwrap();
+        assert!((toi - 0.16).abs() < 1e-5);
+        assert!((normal - Vec2::new(-0.8, -0.6)).norm() < 1e-5);
+    }
+
+    #[test]
+    fn test_trajectory_reflects_off_wall() {
+        let bounds = WorldBounds::from_size(100.0, 100.0);
+        let path = simulate_trajectory(
+            Vec2::new(80.0, 50.0),
+            Vec2::new(100.0, -50.0),
+            4,
+            0.1,
+            &bounds,
+        );
+        assert_eq!(path.len(), 5);
+        assert_eq!(path[0], Vec2::new(80.0, 50.0));
+        assert!((path[1] - Vec2::new(90.0, 45.0)).norm() < 1e-4);
+        // hit the right wall at x = 100 and came back
+        assert!((path[3] - Vec2::new(90.0, 35.0)).norm() < 1e-4);
+        assert!((path[4] - Vec2::new(80.0, 30.0)).norm() < 1e-4);
+        assert!(path.iter().all(|p| bounds.contains(*p)));
+    }
+
+    #[test]
+    fn test_raycast_miss_returns_none() {
+        let mut world = crate::init().unwrap();
+        spawn(&mut world, 100.0, 0.0, Collider::new_circle(10.0));
+
+        // pointing away
+        assert!(raycast(&world, Vec2::new(0.0, 0.0), Vec2::new(-1.0, 0.0), 500.0).is_none());
+        // passing above
+        assert!(raycast(&world, Vec2::new(0.0, -20.0), Vec2::new(1.0, 0.0), 500.0).is_none());
+        // too short
+        assert!(raycast(&world, Vec2::new(0.0, 0.0), Vec2::new(1.0, 0.0), 50.0).is_none());
+    }
+}
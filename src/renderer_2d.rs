@@ -3,8 +3,10 @@
 //! Provides basic 2D rendering capabilities for games.
 //! Supports shapes, text, and frame buffer management.
 
-use crate::font::{FontSystem, TextBitmap};
+use crate::font::{BitmapFont, FontMetrics, FontSystem, GlyphMetrics, TextBitmap};
+use crate::rendering::Camera2D;
 use crate::window::WindowManager;
+use crate::{Point2, Vec2};
 use std::path::Path;
 
 /// Color representation (ARGB format)
@@ -31,6 +33,31 @@ impl Color {
         Color(((a as u32) << 24) | ((r as u32) << 16) | ((g as u32) << 8) | (b as u32))
     }
 
+    /// Create a color from normalized `[r, g, b, a]` floats, clamped to `0.0..=1.0`
+    pub fn from_f32(rgba: [f32; 4]) -> Self {
+        let [r, g, b, a] = rgba.map(|c| (c.clamp(0.0, 1.0) * 255.0).round() as u8);
+        Color::rgba(r, g, b, a)
+    }
+
+    /// Create an opaque color from a hue in degrees (wrapped to `0..360`) and
+    /// saturation/value in `0.0..=1.0`
+    pub fn from_hsv(hue: f32, saturation: f32, value: f32) -> Self {
+        let (s, v) = (saturation.clamp(0.0, 1.0), value.clamp(0.0, 1.0));
+        let h = hue.rem_euclid(360.0) / 60.0;
+        let c = v * s;
+        let x = c * (1.0 - (h % 2.0 - 1.0).abs());
+        let (r, g, b) = match h as u32 {
+            0 => (c, x, 0.0),
+            1 => (x, c, 0.0),
+            2 => (0.0, c, x),
+            3 => (0.0, x, c),
+            4 => (x, 0.0, c),
+            _ => (c, 0.0, x),
+        };
+        let m = v - c;
+        Color::from_f32([r + m, g + m, b + m, 1.0])
+    }
+
     /// Get red component
     pub fn r(&self) -> u8 {
         ((self.0 >> 16) & 0xFF) as u8
@@ -50,6 +77,384 @@ impl Color {
     pub fn a(&self) -> u8 {
         ((self.0 >> 24) & 0xFF) as u8
     }
+
+    /// Linearly interpolate every channel (including alpha) towards `other`.
+    /// `t` is clamped to `0.0..=1.0`.
+    pub fn lerp(self, other: Color, t: f32) -> Color {
+        let t = t.clamp(0.0, 1.0);
+        let mix = |a: u8, b: u8| (a as f32 + (b as f32 - a as f32) * t).round() as u8;
+        Color::rgba(
+            mix(self.r(), other.r()),
+            mix(self.g(), other.g()),
+            mix(self.b(), other.b()),
+            mix(self.a(), other.a()),
+        )
+    }
+}
+
+/// Color vision deficiency simulation applied to the final frame
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ColorFilter {
+    #[default]
+    None,
+    Protanopia,
+    Deuteranopia,
+    Tritanopia,
+}
+
+impl ColorFilter {
+    /// Row-major RGB transform for this filter, `None` for the identity
+    pub fn matrix(self) -> Option<[[f32; 3]; 3]> {
+        match self {
+            ColorFilter::None => None,
+            ColorFilter::Protanopia => Some([
+                [0.567, 0.433, 0.0],
+                [0.558, 0.442, 0.0],
+                [0.0, 0.242, 0.758],
+            ]),
+            ColorFilter::Deuteranopia => {
+                Some([[0.625, 0.375, 0.0], [0.7, 0.3, 0.0], [0.0, 0.3, 0.7]])
+            }
+            ColorFilter::Tritanopia => {
+                Some([[0.95, 0.05, 0.0], [0.0, 0.433, 0.567], [0.0, 0.475, 0.525]])
+            }
+        }
+    }
+
+    /// Transform one color, keeping its alpha
+    pub fn apply(self, color: Color) -> Color {
+        match self.matrix() {
+            Some(matrix) => Self::transform(&matrix, color),
+            None => color,
+        }
+    }
+
+    /// Transform every pixel of an ARGB buffer in place
+    pub fn apply_buffer(self, buffer: &mut [u32]) {
+        if let Some(matrix) = self.matrix() {
+            for pixel in buffer {
+                *pixel = Self::transform(&matrix, Color(*pixel)).0;
+            }
+        }
+    }
+
+    fn transform(matrix: &[[f32; 3]; 3], color: Color) -> Color {
+        let rgb = [color.r() as f32, color.g() as f32, color.b() as f32];
+        let channel = |row: &[f32; 3]| {
+            (row[0] * rgb[0] + row[1] * rgb[1] + row[2] * rgb[2])
+                .round()
+                .clamp(0.0, 255.0) as u8
+        };
+        Color::rgba(
+            channel(&matrix[0]),
+            channel(&matrix[1]),
+            channel(&matrix[2]),
+            color.a(),
+        )
+    }
+}
+
+/// Axis along which a gradient changes color
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum GradientDirection {
+    /// First color at the top, second at the bottom
+    Vertical,
+    /// First color on the left, second on the right
+    Horizontal,
+}
+
+/// Axis-aligned rectangle in pixel coordinates
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Rect {
+    pub x: i32,
+    pub y: i32,
+    pub width: i32,
+    pub height: i32,
+}
+
+impl Rect {
+    /// Create a new rectangle
+    pub fn new(x: i32, y: i32, width: i32, height: i32) -> Self {
+        Self {
+            x,
+            y,
+            width,
+            height,
+        }
+    }
+
+    /// Whether the point lies inside the rectangle
+    pub fn contains(&self, x: i32, y: i32) -> bool {
+        x >= self.x && x < self.x + self.width && y >= self.y && y < self.y + self.height
+    }
+
+    /// Overlapping part of two rectangles, `None` if they don't overlap
+    pub fn intersect(&self, other: &Rect) -> Option<Rect> {
+        let x0 = self.x.max(other.x);
+        let y0 = self.y.max(other.y);
+        let x1 = (self.x + self.width).min(other.x + other.width);
+        let y1 = (self.y + self.height).min(other.y + other.height);
+        if x1 > x0 && y1 > y0 {
+            Some(Rect::new(x0, y0, x1 - x0, y1 - y0))
+        } else {
+            None
+        }
+    }
+
+    /// Smallest rectangle containing both `self` and `other`
+    pub fn union(&self, other: &Rect) -> Rect {
+        let x0 = self.x.min(other.x);
+        let y0 = self.y.min(other.y);
+        let x1 = (self.x + self.width).max(other.x + other.width);
+        let y1 = (self.y + self.height).max(other.y + other.height);
+        Rect::new(x0, y0, x1 - x0, y1 - y0)
+    }
+}
+
+/// CPU-side image that can be drawn into the renderer (ARGB pixels, row-major)
+#[derive(Debug, Clone, PartialEq)]
+pub struct Sprite {
+    pub width: usize,
+    pub height: usize,
+    pub pixels: Vec<u32>,
+}
+
+impl Sprite {
+    /// Create a sprite from raw ARGB pixels
+    pub fn new(width: usize, height: usize, pixels: Vec<u32>) -> Self {
+        assert_eq!(
+            pixels.len(),
+            width * height,
+            "sprite pixel count must match its dimensions"
+        );
+        Self {
+            width,
+            height,
+            pixels,
+        }
+    }
+
+    /// Create a sprite from tightly packed RGBA8 bytes (as produced by the `image` crate)
+    pub fn from_rgba(width: usize, height: usize, rgba: &[u8]) -> Self {
+        let pixels = rgba
+            .chunks_exact(4)
+            .map(|p| Color::rgba(p[0], p[1], p[2], p[3]).0)
+            .collect();
+        Self::new(width, height, pixels)
+    }
+
+    /// Decode an encoded image (PNG, JPEG, ...) held in memory, e.g. an
+    /// asset embedded with `include_bytes!`
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, image::ImageError> {
+        let image = image::load_from_memory(bytes)?.to_rgba8();
+        Ok(Self::from_rgba(
+            image.width() as usize,
+            image.height() as usize,
+            image.as_raw(),
+        ))
+    }
+
+    /// Create a sprite filled with a single color
+    pub fn filled(width: usize, height: usize, color: Color) -> Self {
+        Self::new(width, height, vec![color.0; width * height])
+    }
+
+    /// Get the color of a source pixel
+    pub fn get_pixel(&self, x: usize, y: usize) -> Color {
+        Color(self.pixels[y * self.width + x])
+    }
+}
+
+/// A single recorded drawing command
+#[derive(Debug, Clone)]
+pub enum DrawCommand {
+    Rect {
+        rect: Rect,
+        color: Color,
+        filled: bool,
+    },
+    Circle {
+        center_x: i32,
+        center_y: i32,
+        radius: i32,
+        color: Color,
+        filled: bool,
+    },
+    Line {
+        x1: i32,
+        y1: i32,
+        x2: i32,
+        y2: i32,
+        color: Color,
+    },
+    Text {
+        text: String,
+        x: usize,
+        y: usize,
+        color: Color,
+        scale: usize,
+    },
+    Sprite {
+        sprite: Sprite,
+        x: i32,
+        y: i32,
+    },
+}
+
+/// Named bands of `DisplayList` z values, composited in declaration order.
+///
+/// Systems draw into their layer without knowing what else is drawn; within a
+/// layer, `depth` orders commands as plain z values do.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum RenderLayer {
+    Background,
+    World,
+    Particles,
+    UI,
+    Debug,
+}
+
+impl RenderLayer {
+    /// Number of z values reserved for each layer
+    pub const SPAN: i32 = 1000;
+
+    /// z value for `depth` within this layer. `World` is centered on 0, so
+    /// small raw z values (e.g. `ZOrder`) already fall in it; depth is clamped
+    /// to the layer's band so layers never interleave.
+    pub fn z(self, depth: i32) -> i32 {
+        let depth = depth.clamp(-Self::SPAN / 2, Self::SPAN / 2 - 1);
+        (self as i32 - RenderLayer::World as i32) * Self::SPAN + depth
+    }
+}
+
+/// Recorded list of drawing commands with explicit z layers.
+///
+/// Replay it with `Renderer2D::execute`; higher layers are drawn on top and
+/// commands on the same layer keep their recording order. A list can be kept
+/// around and replayed every frame (e.g. a static background).
+#[derive(Debug, Clone, Default)]
+pub struct DisplayList {
+    commands: Vec<(i32, DrawCommand)>,
+}
+
+impl DisplayList {
+    /// Create an empty display list
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Record a command on layer `z`
+    pub fn push(&mut self, z: i32, command: DrawCommand) {
+        self.commands.push((z, command));
+    }
+
+    /// Record a command at depth 0 of a named layer
+    pub fn push_layer(&mut self, layer: RenderLayer, command: DrawCommand) {
+        self.push(layer.z(0), command);
+    }
+
+    /// Record a filled rectangle
+    pub fn rect(&mut self, z: i32, x: i32, y: i32, width: i32, height: i32, color: Color) {
+        self.push(
+            z,
+            DrawCommand::Rect {
+                rect: Rect::new(x, y, width, height),
+                color,
+                filled: true,
+            },
+        );
+    }
+
+    /// Record a rectangle outline
+    pub fn rect_outline(&mut self, z: i32, x: i32, y: i32, width: i32, height: i32, color: Color) {
+        self.push(
+            z,
+            DrawCommand::Rect {
+                rect: Rect::new(x, y, width, height),
+                color,
+                filled: false,
+            },
+        );
+    }
+
+    /// Record a circle (filled or outline)
+    pub fn circle(
+        &mut self,
+        z: i32,
+        center_x: i32,
+        center_y: i32,
+        radius: i32,
+        color: Color,
+        filled: bool,
+    ) {
+        self.push(
+            z,
+            DrawCommand::Circle {
+                center_x,
+                center_y,
+                radius,
+                color,
+                filled,
+            },
+        );
+    }
+
+    /// Record a line
+    pub fn line(&mut self, z: i32, x1: i32, y1: i32, x2: i32, y2: i32, color: Color) {
+        self.push(
+            z,
+            DrawCommand::Line {
+                x1,
+                y1,
+                x2,
+                y2,
+                color,
+            },
+        );
+    }
+
+    /// Record a text string
+    pub fn text(&mut self, z: i32, text: &str, x: usize, y: usize, color: Color, scale: usize) {
+        self.push(
+            z,
+            DrawCommand::Text {
+                text: text.to_string(),
+                x,
+                y,
+                color,
+                scale,
+            },
+        );
+    }
+
+    /// Record a sprite drawn with its top-left corner at `(x, y)`
+    pub fn sprite(&mut self, z: i32, sprite: Sprite, x: i32, y: i32) {
+        self.push(z, DrawCommand::Sprite { sprite, x, y });
+    }
+
+    /// Remove all recorded commands
+    pub fn clear(&mut self) {
+        self.commands.clear();
+    }
+
+    /// Number of recorded commands
+    pub fn len(&self) -> usize {
+        self.commands.len()
+    }
+
+    /// Whether no commands are recorded
+    pub fn is_empty(&self) -> bool {
+        self.commands.is_empty()
+    }
+}
+
+/// Work done by a `Renderer2D` since the last `take_stats`
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct RenderStats {
+    /// Drawing primitives called; composite calls such as `draw_text` count once
+    pub draw_calls: usize,
+    /// Pixels actually written, after clipping and masking
+    pub pixels_written: usize,
 }
 
 /// 2D Renderer for basic graphics operations
@@ -58,6 +463,20 @@ pub struct Renderer2D {
     width: usize,
     height: usize,
     font_system: FontSystem,
+    /// Union of all regions written since the last `take_dirty_rect`
+    dirty: Option<Rect>,
+    /// Optional clip rectangle; pixels outside it are never written
+    clip: Option<Rect>,
+    /// Offset added to every draw call, set by `with_viewport`
+    origin: (i32, i32),
+    /// When set, pixels are alpha-blended at this opacity instead of overwritten
+    blend_opacity: Option<f32>,
+    /// When set, only pixels whose entry is `true` are written
+    mask: Option<Vec<bool>>,
+    /// Counters reset by `take_stats`
+    stats: RenderStats,
+    /// Nesting depth of composite draw calls, so their parts aren't counted
+    draw_depth: usize,
 }
 
 impl Renderer2D {
@@ -75,6 +494,13 @@ impl Renderer2D {
             width,
             height,
             font_system,
+            dirty: None,
+            clip: None,
+            origin: (0, 0),
+            blend_opacity: None,
+            mask: None,
+            stats: RenderStats::default(),
+            draw_depth: 0,
         }
     }
 
@@ -84,13 +510,95 @@ impl Renderer2D {
         Self::new(width, height)
     }
 
-    /// Clear the buffer with a specific color
+    /// Clear the buffer with a specific color. With a clip rect (or inside
+    /// `with_viewport`) only the clipped area is cleared.
     pub fn clear(&mut self, color: Color) {
-        self.buffer.fill(color.0);
+        self.count_draw_call();
+        if self.clip.is_none() && self.mask.is_none() {
+            self.buffer.fill(color.0);
+            self.stats.pixels_written += self.buffer.len();
+            self.mark_all_dirty();
+            return;
+        }
+        let Some(area) = self.writable_rect() else {
+            return;
+        };
+        for y in area.y..area.y + area.height {
+            let start = y as usize * self.width + area.x as usize;
+            let row = start..start + area.width as usize;
+            match &self.mask {
+                None => {
+                    self.buffer[row].fill(color.0);
+                    self.stats.pixels_written += area.width as usize;
+                }
+                Some(mask) => {
+                    for index in row.filter(|&index| mask.get(index) == Some(&true)) {
+                        self.buffer[index] = color.0;
+                        self.stats.pixels_written += 1;
+                    }
+                }
+            }
+        }
+        self.mark_dirty(area);
+    }
+
+    /// Run `draw` with all drawing offset to `(x, y)` and clipped to a
+    /// `width` x `height` sub-viewport, e.g. one half of a split screen.
+    /// Viewports nest; the previous offset and clip are restored afterwards.
+    pub fn with_viewport<R>(
+        &mut self,
+        x: i32,
+        y: i32,
+        width: i32,
+        height: i32,
+        draw: impl FnOnce(&mut Self) -> R,
+    ) -> R {
+        let saved_origin = self.origin;
+        let saved_clip = self.clip;
+
+        let origin = (saved_origin.0 + x, saved_origin.1 + y);
+        let viewport = Rect::new(origin.0, origin.1, width, height);
+        let clip = match saved_clip {
+            Some(clip) => clip.intersect(&viewport),
+            None => Some(viewport),
+        };
+        self.origin = origin;
+        self.clip = Some(clip.unwrap_or(Rect::new(0, 0, 0, 0)));
+
+        let result = draw(self);
+
+        self.origin = saved_origin;
+        self.clip = saved_clip;
+        result
     }
 
     /// Draw a filled rectangle
     pub fn draw_rect(&mut self, x: i32, y: i32, width: i32, height: i32, color: Color) {
+        self.count_draw_call();
+        if width <= 0 || height <= 0 {
+            return;
+        }
+
+        // Fast path: an unblended fill fully inside the buffer and clip rect
+        // is written a row slice at a time
+        let target = Rect::new(x + self.origin.0, y + self.origin.1, width, height);
+        let covers = |outer: Rect| outer.intersect(&target) == Some(target);
+        let in_buffer = covers(Rect::new(0, 0, self.width as i32, self.height as i32));
+        if self.blend_opacity.is_none()
+            && self.mask.is_none()
+            && in_buffer
+            && self.clip.is_none_or(covers)
+        {
+            let (left, row_width) = (target.x as usize, target.width as usize);
+            for row in target.y as usize..(target.y + target.height) as usize {
+                let start = row * self.width + left;
+                self.buffer[start..start + row_width].fill(color.0);
+            }
+            self.stats.pixels_written += row_width * target.height as usize;
+            self.mark_dirty(target);
+            return;
+        }
+
         for dy in 0..height {
             for dx in 0..width {
                 let px = x + dx;
@@ -100,8 +608,76 @@ impl Renderer2D {
         }
     }
 
+    /// Draw a filled rectangle blended over the existing pixels using the color's alpha
+    pub fn draw_rect_blended(&mut self, x: i32, y: i32, width: i32, height: i32, color: Color) {
+        self.count_draw_call();
+        let alpha = color.a() as f32 / 255.0;
+        for dy in 0..height {
+            for dx in 0..width {
+                let (px, py) = (x + dx, y + dy);
+                // `get_pixel` reads buffer coordinates; `set_pixel` adds the origin
+                if let Some(dst) = self.get_pixel(px + self.origin.0, py + self.origin.1) {
+                    let blended = dst.lerp(color, alpha);
+                    self.set_pixel(
+                        px,
+                        py,
+                        Color::rgba(blended.r(), blended.g(), blended.b(), dst.a()),
+                    );
+                }
+            }
+        }
+    }
+
+    /// Draw a rectangle filled with a two-color gradient. With
+    /// `GradientDirection::Vertical` each row is one color, going from `start`
+    /// at the top to `end` at the bottom; `Horizontal` does the same per column.
+    #[allow(clippy::too_many_arguments)]
+    pub fn draw_rect_gradient(
+        &mut self,
+        x: i32,
+        y: i32,
+        width: i32,
+        height: i32,
+        start: Color,
+        end: Color,
+        direction: GradientDirection,
+    ) {
+        self.begin_composite();
+        let steps = match direction {
+            GradientDirection::Vertical => height,
+            GradientDirection::Horizontal => width,
+        };
+        for i in 0..steps.max(0) {
+            let t = if steps > 1 {
+                i as f32 / (steps - 1) as f32
+            } else {
+                0.0
+            };
+            let color = start.lerp(end, t);
+            match direction {
+                GradientDirection::Vertical => self.draw_rect(x, y + i, width, 1, color),
+                GradientDirection::Horizontal => self.draw_rect(x + i, y, 1, height, color),
+            }
+        }
+        self.end_composite();
+    }
+
+    /// Fill the whole buffer with a vertical gradient (useful for backgrounds)
+    pub fn fill_gradient(&mut self, top: Color, bottom: Color) {
+        self.draw_rect_gradient(
+            0,
+            0,
+            self.width as i32,
+            self.height as i32,
+            top,
+            bottom,
+            GradientDirection::Vertical,
+        );
+    }
+
     /// Draw a rectangle outline
     pub fn draw_rect_outline(&mut self, x: i32, y: i32, width: i32, height: i32, color: Color) {
+        self.count_draw_call();
         // Top and bottom lines
         for dx in 0..width {
             self.set_pixel(x + dx, y, color);
@@ -116,6 +692,7 @@ impl Renderer2D {
 
     /// Draw a line between two points
     pub fn draw_line(&mut self, x1: i32, y1: i32, x2: i32, y2: i32, color: Color) {
+        self.count_draw_call();
         let dx = (x2 - x1).abs();
         let dy = (y2 - y1).abs();
         let sx = if x1 < x2 { 1 } else { -1 };
@@ -144,8 +721,176 @@ impl Renderer2D {
         }
     }
 
+    /// Draw a line alternating `dash` drawn pixels with `gap` skipped pixels,
+    /// starting with a dash at `(x1, y1)`
+    #[allow(clippy::too_many_arguments)]
+    pub fn draw_dashed_line(
+        &mut self,
+        x1: i32,
+        y1: i32,
+        x2: i32,
+        y2: i32,
+        color: Color,
+        dash: usize,
+        gap: usize,
+    ) {
+        self.count_draw_call();
+        if dash == 0 {
+            return;
+        }
+        let period = dash + gap;
+        let dx = (x2 - x1).abs();
+        let dy = (y2 - y1).abs();
+        let sx = if x1 < x2 { 1 } else { -1 };
+        let sy = if y1 < y2 { 1 } else { -1 };
+        let mut err = dx - dy;
+
+        let mut x = x1;
+        let mut y = y1;
+        let mut step = 0;
+
+        loop {
+            if step % period < dash {
+                self.set_pixel(x, y, color);
+            }
+
+            if x == x2 && y == y2 {
+                break;
+            }
+
+            let e2 = 2 * err;
+            if e2 > -dy {
+                err -= dy;
+                x += sx;
+            }
+            if e2 < dx {
+                err += dx;
+                y += sy;
+            }
+            step += 1;
+        }
+    }
+
+    /// Draw a debug grid of lines every `spacing` pixels, offset so that a
+    /// vertical and a horizontal line pass through `origin`
+    pub fn draw_grid(&mut self, spacing: f32, color: Color, origin: Point2) {
+        if spacing < 1.0 {
+            return;
+        }
+        self.begin_composite();
+        let (width, height) = (self.width as f32, self.height as f32);
+        let mut x = origin.x.rem_euclid(spacing);
+        while x < width {
+            self.draw_line(x as i32, 0, x as i32, height as i32 - 1, color);
+            x += spacing;
+        }
+        let mut y = origin.y.rem_euclid(spacing);
+        while y < height {
+            self.draw_line(0, y as i32, width as i32 - 1, y as i32, color);
+            y += spacing;
+        }
+        self.end_composite();
+    }
+
+    /// Draw a grid of world-space lines every `spacing` units as seen through
+    /// `camera`, following its position, zoom and rotation
+    pub fn draw_grid_camera(&mut self, spacing: f32, color: Color, camera: &Camera2D) {
+        if spacing * camera.zoom < 1.0 {
+            return;
+        }
+        self.begin_composite();
+        let (width, height) = (self.width as f32, self.height as f32);
+        let corners = [
+            camera.screen_to_world(Vec2::new(0.0, 0.0)),
+            camera.screen_to_world(Vec2::new(width, 0.0)),
+            camera.screen_to_world(Vec2::new(0.0, height)),
+            camera.screen_to_world(Vec2::new(width, height)),
+        ];
+        let min = corners.iter().fold(corners[0], |a, b| a.inf(b));
+        let max = corners.iter().fold(corners[0], |a, b| a.sup(b));
+
+        let line = |renderer: &mut Self, a: Vec2, b: Vec2| {
+            let (a, b) = (camera.world_to_screen(a), camera.world_to_screen(b));
+            renderer.draw_line(a.x as i32, a.y as i32, b.x as i32, b.y as i32, color);
+        };
+        let mut x = (min.x / spacing).ceil() * spacing;
+        while x <= max.x {
+            line(self, Vec2::new(x, min.y), Vec2::new(x, max.y));
+            x += spacing;
+        }
+        let mut y = (min.y / spacing).ceil() * spacing;
+        while y <= max.y {
+            line(self, Vec2::new(min.x, y), Vec2::new(max.x, y));
+            y += spacing;
+        }
+        self.end_composite();
+    }
+
+    /// Draw connected line segments through `points`; `closed` also joins the
+    /// last point back to the first
+    pub fn draw_polyline(&mut self, points: &[Point2], color: Color, closed: bool) {
+        self.begin_composite();
+        for pair in points.windows(2) {
+            self.draw_line_points(pair[0], pair[1], color);
+        }
+        if closed && points.len() > 2 {
+            self.draw_line_points(points[points.len() - 1], points[0], color);
+        }
+        self.end_composite();
+    }
+
+    /// Fill a polygon using a scanline fill with the even-odd rule, so
+    /// concave and self-intersecting outlines are handled correctly
+    pub fn draw_polygon_filled(&mut self, points: &[Point2], color: Color) {
+        self.count_draw_call();
+        if points.len() < 3 {
+            return;
+        }
+
+        let min_y = points.iter().map(|p| p.y).fold(f32::INFINITY, f32::min);
+        let max_y = points.iter().map(|p| p.y).fold(f32::NEG_INFINITY, f32::max);
+        let start = (min_y.floor() as i32).max(0);
+        let end = (max_y.ceil() as i32).min(self.height as i32);
+
+        let mut crossings: Vec<f32> = Vec::new();
+        for y in start..end {
+            // sample at the pixel center
+            let sample_y = y as f32 + 0.5;
+            crossings.clear();
+            for (i, a) in points.iter().enumerate() {
+                let b = points[(i + 1) % points.len()];
+                // half-open test so shared vertices are counted exactly once
+                if (a.y <= sample_y) != (b.y <= sample_y) {
+                    let t = (sample_y - a.y) / (b.y - a.y);
+                    crossings.push(a.x + t * (b.x - a.x));
+                }
+            }
+            crossings.sort_by(|a, b| a.total_cmp(b));
+
+            for span in crossings.chunks_exact(2) {
+                // fill pixels whose centers lie inside [span[0], span[1])
+                let x_start = (span[0] - 0.5).ceil() as i32;
+                let x_end = (span[1] - 0.5).ceil() as i32;
+                for x in x_start..x_end {
+                    self.set_pixel(x, y, color);
+                }
+            }
+        }
+    }
+
+    fn draw_line_points(&mut self, a: Point2, b: Point2, color: Color) {
+        self.draw_line(
+            a.x.round() as i32,
+            a.y.round() as i32,
+            b.x.round() as i32,
+            b.y.round() as i32,
+            color,
+        );
+    }
+
     /// Draw a circle
     pub fn draw_circle(&mut self, center_x: i32, center_y: i32, radius: i32, color: Color) {
+        self.count_draw_call();
         let mut x = 0;
         let mut y = radius;
         let mut d = 3 - 2 * radius;
@@ -171,8 +916,83 @@ impl Renderer2D {
         }
     }
 
+    /// Draw a ring filling the annulus between `radius - thickness / 2` and
+    /// `radius + thickness / 2`. Use `draw_circle` for a cheap 1px outline.
+    pub fn draw_circle_thick(
+        &mut self,
+        center_x: i32,
+        center_y: i32,
+        radius: i32,
+        thickness: i32,
+        color: Color,
+    ) {
+        self.count_draw_call();
+        let half = thickness.max(1) as f32 / 2.0;
+        let inner = (radius as f32 - half).max(0.0);
+        let outer = radius as f32 + half;
+        let (inner_sq, outer_sq) = (inner * inner, outer * outer);
+        let extent = outer.ceil() as i32;
+
+        for y in -extent..=extent {
+            for x in -extent..=extent {
+                let distance_sq = (x * x + y * y) as f32;
+                if distance_sq >= inner_sq && distance_sq <= outer_sq {
+                    self.set_pixel(center_x + x, center_y + y, color);
+                }
+            }
+        }
+    }
+
+    /// Copy a `src_w` x `src_h` pixel buffer with its top-left at `(dst_x, dst_y)`,
+    /// clipped to the buffer, clip rect and viewport. Pixels are copied as-is.
+    pub fn blit(&mut self, src: &[u32], src_w: usize, src_h: usize, dst_x: i32, dst_y: i32) {
+        self.blit_pixels(src, src_w, src_h, dst_x, dst_y, false);
+    }
+
+    /// Like `blit`, but blends each source pixel over the destination by its alpha
+    pub fn blit_blended(
+        &mut self,
+        src: &[u32],
+        src_w: usize,
+        src_h: usize,
+        dst_x: i32,
+        dst_y: i32,
+    ) {
+        self.blit_pixels(src, src_w, src_h, dst_x, dst_y, true);
+    }
+
+    /// Copy another renderer's whole buffer, e.g. a pre-rendered background
+    pub fn blit_from(&mut self, other: &Renderer2D, dst_x: i32, dst_y: i32) {
+        let (width, height) = other.dimensions();
+        self.blit(other.buffer(), width, height, dst_x, dst_y);
+    }
+
+    fn blit_pixels(
+        &mut self,
+        src: &[u32],
+        src_w: usize,
+        src_h: usize,
+        dst_x: i32,
+        dst_y: i32,
+        blend: bool,
+    ) {
+        self.count_draw_call();
+        let rows = src_h.min(src.len() / src_w.max(1));
+        let saved = self.blend_opacity;
+        if blend {
+            self.blend_opacity = Some(1.0);
+        }
+        for (sy, row) in src.chunks_exact(src_w.max(1)).take(rows).enumerate() {
+            for (sx, &pixel) in row.iter().enumerate() {
+                self.set_pixel(dst_x + sx as i32, dst_y + sy as i32, Color(pixel));
+            }
+        }
+        self.blend_opacity = saved;
+    }
+
     /// Draw filled circle
     pub fn draw_circle_filled(&mut self, center_x: i32, center_y: i32, radius: i32, color: Color) {
+        self.count_draw_call();
         for y in -radius..=radius {
             for x in -radius..=radius {
                 if x * x + y * y <= radius * radius {
@@ -182,49 +1002,263 @@ impl Renderer2D {
         }
     }
 
+    /// Draw a sprite as a scalable nine-patch.
+    ///
+    /// `insets` are `(left, top, right, bottom)` in source pixels. Corners keep
+    /// their size, edges stretch along one axis and the center stretches along
+    /// both. Insets are clamped so they never exceed the source size.
+    pub fn draw_nine_patch(&mut self, sprite: &Sprite, dst: Rect, insets: (u32, u32, u32, u32)) {
+        self.count_draw_call();
+        if sprite.width == 0 || sprite.height == 0 || dst.width <= 0 || dst.height <= 0 {
+            return;
+        }
+
+        let src_w = sprite.width as i32;
+        let src_h = sprite.height as i32;
+        let left = (insets.0 as i32).min(src_w);
+        let right = (insets.2 as i32).min(src_w - left);
+        let top = (insets.1 as i32).min(src_h);
+        let bottom = (insets.3 as i32).min(src_h - top);
+
+        for dy in 0..dst.height {
+            let sy = nine_patch_source(dy, dst.height, src_h, top, bottom);
+            for dx in 0..dst.width {
+                let sx = nine_patch_source(dx, dst.width, src_w, left, right);
+                let color = sprite.get_pixel(sx as usize, sy as usize);
+                if color.a() > 0 {
+                    self.set_pixel(dst.x + dx, dst.y + dy, color);
+                }
+            }
+        }
+    }
+
+    /// Draw a sprite with its top-left corner at `(x, y)`; fully transparent pixels are skipped
+    pub fn draw_sprite(&mut self, sprite: &Sprite, x: i32, y: i32) {
+        let full = Rect::new(0, 0, sprite.width as i32, sprite.height as i32);
+        self.draw_sprite_region(sprite, full, x, y);
+    }
+
+    /// Draw the `src` sub-rectangle of a sprite (e.g. one entry of a
+    /// `rendering::TextureAtlas`) with its top-left corner at `(x, y)`.
+    /// `src` is clamped to the sprite bounds.
+    pub fn draw_sprite_region(&mut self, sprite: &Sprite, src: Rect, x: i32, y: i32) {
+        self.count_draw_call();
+        let bounds = Rect::new(0, 0, sprite.width as i32, sprite.height as i32);
+        let Some(region) = bounds.intersect(&src) else {
+            return;
+        };
+        // keep the on-screen offset if clamping cut off the left/top of `src`
+        let (ox, oy) = (x + region.x - src.x, y + region.y - src.y);
+        for dy in 0..region.height {
+            for dx in 0..region.width {
+                let color = sprite.get_pixel((region.x + dx) as usize, (region.y + dy) as usize);
+                if color.a() > 0 {
+                    self.set_pixel(ox + dx, oy + dy, color);
+                }
+            }
+        }
+    }
+
+    /// Replay a display list, lowest z layer first
+    pub fn execute(&mut self, list: &DisplayList) {
+        let mut order: Vec<&(i32, DrawCommand)> = list.commands.iter().collect();
+        // stable sort keeps recording order within a layer
+        order.sort_by_key(|(z, _)| *z);
+
+        for (_, command) in order {
+            match command {
+                DrawCommand::Rect {
+                    rect,
+                    color,
+                    filled: true,
+                } => self.draw_rect(rect.x, rect.y, rect.width, rect.height, *color),
+                DrawCommand::Rect {
+                    rect,
+                    color,
+                    filled: false,
+                } => self.draw_rect_outline(rect.x, rect.y, rect.width, rect.height, *color),
+                DrawCommand::Circle {
+                    center_x,
+                    center_y,
+                    radius,
+                    color,
+                    filled,
+                } => {
+                    if *filled {
+                        self.draw_circle_filled(*center_x, *center_y, *radius, *color);
+                    } else {
+                        self.draw_circle(*center_x, *center_y, *radius, *color);
+                    }
+                }
+                DrawCommand::Line {
+                    x1,
+                    y1,
+                    x2,
+                    y2,
+                    color,
+                } => self.draw_line(*x1, *y1, *x2, *y2, *color),
+                DrawCommand::Text {
+                    text,
+                    x,
+                    y,
+                    color,
+                    scale,
+                } => self.draw_text(text, *x, *y, *color, *scale),
+                DrawCommand::Sprite { sprite, x, y } => self.draw_sprite(sprite, *x, *y),
+            }
+        }
+    }
+
     /// Draw simple text using FreeType fonts
     pub fn draw_text(&mut self, text: &str, x: usize, y: usize, color: Color, scale: usize) {
-        // Better font size calculation with minimum size for readability
-        let base_size = 16.0; // Minimum readable font size
-        let font_size = base_size + (scale as f32 - 1.0) * 8.0; // Scale up from base
+        let font_size = font_size_for_scale(scale);
+        self.begin_composite();
 
-        // Try FreeType first
-        if let Ok(text_bitmap) = self.font_system.render_text(text, None, font_size, color) {
-            if text_bitmap.width > 0 && text_bitmap.height > 0 {
+        // Try FreeType first, falling back to the bitmap font if it fails
+        match self.font_system.render_text(text, None, font_size, color) {
+            Ok(text_bitmap) if text_bitmap.width > 0 && text_bitmap.height > 0 => {
                 self.draw_text_bitmap(&text_bitmap, x, y);
-                return; // Success, don't use fallback
             }
+            _ => self.draw_text_fallback(text, x, y, color, scale),
         }
 
-        // Fallback to bitmap font if FreeType fails
-        self.draw_text_fallback(text, x, y, color, scale);
+        self.end_composite();
     }
 
-    /// Draw text centered at a position
-    pub fn draw_text_centered(
+    /// Draw text with a one-pixel outline: the glyphs are drawn in `outline_color`
+    /// at all 8 neighboring offsets first, then filled with `color` on top
+    pub fn draw_text_outlined(
         &mut self,
         text: &str,
-        center_x: usize,
+        x: usize,
         y: usize,
         color: Color,
+        outline_color: Color,
         scale: usize,
     ) {
-        // Use same font size calculation as draw_text
-        let base_size = 16.0;
-        let font_size = base_size + (scale as f32 - 1.0) * 8.0;
-
-        if let Ok(metrics) = self.font_system.get_text_metrics(text, None, font_size) {
-            let text_width = metrics.width as usize;
-            let x = center_x.saturating_sub(text_width / 2);
-            self.draw_text(text, x, y, color, scale);
-        } else {
-            // Fallback calculation
-            let text_width = text.chars().filter(|&c| c != ' ').count() * 8 * scale;
-            let x = center_x.saturating_sub(text_width / 2);
-            self.draw_text(text, x, y, color, scale);
+        for dy in -1..=1 {
+            for dx in -1..=1 {
+                if dx != 0 || dy != 0 {
+                    self.draw_text_offset(text, x, y, (dx, dy), outline_color, scale);
+                }
+            }
+        }
+        self.draw_text(text, x, y, color, scale);
+    }
+
+    /// Draw text over a drop shadow offset `scale` pixels down and to the right
+    pub fn draw_text_shadow(
+        &mut self,
+        text: &str,
+        x: usize,
+        y: usize,
+        color: Color,
+        shadow_color: Color,
+        scale: usize,
+    ) {
+        let offset = scale.max(1) as i32;
+        self.draw_text_offset(text, x, y, (offset, offset), shadow_color, scale);
+        self.draw_text(text, x, y, color, scale);
+    }
+
+    /// `draw_text` shifted by a possibly negative pixel offset
+    fn draw_text_offset(
+        &mut self,
+        text: &str,
+        x: usize,
+        y: usize,
+        offset: (i32, i32),
+        color: Color,
+        scale: usize,
+    ) {
+        let saved_origin = self.origin;
+        self.origin = (saved_origin.0 + offset.0, saved_origin.1 + offset.1);
+        self.draw_text(text, x, y, color, scale);
+        self.origin = saved_origin;
+    }
+
+    /// Draw text blended over the existing pixels using the color's alpha as opacity
+    pub fn draw_text_alpha(&mut self, text: &str, x: usize, y: usize, color: Color, scale: usize) {
+        let opacity = color.a() as f32 / 255.0;
+        if opacity <= 0.0 {
+            return;
+        }
+        let opaque = Color::rgba(color.r(), color.g(), color.b(), 255);
+        let saved = self.blend_opacity.replace(opacity);
+        self.draw_text(text, x, y, opaque, scale);
+        self.blend_opacity = saved;
+    }
+
+    /// Draw differently colored pieces of text one after another, each span
+    /// starting where the previous one's measured width ends
+    pub fn draw_text_spans(&mut self, spans: &[(String, Color)], x: usize, y: usize, scale: usize) {
+        let mut current_x = x;
+        for (text, color) in spans {
+            self.draw_text(text, current_x, y, *color, scale);
+            current_x += self.measure_text(text, scale).0;
         }
     }
 
+    /// Draw text centered at a position
+    pub fn draw_text_centered(
+        &mut self,
+        text: &str,
+        center_x: usize,
+        y: usize,
+        color: Color,
+        scale: usize,
+    ) {
+        let (text_width, _) = self.measure_text(text, scale);
+        let x = center_x.saturating_sub(text_width / 2);
+        self.draw_text(text, x, y, color, scale);
+    }
+
+    /// Metrics of a glyph as `draw_text` would lay it out at `scale`
+    pub fn glyph_metrics(&self, c: char, scale: usize) -> GlyphMetrics {
+        let font_size = font_size_for_scale(scale);
+        self.font_system
+            .glyph_metrics(c, None, font_size)
+            .unwrap_or_else(|_| {
+                let drawn = if c == ' ' { 0.0 } else { 1.0 };
+                GlyphMetrics {
+                    advance: (BitmapFont::ADVANCE * scale) as f32,
+                    bearing: 0.0,
+                    width: drawn * (BitmapFont::GLYPH_WIDTH * scale) as f32,
+                    height: drawn * (BitmapFont::GLYPH_HEIGHT * scale) as f32,
+                }
+            })
+    }
+
+    /// Vertical font metrics as `draw_text` would lay text out at `scale`
+    pub fn font_metrics(&self, scale: usize) -> FontMetrics {
+        let font_size = font_size_for_scale(scale);
+        self.font_system
+            .font_metrics(None, font_size)
+            .unwrap_or(FontMetrics {
+                ascent: (BitmapFont::GLYPH_HEIGHT * scale) as f32,
+                descent: 0.0,
+                line_gap: scale as f32,
+            })
+    }
+
+    /// Measure the `(width, height)` in pixels of text as `draw_text` would draw it
+    pub fn measure_text(&self, text: &str, scale: usize) -> (usize, usize) {
+        let font_size = font_size_for_scale(scale);
+        if let Ok(metrics) = self.font_system.get_text_metrics(text, None, font_size) {
+            if metrics.width > 0.0 && metrics.height > 0.0 {
+                return (
+                    metrics.width.ceil() as usize,
+                    metrics.height.ceil() as usize,
+                );
+            }
+        }
+
+        (
+            text.chars().count() * BitmapFont::ADVANCE * scale,
+            BitmapFont::GLYPH_HEIGHT * scale,
+        )
+    }
+
     /// Draw rendered text bitmap to the screen
     fn draw_text_bitmap(&mut self, bitmap: &TextBitmap, x: usize, y: usize) {
         for by in 0..bitmap.height {
@@ -250,750 +1284,222 @@ impl Renderer2D {
             if ch != ' ' {
                 self.draw_char_fallback(ch, current_x, y, color, scale);
             }
-            current_x += 8 * scale;
+            current_x += BitmapFont::ADVANCE * scale;
         }
     }
 
     /// Draw a single character (fallback bitmap font)
     pub fn draw_char_fallback(&mut self, ch: char, x: usize, y: usize, color: Color, scale: usize) {
-        // Improved 7x9 font for better readability
-        let font_data = match ch {
-            '0' => [
-                [false, true, true, true, true, true, false],
-                [true, false, false, false, false, false, true],
-                [true, false, false, false, false, false, true],
-                [true, false, false, false, false, false, true],
-                [true, false, false, false, false, false, true],
-                [true, false, false, false, false, false, true],
-                [true, false, false, false, false, false, true],
-                [true, false, false, false, false, false, true],
-                [false, true, true, true, true, true, false],
-            ],
-            '1' => [
-                [false, false, false, true, false, false, false],
-                [false, false, true, true, false, false, false],
-                [false, true, false, true, false, false, false],
-                [false, false, false, true, false, false, false],
-                [false, false, false, true, false, false, false],
-                [false, false, false, true, false, false, false],
-                [false, false, false, true, false, false, false],
-                [false, false, false, true, false, false, false],
-                [true, true, true, true, true, true, true],
-            ],
-            '2' => [
-                [false, true, true, true, true, true, false],
-                [true, false, false, false, false, false, true],
-                [false, false, false, false, false, false, true],
-                [false, false, false, false, false, true, false],
-                [false, false, false, false, true, false, false],
-                [false, false, false, true, false, false, false],
-                [false, false, true, false, false, false, false],
-                [false, true, false, false, false, false, false],
-                [true, true, true, true, true, true, true],
-            ],
-            '3' => [
-                [false, true, true, true, true, true, false],
-                [true, false, false, false, false, false, true],
-                [false, false, false, false, false, false, true],
-                [false, false, false, false, true, true, false],
-                [false, false, false, true, true, false, false],
-                [false, false, false, false, false, false, true],
-                [false, false, false, false, false, false, true],
-                [true, false, false, false, false, false, true],
-                [false, true, true, true, true, true, false],
-            ],
-            '4' => [
-                [false, false, false, false, true, false, false],
-                [false, false, false, true, true, false, false],
-                [false, false, true, false, true, false, false],
-                [false, true, false, false, true, false, false],
-                [true, false, false, false, true, false, false],
-                [true, true, true, true, true, true, true],
-                [false, false, false, false, true, false, false],
-                [false, false, false, false, true, false, false],
-                [false, false, false, false, true, false, false],
-            ],
-            '5' => [
-                [true, true, true, true, true, true, true],
-                [true, false, false, false, false, false, false],
-                [true, false, false, false, false, false, false],
-                [true, true, true, true, true, true, false],
-                [false, false, false, false, false, false, true],
-                [false, false, false, false, false, false, true],
-                [false, false, false, false, false, false, true],
-                [true, false, false, false, false, false, true],
-                [false, true, true, true, true, true, false],
-            ],
-            '6' => [
-                [false, true, true, true, true, true, false],
-                [true, false, false, false, false, false, true],
-                [true, false, false, false, false, false, false],
-                [true, false, false, false, false, false, false],
-                [true, true, true, true, true, true, false],
-                [true, false, false, false, false, false, true],
-                [true, false, false, false, false, false, true],
-                [true, false, false, false, false, false, true],
-                [false, true, true, true, true, true, false],
-            ],
-            '7' => [
-                [true, true, true, true, true, true, true],
-                [false, false, false, false, false, false, true],
-                [false, false, false, false, false, true, false],
-                [false, false, false, false, true, false, false],
-                [false, false, false, true, false, false, false],
-                [false, false, true, false, false, false, false],
-                [false, true, false, false, false, false, false],
-                [true, false, false, false, false, false, false],
-                [false, false, false, false, false, false, false],
-            ],
-            '8' => [
-                [false, true, true, true, true, true, false],
-                [true, false, false, false, false, false, true],
-                [true, false, false, false, false, false, true],
-                [true, false, false, false, false, false, true],
-                [false, true, true, true, true, true, false],
-                [true, false, false, false, false, false, true],
-                [true, false, false, false, false, false, true],
-                [true, false, false, false, false, false, true],
-                [false, true, true, true, true, true, false],
-            ],
-            '9' => [
-                [false, true, true, true, true, true, false],
-                [true, false, false, false, false, false, true],
-                [true, false, false, false, false, false, true],
-                [true, false, false, false, false, false, true],
-                [true, false, false, false, false, false, true],
-                [true, false, false, false, false, false, true],
-                [true, false, false, false, false, false, true],
-                [true, false, false, false, false, false, true],
-                [true, true, true, true, true, true, false],
-            ],
-            // Lowercase letters
-            'a' => [
-                [false, false, false, false, false, false, false],
-                [false, false, false, false, false, false, false],
-                [false, false, true, true, true, false, false],
-                [true, false, false, false, false, true, false],
-                [true, false, false, false, false, true, false],
-                [true, false, false, false, false, true, false],
-                [true, false, false, false, false, true, false],
-                [false, true, true, true, true, false, false],
-                [false, false, false, false, false, false, false],
-            ],
-            'b' => [
-                [true, false, false, false, false, false, false],
-                [true, false, false, false, false, false, false],
-                [true, false, false, false, false, false, false],
-                [true, true, true, true, true, false, false],
-                [true, false, false, false, false, true, false],
-                [true, false, false, false, false, true, false],
-                [true, false, false, false, false, true, false],
-                [true, true, true, true, true, false, false],
-                [false, false, false, false, false, false, false],
-            ],
-            'c' => [
-                [false, false, false, false, false, false, false],
-                [false, false, false, false, false, false, false],
-                [false, true, true, true, true, false, false],
-                [true, false, false, false, false, true, false],
-                [true, false, false, false, false, false, false],
-                [true, false, false, false, false, false, false],
-                [true, false, false, false, false, true, false],
-                [false, true, true, true, true, false, false],
-                [false, false, false, false, false, false, false],
-            ],
-            'd' => [
-                [false, false, false, false, false, true, false],
-                [false, false, false, false, false, true, false],
-                [false, false, false, false, false, true, false],
-                [false, true, true, true, true, true, false],
-                [true, false, false, false, false, true, false],
-                [true, false, false, false, false, true, false],
-                [true, false, false, false, false, true, false],
-                [false, true, true, true, true, true, false],
-                [false, false, false, false, false, false, false],
-            ],
-            'e' => [
-                [false, false, false, false, false, false, false],
-                [false, false, false, false, false, false, false],
-                [false, true, true, true, true, false, false],
-                [true, false, false, false, false, true, false],
-                [true, true, true, true, true, true, false],
-                [true, false, false, false, false, false, false],
-                [true, false, false, false, false, true, false],
-                [false, true, true, true, true, false, false],
-                [false, false, false, false, false, false, false],
-            ],
-            'f' => [
-                [false, false, true, true, true, false, false],
-                [false, true, false, false, false, true, false],
-                [false, true, false, false, false, false, false],
-                [true, true, true, true, true, false, false],
-                [false, true, false, false, false, false, false],
-                [false, true, false, false, false, false, false],
-                [false, true, false, false, false, false, false],
-                [false, true, false, false, false, false, false],
-                [false, false, false, false, false, false, false],
-            ],
-            'g' => [
-                [false, false, false, false, false, false, false],
-                [false, false, false, false, false, false, false],
-                [false, true, true, true, true, true, false],
-                [true, false, false, false, false, true, false],
-                [true, false, false, false, false, true, false],
-                [true, false, false, false, false, true, false],
-                [false, true, true, true, true, true, false],
-                [false, false, false, false, false, true, false],
-                [false, true, true, true, true, false, false],
-            ],
-            'h' => [
-                [true, false, false, false, false, false, false],
-                [true, false, false, false, false, false, false],
-                [true, false, false, false, false, false, false],
-                [true, true, true, true, true, false, false],
-                [true, false, false, false, false, true, false],
-                [true, false, false, false, false, true, false],
-                [true, false, false, false, false, true, false],
-                [true, false, false, false, false, true, false],
-                [false, false, false, false, false, false, false],
-            ],
-            'i' => [
-                [false, false, true, false, false, false, false],
-                [false, false, false, false, false, false, false],
-                [false, true, true, true, false, false, false],
-                [false, false, true, false, false, false, false],
-                [false, false, true, false, false, false, false],
-                [false, false, true, false, false, false, false],
-                [false, false, true, false, false, false, false],
-                [false, true, true, true, false, false, false],
-                [false, false, false, false, false, false, false],
-            ],
-            'j' => [
-                [false, false, false, true, false, false, false],
-                [false, false, false, false, false, false, false],
-                [false, false, true, true, true, false, false],
-                [false, false, false, true, false, false, false],
-                [false, false, false, true, false, false, false],
-                [false, false, false, true, false, false, false],
-                [false, false, false, true, false, false, false],
-                [true, false, false, true, false, false, false],
-                [false, true, true, false, false, false, false],
-            ],
-            'k' => [
-                [true, false, false, false, false, false, false],
-                [true, false, false, false, false, false, false],
-                [true, false, false, false, true, false, false],
-                [true, false, false, true, false, false, false],
-                [true, true, true, false, false, false, false],
-                [true, false, false, true, false, false, false],
-                [true, false, false, false, true, false, false],
-                [true, false, false, false, false, true, false],
-                [false, false, false, false, false, false, false],
-            ],
-            'l' => [
-                [false, true, true, true, false, false, false],
-                [false, false, true, false, false, false, false],
-                [false, false, true, false, false, false, false],
-                [false, false, true, false, false, false, false],
-                [false, false, true, false, false, false, false],
-                [false, false, true, false, false, false, false],
-                [false, false, true, false, false, false, false],
-                [false, true, true, true, false, false, false],
-                [false, false, false, false, false, false, false],
-            ],
-            'm' => [
-                [false, false, false, false, false, false, false],
-                [false, false, false, false, false, false, false],
-                [true, true, false, true, true, false, false],
-                [true, false, true, false, true, false, false],
-                [true, false, true, false, true, false, false],
-                [true, false, true, false, true, false, false],
-                [true, false, true, false, true, false, false],
-                [true, false, true, false, true, false, false],
-                [false, false, false, false, false, false, false],
-            ],
-            'n' => [
-                [false, false, false, false, false, false, false],
-                [false, false, false, false, false, false, false],
-                [true, true, true, true, true, false, false],
-                [true, false, false, false, true, false, false],
-                [true, false, false, false, true, false, false],
-                [true, false, false, false, true, false, false],
-                [true, false, false, false, true, false, false],
-                [true, false, false, false, true, false, false],
-                [false, false, false, false, false, false, false],
-            ],
-            'o' => [
-                [false, false, false, false, false, false, false],
-                [false, false, false, false, false, false, false],
-                [false, true, true, true, true, false, false],
-                [true, false, false, false, false, true, false],
-                [true, false, false, false, false, true, false],
-                [true, false, false, false, false, true, false],
-                [true, false, false, false, false, true, false],
-                [false, true, true, true, true, false, false],
-                [false, false, false, false, false, false, false],
-            ],
-            'p' => [
-                [false, false, false, false, false, false, false],
-                [false, false, false, false, false, false, false],
-                [true, true, true, true, true, false, false],
-                [true, false, false, false, false, true, false],
-                [true, false, false, false, false, true, false],
-                [true, false, false, false, false, true, false],
-                [true, true, true, true, true, false, false],
-                [true, false, false, false, false, false, false],
-                [true, false, false, false, false, false, false],
-            ],
-            'q' => [
-                [false, false, false, false, false, false, false],
-                [false, false, false, false, false, false, false],
-                [false, true, true, true, true, true, false],
-                [true, false, false, false, false, true, false],
-                [true, false, false, false, false, true, false],
-                [true, false, false, false, false, true, false],
-                [false, true, true, true, true, true, false],
-                [false, false, false, false, false, true, false],
-                [false, false, false, false, false, true, false],
-            ],
-            'r' => [
-                [false, false, false, false, false, false, false],
-                [false, false, false, false, false, false, false],
-                [true, true, true, true, true, false, false],
-                [true, false, false, false, false, true, false],
-                [true, false, false, false, false, false, false],
-                [true, false, false, false, false, false, false],
-                [true, false, false, false, false, false, false],
-                [true, false, false, false, false, false, false],
-                [false, false, false, false, false, false, false],
-            ],
-            's' => [
-                [false, false, false, false, false, false, false],
-                [false, false, false, false, false, false, false],
-                [false, true, true, true, true, false, false],
-                [true, false, false, false, false, true, false],
-                [true, true, true, true, false, false, false],
-                [false, false, false, false, true, false, false],
-                [true, false, false, false, false, true, false],
-                [false, true, true, true, true, false, false],
-                [false, false, false, false, false, false, false],
-            ],
-            't' => [
-                [false, true, false, false, false, false, false],
-                [false, true, false, false, false, false, false],
-                [true, true, true, true, true, false, false],
-                [false, true, false, false, false, false, false],
-                [false, true, false, false, false, false, false],
-                [false, true, false, false, false, false, false],
-                [false, true, false, false, false, false, false],
-                [false, false, true, true, true, false, false],
-                [false, false, false, false, false, false, false],
-            ],
-            'u' => [
-                [false, false, false, false, false, false, false],
-                [false, false, false, false, false, false, false],
-                [true, false, false, false, false, true, false],
-                [true, false, false, false, false, true, false],
-                [true, false, false, false, false, true, false],
-                [true, false, false, false, false, true, false],
-                [true, false, false, false, false, true, false],
-                [false, true, true, true, true, false, false],
-                [false, false, false, false, false, false, false],
-            ],
-            'v' => [
-                [false, false, false, false, false, false, false],
-                [false, false, false, false, false, false, false],
-                [true, false, false, false, false, true, false],
-                [true, false, false, false, false, true, false],
-                [true, false, false, false, false, true, false],
-                [true, false, false, false, false, true, false],
-                [false, true, false, false, true, false, false],
-                [false, false, true, true, false, false, false],
-                [false, false, false, false, false, false, false],
-            ],
-            'w' => [
-                [false, false, false, false, false, false, false],
-                [false, false, false, false, false, false, false],
-                [true, false, false, false, false, true, false],
-                [true, false, false, false, false, true, false],
-                [true, false, false, false, false, true, false],
-                [true, false, true, false, true, false, false],
-                [true, false, true, false, true, false, false],
-                [false, true, false, false, false, true, false],
-                [false, false, false, false, false, false, false],
-            ],
-            'x' => [
-                [false, false, false, false, false, false, false],
-                [false, false, false, false, false, false, false],
-                [true, false, false, false, false, true, false],
-                [false, true, false, false, true, false, false],
-                [false, false, true, true, false, false, false],
-                [false, false, true, true, false, false, false],
-                [false, true, false, false, true, false, false],
-                [true, false, false, false, false, true, false],
-                [false, false, false, false, false, false, false],
-            ],
-            'y' => [
-                [false, false, false, false, false, false, false],
-                [false, false, false, false, false, false, false],
-                [true, false, false, false, false, true, false],
-                [true, false, false, false, false, true, false],
-                [true, false, false, false, false, true, false],
-                [false, true, false, false, true, false, false],
-                [false, false, true, true, false, false, false],
-                [false, false, false, false, true, false, false],
-                [false, false, false, true, false, false, false],
-            ],
-            'z' => [
-                [false, false, false, false, false, false, false],
-                [false, false, false, false, false, false, false],
-                [true, true, true, true, true, true, false],
-                [false, false, false, false, true, false, false],
-                [false, false, false, true, false, false, false],
-                [false, false, true, false, false, false, false],
-                [false, true, false, false, false, false, false],
-                [true, true, true, true, true, true, false],
-                [false, false, false, false, false, false, false],
-            ],
-            'A' => [
-                [false, true, true, true, true, true, false],
-                [true, false, false, false, false, false, true],
-                [true, false, false, false, false, false, true],
-                [true, false, false, false, false, false, true],
-                [true, true, true, true, true, true, true],
-                [true, false, false, false, false, false, true],
-                [true, false, false, false, false, false, true],
-                [true, false, false, false, false, false, true],
-                [true, false, false, false, false, false, true],
-            ],
-            'B' => [
-                [true, true, true, true, true, true, false],
-                [true, false, false, false, false, false, true],
-                [true, false, false, false, false, false, true],
-                [true, false, false, false, false, false, true],
-                [true, true, true, true, true, true, false],
-                [true, false, false, false, false, false, true],
-                [true, false, false, false, false, false, true],
-                [true, false, false, false, false, false, true],
-                [true, true, true, true, true, true, false],
-            ],
-            'C' => [
-                [false, true, true, true, true, true, false],
-                [true, false, false, false, false, false, true],
-                [true, false, false, false, false, false, false],
-                [true, false, false, false, false, false, false],
-                [true, false, false, false, false, false, false],
-                [true, false, false, false, false, false, false],
-                [true, false, false, false, false, false, false],
-                [true, false, false, false, false, false, true],
-                [false, true, true, true, true, true, false],
-            ],
-            'D' => [
-                [true, true, true, true, true, true, false],
-                [true, false, false, false, false, false, true],
-                [true, false, false, false, false, false, true],
-                [true, false, false, false, false, false, true],
-                [true, false, false, false, false, false, true],
-                [true, false, false, false, false, false, true],
-                [true, false, false, false, false, false, true],
-                [true, false, false, false, false, false, true],
-                [true, true, true, true, true, true, false],
-            ],
-            'E' => [
-                [true, true, true, true, true, true, true],
-                [true, false, false, false, false, false, false],
-                [true, false, false, false, false, false, false],
-                [true, false, false, false, false, false, false],
-                [true, true, true, true, true, true, false],
-                [true, false, false, false, false, false, false],
-                [true, false, false, false, false, false, false],
-                [true, false, false, false, false, false, false],
-                [true, true, true, true, true, true, true],
-            ],
-            'F' => [
-                [true, true, true, true, true, true, true],
-                [true, false, false, false, false, false, false],
-                [true, false, false, false, false, false, false],
-                [true, false, false, false, false, false, false],
-                [true, true, true, true, true, true, false],
-                [true, false, false, false, false, false, false],
-                [true, false, false, false, false, false, false],
-                [true, false, false, false, false, false, false],
-                [true, false, false, false, false, false, false],
-            ],
-            'G' => [
-                [false, true, true, true, true, true, false],
-                [true, false, false, false, false, false, true],
-                [true, false, false, false, false, false, false],
-                [true, false, false, false, false, false, false],
-                [true, false, false, true, true, true, true],
-                [true, false, false, false, false, false, true],
-                [true, false, false, false, false, false, true],
-                [true, false, false, false, false, false, true],
-                [false, true, true, true, true, true, false],
-            ],
-            'H' => [
-                [true, false, false, false, false, false, true],
-                [true, false, false, false, false, false, true],
-                [true, false, false, false, false, false, true],
-                [true, false, false, false, false, false, true],
-                [true, true, true, true, true, true, true],
-                [true, false, false, false, false, false, true],
-                [true, false, false, false, false, false, true],
-                [true, false, false, false, false, false, true],
-                [true, false, false, false, false, false, true],
-            ],
-            'I' => [
-                [true, true, true, true, true, true, true],
-                [false, false, false, true, false, false, false],
-                [false, false, false, true, false, false, false],
-                [false, false, false, true, false, false, false],
-                [false, false, false, true, false, false, false],
-                [false, false, false, true, false, false, false],
-                [false, false, false, true, false, false, false],
-                [false, false, false, true, false, false, false],
-                [true, true, true, true, true, true, true],
-            ],
-            'J' => [
-                [true, true, true, true, true, true, true],
-                [false, false, false, false, false, true, false],
-                [false, false, false, false, false, true, false],
-                [false, false, false, false, false, true, false],
-                [false, false, false, false, false, true, false],
-                [false, false, false, false, false, true, false],
-                [true, false, false, false, false, true, false],
-                [true, false, false, false, false, true, false],
-                [false, true, true, true, true, false, false],
-            ],
-            'K' => [
-                [true, false, false, false, false, true, false],
-                [true, false, false, false, true, false, false],
-                [true, false, false, true, false, false, false],
-                [true, false, true, false, false, false, false],
-                [true, true, false, false, false, false, false],
-                [true, false, true, false, false, false, false],
-                [true, false, false, true, false, false, false],
-                [true, false, false, false, true, false, false],
-                [true, false, false, false, false, true, false],
-            ],
-            'L' => [
-                [true, false, false, false, false, false, false],
-                [true, false, false, false, false, false, false],
-                [true, false, false, false, false, false, false],
-                [true, false, false, false, false, false, false],
-                [true, false, false, false, false, false, false],
-                [true, false, false, false, false, false, false],
-                [true, false, false, false, false, false, false],
-                [true, false, false, false, false, false, false],
-                [true, true, true, true, true, true, true],
-            ],
-            'M' => [
-                [true, false, false, false, false, false, true],
-                [true, true, false, false, false, true, true],
-                [true, false, true, false, true, false, true],
-                [true, false, true, false, true, false, true],
-                [true, false, false, true, false, false, true],
-                [true, false, false, true, false, false, true],
-                [true, false, false, false, false, false, true],
-                [true, false, false, false, false, false, true],
-                [true, false, false, false, false, false, true],
-            ],
-            'N' => [
-                [true, false, false, false, false, false, true],
-                [true, true, false, false, false, false, true],
-                [true, false, true, false, false, false, true],
-                [true, false, true, false, false, false, true],
-                [true, false, false, true, false, false, true],
-                [true, false, false, true, false, false, true],
-                [true, false, false, false, true, false, true],
-                [true, false, false, false, true, false, true],
-                [true, false, false, false, false, true, true],
-            ],
-            'O' => [
-                [false, true, true, true, true, true, false],
-                [true, false, false, false, false, false, true],
-                [true, false, false, false, false, false, true],
-                [true, false, false, false, false, false, true],
-                [true, false, false, false, false, false, true],
-                [true, false, false, false, false, false, true],
-                [true, false, false, false, false, false, true],
-                [true, false, false, false, false, false, true],
-                [false, true, true, true, true, true, false],
-            ],
-            'P' => [
-                [true, true, true, true, true, true, false],
-                [true, false, false, false, false, false, true],
-                [true, false, false, false, false, false, true],
-                [true, false, false, false, false, false, true],
-                [true, true, true, true, true, true, false],
-                [true, false, false, false, false, false, false],
-                [true, false, false, false, false, false, false],
-                [true, false, false, false, false, false, false],
-                [true, false, false, false, false, false, false],
-            ],
-            'Q' => [
-                [false, true, true, true, true, true, false],
-                [true, false, false, false, false, false, true],
-                [true, false, false, false, false, false, true],
-                [true, false, false, false, false, false, true],
-                [true, false, false, false, false, false, true],
-                [true, false, false, false, true, false, true],
-                [true, false, false, false, false, true, true],
-                [true, false, false, false, false, false, true],
-                [false, true, true, true, true, true, true],
-            ],
-            'R' => [
-                [true, true, true, true, true, true, false],
-                [true, false, false, false, false, false, true],
-                [true, false, false, false, false, false, true],
-                [true, false, false, false, false, false, true],
-                [true, true, true, true, true, true, false],
-                [true, false, false, false, true, false, false],
-                [true, false, false, false, false, true, false],
-                [true, false, false, false, false, false, true],
-                [true, false, false, false, false, false, true],
-            ],
-            'S' => [
-                [false, true, true, true, true, true, false],
-                [true, false, false, false, false, false, true],
-                [true, false, false, false, false, false, false],
-                [true, false, false, false, false, false, false],
-                [false, true, true, true, true, true, false],
-                [false, false, false, false, false, false, true],
-                [false, false, false, false, false, false, true],
-                [true, false, false, false, false, false, true],
-                [false, true, true, true, true, true, false],
-            ],
-            'T' => [
-                [true, true, true, true, true, true, true],
-                [false, false, false, true, false, false, false],
-                [false, false, false, true, false, false, false],
-                [false, false, false, true, false, false, false],
-                [false, false, false, true, false, false, false],
-                [false, false, false, true, false, false, false],
-                [false, false, false, true, false, false, false],
-                [false, false, false, true, false, false, false],
-                [false, false, false, true, false, false, false],
-            ],
-            'U' => [
-                [true, false, false, false, false, false, true],
-                [true, false, false, false, false, false, true],
-                [true, false, false, false, false, false, true],
-                [true, false, false, false, false, false, true],
-                [true, false, false, false, false, false, true],
-                [true, false, false, false, false, false, true],
-                [true, false, false, false, false, false, true],
-                [true, false, false, false, false, false, true],
-                [false, true, true, true, true, true, false],
-            ],
-            'V' => [
-                [true, false, false, false, false, false, true],
-                [true, false, false, false, false, false, true],
-                [true, false, false, false, false, false, true],
-                [true, false, false, false, false, false, true],
-                [true, false, false, false, false, false, true],
-                [true, false, false, false, false, false, true],
-                [false, true, false, false, false, true, false],
-                [false, true, false, false, false, true, false],
-                [false, false, true, true, true, false, false],
-            ],
-            'W' => [
-                [true, false, false, false, false, false, true],
-                [true, false, false, false, false, false, true],
-                [true, false, false, false, false, false, true],
-                [true, false, false, false, false, false, true],
-                [true, false, false, false, false, false, true],
-                [true, false, true, false, true, false, true],
-                [true, false, true, false, true, false, true],
-                [true, true, false, false, false, true, true],
-                [true, false, false, false, false, false, true],
-            ],
-            'X' => [
-                [true, false, false, false, false, false, true],
-                [false, true, false, false, false, true, false],
-                [false, true, false, false, false, true, false],
-                [false, false, true, false, true, false, false],
-                [false, false, true, false, true, false, false],
-                [false, false, false, true, false, false, false],
-                [false, false, true, false, true, false, false],
-                [false, true, false, false, false, true, false],
-                [true, false, false, false, false, false, true],
-            ],
-            'Y' => [
-                [true, false, false, false, false, false, true],
-                [false, true, false, false, false, true, false],
-                [false, true, false, false, false, true, false],
-                [false, false, true, false, true, false, false],
-                [false, false, true, false, true, false, false],
-                [false, false, false, true, false, false, false],
-                [false, false, false, true, false, false, false],
-                [false, false, false, true, false, false, false],
-                [false, false, false, true, false, false, false],
-            ],
-            'Z' => [
-                [true, true, true, true, true, true, true],
-                [false, false, false, false, false, true, false],
-                [false, false, false, false, true, false, false],
-                [false, false, false, false, false, false, false],
-                [false, false, false, false, false, false, false],
-                [false, false, false, false, false, false, false],
-                [false, false, true, false, false, false, false],
-                [false, true, false, false, false, false, false],
-                [false, false, false, false, false, false, false],
-            ],
-            ':' => [
-                [false, false, false, false, false, false, false],
-                [false, false, true, false, false, false, false],
-                [false, false, true, false, false, false, false],
-                [false, false, false, false, false, false, false],
-                [false, false, false, false, false, false, false],
-                [false, false, false, false, false, false, false],
-                [false, false, true, false, false, false, false],
-                [false, false, true, false, false, false, false],
-                [false, false, false, false, false, false, false],
-            ],
-            '/' => [
-                [false, false, false, false, false, true, false],
-                [false, false, false, false, true, false, false],
-                [false, false, false, true, false, false, false],
-                [false, false, true, false, false, false, false],
-                [false, true, false, false, false, false, false],
-                [true, false, false, false, false, false, false],
-                [false, false, false, false, false, false, false],
-                [false, false, false, false, false, false, false],
-                [false, false, false, false, false, false, false],
-            ],
-            _ => [
-                [true, true, true, true, true, true, true],
-                [true, false, false, false, false, false, true],
-                [true, false, false, false, false, false, true],
-                [true, false, false, false, false, false, true],
-                [true, false, false, false, false, false, true],
-                [true, false, false, false, false, false, true],
-                [true, false, false, false, false, false, true],
-                [true, false, false, false, false, false, true],
-                [true, true, true, true, true, true, true],
-            ], // Default box for unknown characters
+        self.begin_composite();
+        BitmapFont.draw_glyph(ch, |col, row| {
+            let px = x + col * scale;
+            let py = y + row * scale;
+            self.draw_rect(px as i32, py as i32, scale as i32, scale as i32, color);
+        });
+        self.end_composite();
+    }
+
+    /// Set a single pixel
+    pub fn set_pixel(&mut self, x: i32, y: i32, color: Color) {
+        let (x, y) = (x + self.origin.0, y + self.origin.1);
+        if let Some(clip) = self.clip {
+            if !clip.contains(x, y) {
+                return;
+            }
+        }
+        if x >= 0 && x < self.width as i32 && y >= 0 && y < self.height as i32 {
+            let index = (y as usize) * self.width + (x as usize);
+            if index < self.buffer.len() && self.mask_allows(index) {
+                self.buffer[index] = match self.blend_opacity {
+                    Some(opacity) => {
+                        let dst = Color(self.buffer[index]);
+                        let blended = dst.lerp(color, color.a() as f32 / 255.0 * opacity);
+                        Color::rgba(blended.r(), blended.g(), blended.b(), dst.a()).0
+                    }
+                    None => color.0,
+                };
+                self.stats.pixels_written += 1;
+                self.mark_dirty(Rect::new(x, y, 1, 1));
+            }
+        }
+    }
+
+    /// Restrict drawing to `clip` (or remove the restriction with `None`).
+    /// The rectangle is in buffer coordinates and ignores any viewport offset.
+    pub fn set_clip_rect(&mut self, clip: Option<Rect>) {
+        self.clip = clip;
+    }
+
+    /// Only write pixels where `mask` is `true` (or remove the mask with `None`).
+    /// The mask is row-major in buffer coordinates, `width * height` long;
+    /// pixels past its end are never written. Resizing removes the mask.
+    pub fn set_mask(&mut self, mask: Option<Vec<bool>>) {
+        self.mask = mask;
+    }
+
+    /// Current draw mask, if any
+    pub fn mask(&self) -> Option<&[bool]> {
+        self.mask.as_deref()
+    }
+
+    /// Mask that is `true` inside the circle, e.g. for a circular wipe that grows `radius`
+    pub fn circle_mask(&self, cx: i32, cy: i32, radius: f32) -> Vec<bool> {
+        self.mask_from(|x, y| {
+            let (dx, dy) = ((x - cx) as f32, (y - cy) as f32);
+            dx * dx + dy * dy <= radius * radius
+        })
+    }
+
+    /// Mask that is `true` inside `rect`
+    pub fn rect_mask(&self, rect: Rect) -> Vec<bool> {
+        self.mask_from(|x, y| rect.contains(x, y))
+    }
+
+    fn mask_from(&self, inside: impl Fn(i32, i32) -> bool) -> Vec<bool> {
+        (0..self.height as i32)
+            .flat_map(|y| (0..self.width as i32).map(move |x| (x, y)))
+            .map(|(x, y)| inside(x, y))
+            .collect()
+    }
+
+    fn mask_allows(&self, index: usize) -> bool {
+        self.mask
+            .as_ref()
+            .is_none_or(|mask| mask.get(index) == Some(&true))
+    }
+
+    /// Current clip rectangle, if any
+    pub fn clip_rect(&self) -> Option<Rect> {
+        self.clip
+    }
+
+    /// Buffer area that may currently be written (buffer bounds intersected with the clip rect)
+    fn writable_rect(&self) -> Option<Rect> {
+        let bounds = Rect::new(0, 0, self.width as i32, self.height as i32);
+        match self.clip {
+            Some(clip) => bounds.intersect(&clip),
+            None => Some(bounds).filter(|b| b.width > 0 && b.height > 0),
+        }
+    }
+
+    /// Scanline flood fill: replace the contiguous (4-connected) region that
+    /// has the same color as the start pixel. Stays inside the clip rect and
+    /// uses an explicit stack so large regions can't overflow the call stack.
+    pub fn flood_fill(&mut self, x: i32, y: i32, fill_color: Color) {
+        self.count_draw_call();
+        let (x, y) = (x + self.origin.0, y + self.origin.1);
+        let Some(area) = self.writable_rect() else {
+            return;
         };
+        if !area.contains(x, y) {
+            return;
+        }
+        let target = self.buffer[y as usize * self.width + x as usize];
+        if target == fill_color.0 {
+            return;
+        }
+
+        // masked-out pixels act as borders so the fill can't revisit them
+        let mask = self.mask.take();
+        let matches = |buffer: &[u32], width: usize, px: i32, py: i32| {
+            let index = py as usize * width + px as usize;
+            buffer[index] == target && mask.as_ref().is_none_or(|m| m.get(index) == Some(&true))
+        };
+
+        let mut stack = vec![(x, y)];
+        while let Some((sx, sy)) = stack.pop() {
+            if !matches(&self.buffer, self.width, sx, sy) {
+                continue;
+            }
 
-        for (row, pixels) in font_data.iter().enumerate() {
-            for (col, pixel) in pixels.iter().enumerate() {
-                if *pixel {
-                    let px = x + col * scale;
-                    let py = y + row * scale;
-                    self.draw_rect(px as i32, py as i32, scale as i32, scale as i32, color);
+            // extend the span left and right
+            let mut left = sx;
+            while left > area.x && matches(&self.buffer, self.width, left - 1, sy) {
+                left -= 1;
+            }
+            let mut right = sx;
+            while right + 1 < area.x + area.width
+                && matches(&self.buffer, self.width, right + 1, sy)
+            {
+                right += 1;
+            }
+
+            for px in left..=right {
+                self.buffer[sy as usize * self.width + px as usize] = fill_color.0;
+            }
+            self.stats.pixels_written += (right - left + 1) as usize;
+            self.mark_dirty(Rect::new(left, sy, right - left + 1, 1));
+
+            // queue one seed per matching run in the rows above and below
+            for ny in [sy - 1, sy + 1] {
+                if ny < area.y || ny >= area.y + area.height {
+                    continue;
+                }
+                let mut in_run = false;
+                for px in left..=right {
+                    if matches(&self.buffer, self.width, px, ny) {
+                        if !in_run {
+                            stack.push((px, ny));
+                            in_run = true;
+                        }
+                    } else {
+                        in_run = false;
+                    }
                 }
             }
         }
+        self.mask = mask;
     }
 
-    /// Set a single pixel
-    pub fn set_pixel(&mut self, x: i32, y: i32, color: Color) {
+    /// Return the region touched by draw calls since the last call, and reset it.
+    /// `None` means nothing was drawn.
+    pub fn take_dirty_rect(&mut self) -> Option<Rect> {
+        self.dirty.take()
+    }
+
+    /// Return the draw calls and pixels written since the last call, and reset them
+    pub fn take_stats(&mut self) -> RenderStats {
+        std::mem::take(&mut self.stats)
+    }
+
+    /// Count one draw call unless it is part of an enclosing composite call
+    fn count_draw_call(&mut self) {
+        if self.draw_depth == 0 {
+            self.stats.draw_calls += 1;
+        }
+    }
+
+    /// Start a composite draw call; the primitives it uses aren't counted
+    fn begin_composite(&mut self) {
+        self.count_draw_call();
+        self.draw_depth += 1;
+    }
+
+    fn end_composite(&mut self) {
+        self.draw_depth -= 1;
+    }
+
+    /// Extend the dirty region with an already in-bounds rectangle
+    fn mark_dirty(&mut self, rect: Rect) {
+        self.dirty = Some(match self.dirty {
+            Some(dirty) => dirty.union(&rect),
+            None => rect,
+        });
+    }
+
+    /// Mark the whole buffer as dirty
+    fn mark_all_dirty(&mut self) {
+        if self.width > 0 && self.height > 0 {
+            self.dirty = Some(Rect::new(0, 0, self.width as i32, self.height as i32));
+        }
+    }
+
+    /// Read a single pixel, if it is inside the buffer
+    pub fn get_pixel(&self, x: i32, y: i32) -> Option<Color> {
         if x >= 0 && x < self.width as i32 && y >= 0 && y < self.height as i32 {
-            let index = (y as usize) * self.width + (x as usize);
-            if index < self.buffer.len() {
-                self.buffer[index] = color.0;
-            }
+            Some(Color(self.buffer[(y as usize) * self.width + (x as usize)]))
+        } else {
+            None
         }
     }
 
@@ -1002,8 +1508,10 @@ impl Renderer2D {
         &self.buffer
     }
 
-    /// Get mutable buffer access
+    /// Get mutable buffer access. Direct writes can't be tracked, so this
+    /// conservatively marks the whole buffer dirty.
     pub fn buffer_mut(&mut self) -> &mut [u32] {
+        self.mark_all_dirty();
         &mut self.buffer
     }
 
@@ -1012,6 +1520,15 @@ impl Renderer2D {
         (self.width, self.height)
     }
 
+    /// Resize the buffer (contents are cleared to black); loaded fonts are kept
+    pub fn resize(&mut self, width: usize, height: usize) {
+        self.buffer = vec![0; width * height];
+        self.mask = None;
+        self.width = width;
+        self.height = height;
+        self.mark_all_dirty();
+    }
+
     /// Load a TTF font from file
     pub fn load_font<P: AsRef<Path>>(
         &mut self,
@@ -1027,10 +1544,148 @@ impl Renderer2D {
     }
 }
 
+/// Font size in points used for a given integer text scale
+fn font_size_for_scale(scale: usize) -> f32 {
+    // Better font size calculation with minimum size for readability
+    let base_size = 16.0; // Minimum readable font size
+    base_size + (scale as f32 - 1.0) * 8.0 // Scale up from base
+}
+
+/// Map a destination coordinate of a nine-patch back to its source coordinate
+/// along one axis. `lead`/`trail` are the (already clamped) insets.
+fn nine_patch_source(d: i32, dst_len: i32, src_len: i32, lead: i32, trail: i32) -> i32 {
+    if d < lead {
+        d
+    } else if d >= dst_len - trail {
+        src_len - (dst_len - d)
+    } else {
+        let src_center = src_len - lead - trail;
+        let dst_center = dst_len - lead - trail;
+        if src_center <= 0 || dst_center <= 0 {
+            lead.min(src_len - 1)
+        } else {
+            lead + (d - lead) * src_center / dst_center
+        }
+    }
+    .clamp(0, src_len - 1)
+}
+
+/// Aspect-preserving mapping of a logical resolution into a window, leaving
+/// letterbox (top/bottom) or pillarbox (left/right) bars in the margins
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Letterbox {
+    pub logical_size: (usize, usize),
+    pub window_size: (usize, usize),
+    /// Area of the window the logical buffer is scaled into
+    pub viewport: Rect,
+    pub scale: f32,
+}
+
+impl Letterbox {
+    /// Fit `logical_size` into `window_size` as large as possible, centered
+    pub fn new(logical_size: (usize, usize), window_size: (usize, usize)) -> Self {
+        let (lw, lh) = (logical_size.0.max(1) as f32, logical_size.1.max(1) as f32);
+        let (ww, wh) = (window_size.0 as f32, window_size.1 as f32);
+        let scale = (ww / lw).min(wh / lh);
+        let width = (lw * scale).round() as i32;
+        let height = (lh * scale).round() as i32;
+        let viewport = Rect::new(
+            (window_size.0 as i32 - width) / 2,
+            (window_size.1 as i32 - height) / 2,
+            width,
+            height,
+        );
+
+        Self {
+            logical_size,
+            window_size,
+            viewport,
+            scale,
+        }
+    }
+
+    /// Map window coordinates (e.g. the mouse) into logical coordinates.
+    /// Points in the bars map outside `0..logical_size`.
+    pub fn window_to_logical(&self, x: i32, y: i32) -> (i32, i32) {
+        if self.scale <= 0.0 {
+            return (-1, -1);
+        }
+        (
+            ((x - self.viewport.x) as f32 / self.scale).floor() as i32,
+            ((y - self.viewport.y) as f32 / self.scale).floor() as i32,
+        )
+    }
+
+    /// Whether window coordinates fall inside the logical area
+    pub fn contains_window_point(&self, x: i32, y: i32) -> bool {
+        let (lx, ly) = self.window_to_logical(x, y);
+        lx >= 0
+            && ly >= 0
+            && (lx as usize) < self.logical_size.0
+            && (ly as usize) < self.logical_size.1
+    }
+
+    /// Scale `src` (logical size) into `dst` (window size) with nearest-neighbor
+    /// sampling, filling the margins with `bar_color`
+    pub fn blit(&self, src: &[u32], dst: &mut [u32], bar_color: Color) {
+        let (lw, lh) = self.logical_size;
+        let (ww, wh) = self.window_size;
+        let vp = self.viewport;
+        for y in 0..wh {
+            for x in 0..ww {
+                let (vx, vy) = (x as i32 - vp.x, y as i32 - vp.y);
+                dst[y * ww + x] = if vp.contains(x as i32, y as i32) {
+                    let sx = (vx as usize * lw / vp.width as usize).min(lw - 1);
+                    let sy = (vy as usize * lh / vp.height as usize).min(lh - 1);
+                    src[sy * lw + sx]
+                } else {
+                    bar_color.0
+                };
+            }
+        }
+    }
+}
+
+/// Nearest-neighbor upscale of a `width` x `height` buffer by a whole-number
+/// `factor`, so each source pixel becomes a `factor` x `factor` block in `dst`
+pub fn upscale_integer(
+    src: &[u32],
+    width: usize,
+    height: usize,
+    factor: usize,
+    dst: &mut Vec<u32>,
+) {
+    let factor = factor.max(1);
+    let out_width = width * factor;
+    dst.resize(out_width * height * factor, 0);
+    for y in 0..height {
+        let row = &src[y * width..(y + 1) * width];
+        let out_start = y * factor * out_width;
+        for (x, &pixel) in row.iter().enumerate() {
+            dst[out_start + x * factor..out_start + (x + 1) * factor].fill(pixel);
+        }
+        for i in 1..factor {
+            dst.copy_within(out_start..out_start + out_width, out_start + i * out_width);
+        }
+    }
+}
+
 /// Rendering context that combines window and renderer
 pub struct RenderContext {
     pub window: WindowManager,
     pub renderer: Renderer2D,
+    /// Color of the letterbox bars when a logical size is set
+    pub bar_color: Color,
+    /// Window size at the last upload, `None` before the first frame
+    presented_size: Option<(usize, usize)>,
+    /// Fixed logical resolution of `renderer`, if letterboxing is enabled
+    logical_size: Option<(usize, usize)>,
+    /// Whole-number pixel-art upscale factor, if enabled
+    integer_scale: Option<usize>,
+    /// Accessibility filter applied to the final frame
+    color_filter: ColorFilter,
+    /// Window-sized scratch buffer the logical frame is scaled into
+    output: Vec<u32>,
 }
 
 impl RenderContext {
@@ -1039,7 +1694,95 @@ impl RenderContext {
         let window = WindowManager::new(config)?;
         let renderer = Renderer2D::from_window(&window);
 
-        Ok(Self { window, renderer })
+        Ok(Self {
+            window,
+            renderer,
+            bar_color: Color::BLACK,
+            presented_size: None,
+            logical_size: None,
+            integer_scale: None,
+            color_filter: ColorFilter::None,
+            output: Vec::new(),
+        })
+    }
+
+    /// Create a rendering context without a window (see `HeadlessBackend`)
+    pub fn new_headless(width: usize, height: usize) -> Self {
+        let window = WindowManager::new_headless(crate::window::WindowConfig {
+            width,
+            height,
+            ..Default::default()
+        });
+        let renderer = Renderer2D::from_window(&window);
+
+        Self {
+            window,
+            renderer,
+            bar_color: Color::BLACK,
+            presented_size: None,
+            logical_size: None,
+            integer_scale: None,
+            color_filter: ColorFilter::None,
+            output: Vec::new(),
+        }
+    }
+
+    /// Render into a fixed `width` x `height` logical buffer that `present`
+    /// scales into the window, preserving its aspect ratio with bars
+    pub fn set_logical_size(&mut self, width: usize, height: usize) {
+        self.logical_size = Some((width, height));
+        self.integer_scale = None;
+        self.renderer.resize(width, height);
+    }
+
+    /// Render at `1 / factor` of the window size and upscale crisply by `factor`
+    /// on `present`. A factor of 0 or 1 renders at window size again.
+    pub fn set_integer_scale(&mut self, factor: usize) {
+        let (width, height) = self.window.dimensions();
+        self.logical_size = None;
+        if factor <= 1 {
+            self.integer_scale = None;
+            self.renderer.resize(width, height);
+        } else {
+            self.integer_scale = Some(factor);
+            self.renderer.resize(width / factor, height / factor);
+        }
+    }
+
+    /// Remap the presented frame through `filter`; the renderer buffer is untouched
+    pub fn set_color_filter(&mut self, filter: ColorFilter) {
+        if filter != self.color_filter {
+            self.color_filter = filter;
+            // force the next present to upload even if nothing was drawn
+            self.presented_size = None;
+        }
+    }
+
+    pub fn color_filter(&self) -> ColorFilter {
+        self.color_filter
+    }
+
+    /// Current integer upscale factor (1 when disabled)
+    pub fn integer_scale(&self) -> usize {
+        self.integer_scale.unwrap_or(1)
+    }
+
+    /// Current letterbox mapping, if a logical size is set
+    pub fn letterbox(&self) -> Option<Letterbox> {
+        self.logical_size
+            .map(|logical| Letterbox::new(logical, self.window.dimensions()))
+    }
+
+    /// Translate window/mouse coordinates into the renderer's coordinate space
+    pub fn window_to_logical(&self, x: i32, y: i32) -> (i32, i32) {
+        if let Some(factor) = self.integer_scale {
+            let factor = factor as i32;
+            return (x.div_euclid(factor), y.div_euclid(factor));
+        }
+        match self.letterbox() {
+            Some(letterbox) => letterbox.window_to_logical(x, y),
+            None => (x, y),
+        }
     }
 
     /// Update the rendering context
@@ -1047,14 +1790,56 @@ impl RenderContext {
         self.window.update();
     }
 
-    /// Present the current frame
+    /// Present the current frame. minifb only accepts whole buffers, so the
+    /// upload is skipped entirely when nothing was drawn and the window kept its size.
     pub fn present(&mut self) -> Result<(), Box<dyn std::error::Error>> {
-        self.window.window().update_with_buffer(
-            self.renderer.buffer(),
-            self.renderer.dimensions().0,
-            self.renderer.dimensions().1,
-        )?;
-        Ok(())
+        let size = self.window.dimensions();
+        let dirty = self.renderer.take_dirty_rect();
+        if dirty.is_none() && self.presented_size == Some(size) {
+            return Ok(());
+        }
+        self.presented_size = Some(size);
+
+        if let Some(factor) = self.integer_scale {
+            let (width, height) = self.renderer.dimensions();
+            upscale_integer(
+                self.renderer.buffer(),
+                width,
+                height,
+                factor,
+                &mut self.output,
+            );
+            self.color_filter.apply_buffer(&mut self.output);
+            return self
+                .window
+                .present(&self.output, width * factor, height * factor);
+        }
+
+        if let Some(letterbox) = self.letterbox() {
+            self.output.resize(size.0 * size.1, 0);
+            letterbox.blit(self.renderer.buffer(), &mut self.output, self.bar_color);
+            self.color_filter.apply_buffer(&mut self.output);
+            return self.window.present(&self.output, size.0, size.1);
+        }
+
+        let (width, height) = self.renderer.dimensions();
+        if self.color_filter != ColorFilter::None {
+            self.output.clear();
+            self.output.extend_from_slice(self.renderer.buffer());
+            self.color_filter.apply_buffer(&mut self.output);
+            return self.window.present(&self.output, width, height);
+        }
+        self.window.present(self.renderer.buffer(), width, height)
+    }
+
+    /// Toggle borderless fullscreen, keeping the current render resolution as
+    /// a fixed logical size so the game's coordinates don't change
+    pub fn toggle_fullscreen(&mut self) -> Result<bool, Box<dyn std::error::Error>> {
+        let (width, height) = self.renderer.dimensions();
+        let fullscreen = self.window.toggle_fullscreen()?;
+        self.set_logical_size(width, height);
+        self.presented_size = None;
+        Ok(fullscreen)
     }
 
     /// Check if the context should close
@@ -1062,3 +1847,707 @@ impl RenderContext {
         self.window.should_close()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_fullscreen_toggle_keeps_logical_size() {
+        let mut context = RenderContext::new_headless(320, 240);
+        assert!(!context.window.is_fullscreen());
+
+        assert!(context.toggle_fullscreen().unwrap());
+        assert_eq!(context.window.dimensions(), (1920, 1080));
+        assert_eq!(context.renderer.dimensions(), (320, 240));
+        context.renderer.clear(Color::WHITE);
+        context.present().unwrap();
+        assert_eq!(context.output.len(), 1920 * 1080);
+        // 4.5x vertical fit, pillarboxed horizontally
+        assert_eq!(context.window_to_logical(960, 540), (160, 120));
+
+        assert!(!context.toggle_fullscreen().unwrap());
+        assert_eq!(context.window.dimensions(), (320, 240));
+        assert_eq!(context.renderer.dimensions(), (320, 240));
+    }
+
+    #[test]
+    fn test_headless_game_loop_advances_score() {
+        use crate::games::pong::{PongConfig, PongGame};
+        use crate::{Position, WorldExt};
+
+        let (width, height) = (320, 240);
+        let mut context = RenderContext::new_headless(width, height);
+        assert!(context.window.is_headless());
+
+        let config = PongConfig {
+            max_serve_angle: 0.0,
+            ..PongConfig::new().with_size(width as f32, height as f32)
+        };
+        let mut game = PongGame::new(config);
+        // the idle player paddle sits above the ball's path, so the AI scores
+        game.world
+            .write_storage::<Position>()
+            .get_mut(game.entities.player_paddle)
+            .unwrap()
+            .y = 0.0;
+
+        let input = Default::default();
+        for _ in 0..100 {
+            context.update();
+            game.update(1.0 / 60.0, &input);
+            game.render(&mut context.renderer);
+            context.present().unwrap();
+        }
+
+        assert!(!context.should_close());
+        let (player, ai) = game.score();
+        assert_eq!(player, 0);
+        assert!(ai > 0, "the AI should have scored");
+        assert!(context.renderer.buffer().contains(&Color::WHITE.0));
+    }
+
+    #[test]
+    fn test_letterbox_maps_mouse_into_logical_space() {
+        // 4:3 logical area in a 16:9 window -> pillarbox bars on the sides
+        let letterbox = Letterbox::new((320, 240), (1280, 720));
+        assert_eq!(letterbox.scale, 3.0);
+        assert_eq!(letterbox.viewport, Rect::new(160, 0, 960, 720));
+
+        // left and right bars are outside the logical area
+        assert!(!letterbox.contains_window_point(50, 360));
+        assert!(letterbox.window_to_logical(50, 360).0 < 0);
+        assert!(letterbox.window_to_logical(1200, 360).0 >= 320);
+
+        // interior points scale back down
+        assert_eq!(letterbox.window_to_logical(160, 0), (0, 0));
+        assert_eq!(letterbox.window_to_logical(640, 360), (160, 120));
+        assert_eq!(letterbox.window_to_logical(1119, 719), (319, 239));
+    }
+
+    #[test]
+    fn test_integer_upscale_makes_blocks() {
+        let src = [Color::RED.0, Color::GREEN.0, Color::BLUE.0, Color::WHITE.0];
+        let mut dst = Vec::new();
+        upscale_integer(&src, 2, 2, 3, &mut dst);
+        assert_eq!(dst.len(), 36);
+        for y in 0..6 {
+            for x in 0..6 {
+                assert_eq!(dst[y * 6 + x], src[(y / 3) * 2 + x / 3], "pixel ({x}, {y})");
+            }
+        }
+
+        let mut context = RenderContext::new_headless(960, 720);
+        context.set_integer_scale(3);
+        assert_eq!(context.renderer.dimensions(), (320, 240));
+        assert_eq!(context.window_to_logical(959, 719), (319, 239));
+        context.present().unwrap();
+    }
+
+    #[test]
+    fn test_deuteranopia_remaps_red() {
+        let filtered = ColorFilter::Deuteranopia.apply(Color::RED);
+        assert_eq!(
+            (filtered.r(), filtered.g(), filtered.b(), filtered.a()),
+            (159, 179, 0, 255)
+        );
+        assert_eq!(ColorFilter::None.apply(Color::RED), Color::RED);
+
+        let mut buffer = vec![Color::RED.0, Color::WHITE.0];
+        ColorFilter::Deuteranopia.apply_buffer(&mut buffer);
+        assert_eq!(buffer, vec![Color::rgb(159, 179, 0).0, Color::WHITE.0]);
+    }
+
+    #[test]
+    fn test_letterbox_blit_fills_bars() {
+        let letterbox = Letterbox::new((2, 2), (4, 2));
+        let src = vec![Color::RED.0; 4];
+        let mut dst = vec![0; 8];
+        letterbox.blit(&src, &mut dst, Color::BLUE);
+        assert_eq!(
+            dst,
+            vec![
+                Color::BLUE.0,
+                Color::RED.0,
+                Color::RED.0,
+                Color::BLUE.0,
+                Color::BLUE.0,
+                Color::RED.0,
+                Color::RED.0,
+                Color::BLUE.0,
+            ]
+        );
+    }
+
+    #[test]
+    fn test_open_polyline_does_not_close() {
+        let points = [
+            Point2::new(2.0, 2.0),
+            Point2::new(12.0, 2.0),
+            Point2::new(12.0, 12.0),
+        ];
+
+        let mut renderer = Renderer2D::new(16, 16);
+        renderer.clear(Color::BLACK);
+        renderer.draw_polyline(&points, Color::WHITE, false);
+        assert_eq!(renderer.get_pixel(7, 2), Some(Color::WHITE));
+        assert_eq!(renderer.get_pixel(12, 7), Some(Color::WHITE));
+        // the diagonal from the last point back to the first is missing
+        assert_eq!(renderer.get_pixel(7, 7), Some(Color::BLACK));
+
+        renderer.draw_polyline(&points, Color::WHITE, true);
+        assert_eq!(renderer.get_pixel(7, 7), Some(Color::WHITE));
+    }
+
+    #[test]
+    fn test_concave_arrow_fills_only_interior() {
+        // Arrow pointing right: a shaft with a triangular head
+        let arrow = [
+            Point2::new(0.0, 4.0),
+            Point2::new(10.0, 4.0),
+            Point2::new(10.0, 0.0),
+            Point2::new(18.0, 8.0),
+            Point2::new(10.0, 16.0),
+            Point2::new(10.0, 12.0),
+            Point2::new(0.0, 12.0),
+        ];
+
+        let mut renderer = Renderer2D::new(20, 20);
+        renderer.clear(Color::BLACK);
+        renderer.draw_polygon_filled(&arrow, Color::GREEN);
+
+        // shaft and head
+        assert_eq!(renderer.get_pixel(5, 8), Some(Color::GREEN));
+        assert_eq!(renderer.get_pixel(14, 8), Some(Color::GREEN));
+        assert_eq!(renderer.get_pixel(11, 2), Some(Color::GREEN));
+        // concave notches above and below the shaft stay empty
+        assert_eq!(renderer.get_pixel(5, 2), Some(Color::BLACK));
+        assert_eq!(renderer.get_pixel(5, 14), Some(Color::BLACK));
+        // outside the head
+        assert_eq!(renderer.get_pixel(16, 2), Some(Color::BLACK));
+        assert_eq!(renderer.get_pixel(19, 8), Some(Color::BLACK));
+    }
+
+    #[test]
+    fn test_flood_fill_only_changes_interior() {
+        let mut renderer = Renderer2D::new(20, 20);
+        renderer.clear(Color::BLACK);
+        renderer.draw_rect_outline(4, 4, 10, 8, Color::WHITE);
+
+        renderer.flood_fill(8, 8, Color::RED);
+
+        for y in 0..20 {
+            for x in 0..20 {
+                let on_border = (x == 4 || x == 13) && (4..12).contains(&y)
+                    || (y == 4 || y == 11) && (4..14).contains(&x);
+                let inside = (5..13).contains(&x) && (5..11).contains(&y);
+                let expected = if on_border {
+                    Color::WHITE
+                } else if inside {
+                    Color::RED
+                } else {
+                    Color::BLACK
+                };
+                assert_eq!(renderer.get_pixel(x, y), Some(expected), "pixel ({x}, {y})");
+            }
+        }
+    }
+
+    #[test]
+    fn test_flood_fill_respects_clip_rect() {
+        let mut renderer = Renderer2D::new(10, 10);
+        renderer.clear(Color::BLACK);
+        renderer.set_clip_rect(Some(Rect::new(0, 0, 5, 10)));
+        renderer.flood_fill(2, 2, Color::GREEN);
+
+        assert_eq!(renderer.get_pixel(4, 9), Some(Color::GREEN));
+        assert_eq!(renderer.get_pixel(5, 0), Some(Color::BLACK));
+    }
+
+    #[test]
+    fn test_thick_circle_fills_annulus() {
+        let mut renderer = Renderer2D::new(41, 41);
+        renderer.clear(Color::BLACK);
+        renderer.draw_circle_thick(20, 20, 10, 3, Color::WHITE);
+
+        for (x, y) in [(30, 20), (10, 20), (20, 30), (20, 10), (29, 20), (31, 20)] {
+            assert_eq!(
+                renderer.get_pixel(x, y),
+                Some(Color::WHITE),
+                "({}, {})",
+                x,
+                y
+            );
+        }
+        for (x, y) in [(20, 20), (27, 20), (33, 20), (20, 6), (29, 29)] {
+            assert_eq!(
+                renderer.get_pixel(x, y),
+                Some(Color::BLACK),
+                "({}, {})",
+                x,
+                y
+            );
+        }
+    }
+
+    #[test]
+    fn test_blit_copies_with_clipping() {
+        let src = [Color::RED.0, Color::GREEN.0, Color::BLUE.0, Color::WHITE.0];
+        let mut renderer = Renderer2D::new(4, 4);
+        renderer.clear(Color::BLACK);
+        renderer.blit(&src, 2, 2, 1, 1);
+        assert_eq!(renderer.get_pixel(1, 1), Some(Color::RED));
+        assert_eq!(renderer.get_pixel(2, 1), Some(Color::GREEN));
+        assert_eq!(renderer.get_pixel(1, 2), Some(Color::BLUE));
+        assert_eq!(renderer.get_pixel(2, 2), Some(Color::WHITE));
+        assert_eq!(renderer.get_pixel(0, 0), Some(Color::BLACK));
+        assert_eq!(renderer.get_pixel(3, 3), Some(Color::BLACK));
+
+        // hanging off the bottom-right corner keeps only the top-left source pixel
+        let mut clipped = Renderer2D::new(4, 4);
+        clipped.clear(Color::BLACK);
+        let layer = {
+            let mut layer = Renderer2D::new(2, 2);
+            layer.blit(&src, 2, 2, 0, 0);
+            layer
+        };
+        clipped.blit_from(&layer, 3, 3);
+        assert_eq!(clipped.get_pixel(3, 3), Some(Color::RED));
+        assert_eq!(clipped.get_pixel(2, 3), Some(Color::BLACK));
+        assert_eq!(clipped.get_pixel(3, 2), Some(Color::BLACK));
+
+        // translucent pixels blend when asked to
+        let half_white = [Color::rgba(255, 255, 255, 128).0];
+        clipped.blit_blended(&half_white, 1, 1, 0, 0);
+        let blended = clipped.get_pixel(0, 0).unwrap();
+        assert!(blended.r() > 120 && blended.r() < 136);
+        assert_eq!(blended.a(), 255);
+    }
+
+    #[test]
+    fn test_grid_lines_at_spacing() {
+        let mut renderer = Renderer2D::new(800, 300);
+        renderer.clear(Color::BLACK);
+        renderer.draw_grid(100.0, Color::WHITE, Point2::new(0.0, 0.0));
+
+        let vertical: Vec<i32> = (0..800)
+            .filter(|&x| renderer.get_pixel(x, 50) == Some(Color::WHITE))
+            .collect();
+        assert_eq!(vertical, vec![0, 100, 200, 300, 400, 500, 600, 700]);
+        let horizontal: Vec<i32> = (0..300)
+            .filter(|&y| renderer.get_pixel(50, y) == Some(Color::WHITE))
+            .collect();
+        assert_eq!(horizontal, vec![0, 100, 200]);
+
+        // an offset origin shifts the lines; a zoomed camera spreads them out
+        renderer.clear(Color::BLACK);
+        renderer.draw_grid(100.0, Color::WHITE, Point2::new(-30.0, 0.0));
+        assert_eq!(renderer.get_pixel(70, 50), Some(Color::WHITE));
+        assert_eq!(renderer.get_pixel(0, 50), Some(Color::BLACK));
+
+        renderer.clear(Color::BLACK);
+        let camera = Camera2D {
+            position: Vec2::new(400.0, 150.0),
+            zoom: 2.0,
+            rotation: 0.0,
+            viewport_size: Vec2::new(800.0, 300.0),
+        };
+        renderer.draw_grid_camera(100.0, Color::WHITE, &camera);
+        let vertical: Vec<i32> = (0..800)
+            .filter(|&x| renderer.get_pixel(x, 10) == Some(Color::WHITE))
+            .collect();
+        assert_eq!(vertical, vec![0, 200, 400, 600]);
+    }
+
+    #[test]
+    fn test_dashed_line_leaves_gaps() {
+        let mut renderer = Renderer2D::new(30, 3);
+        renderer.clear(Color::BLACK);
+        renderer.draw_dashed_line(0, 1, 29, 1, Color::WHITE, 4, 2);
+
+        for x in 0..30 {
+            let expected = if x % 6 < 4 {
+                Color::WHITE
+            } else {
+                Color::BLACK
+            };
+            assert_eq!(renderer.get_pixel(x, 1), Some(expected), "x = {}", x);
+        }
+        assert_eq!(renderer.get_pixel(0, 0), Some(Color::BLACK));
+    }
+
+    #[test]
+    fn test_text_spans_continue_at_measured_width() {
+        let mut spans = Renderer2D::new(120, 30);
+        spans.clear(Color::BLACK);
+        spans.draw_text_spans(
+            &[
+                ("Mode ".to_string(), Color::WHITE),
+                ("Hard".to_string(), Color::RED),
+            ],
+            5,
+            10,
+            1,
+        );
+
+        let mut manual = Renderer2D::new(120, 30);
+        manual.clear(Color::BLACK);
+        manual.draw_text("Mode ", 5, 10, Color::WHITE, 1);
+        let (width, _) = manual.measure_text("Mode ", 1);
+        manual.draw_text("Hard", 5 + width, 10, Color::RED, 1);
+
+        assert!(width > 0);
+        let second_span_drawn = (0..30)
+            .any(|y| (5 + width..120).any(|x| spans.get_pixel(x as i32, y) != Some(Color::BLACK)));
+        assert!(second_span_drawn);
+        assert_eq!(spans.buffer(), manual.buffer());
+    }
+
+    #[test]
+    fn test_outlined_text_surrounds_fill() {
+        let fill_pixels = {
+            let mut plain = Renderer2D::new(40, 30);
+            plain.clear(Color::BLACK);
+            plain.draw_text("H", 10, 10, Color::WHITE, 1);
+            let mut pixels = Vec::new();
+            for y in 0..30 {
+                for x in 0..40 {
+                    if plain.get_pixel(x, y) == Some(Color::WHITE) {
+                        pixels.push((x, y));
+                    }
+                }
+            }
+            pixels
+        };
+        assert!(!fill_pixels.is_empty());
+        let min_x = fill_pixels.iter().map(|p| p.0).min().unwrap();
+        let max_x = fill_pixels.iter().map(|p| p.0).max().unwrap();
+        let min_y = fill_pixels.iter().map(|p| p.1).min().unwrap();
+        let max_y = fill_pixels.iter().map(|p| p.1).max().unwrap();
+
+        let mut renderer = Renderer2D::new(40, 30);
+        renderer.clear(Color::BLACK);
+        renderer.draw_text_outlined("H", 10, 10, Color::WHITE, Color::RED, 1);
+
+        for &(x, y) in &fill_pixels {
+            assert_eq!(renderer.get_pixel(x, y), Some(Color::WHITE));
+            if x == min_x {
+                assert_eq!(renderer.get_pixel(x - 1, y), Some(Color::RED));
+            }
+            if x == max_x {
+                assert_eq!(renderer.get_pixel(x + 1, y), Some(Color::RED));
+            }
+            if y == min_y {
+                assert_eq!(renderer.get_pixel(x, y - 1), Some(Color::RED));
+            }
+            if y == max_y {
+                assert_eq!(renderer.get_pixel(x, y + 1), Some(Color::RED));
+            }
+        }
+        assert_eq!(renderer.get_pixel(min_x - 2, min_y), Some(Color::BLACK));
+    }
+
+    #[test]
+    fn test_viewport_offsets_and_clips_draws() {
+        let mut renderer = Renderer2D::new(20, 10);
+        renderer.clear(Color::BLACK);
+        renderer.with_viewport(10, 0, 10, 10, |r| {
+            r.draw_rect(0, 0, 20, 10, Color::RED);
+            r.set_pixel(0, 0, Color::GREEN);
+        });
+
+        for y in 0..10 {
+            for x in 0..20 {
+                let expected = match (x, y) {
+                    (10, 0) => Color::GREEN,
+                    _ if x >= 10 => Color::RED,
+                    _ => Color::BLACK,
+                };
+                assert_eq!(renderer.get_pixel(x, y), Some(expected), "pixel ({x}, {y})");
+            }
+        }
+        assert_eq!(renderer.clip_rect(), None);
+    }
+
+    #[test]
+    fn test_blended_rect_reads_through_viewport() {
+        let mut renderer = Renderer2D::new(20, 10);
+        renderer.clear(Color::BLACK);
+        renderer.draw_rect(10, 0, 10, 10, Color::WHITE);
+        renderer.with_viewport(10, 0, 10, 10, |r| {
+            r.draw_rect_blended(0, 0, 10, 10, Color::rgba(0, 0, 0, 128));
+        });
+
+        let expected = Color::WHITE.lerp(Color::BLACK, 128.0 / 255.0);
+        assert_eq!(renderer.get_pixel(15, 5), Some(expected));
+        assert_eq!(renderer.get_pixel(5, 5), Some(Color::BLACK));
+    }
+
+    #[test]
+    fn test_vertical_gradient_midpoint_is_lerp() {
+        let top = Color::rgb(20, 20, 30);
+        let bottom = Color::rgb(80, 40, 200);
+
+        let mut renderer = Renderer2D::new(4, 11);
+        renderer.fill_gradient(top, bottom);
+
+        let mid = top.lerp(bottom, 0.5);
+        for x in 0..4 {
+            assert_eq!(renderer.get_pixel(x, 5), Some(mid));
+        }
+        assert_eq!(renderer.get_pixel(0, 0), Some(top));
+        assert_eq!(renderer.get_pixel(0, 10), Some(bottom));
+    }
+
+    #[test]
+    fn test_horizontal_gradient_varies_per_column() {
+        let mut renderer = Renderer2D::new(3, 2);
+        renderer.draw_rect_gradient(
+            0,
+            0,
+            3,
+            2,
+            Color::BLACK,
+            Color::WHITE,
+            GradientDirection::Horizontal,
+        );
+        assert_eq!(renderer.get_pixel(0, 1), Some(Color::BLACK));
+        assert_eq!(renderer.get_pixel(1, 1), Some(Color::rgb(128, 128, 128)));
+        assert_eq!(renderer.get_pixel(2, 0), Some(Color::WHITE));
+    }
+
+    #[test]
+    fn test_display_list_higher_layer_wins() {
+        let mut list = DisplayList::new();
+        // Recorded top layer first to make sure execution reorders by z
+        list.rect(5, 4, 4, 8, 8, Color::RED);
+        list.rect(1, 0, 0, 8, 8, Color::BLUE);
+        assert_eq!(list.len(), 2);
+
+        let mut renderer = Renderer2D::new(16, 16);
+        renderer.clear(Color::BLACK);
+        renderer.execute(&list);
+
+        // overlap
+        assert_eq!(renderer.get_pixel(5, 5), Some(Color::RED));
+        // lower layer only
+        assert_eq!(renderer.get_pixel(1, 1), Some(Color::BLUE));
+        // higher layer only
+        assert_eq!(renderer.get_pixel(11, 11), Some(Color::RED));
+    }
+
+    #[test]
+    fn test_named_layers_composite_in_order() {
+        let rect = |color| DrawCommand::Rect {
+            rect: Rect::new(0, 0, 8, 8),
+            color,
+            filled: true,
+        };
+        let mut list = DisplayList::new();
+        list.push_layer(RenderLayer::UI, rect(Color::GREEN));
+        // a huge depth can't lift the background over the UI
+        list.push(RenderLayer::Background.z(10_000), rect(Color::BLUE));
+        list.rect(RenderLayer::World.z(3), 0, 0, 8, 8, Color::RED);
+
+        let mut renderer = Renderer2D::new(8, 8);
+        renderer.execute(&list);
+        assert_eq!(renderer.get_pixel(4, 4), Some(Color::GREEN));
+
+        assert!(RenderLayer::Background.z(499) < RenderLayer::World.z(-500));
+        assert!(RenderLayer::Particles.z(499) < RenderLayer::UI.z(-500));
+        assert_eq!(RenderLayer::World.z(7), 7);
+    }
+
+    #[test]
+    fn test_circle_mask_limits_drawing() {
+        let mut renderer = Renderer2D::new(40, 30);
+        renderer.clear(Color::BLACK);
+        let mask = renderer.circle_mask(20, 15, 5.0);
+        renderer.set_mask(Some(mask.clone()));
+        renderer.draw_rect(0, 0, 40, 30, Color::WHITE);
+
+        let inside = mask.iter().filter(|&&m| m).count();
+        assert!(inside > 0 && inside < mask.len());
+        for (index, &pixel) in renderer.buffer().iter().enumerate() {
+            let expected = if mask[index] {
+                Color::WHITE
+            } else {
+                Color::BLACK
+            };
+            assert_eq!(pixel, expected.0, "pixel {}", index);
+        }
+        assert_eq!(renderer.get_pixel(20, 15), Some(Color::WHITE));
+        assert_eq!(renderer.get_pixel(20, 21), Some(Color::BLACK));
+
+        // clears respect the mask; flood fill stops at its edge
+        renderer.set_mask(Some(renderer.rect_mask(Rect::new(0, 0, 10, 10))));
+        renderer.clear(Color::RED);
+        renderer.flood_fill(0, 20, Color::BLUE);
+        renderer.set_mask(None);
+        assert_eq!(renderer.get_pixel(9, 9), Some(Color::RED));
+        assert_eq!(renderer.get_pixel(10, 10), Some(Color::BLACK));
+    }
+
+    #[test]
+    fn test_fast_rect_fill_matches_per_pixel_path() {
+        let per_pixel = |renderer: &mut Renderer2D, x: i32, y: i32, w: i32, h: i32, c: Color| {
+            for py in y..y + h {
+                for px in x..x + w {
+                    renderer.set_pixel(px, py, c);
+                }
+            }
+        };
+        let rects = [
+            (10, 10, 50, 30), // inside
+            (-5, 20, 30, 10), // clipped left
+            (70, 50, 40, 40), // clipped bottom-right
+            (0, 0, 80, 60),   // exactly the buffer
+            (20, 20, 0, 5),   // empty
+        ];
+        for clip in [None, Some(Rect::new(5, 5, 60, 40))] {
+            for origin in [(0, 0), (3, -2)] {
+                let mut fast = Renderer2D::new(80, 60);
+                let mut slow = Renderer2D::new(80, 60);
+                for renderer in [&mut fast, &mut slow] {
+                    renderer.clear(Color::BLACK);
+                    renderer.set_clip_rect(clip);
+                    renderer.origin = origin;
+                    renderer.take_dirty_rect();
+                }
+                for (i, &(x, y, w, h)) in rects.iter().enumerate() {
+                    let color = Color::rgb(40 * i as u8, 200, 30);
+                    fast.draw_rect(x, y, w, h, color);
+                    per_pixel(&mut slow, x, y, w, h, color);
+                }
+                assert_eq!(fast.buffer(), slow.buffer());
+                assert_eq!(fast.take_dirty_rect(), slow.take_dirty_rect());
+            }
+        }
+    }
+
+    #[test]
+    fn test_dirty_rect_tracks_draw_calls() {
+        let mut renderer = Renderer2D::new(64, 64);
+        renderer.clear(Color::BLACK);
+        assert_eq!(renderer.take_dirty_rect(), Some(Rect::new(0, 0, 64, 64)));
+
+        // A frame without draw calls has nothing to upload
+        assert_eq!(renderer.take_dirty_rect(), None);
+
+        renderer.draw_rect(10, 12, 4, 3, Color::RED);
+        renderer.set_pixel(30, 5, Color::RED);
+        assert_eq!(renderer.take_dirty_rect(), Some(Rect::new(10, 5, 21, 10)));
+
+        // Off-screen drawing touches nothing
+        renderer.draw_rect(-20, -20, 5, 5, Color::RED);
+        assert_eq!(renderer.take_dirty_rect(), None);
+    }
+
+    #[test]
+    fn test_render_stats_count_draw_calls_and_pixels() {
+        let mut renderer = Renderer2D::new(64, 64);
+        renderer.draw_rect(5, 5, 10, 10, Color::RED);
+        assert_eq!(
+            renderer.take_stats(),
+            RenderStats {
+                draw_calls: 1,
+                pixels_written: 100,
+            }
+        );
+        assert_eq!(renderer.take_stats(), RenderStats::default());
+
+        // clipped pixels aren't counted, and a gradient is a single call
+        renderer.draw_rect(60, 60, 10, 10, Color::RED);
+        renderer.draw_rect_gradient(
+            0,
+            0,
+            4,
+            4,
+            Color::RED,
+            Color::BLUE,
+            GradientDirection::Vertical,
+        );
+        assert_eq!(
+            renderer.take_stats(),
+            RenderStats {
+                draw_calls: 2,
+                pixels_written: 16 + 16,
+            }
+        );
+    }
+
+    #[test]
+    fn test_sprite_decodes_embedded_png() {
+        // 2x2 RGBA PNG: blue, red / green, transparent
+        const PNG: &[u8] = &[
+            0x89, 0x50, 0x4e, 0x47, 0x0d, 0x0a, 0x1a, 0x0a, 0x00, 0x00, 0x00, 0x0d, 0x49, 0x48,
+            0x44, 0x52, 0x00, 0x00, 0x00, 0x02, 0x00, 0x00, 0x00, 0x02, 0x08, 0x06, 0x00, 0x00,
+            0x00, 0x72, 0xb6, 0x0d, 0x24, 0x00, 0x00, 0x00, 0x14, 0x49, 0x44, 0x41, 0x54, 0x78,
+            0xda, 0x63, 0x60, 0x60, 0xf8, 0xff, 0xff, 0x3f, 0x90, 0x00, 0xa1, 0xff, 0x20, 0x26,
+            0x00, 0x48, 0xc9, 0x08, 0xf8, 0x45, 0xa4, 0x23, 0xba, 0x00, 0x00, 0x00, 0x00, 0x49,
+            0x45, 0x4e, 0x44, 0xae, 0x42, 0x60, 0x82,
+        ];
+
+        let sprite = Sprite::from_bytes(PNG).unwrap();
+        assert_eq!((sprite.width, sprite.height), (2, 2));
+        assert_eq!(sprite.get_pixel(0, 0), Color::BLUE);
+        assert_eq!(sprite.get_pixel(1, 0), Color::RED);
+        assert_eq!(sprite.get_pixel(0, 1), Color::GREEN);
+        assert_eq!(sprite.get_pixel(1, 1).a(), 0);
+
+        assert!(Sprite::from_bytes(&PNG[..20]).is_err());
+    }
+
+    #[test]
+    fn test_nine_patch_keeps_corners_and_fills_center() {
+        let tl = Color::RED;
+        let tr = Color::GREEN;
+        let bl = Color::BLUE;
+        let br = Color::YELLOW;
+        let edge = Color::CYAN;
+        let center = Color::MAGENTA;
+        let source = Sprite::new(
+            3,
+            3,
+            vec![
+                tl.0, edge.0, tr.0, //
+                edge.0, center.0, edge.0, //
+                bl.0, edge.0, br.0,
+            ],
+        );
+
+        let mut renderer = Renderer2D::new(9, 9);
+        renderer.clear(Color::BLACK);
+        renderer.draw_nine_patch(&source, Rect::new(0, 0, 9, 9), (1, 1, 1, 1));
+
+        // Corners are copied 1:1
+        assert_eq!(renderer.get_pixel(0, 0), Some(tl));
+        assert_eq!(renderer.get_pixel(8, 0), Some(tr));
+        assert_eq!(renderer.get_pixel(0, 8), Some(bl));
+        assert_eq!(renderer.get_pixel(8, 8), Some(br));
+        assert_eq!(renderer.get_pixel(1, 0), Some(edge));
+        assert_eq!(renderer.get_pixel(0, 1), Some(edge));
+
+        // The whole interior is stretched from the center pixel
+        for y in 1..8 {
+            for x in 1..8 {
+                assert_eq!(renderer.get_pixel(x, y), Some(center));
+            }
+        }
+    }
+
+    #[test]
+    fn test_nine_patch_clamps_oversized_insets() {
+        let source = Sprite::filled(2, 2, Color::WHITE);
+        let mut renderer = Renderer2D::new(6, 6);
+        renderer.clear(Color::BLACK);
+        renderer.draw_nine_patch(&source, Rect::new(1, 1, 4, 4), (10, 10, 10, 10));
+
+        assert_eq!(renderer.get_pixel(0, 0), Some(Color::BLACK));
+        for y in 1..5 {
+            for x in 1..5 {
+                assert_eq!(renderer.get_pixel(x, y), Some(Color::WHITE));
+            }
+        }
+    }
+}
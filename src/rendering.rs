@@ -2,8 +2,11 @@
 //!
 //! Graphics rendering with sprites, cameras, and visual effects.
 
-use crate::Vec2;
-use specs::{Component, DenseVecStorage, VecStorage};
+use crate::font::FontSystem;
+use crate::{renderer_2d, Vec2};
+use crate::{Collider, Inactive, Position};
+use specs::{Component, DenseVecStorage, Entity, Join, VecStorage, WorldExt};
+use std::collections::HashMap;
 
 /// Sprite component for 2D rendering
 #[derive(Component, Debug, Clone)]
@@ -24,6 +27,389 @@ pub struct Camera2D {
     pub viewport_size: Vec2,
 }
 
+impl Camera2D {
+    /// Convert a screen position (pixels, origin top-left) to world space.
+    /// The camera's `position` is the world point at the center of the viewport.
+    pub fn screen_to_world(&self, screen: Vec2) -> Vec2 {
+        let offset = (screen - self.viewport_size / 2.0) / self.zoom;
+        let (sin, cos) = self.rotation.sin_cos();
+        self.position
+            + Vec2::new(
+                offset.x * cos - offset.y * sin,
+                offset.x * sin + offset.y * cos,
+            )
+    }
+
+    /// Convert a world position to screen space (inverse of `screen_to_world`)
+    pub fn world_to_screen(&self, world: Vec2) -> Vec2 {
+        let offset = world - self.position;
+        let (sin, cos) = (-self.rotation).sin_cos();
+        let rotated = Vec2::new(
+            offset.x * cos - offset.y * sin,
+            offset.x * sin + offset.y * cos,
+        );
+        rotated * self.zoom + self.viewport_size / 2.0
+    }
+
+    /// World-space bounding box `(min, max)` of what the camera sees in a
+    /// `viewport`-sized view; rotated views give the box around all four corners
+    pub fn visible_rect(&self, viewport: Vec2) -> (Vec2, Vec2) {
+        let camera = Camera2D {
+            viewport_size: viewport,
+            ..self.clone()
+        };
+        let corners = [
+            Vec2::new(0.0, 0.0),
+            Vec2::new(viewport.x, 0.0),
+            Vec2::new(0.0, viewport.y),
+            viewport,
+        ]
+        .map(|corner| camera.screen_to_world(corner));
+        let min = corners.iter().fold(corners[0], |min, c| min.inf(c));
+        let max = corners.iter().fold(corners[0], |max, c| max.sup(c));
+        (min, max)
+    }
+}
+
+/// Active entities whose bounding box intersects `camera`'s visible rectangle
+/// for a `viewport`-sized view, in storage order.
+///
+/// The box is the `Collider`'s, or else the `Sprite` size, centered on the
+/// `Position`. Entities with neither are treated as points.
+pub fn visible_entities(world: &specs::World, camera: &Camera2D, viewport: Vec2) -> Vec<Entity> {
+    let (view_min, view_max) = camera.visible_rect(viewport);
+    let entities = world.entities();
+    let positions = world.read_storage::<Position>();
+    let colliders = world.read_storage::<Collider>();
+    let sprites = world.read_storage::<Sprite>();
+    let inactive = world.read_storage::<Inactive>();
+
+    (
+        &entities,
+        &positions,
+        colliders.maybe(),
+        sprites.maybe(),
+        !&inactive,
+    )
+        .join()
+        .filter(|(_, position, collider, sprite, _)| {
+            let half = match (collider, sprite) {
+                (Some(collider), _) => crate::physics::half_extents(&collider.shape),
+                (None, Some(sprite)) => sprite.size / 2.0,
+                (None, None) => Vec2::zeros(),
+            };
+            let center = Vec2::new(position.x, position.y);
+            let (min, max) = (center - half, center + half);
+            min.x <= view_max.x && max.x >= view_min.x && min.y <= view_max.y && max.y >= view_min.y
+        })
+        .map(|(entity, ..)| entity)
+        .collect()
+}
+
+/// Several sprites packed into one shared backing image with a shelf packer.
+///
+/// `pack` copies a sprite in and returns the sub-rectangle it occupies; draw it
+/// with `draw` (or `Renderer2D::draw_sprite_region` on `sprite()`).
+#[derive(Debug, Clone)]
+pub struct TextureAtlas {
+    sprite: renderer_2d::Sprite,
+    regions: Vec<renderer_2d::Rect>,
+    /// Top of the current shelf
+    shelf_y: i32,
+    /// Height of the tallest entry on the current shelf
+    shelf_height: i32,
+    /// Next free x on the current shelf
+    cursor_x: i32,
+}
+
+impl TextureAtlas {
+    /// Create an empty (fully transparent) atlas
+    pub fn new(width: usize, height: usize) -> Self {
+        Self {
+            sprite: renderer_2d::Sprite::new(width, height, vec![0; width * height]),
+            regions: Vec::new(),
+            shelf_y: 0,
+            shelf_height: 0,
+            cursor_x: 0,
+        }
+    }
+
+    /// Copy `image` into the atlas. Returns its region, or `None` if it doesn't fit.
+    pub fn pack(&mut self, image: &renderer_2d::Sprite) -> Option<renderer_2d::Rect> {
+        let (atlas_w, atlas_h) = (self.sprite.width as i32, self.sprite.height as i32);
+        let (w, h) = (image.width as i32, image.height as i32);
+        if w > atlas_w {
+            return None;
+        }
+
+        // start a new shelf when the current one is full
+        if self.cursor_x + w > atlas_w {
+            self.shelf_y += self.shelf_height;
+            self.shelf_height = 0;
+            self.cursor_x = 0;
+        }
+        if self.shelf_y + h > atlas_h {
+            return None;
+        }
+
+        let region = renderer_2d::Rect::new(self.cursor_x, self.shelf_y, w, h);
+        for y in 0..image.height {
+            let dst = (region.y as usize + y) * self.sprite.width + region.x as usize;
+            self.sprite.pixels[dst..dst + image.width]
+                .copy_from_slice(&image.pixels[y * image.width..(y + 1) * image.width]);
+        }
+
+        self.cursor_x += w;
+        self.shelf_height = self.shelf_height.max(h);
+        self.regions.push(region);
+        Some(region)
+    }
+
+    /// The shared backing image
+    pub fn sprite(&self) -> &renderer_2d::Sprite {
+        &self.sprite
+    }
+
+    /// Regions of all packed sprites, in packing order
+    pub fn regions(&self) -> &[renderer_2d::Rect] {
+        &self.regions
+    }
+
+    /// Draw a packed region with its top-left corner at `(x, y)`
+    pub fn draw(
+        &self,
+        renderer: &mut renderer_2d::Renderer2D,
+        region: renderer_2d::Rect,
+        x: i32,
+        y: i32,
+    ) {
+        renderer.draw_sprite_region(&self.sprite, region, x, y);
+    }
+}
+
+/// Corners of the unit quad each instance is stretched over, as a triangle list
+/// through `QUAD_INDICES`
+pub const UNIT_QUAD: [[f32; 2]; 4] = [[0.0, 0.0], [1.0, 0.0], [1.0, 1.0], [0.0, 1.0]];
+pub const QUAD_INDICES: [u16; 6] = [0, 1, 2, 0, 2, 3];
+
+/// Per-instance data for one quad.
+///
+/// `transform` holds the affine columns (x axis, y axis, translation) that map
+/// a `UNIT_QUAD` corner `(u, v)` to `u * x_axis + v * y_axis + translation`.
+#[repr(C)]
+#[derive(Debug, Clone, Copy, PartialEq, bytemuck::Pod, bytemuck::Zeroable)]
+pub struct QuadInstance {
+    pub transform: [[f32; 2]; 3],
+    pub color: [f32; 4],
+}
+
+impl QuadInstance {
+    const ATTRIBUTES: [wgpu::VertexAttribute; 4] =
+        wgpu::vertex_attr_array![1 => Float32x2, 2 => Float32x2, 3 => Float32x2, 4 => Float32x4];
+
+    /// Axis-aligned rectangle with its top-left corner at `(x, y)`
+    pub fn rect(x: f32, y: f32, width: f32, height: f32, color: [f32; 4]) -> Self {
+        Self {
+            transform: [[width, 0.0], [0.0, height], [x, y]],
+            color,
+        }
+    }
+
+    /// Rectangle of `size` centered on `center`, rotated by `rotation` radians
+    pub fn rotated(center: Vec2, size: Vec2, rotation: f32, color: [f32; 4]) -> Self {
+        let (sin, cos) = rotation.sin_cos();
+        let x_axis = Vec2::new(cos, sin) * size.x;
+        let y_axis = Vec2::new(-sin, cos) * size.y;
+        let origin = center - (x_axis + y_axis) / 2.0;
+        Self {
+            transform: [
+                [x_axis.x, x_axis.y],
+                [y_axis.x, y_axis.y],
+                [origin.x, origin.y],
+            ],
+            color,
+        }
+    }
+
+    /// Where a unit-quad corner lands after the instance transform
+    pub fn apply(&self, corner: [f32; 2]) -> [f32; 2] {
+        let [x_axis, y_axis, offset] = self.transform;
+        [
+            corner[0] * x_axis[0] + corner[1] * y_axis[0] + offset[0],
+            corner[0] * x_axis[1] + corner[1] * y_axis[1] + offset[1],
+        ]
+    }
+
+    /// Instance-rate vertex layout; locations 1-3 are the transform columns,
+    /// 4 is the color (location 0 is left for the unit-quad corner)
+    pub fn layout() -> wgpu::VertexBufferLayout<'static> {
+        wgpu::VertexBufferLayout {
+            array_stride: std::mem::size_of::<Self>() as wgpu::BufferAddress,
+            step_mode: wgpu::VertexStepMode::Instance,
+            attributes: &Self::ATTRIBUTES,
+        }
+    }
+}
+
+/// CPU-side scene description for instanced drawing: every rectangle becomes
+/// one `QuadInstance`, so a frame is a single draw of `UNIT_QUAD` with
+/// `len()` instances instead of a rebuilt triangle buffer.
+#[derive(Debug, Clone, Default)]
+pub struct InstanceBatch {
+    instances: Vec<QuadInstance>,
+}
+
+impl InstanceBatch {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Queue an axis-aligned rectangle
+    pub fn draw_rectangle(&mut self, x: f32, y: f32, width: f32, height: f32, color: [f32; 4]) {
+        self.push(QuadInstance::rect(x, y, width, height, color));
+    }
+
+    pub fn push(&mut self, instance: QuadInstance) {
+        self.instances.push(instance);
+    }
+
+    /// Drop queued instances, keeping the allocation for the next frame
+    pub fn clear(&mut self) {
+        self.instances.clear();
+    }
+
+    pub fn instances(&self) -> &[QuadInstance] {
+        &self.instances
+    }
+
+    pub fn len(&self) -> usize {
+        self.instances.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.instances.is_empty()
+    }
+
+    /// Raw bytes for uploading to an instance buffer
+    pub fn as_bytes(&self) -> &[u8] {
+        bytemuck::cast_slice(&self.instances)
+    }
+}
+
+/// One textured quad of laid-out text, in pixels with normalized atlas UVs
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct GlyphQuad {
+    pub c: char,
+    /// Top-left corner on screen
+    pub position: [f32; 2],
+    pub size: [f32; 2],
+    pub uv_min: [f32; 2],
+    pub uv_max: [f32; 2],
+}
+
+/// Glyphs rasterized once by `FontSystem` into a `TextureAtlas`, for drawing
+/// text as textured quads. Glyphs are rendered white so the draw can tint them.
+///
+/// Each glyph occupies a cell of its advance width by the line height, so
+/// laying out a string only needs the pen position.
+#[derive(Debug, Clone)]
+pub struct GlyphAtlas {
+    atlas: TextureAtlas,
+    glyphs: HashMap<char, renderer_2d::Rect>,
+}
+
+impl GlyphAtlas {
+    /// Rasterize `chars` from `font_name` (or the default font) at `font_size`
+    /// into a `width` x `height` atlas. Whitespace gets a cell but no pixels.
+    pub fn build(
+        fonts: &mut FontSystem,
+        font_name: Option<&str>,
+        font_size: f32,
+        chars: impl IntoIterator<Item = char>,
+        width: usize,
+        height: usize,
+    ) -> Result<Self, Box<dyn std::error::Error>> {
+        let mut atlas = TextureAtlas::new(width, height);
+        let mut glyphs = HashMap::new();
+
+        for c in chars {
+            if glyphs.contains_key(&c) {
+                continue;
+            }
+            let bitmap = fonts.render_text(
+                &c.to_string(),
+                font_name,
+                font_size,
+                renderer_2d::Color::WHITE,
+            )?;
+            if bitmap.width == 0 || bitmap.height == 0 {
+                continue;
+            }
+            let pixels = bitmap
+                .data
+                .chunks_exact(4)
+                .map(|p| renderer_2d::Color::rgba(p[0], p[1], p[2], p[3]).0)
+                .collect();
+            let image = renderer_2d::Sprite::new(bitmap.width, bitmap.height, pixels);
+            let region = atlas
+                .pack(&image)
+                .ok_or_else(|| format!("glyph atlas is full at {:?}", c))?;
+            glyphs.insert(c, region);
+        }
+
+        Ok(Self { atlas, glyphs })
+    }
+
+    /// Printable ASCII, enough for scores and menus
+    pub fn ascii(
+        fonts: &mut FontSystem,
+        font_name: Option<&str>,
+        font_size: f32,
+    ) -> Result<Self, Box<dyn std::error::Error>> {
+        Self::build(fonts, font_name, font_size, ' '..='~', 512, 512)
+    }
+
+    /// Atlas region of `c`, if it was rasterized
+    pub fn region(&self, c: char) -> Option<renderer_2d::Rect> {
+        self.glyphs.get(&c).copied()
+    }
+
+    /// The backing image to upload as the atlas texture
+    pub fn texture(&self) -> &renderer_2d::Sprite {
+        self.atlas.sprite()
+    }
+
+    /// Quads for `text` with its top-left corner at `(x, y)`, scaled by `scale`.
+    /// Whitespace only advances the pen; characters not in the atlas are skipped.
+    pub fn layout(&self, text: &str, x: f32, y: f32, scale: f32) -> Vec<GlyphQuad> {
+        let texture = self.atlas.sprite();
+        let (tex_w, tex_h) = (texture.width as f32, texture.height as f32);
+        let mut pen = x;
+        let mut quads = Vec::new();
+
+        for c in text.chars() {
+            let Some(region) = self.region(c) else {
+                continue;
+            };
+            let size = [region.width as f32 * scale, region.height as f32 * scale];
+            if !c.is_whitespace() {
+                quads.push(GlyphQuad {
+                    c,
+                    position: [pen, y],
+                    size,
+                    uv_min: [region.x as f32 / tex_w, region.y as f32 / tex_h],
+                    uv_max: [
+                        (region.x + region.width) as f32 / tex_w,
+                        (region.y + region.height) as f32 / tex_h,
+                    ],
+                });
+            }
+            pen += size[0];
+        }
+        quads
+    }
+}
+
 /// Renderer placeholder
 pub struct Renderer {
     pub clear_color: [f32; 4],
@@ -46,3 +432,158 @@ impl Renderer {
         // Rendering logic
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::renderer_2d::{Color, Rect, Renderer2D, Sprite};
+    use specs::Builder;
+
+    #[test]
+    fn test_atlas_packs_without_overlap() {
+        let mut atlas = TextureAtlas::new(40, 40);
+        let colors = [Color::RED, Color::GREEN, Color::BLUE];
+        let regions: Vec<Rect> = colors
+            .iter()
+            .map(|&c| atlas.pack(&Sprite::filled(16, 16, c)).unwrap())
+            .collect();
+
+        for (i, a) in regions.iter().enumerate() {
+            assert!(a.x >= 0 && a.y >= 0 && a.x + a.width <= 40 && a.y + a.height <= 40);
+            for b in &regions[i + 1..] {
+                assert!(a.intersect(b).is_none(), "{:?} overlaps {:?}", a, b);
+            }
+        }
+        // the third image wraps onto a second shelf
+        assert_eq!(regions[2], Rect::new(0, 16, 16, 16));
+        assert_eq!(atlas.regions(), regions.as_slice());
+
+        // atlas is full for another row of 16px images once 2 shelves are used
+        assert!(atlas.pack(&Sprite::filled(16, 16, Color::WHITE)).is_some());
+        assert!(atlas.pack(&Sprite::filled(16, 16, Color::WHITE)).is_none());
+
+        // drawing a region only copies that sub-image
+        let mut renderer = Renderer2D::new(20, 20);
+        renderer.clear(Color::BLACK);
+        atlas.draw(&mut renderer, regions[1], 2, 2);
+        assert_eq!(renderer.get_pixel(2, 2), Some(Color::GREEN));
+        assert_eq!(renderer.get_pixel(17, 17), Some(Color::GREEN));
+        assert_eq!(renderer.get_pixel(18, 18), Some(Color::BLACK));
+    }
+
+    #[test]
+    fn test_instance_batch_holds_one_transform_per_object() {
+        let red = [1.0, 0.0, 0.0, 1.0];
+        let mut batch = InstanceBatch::new();
+        batch.draw_rectangle(10.0, 20.0, 30.0, 5.0, red);
+        batch.draw_rectangle(0.0, 0.0, 1.0, 1.0, [0.0, 1.0, 0.0, 1.0]);
+        batch.push(QuadInstance::rotated(
+            Vec2::new(50.0, 50.0),
+            Vec2::new(4.0, 2.0),
+            std::f32::consts::FRAC_PI_2,
+            red,
+        ));
+
+        assert_eq!(batch.len(), 3);
+        assert_eq!(
+            batch.as_bytes().len(),
+            3 * std::mem::size_of::<QuadInstance>()
+        );
+
+        let brick = batch.instances()[0];
+        assert_eq!(brick.transform, [[30.0, 0.0], [0.0, 5.0], [10.0, 20.0]]);
+        assert_eq!(brick.apply(UNIT_QUAD[2]), [40.0, 25.0]);
+        assert_eq!(batch.instances()[1].color, [0.0, 1.0, 0.0, 1.0]);
+
+        // a quarter turn swaps the extents around the center
+        let spun = batch.instances()[2];
+        let far = spun.apply(UNIT_QUAD[2]);
+        let near = spun.apply(UNIT_QUAD[0]);
+        assert!((far[0] - 49.0).abs() < 1e-4 && (far[1] - 52.0).abs() < 1e-4);
+        assert!((near[0] - 51.0).abs() < 1e-4 && (near[1] - 48.0).abs() < 1e-4);
+
+        batch.clear();
+        assert!(batch.is_empty());
+    }
+
+    #[test]
+    fn test_glyph_quads_index_distinct_atlas_regions() {
+        let mut fonts = FontSystem::new();
+        let path = concat!(env!("CARGO_MANIFEST_DIR"), "/assets/fonts/DejaVuSans.ttf");
+        fonts.load_font("dejavu", path).unwrap();
+        let glyphs = GlyphAtlas::ascii(&mut fonts, None, 24.0).unwrap();
+
+        let quads = glyphs.layout("PONG", 10.0, 5.0, 1.0);
+        assert_eq!(quads.len(), 4);
+        assert_eq!(quads[0].position, [10.0, 5.0]);
+        for (i, a) in quads.iter().enumerate() {
+            assert!(a.uv_min[0] < a.uv_max[0] && a.uv_min[1] < a.uv_max[1]);
+            assert!(a.uv_max[0] <= 1.0 && a.uv_max[1] <= 1.0);
+            for b in &quads[i + 1..] {
+                assert!(a.position[0] < b.position[0]);
+                let overlap = a.uv_min[0] < b.uv_max[0]
+                    && b.uv_min[0] < a.uv_max[0]
+                    && a.uv_min[1] < b.uv_max[1]
+                    && b.uv_min[1] < a.uv_max[1];
+                assert!(!overlap, "{} and {} share atlas texels", a.c, b.c);
+            }
+        }
+
+        // spaces advance without emitting a quad
+        let spaced = glyphs.layout("P G", 0.0, 0.0, 2.0);
+        assert_eq!(spaced.len(), 2);
+        let advance = |c| glyphs.region(c).unwrap().width as f32 * 2.0;
+        assert_eq!(spaced[1].position[0], advance('P') + advance(' '));
+        assert_eq!(spaced[0].size[1], quads[0].size[1] * 2.0);
+
+        // the atlas holds real coverage for glyphs
+        let region = glyphs.region('P').unwrap();
+        let texture = glyphs.texture();
+        let inked = (region.y..region.y + region.height).any(|y| {
+            (region.x..region.x + region.width)
+                .any(|x| texture.pixels[y as usize * texture.width + x as usize] >> 24 > 0)
+        });
+        assert!(inked);
+    }
+
+    #[test]
+    fn test_visible_entities_skips_off_screen() {
+        let mut world = specs::World::new();
+        world.register::<Position>();
+        world.register::<Collider>();
+        world.register::<super::Sprite>();
+        world.register::<Inactive>();
+        let mut spawn = |x: f32, y: f32, collider: Option<Collider>| {
+            let builder = world.create_entity().with(Position::new(x, y));
+            match collider {
+                Some(collider) => builder.with(collider).build(),
+                None => builder.build(),
+            }
+        };
+        let inside = spawn(400.0, 300.0, None);
+        let outside = spawn(900.0, 300.0, None);
+        // center is off screen but the box reaches 20px in
+        let straddling = spawn(820.0, 300.0, Some(Collider::new_rectangle(80.0, 10.0)));
+        let far_circle = spawn(-100.0, 300.0, Some(Collider::new_circle(50.0)));
+
+        let camera = Camera2D {
+            position: Vec2::new(400.0, 300.0),
+            zoom: 1.0,
+            rotation: 0.0,
+            viewport_size: Vec2::new(800.0, 600.0),
+        };
+        let visible = visible_entities(&world, &camera, Vec2::new(800.0, 600.0));
+        assert_eq!(visible, vec![inside, straddling]);
+        assert!(!visible.contains(&outside) && !visible.contains(&far_circle));
+
+        // zooming out brings everything into view
+        let zoomed = Camera2D {
+            zoom: 0.5,
+            ..camera
+        };
+        assert_eq!(
+            visible_entities(&world, &zoomed, Vec2::new(800.0, 600.0)).len(),
+            4
+        );
+    }
+}
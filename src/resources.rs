@@ -2,7 +2,18 @@
 //!
 //! Asset loading and caching system.
 
+use crate::difficulty::DifficultyLevel;
+use crate::menu::MenuAction;
+use crate::renderer_2d::Sprite;
+use crate::window::{PresentMode, WindowConfig};
+use crate::Vec2;
+use std::any::Any;
 use std::collections::HashMap;
+use std::marker::PhantomData;
+use std::path::{Path, PathBuf};
+use std::panic::{self, AssertUnwindSafe};
+use std::sync::{mpsc, Arc, Mutex};
+use std::thread;
 
 /// Resource manager placeholder
 pub struct ResourceManager {
@@ -29,6 +40,673 @@ impl ResourceManager {
     }
 }
 
+/// Soft cap on live entities, guarding against runaway spawning (e.g. a
+/// multiball loop). Spawns queued with `CommandBuffer::try_spawn` past the
+/// cap log a warning and, with `refuse_over_cap`, are rejected.
+/// `GameWorldExt::apply_commands` refreshes the live count.
+#[derive(Debug, Clone)]
+pub struct EntityBudget {
+    pub soft_cap: usize,
+    /// Reject spawns over the cap instead of only warning
+    pub refuse_over_cap: bool,
+    /// Live entities as of the last `apply_commands`
+    live: usize,
+    /// Spawns reserved since then
+    queued: usize,
+    warned: bool,
+}
+
+impl Default for EntityBudget {
+    fn default() -> Self {
+        Self::new(10_000)
+    }
+}
+
+impl EntityBudget {
+    /// Warn-only budget of `soft_cap` entities
+    pub fn new(soft_cap: usize) -> Self {
+        Self {
+            soft_cap,
+            refuse_over_cap: false,
+            live: 0,
+            queued: 0,
+            warned: false,
+        }
+    }
+
+    /// Reject spawns over the cap
+    pub fn refusing(mut self) -> Self {
+        self.refuse_over_cap = true;
+        self
+    }
+
+    /// Live entities plus spawns still queued
+    pub fn used(&self) -> usize {
+        self.live + self.queued
+    }
+
+    /// Claim room for one more entity. Over the cap this warns once (until
+    /// the count drops back under it) and fails if spawns are refused.
+    pub fn reserve(&mut self) -> Result<(), String> {
+        if self.used() >= self.soft_cap {
+            if !self.warned {
+                eprintln!(
+                    "Warning: entity budget of {} exceeded ({} live, {} queued)",
+                    self.soft_cap, self.live, self.queued
+                );
+                self.warned = true;
+            }
+            if self.refuse_over_cap {
+                return Err(format!("entity budget of {} exceeded", self.soft_cap));
+            }
+        }
+        self.queued += 1;
+        Ok(())
+    }
+
+    /// Record the live entity count once queued commands have been applied
+    pub fn set_live(&mut self, live: usize) {
+        self.live = live;
+        self.queued = 0;
+        if live < self.soft_cap {
+            self.warned = false;
+        }
+    }
+}
+
+/// Player-facing settings persisted between runs as JSON in the platform
+/// config directory. Missing or unreadable files give the defaults.
+///
+/// `init` inserts the defaults so worlds never depend on the host's config
+/// files; games insert `Settings::load()` themselves.
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+#[serde(default)]
+pub struct Settings {
+    pub master_volume: f32,
+    pub music_volume: f32,
+    pub difficulty: DifficultyLevel,
+    /// Also accepts the `vsync: bool` older settings files stored
+    #[serde(alias = "vsync", deserialize_with = "deserialize_present_mode")]
+    pub present_mode: PresentMode,
+    pub fullscreen: bool,
+}
+
+fn deserialize_present_mode<'de, D>(deserializer: D) -> Result<PresentMode, D::Error>
+where
+    D: serde::Deserializer<'de>,
+{
+    #[derive(serde::Deserialize)]
+    #[serde(untagged)]
+    enum Stored {
+        Mode(PresentMode),
+        Vsync(bool),
+    }
+    Ok(match serde::Deserialize::deserialize(deserializer)? {
+        Stored::Mode(mode) => mode,
+        Stored::Vsync(vsync) => PresentMode::from_vsync(vsync),
+    })
+}
+
+impl Default for Settings {
+    fn default() -> Self {
+        Self {
+            master_volume: 1.0,
+            music_volume: 0.7,
+            difficulty: DifficultyLevel::Normal,
+            present_mode: PresentMode::Fifo,
+            fullscreen: false,
+        }
+    }
+}
+
+impl Settings {
+    /// Difficulty levels a settings menu offers, in order
+    pub const DIFFICULTIES: [DifficultyLevel; 3] = [
+        DifficultyLevel::Easy,
+        DifficultyLevel::Normal,
+        DifficultyLevel::Hard,
+    ];
+
+    /// `<config dir>/modular_game_engine/settings.json`; see `config_path`
+    pub fn default_path() -> Option<PathBuf> {
+        Self::config_path("settings.json")
+    }
+
+    /// `<config dir>/modular_game_engine/<file_name>`, where the config dir is
+    /// `%APPDATA%` on Windows, `~/Library/Application Support` on macOS and
+    /// `$XDG_CONFIG_HOME` (or `~/.config`) elsewhere. Key bindings and other
+    /// per-player files live next to the settings.
+    pub fn config_path(file_name: &str) -> Option<PathBuf> {
+        let var = |name| std::env::var_os(name).filter(|v| !v.is_empty());
+        let config_dir = if cfg!(windows) {
+            var("APPDATA").map(PathBuf::from)
+        } else if cfg!(target_os = "macos") {
+            var("HOME").map(|home| PathBuf::from(home).join("Library/Application Support"))
+        } else {
+            var("XDG_CONFIG_HOME")
+                .map(PathBuf::from)
+                .or_else(|| var("HOME").map(|home| PathBuf::from(home).join(".config")))
+        }?;
+        Some(config_dir.join("modular_game_engine").join(file_name))
+    }
+
+    /// Load from `default_path`, falling back to the defaults
+    pub fn load() -> Self {
+        Self::default_path()
+            .map(|path| Self::load_from(&path))
+            .unwrap_or_default()
+    }
+
+    /// Save to `default_path`
+    pub fn save(&self) -> Result<(), String> {
+        let path = Self::default_path().ok_or("No config directory for settings")?;
+        self.save_to(&path)
+    }
+
+    /// Load from `path`; a missing file gives the defaults, a corrupt one also warns
+    pub fn load_from(path: impl AsRef<Path>) -> Self {
+        let Ok(text) = std::fs::read_to_string(path.as_ref()) else {
+            return Self::default();
+        };
+        serde_json::from_str(&text).unwrap_or_else(|e| {
+            eprintln!(
+                "Warning: ignoring corrupt settings file {}: {}",
+                path.as_ref().display(),
+                e
+            );
+            Self::default()
+        })
+    }
+
+    /// Write to `path`, creating its directory if needed
+    pub fn save_to(&self, path: impl AsRef<Path>) -> Result<(), String> {
+        let path = path.as_ref();
+        if let Some(dir) = path.parent() {
+            std::fs::create_dir_all(dir).map_err(|e| e.to_string())?;
+        }
+        let json = serde_json::to_string_pretty(self).map_err(|e| e.to_string())?;
+        std::fs::write(path, json).map_err(|e| e.to_string())
+    }
+
+    /// Copy the display settings into `config`
+    pub fn apply_to_window(&self, config: &mut WindowConfig) {
+        config.present_mode = self.present_mode;
+        config.fullscreen = self.fullscreen;
+    }
+
+    /// Apply a settings menu action (`SetSetting` / `ToggleSetting` /
+    /// `SelectOption` named after a field; `vsync` toggles between `Fifo` and
+    /// `Immediate`). Returns whether anything changed, i.e. whether to `save`.
+    pub fn apply_menu_action(&mut self, action: &MenuAction) -> bool {
+        let before = self.clone();
+        match action {
+            MenuAction::SetSetting(name, value) => match name.as_str() {
+                "master_volume" => self.master_volume = value.clamp(0.0, 1.0),
+                "music_volume" => self.music_volume = value.clamp(0.0, 1.0),
+                _ => {}
+            },
+            MenuAction::ToggleSetting(name) => match name.as_str() {
+                "vsync" => {
+                    self.present_mode = PresentMode::from_vsync(!self.present_mode.is_vsync())
+                }
+                "fullscreen" => self.fullscreen = !self.fullscreen,
+                _ => {}
+            },
+            MenuAction::SelectOption(name, index) => match name.as_str() {
+                "difficulty" => {
+                    if let Some(level) = Self::DIFFICULTIES.get(*index) {
+                        self.difficulty = *level;
+                    }
+                }
+                "present_mode" => {
+                    if let Some(mode) = PresentMode::ALL.get(*index) {
+                        self.present_mode = *mode;
+                    }
+                }
+                _ => {}
+            },
+            _ => {}
+        }
+        *self != before
+    }
+}
+
+/// One brick parsed from an ASCII level
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct BrickSpec {
+    /// Top-left corner of the brick's tile
+    pub position: Vec2,
+    pub hits: u32,
+    /// The level character it came from
+    pub symbol: char,
+    pub row: usize,
+    pub col: usize,
+}
+
+/// Builds brick layouts from ASCII grids, one text line per row.
+///
+/// By default `#` is a 3-hit brick, `=` a 1-hit brick and `.` or a space is
+/// empty. Rows may differ in length; unknown characters are skipped with a warning.
+#[derive(Debug, Clone)]
+pub struct LevelLoader {
+    tiles: HashMap<char, u32>,
+}
+
+impl Default for LevelLoader {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl LevelLoader {
+    pub fn new() -> Self {
+        Self {
+            tiles: HashMap::from([('#', 3), ('=', 1)]),
+        }
+    }
+
+    /// Map `symbol` to a brick needing `hits` hits
+    pub fn with_tile(mut self, symbol: char, hits: u32) -> Self {
+        self.tiles.insert(symbol, hits);
+        self
+    }
+
+    /// Parse `text` with the default tiles
+    pub fn from_ascii(text: &str, tile_size: Vec2, origin: Vec2) -> Vec<BrickSpec> {
+        Self::new().parse(text, tile_size, origin)
+    }
+
+    /// Bricks for every known character in `text`, placed `tile_size` apart from `origin`
+    pub fn parse(&self, text: &str, tile_size: Vec2, origin: Vec2) -> Vec<BrickSpec> {
+        let mut bricks = Vec::new();
+        for (row, line) in text.lines().enumerate() {
+            for (col, symbol) in line.chars().enumerate() {
+                if symbol == '.' || symbol == ' ' {
+                    continue;
+                }
+                let Some(&hits) = self.tiles.get(&symbol) else {
+                    eprintln!(
+                        "Warning: unknown level tile {:?} at row {}, column {}",
+                        symbol, row, col
+                    );
+                    continue;
+                };
+                bricks.push(BrickSpec {
+                    position: origin
+                        + Vec2::new(col as f32 * tile_size.x, row as f32 * tile_size.y),
+                    hits,
+                    symbol,
+                    row,
+                    col,
+                });
+            }
+        }
+        bricks
+    }
+}
+
 /// Placeholder types
 pub struct Texture;
 pub struct Sound;
+
+/// Font asset type loaded by `AssetServer`
+pub type Font = rusttype::Font<'static>;
+
+/// Types the `AssetServer` can hand out
+pub trait Asset: Send + Sync + 'static {}
+
+impl Asset for Sprite {}
+impl Asset for Font {}
+
+/// Typed reference to an asset owned by an `AssetServer`
+pub struct Handle<T> {
+    id: u64,
+    _marker: PhantomData<fn() -> T>,
+}
+
+impl<T> Handle<T> {
+    fn new(id: u64) -> Self {
+        Self {
+            id,
+            _marker: PhantomData,
+        }
+    }
+
+    /// Raw id, unique within the server that created it
+    pub fn id(&self) -> u64 {
+        self.id
+    }
+}
+
+// Manual impls so handles are Copy/Eq regardless of `T`
+impl<T> Clone for Handle<T> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<T> Copy for Handle<T> {}
+
+impl<T> PartialEq for Handle<T> {
+    fn eq(&self, other: &Self) -> bool {
+        self.id == other.id
+    }
+}
+
+impl<T> Eq for Handle<T> {}
+
+impl<T> std::hash::Hash for Handle<T> {
+    fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
+        self.id.hash(state);
+    }
+}
+
+impl<T> std::fmt::Debug for Handle<T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_tuple("Handle").field(&self.id).finish()
+    }
+}
+
+/// Loading state of a single asset
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum LoadState {
+    Loading,
+    Loaded,
+    Failed(String),
+}
+
+type LoadedAsset = Arc<dyn Any + Send + Sync>;
+type LoadJob = Box<dyn FnOnce() -> Result<LoadedAsset, String> + Send>;
+
+enum Slot {
+    Loading,
+    Loaded(LoadedAsset),
+    Failed(String),
+}
+
+/// Loads sprites and fonts on a pool of background threads.
+///
+/// `load_*` returns a handle immediately; poll `is_loaded`/`load_state` (e.g.
+/// from a loading screen using `progress`) and fetch the asset with `get`.
+pub struct AssetServer {
+    sender: Option<mpsc::Sender<(u64, LoadJob)>>,
+    workers: Vec<thread::JoinHandle<()>>,
+    slots: Arc<Mutex<HashMap<u64, Slot>>>,
+    next_id: u64,
+}
+
+impl Default for AssetServer {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl AssetServer {
+    /// Create a server with one worker per available CPU (at most 4)
+    pub fn new() -> Self {
+        let threads = thread::available_parallelism()
+            .map(|n| n.get())
+            .unwrap_or(1)
+            .min(4);
+        Self::with_threads(threads)
+    }
+
+    /// Create a server with a fixed number of worker threads
+    pub fn with_threads(threads: usize) -> Self {
+        let (sender, receiver) = mpsc::channel::<(u64, LoadJob)>();
+        let receiver = Arc::new(Mutex::new(receiver));
+        let slots = Arc::new(Mutex::new(HashMap::new()));
+
+        let workers = (0..threads.max(1))
+            .map(|_| {
+                let receiver = Arc::clone(&receiver);
+                let slots = Arc::clone(&slots);
+                thread::spawn(move || loop {
+                    // the lock is released before the job runs
+                    let next = receiver.lock().unwrap().recv();
+                    let Ok((id, job)) = next else {
+                        break; // server dropped
+                    };
+                    // a panicking decoder fails its own asset, not the worker
+                    let slot = match panic::catch_unwind(AssertUnwindSafe(job)) {
+                        Ok(Ok(asset)) => Slot::Loaded(asset),
+                        Ok(Err(error)) => Slot::Failed(error),
+                        Err(payload) => Slot::Failed(panic_message(payload.as_ref())),
+                    };
+                    slots.lock().unwrap().insert(id, slot);
+                })
+            })
+            .collect();
+
+        Self {
+            sender: Some(sender),
+            workers,
+            slots,
+            next_id: 0,
+        }
+    }
+
+    /// Start decoding an image file (PNG, JPEG, ...) into a `Sprite`
+    pub fn load_sprite<P: AsRef<Path>>(&mut self, path: P) -> Handle<Sprite> {
+        let path = path.as_ref().to_path_buf();
+        self.submit(Box::new(move || {
+            let image = image::open(&path)
+                .map_err(|e| format!("failed to load sprite '{}': {}", path.display(), e))?
+                .to_rgba8();
+            let sprite = Sprite::from_rgba(
+                image.width() as usize,
+                image.height() as usize,
+                image.as_raw(),
+            );
+            Ok(Arc::new(sprite) as LoadedAsset)
+        }))
+    }
+
+    /// Start loading a TTF font file
+    pub fn load_font<P: AsRef<Path>>(&mut self, path: P) -> Handle<Font> {
+        let path = path.as_ref().to_path_buf();
+        self.submit(Box::new(move || {
+            let data = std::fs::read(&path)
+                .map_err(|e| format!("failed to load font '{}': {}", path.display(), e))?;
+            let font = Font::try_from_vec(data)
+                .ok_or_else(|| format!("failed to parse font '{}'", path.display()))?;
+            Ok(Arc::new(font) as LoadedAsset)
+        }))
+    }
+
+    fn submit<T: Asset>(&mut self, job: LoadJob) -> Handle<T> {
+        let id = self.next_id;
+        self.next_id += 1;
+        self.slots.lock().unwrap().insert(id, Slot::Loading);
+        if let Some(sender) = &self.sender {
+            if sender.send((id, job)).is_err() {
+                self.slots.lock().unwrap().insert(
+                    id,
+                    Slot::Failed("asset loader threads are not running".to_string()),
+                );
+            }
+        }
+        Handle::new(id)
+    }
+
+    /// Current state of an asset
+    pub fn load_state<T: Asset>(&self, handle: Handle<T>) -> LoadState {
+        match self.slots.lock().unwrap().get(&handle.id) {
+            Some(Slot::Loading) => LoadState::Loading,
+            Some(Slot::Loaded(_)) => LoadState::Loaded,
+            Some(Slot::Failed(error)) => LoadState::Failed(error.clone()),
+            None => LoadState::Failed("unknown asset handle".to_string()),
+        }
+    }
+
+    /// Whether the asset finished loading successfully
+    pub fn is_loaded<T: Asset>(&self, handle: Handle<T>) -> bool {
+        self.load_state(handle) == LoadState::Loaded
+    }
+
+    /// The loaded asset, or `None` while loading or after a failure
+    pub fn get<T: Asset>(&self, handle: Handle<T>) -> Option<Arc<T>> {
+        match self.slots.lock().unwrap().get(&handle.id) {
+            Some(Slot::Loaded(asset)) => Arc::clone(asset).downcast::<T>().ok(),
+            _ => None,
+        }
+    }
+
+    /// `(finished, total)` asset counts; failed loads count as finished
+    pub fn progress(&self) -> (usize, usize) {
+        let slots = self.slots.lock().unwrap();
+        let finished = slots
+            .values()
+            .filter(|slot| !matches!(slot, Slot::Loading))
+            .count();
+        (finished, slots.len())
+    }
+}
+
+fn panic_message(payload: &(dyn Any + Send)) -> String {
+    let reason = payload
+        .downcast_ref::<&str>()
+        .copied()
+        .or_else(|| payload.downcast_ref::<String>().map(String::as_str))
+        .unwrap_or("unknown panic");
+    format!("asset loader panicked: {}", reason)
+}
+
+impl Drop for AssetServer {
+    fn drop(&mut self) {
+        // closing the channel stops the workers once queued jobs are done
+        self.sender.take();
+        for worker in self.workers.drain(..) {
+            let _ = worker.join();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::{Duration, Instant};
+
+    #[test]
+    fn test_level_loader_parses_grid() {
+        let level = "#.=\n=?\n..#";
+        let bricks = LevelLoader::from_ascii(level, Vec2::new(10.0, 5.0), Vec2::new(100.0, 50.0));
+
+        let summary: Vec<_> = bricks
+            .iter()
+            .map(|b| (b.row, b.col, b.hits, b.position))
+            .collect();
+        assert_eq!(
+            summary,
+            vec![
+                (0, 0, 3, Vec2::new(100.0, 50.0)),
+                (0, 2, 1, Vec2::new(120.0, 50.0)),
+                (1, 0, 1, Vec2::new(100.0, 55.0)),
+                (2, 2, 3, Vec2::new(120.0, 60.0)),
+            ]
+        );
+
+        let custom = LevelLoader::new().with_tile('?', 2);
+        let bricks = custom.parse(level, Vec2::new(1.0, 1.0), Vec2::zeros());
+        assert_eq!(bricks.len(), 5);
+        assert_eq!((bricks[3].symbol, bricks[3].hits), ('?', 2));
+    }
+
+    #[test]
+    fn test_settings_round_trip() {
+        let path = std::env::temp_dir()
+            .join(format!("modular_settings_{}", std::process::id()))
+            .join("settings.json");
+        assert_eq!(Settings::load_from(&path), Settings::default());
+
+        let mut settings = Settings {
+            master_volume: 0.4,
+            difficulty: DifficultyLevel::Hard,
+            fullscreen: true,
+            ..Settings::default()
+        };
+        assert!(
+            settings.apply_menu_action(&MenuAction::SetSetting("music_volume".to_string(), 0.25))
+        );
+        assert!(settings.apply_menu_action(&MenuAction::ToggleSetting("vsync".to_string())));
+        assert!(!settings.apply_menu_action(&MenuAction::Back));
+        settings.save_to(&path).unwrap();
+
+        let loaded = Settings::load_from(&path);
+        assert_eq!(loaded, settings);
+        assert_eq!(loaded.music_volume, 0.25);
+        assert_eq!(loaded.present_mode, PresentMode::Immediate);
+
+        // files from before present_mode stored a vsync flag
+        std::fs::write(&path, r#"{ "vsync": false, "fullscreen": true }"#).unwrap();
+        let legacy = Settings::load_from(&path);
+        assert_eq!(legacy.present_mode, PresentMode::Immediate);
+        assert!(legacy.fullscreen);
+        let mut config = WindowConfig::default();
+        legacy.apply_to_window(&mut config);
+        assert_eq!(
+            (config.present_mode, config.fullscreen),
+            (PresentMode::Immediate, true)
+        );
+
+        std::fs::write(&path, "{ not json").unwrap();
+        assert_eq!(Settings::load_from(&path), Settings::default());
+        std::fs::remove_dir_all(path.parent().unwrap()).unwrap();
+    }
+
+    fn wait_until_finished(server: &AssetServer) {
+        let start = Instant::now();
+        while server.progress().0 < server.progress().1 {
+            assert!(
+                start.elapsed() < Duration::from_secs(5),
+                "asset load timed out"
+            );
+            thread::sleep(Duration::from_millis(1));
+        }
+    }
+
+    #[test]
+    fn test_load_sprite_in_background() {
+        let path = std::env::temp_dir().join(format!("asset_server_{}.png", std::process::id()));
+        let mut image = image::RgbaImage::new(3, 2);
+        image.put_pixel(2, 1, image::Rgba([255, 0, 0, 255]));
+        image.save(&path).unwrap();
+
+        let mut server = AssetServer::with_threads(2);
+        let handle = server.load_sprite(&path);
+        wait_until_finished(&server);
+        std::fs::remove_file(&path).ok();
+
+        assert!(server.is_loaded(handle));
+        let sprite = server.get(handle).unwrap();
+        assert_eq!((sprite.width, sprite.height), (3, 2));
+        assert_eq!(sprite.get_pixel(2, 1), crate::renderer_2d::Color::RED);
+    }
+
+    #[test]
+    fn test_failed_load_reports_error() {
+        let mut server = AssetServer::with_threads(1);
+        let handle = server.load_sprite("does/not/exist.png");
+        wait_until_finished(&server);
+
+        assert!(!server.is_loaded(handle));
+        assert!(matches!(server.load_state(handle), LoadState::Failed(_)));
+        assert!(server.get(handle).is_none());
+        assert_eq!(server.progress(), (1, 1));
+    }
+
+    #[test]
+    fn test_panicking_load_fails_and_worker_survives() {
+        let mut server = AssetServer::with_threads(1);
+        let panicked: Handle<Sprite> = server.submit(Box::new(|| panic!("bad header")));
+        let font = server.load_font("assets/fonts/DejaVuSans.ttf");
+        wait_until_finished(&server);
+
+        assert_eq!(
+            server.load_state(panicked),
+            LoadState::Failed("asset loader panicked: bad header".to_string())
+        );
+        // the single worker is still alive to load the next asset
+        assert!(server.is_loaded(font));
+    }
+}
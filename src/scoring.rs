@@ -3,7 +3,11 @@
 //! A flexible scoring system that supports multiple score types, win conditions,
 //! achievements, and scoring mechanics. Extracted and enhanced from the Pong game.
 
+use crate::events::EventBus;
+use std::cell::RefCell;
 use std::collections::HashMap;
+use std::fmt;
+use std::rc::Rc;
 use std::time::{Duration, Instant};
 
 /// Score types for different game mechanics
@@ -195,7 +199,7 @@ impl ScoringSystem {
             })
             .collect();
 
-        leaderboard.sort_by(|a, b| b.1.cmp(&a.1)); // Sort by score descending
+        leaderboard.sort_by_key(|entry| std::cmp::Reverse(entry.1)); // Sort by score descending
         leaderboard
     }
 
@@ -404,6 +408,80 @@ impl Default for ScoringSystem {
     }
 }
 
+/// A player was awarded points; publish it on the `EventBus` for listeners such as `ScoreLog`
+#[derive(Debug, Clone, PartialEq)]
+pub struct ScoreEvent {
+    /// Game time of the award in seconds
+    pub elapsed: f32,
+    pub player: String,
+    pub points: i64,
+}
+
+impl ScoreEvent {
+    pub fn new(elapsed: f32, player: &str, points: i64) -> Self {
+        Self {
+            elapsed,
+            player: player.to_string(),
+            points,
+        }
+    }
+}
+
+impl fmt::Display for ScoreEvent {
+    /// e.g. `Player scored 1 at 00:12`
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let seconds = self.elapsed.max(0.0) as u64;
+        write!(
+            f,
+            "{} scored {} at {:02}:{:02}",
+            self.player,
+            self.points,
+            seconds / 60,
+            seconds % 60
+        )
+    }
+}
+
+/// Timeline of score awards in the order they happened, for an end-of-game summary
+#[derive(Debug, Clone, Default)]
+pub struct ScoreLog {
+    entries: Vec<ScoreEvent>,
+}
+
+impl ScoreLog {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Create a log that records every `ScoreEvent` dispatched on `bus`
+    pub fn listen(bus: &mut EventBus) -> Rc<RefCell<ScoreLog>> {
+        let log = Rc::new(RefCell::new(Self::new()));
+        let recorder = log.clone();
+        bus.on::<ScoreEvent, _>(move |event| recorder.borrow_mut().record(event.clone()));
+        log
+    }
+
+    pub fn record(&mut self, event: ScoreEvent) {
+        self.entries.push(event);
+    }
+
+    pub fn entries(&self) -> &[ScoreEvent] {
+        &self.entries
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    pub fn clear(&mut self) {
+        self.entries.clear();
+    }
+}
+
 /// Helper functions
 fn format_score_type(score_type: &ScoreType) -> String {
     match score_type {
@@ -508,6 +586,27 @@ pub mod presets {
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_score_log_records_bus_events_in_order() {
+        let mut bus = EventBus::new();
+        let log = ScoreLog::listen(&mut bus);
+
+        bus.emit(ScoreEvent::new(12.4, "Player", 1));
+        bus.emit(ScoreEvent::new(30.0, "AI", 1));
+        bus.emit(ScoreEvent::new(75.9, "Player", 2));
+        bus.dispatch_all();
+
+        let log = log.borrow();
+        let timeline: Vec<_> = log
+            .entries()
+            .iter()
+            .map(|e| (e.elapsed, e.player.as_str()))
+            .collect();
+        assert_eq!(timeline, [(12.4, "Player"), (30.0, "AI"), (75.9, "Player")]);
+        assert_eq!(log.entries()[0].to_string(), "Player scored 1 at 00:12");
+        assert_eq!(log.entries()[2].to_string(), "Player scored 2 at 01:15");
+    }
+
     #[test]
     fn test_scoring_system() {
         let mut system = ScoringSystem::new();
@@ -2,8 +2,21 @@
 //!
 //! This module contains all the core systems that operate on components.
 
-use crate::{Acceleration, Health, MarkedForRemoval, Position, Time, Velocity};
-use specs::{Entities, Join, Read, ReadStorage, System, WriteStorage};
+use crate::physics::{half_extents, CollisionEvents};
+use crate::renderer_2d::{Color, DisplayList, RenderLayer, Renderer2D};
+use crate::rendering::Camera2D;
+use crate::util::WeightedTable;
+use crate::{
+    Acceleration, BounceOnBounds, Brick, CameraTarget, ClampToBounds, Collider, CollisionShape,
+    ContactDamage, Cooldown, Health, Inactive, Lifetime, MarkedForRemoval, Paddle, Position,
+    PowerUp, PowerUpType, RngResource, Team, Time, TimedEffect, Vec2, Velocity, ZOrder,
+};
+use specs::Entity;
+use specs::{
+    Component, Entities, Join, Read, ReadStorage, System, World, WorldExt, Write, WriteStorage,
+};
+use std::collections::HashMap;
+use std::marker::PhantomData;
 
 /// Physics system for movement and physics simulation
 pub struct PhysicsSystem;
@@ -13,18 +26,22 @@ impl<'a> System<'a> for PhysicsSystem {
         WriteStorage<'a, Position>,
         WriteStorage<'a, Velocity>,
         ReadStorage<'a, Acceleration>,
+        ReadStorage<'a, Inactive>,
         Read<'a, Time>,
     );
 
-    fn run(&mut self, (mut positions, mut velocities, accelerations, time): Self::SystemData) {
+    fn run(
+        &mut self,
+        (mut positions, mut velocities, accelerations, inactive, time): Self::SystemData,
+    ) {
         // Update velocities based on acceleration
-        for (velocity, acceleration) in (&mut velocities, &accelerations).join() {
+        for (velocity, acceleration, _) in (&mut velocities, &accelerations, !&inactive).join() {
             velocity.x += acceleration.x * time.delta;
             velocity.y += acceleration.y * time.delta;
         }
 
         // Update positions based on velocity
-        for (position, velocity) in (&mut positions, &velocities).join() {
+        for (position, velocity, _) in (&mut positions, &velocities, !&inactive).join() {
             position.x += velocity.x * time.delta;
             position.y += velocity.y * time.delta;
         }
@@ -44,6 +61,20 @@ impl<'a> System<'a> for CleanupSystem {
     }
 }
 
+/// An entity's health reached zero this frame
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct DeathEvent {
+    pub entity: Entity,
+    /// Where it died, if it had a position
+    pub position: Option<Vec2>,
+}
+
+/// Deaths reported by `HealthSystem`, cleared at the start of each run
+#[derive(Debug, Default)]
+pub struct DeathEvents {
+    pub events: Vec<DeathEvent>,
+}
+
 /// Health system for managing entity health
 pub struct HealthSystem;
 
@@ -52,17 +83,552 @@ impl<'a> System<'a> for HealthSystem {
         Entities<'a>,
         WriteStorage<'a, Health>,
         WriteStorage<'a, MarkedForRemoval>,
+        ReadStorage<'a, Position>,
+        Write<'a, DeathEvents>,
     );
 
-    fn run(&mut self, (entities, mut healths, mut marked): Self::SystemData) {
+    fn run(
+        &mut self,
+        (entities, mut healths, mut marked, positions, mut deaths): Self::SystemData,
+    ) {
+        deaths.events.clear();
         for (entity, health) in (&entities, &mut healths).join() {
             if !health.is_alive() {
+                let newly_dead = marked.insert(entity, MarkedForRemoval).unwrap().is_none();
+                if newly_dead {
+                    deaths.events.push(DeathEvent {
+                        entity,
+                        position: positions.get(entity).map(|p| Vec2::new(p.x, p.y)),
+                    });
+                }
+            }
+        }
+    }
+}
+
+/// Counts down `Lifetime` and marks expired entities for `CleanupSystem`
+pub struct LifetimeSystem;
+
+impl<'a> System<'a> for LifetimeSystem {
+    type SystemData = (
+        Entities<'a>,
+        Read<'a, Time>,
+        WriteStorage<'a, Lifetime>,
+        WriteStorage<'a, MarkedForRemoval>,
+    );
+
+    fn run(&mut self, (entities, time, mut lifetimes, mut marked): Self::SystemData) {
+        for (entity, lifetime) in (&entities, &mut lifetimes).join() {
+            lifetime.remaining -= time.delta;
+            if lifetime.remaining <= 0.0 {
                 marked.insert(entity, MarkedForRemoval).unwrap();
             }
         }
     }
 }
 
+/// Counts down every `Cooldown` by the frame's (scaled) delta
+pub struct CooldownSystem;
+
+impl<'a> System<'a> for CooldownSystem {
+    type SystemData = (
+        Read<'a, Time>,
+        WriteStorage<'a, Cooldown>,
+        ReadStorage<'a, Inactive>,
+    );
+
+    fn run(&mut self, (time, mut cooldowns, inactive): Self::SystemData) {
+        for (cooldown, _) in (&mut cooldowns, !&inactive).join() {
+            cooldown.update(time.delta);
+        }
+    }
+}
+
+/// Power-ups picked up by paddles this frame, for effects the game handles itself
+/// (extra lives, multi-ball) and for sound/score hooks
+#[derive(Debug, Default)]
+pub struct CollectedPowerUps {
+    pub collected: Vec<(Entity, PowerUpType)>,
+}
+
+/// Drops power-ups from destroyed bricks and applies them when they reach a paddle.
+///
+/// Paddle width power-ups become a `TimedEffect` on the paddle's rectangle collider
+/// and revert after `duration` seconds. Every pickup is also listed in `CollectedPowerUps`.
+#[derive(Debug, Clone)]
+pub struct PowerUpSystem {
+    /// Probability in `[0, 1]` that a dying brick drops a power-up
+    pub drop_chance: f32,
+    /// Weighted kinds picked from when a drop happens
+    pub drops: WeightedTable<PowerUpType>,
+    pub fall_speed: f32,
+    /// Side length of the spawned trigger collider
+    pub size: f32,
+    /// Seconds a paddle width effect lasts
+    pub duration: f32,
+    /// Width multiplier for `LargerPaddle`; `SmallerPaddle` divides by it
+    pub width_scale: f32,
+}
+
+impl Default for PowerUpSystem {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl PowerUpSystem {
+    pub fn new() -> Self {
+        Self {
+            drop_chance: 0.2,
+            drops: WeightedTable::new()
+                .with(PowerUpType::ExtraLife, 1.0)
+                .with(PowerUpType::MultiBall, 2.0)
+                .with(PowerUpType::SmallerPaddle, 3.0)
+                .with(PowerUpType::LargerPaddle, 4.0),
+            fall_speed: 120.0,
+            size: 12.0,
+            duration: 10.0,
+            width_scale: 1.5,
+        }
+    }
+
+    pub fn with_drop_chance(mut self, drop_chance: f32) -> Self {
+        self.drop_chance = drop_chance;
+        self
+    }
+
+    pub fn with_duration(mut self, duration: f32) -> Self {
+        self.duration = duration;
+        self
+    }
+
+    pub fn with_drops(mut self, drops: WeightedTable<PowerUpType>) -> Self {
+        self.drops = drops;
+        self
+    }
+
+    fn width_factor(&self, power_type: PowerUpType) -> Option<f32> {
+        match power_type {
+            PowerUpType::LargerPaddle => Some(self.width_scale),
+            PowerUpType::SmallerPaddle => Some(1.0 / self.width_scale),
+            _ => None,
+        }
+    }
+}
+
+/// How one team treats another
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Relation {
+    Allied,
+    Neutral,
+    Hostile,
+}
+
+/// Allied/neutral/hostile matrix between `Team`s. Unless overridden, a team is
+/// allied with itself and hostile to every other team. Relations are symmetric.
+#[derive(Debug, Clone, Default)]
+pub struct TeamRelations {
+    overrides: HashMap<(u8, u8), Relation>,
+}
+
+impl TeamRelations {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Set how `a` and `b` treat each other
+    pub fn set(&mut self, a: Team, b: Team, relation: Relation) {
+        self.overrides.insert(Self::key(a, b), relation);
+    }
+
+    pub fn with(mut self, a: Team, b: Team, relation: Relation) -> Self {
+        self.set(a, b, relation);
+        self
+    }
+
+    pub fn relation(&self, a: Team, b: Team) -> Relation {
+        match self.overrides.get(&Self::key(a, b)) {
+            Some(relation) => *relation,
+            None if a == b => Relation::Allied,
+            None => Relation::Hostile,
+        }
+    }
+
+    pub fn are_hostile(&self, a: Team, b: Team) -> bool {
+        self.relation(a, b) == Relation::Hostile
+    }
+
+    /// Like `are_hostile`, for entities that may lack a `Team`; team-less
+    /// entities are hostile to everything
+    pub fn entities_hostile(&self, a: Option<&Team>, b: Option<&Team>) -> bool {
+        match (a, b) {
+            (Some(a), Some(b)) => self.are_hostile(*a, *b),
+            _ => true,
+        }
+    }
+
+    fn key(a: Team, b: Team) -> (u8, u8) {
+        (a.0.min(b.0), a.0.max(b.0))
+    }
+}
+
+/// Applies `ContactDamage` to the other body of each solid collision unless
+/// the two are not hostile under `TeamRelations`. Run after `CollisionSystem`.
+pub struct ContactDamageSystem;
+
+impl<'a> System<'a> for ContactDamageSystem {
+    type SystemData = (
+        Read<'a, CollisionEvents>,
+        Read<'a, TeamRelations>,
+        ReadStorage<'a, Team>,
+        ReadStorage<'a, ContactDamage>,
+        WriteStorage<'a, Health>,
+    );
+
+    fn run(&mut self, (events, relations, teams, damages, mut healths): Self::SystemData) {
+        for event in &events.collisions {
+            for (dealer, victim) in [
+                (event.entity_a, event.entity_b),
+                (event.entity_b, event.entity_a),
+            ] {
+                let Some(damage) = damages.get(dealer) else {
+                    continue;
+                };
+                if !relations.entities_hostile(teams.get(dealer), teams.get(victim)) {
+                    continue;
+                }
+                if let Some(health) = healths.get_mut(victim) {
+                    health.take_damage(damage.0);
+                }
+            }
+        }
+    }
+}
+
+/// Playable area used by `BoundsSystem`; unbounded by default
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct WorldBounds {
+    pub min: Vec2,
+    pub max: Vec2,
+}
+
+impl Default for WorldBounds {
+    fn default() -> Self {
+        Self::new(
+            Vec2::new(f32::NEG_INFINITY, f32::NEG_INFINITY),
+            Vec2::new(f32::INFINITY, f32::INFINITY),
+        )
+    }
+}
+
+impl WorldBounds {
+    pub fn new(min: Vec2, max: Vec2) -> Self {
+        Self { min, max }
+    }
+
+    /// Bounds covering a `width` x `height` screen
+    pub fn from_size(width: f32, height: f32) -> Self {
+        Self::new(Vec2::zeros(), Vec2::new(width, height))
+    }
+
+    pub fn contains(&self, point: Vec2) -> bool {
+        point.x >= self.min.x
+            && point.x <= self.max.x
+            && point.y >= self.min.y
+            && point.y <= self.max.y
+    }
+}
+
+/// Keeps `ClampToBounds` and `BounceOnBounds` entities inside `WorldBounds`.
+/// An entity's collider (centered on its position) is kept fully inside.
+/// Clamped entities lose their velocity into the edge; bouncing ones have
+/// that velocity component reflected.
+pub struct BoundsSystem;
+
+impl<'a> System<'a> for BoundsSystem {
+    type SystemData = (
+        Read<'a, WorldBounds>,
+        WriteStorage<'a, Position>,
+        WriteStorage<'a, Velocity>,
+        ReadStorage<'a, Collider>,
+        ReadStorage<'a, ClampToBounds>,
+        ReadStorage<'a, BounceOnBounds>,
+        ReadStorage<'a, Inactive>,
+    );
+
+    fn run(
+        &mut self,
+        (bounds, mut positions, mut velocities, colliders, clamped, bouncing, inactive): Self::SystemData,
+    ) {
+        for (position, velocity, collider, clamp, bounce, _) in (
+            &mut positions,
+            (&mut velocities).maybe(),
+            colliders.maybe(),
+            clamped.maybe(),
+            bouncing.maybe(),
+            !&inactive,
+        )
+            .join()
+        {
+            if clamp.is_none() && bounce.is_none() {
+                continue;
+            }
+            let half = collider
+                .map(|collider| half_extents(&collider.shape))
+                .unwrap_or_else(Vec2::zeros);
+            let reflect = bounce.is_some();
+            let (min_x, max_x) = (bounds.min.x + half.x, bounds.max.x - half.x);
+            let (min_y, max_y) = (bounds.min.y + half.y, bounds.max.y - half.y);
+            match velocity {
+                Some(velocity) => {
+                    resolve_axis(
+                        &mut position.x,
+                        Some(&mut velocity.x),
+                        min_x,
+                        max_x,
+                        reflect,
+                    );
+                    resolve_axis(
+                        &mut position.y,
+                        Some(&mut velocity.y),
+                        min_y,
+                        max_y,
+                        reflect,
+                    );
+                }
+                None => {
+                    resolve_axis(&mut position.x, None, min_x, max_x, reflect);
+                    resolve_axis(&mut position.y, None, min_y, max_y, reflect);
+                }
+            }
+        }
+    }
+}
+
+/// Moves each `Camera2D` with a `CameraTarget` towards the target's position.
+///
+/// The camera closes `1 - exp(-smoothing * dt)` of the remaining distance per
+/// frame, so it eases in without overshooting. The target may move freely
+/// within `dead_zone` (half extents around the camera center) before the camera follows.
+#[derive(Debug, Clone)]
+pub struct CameraFollowSystem {
+    /// Follow rate per second; higher is snappier, `f32::INFINITY` snaps instantly
+    pub smoothing: f32,
+    pub dead_zone: Vec2,
+}
+
+impl Default for CameraFollowSystem {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl CameraFollowSystem {
+    pub fn new() -> Self {
+        Self {
+            smoothing: 5.0,
+            dead_zone: Vec2::zeros(),
+        }
+    }
+
+    pub fn with_smoothing(mut self, smoothing: f32) -> Self {
+        self.smoothing = smoothing;
+        self
+    }
+
+    pub fn with_dead_zone(mut self, half_width: f32, half_height: f32) -> Self {
+        self.dead_zone = Vec2::new(half_width, half_height);
+        self
+    }
+
+    /// New camera center after following `target` for `dt` seconds
+    ///
+    /// A zero or negative `dt` leaves the camera where it is.
+    pub fn follow(&self, camera: Vec2, target: Vec2, dt: f32) -> Vec2 {
+        if dt <= 0.0 {
+            return camera;
+        }
+        let offset = target - camera;
+        let excess = Vec2::new(
+            offset.x - offset.x.clamp(-self.dead_zone.x, self.dead_zone.x),
+            offset.y - offset.y.clamp(-self.dead_zone.y, self.dead_zone.y),
+        );
+        let t = if self.smoothing.is_infinite() && self.smoothing > 0.0 {
+            1.0
+        } else {
+            1.0 - (-self.smoothing.max(0.0) * dt).exp()
+        };
+        camera + excess * t
+    }
+}
+
+impl<'a> System<'a> for CameraFollowSystem {
+    type SystemData = (
+        Read<'a, Time>,
+        WriteStorage<'a, Camera2D>,
+        ReadStorage<'a, CameraTarget>,
+        ReadStorage<'a, Position>,
+    );
+
+    fn run(&mut self, (time, mut cameras, targets, positions): Self::SystemData) {
+        for (camera, target) in (&mut cameras, &targets).join() {
+            if let Some(position) = positions.get(target.0) {
+                camera.position = self.follow(camera.position, position.as_vec2(), time.delta);
+            }
+        }
+    }
+}
+
+/// Keep one coordinate within `[min, max]`, zeroing or reflecting velocity
+/// heading out through the edge it was stopped at
+fn resolve_axis(pos: &mut f32, vel: Option<&mut f32>, min: f32, max: f32, reflect: bool) {
+    let outward = if *pos <= min {
+        *pos = min;
+        -1.0
+    } else if *pos >= max {
+        *pos = max;
+        1.0
+    } else {
+        return;
+    };
+    if let Some(vel) = vel {
+        if *vel * outward > 0.0 {
+            *vel = if reflect { -*vel } else { 0.0 };
+        }
+    }
+}
+
+fn collider_width(collider: &Collider) -> Option<f32> {
+    match collider.shape {
+        CollisionShape::Rectangle { width, .. } => Some(width),
+        _ => None,
+    }
+}
+
+fn set_collider_width(collider: &mut Collider, new_width: f32) {
+    if let CollisionShape::Rectangle { width, .. } = &mut collider.shape {
+        *width = new_width;
+    }
+}
+
+impl<'a> System<'a> for PowerUpSystem {
+    type SystemData = (
+        Entities<'a>,
+        Read<'a, Time>,
+        Read<'a, DeathEvents>,
+        Read<'a, CollisionEvents>,
+        Write<'a, CollectedPowerUps>,
+        Write<'a, RngResource>,
+        ReadStorage<'a, Brick>,
+        ReadStorage<'a, Paddle>,
+        WriteStorage<'a, PowerUp>,
+        WriteStorage<'a, TimedEffect>,
+        WriteStorage<'a, Collider>,
+        WriteStorage<'a, Position>,
+        WriteStorage<'a, Velocity>,
+    );
+
+    fn run(
+        &mut self,
+        (
+            entities,
+            time,
+            deaths,
+            collisions,
+            mut collected,
+            mut rng,
+            bricks,
+            paddles,
+            mut powerups,
+            mut effects,
+            mut colliders,
+            mut positions,
+            mut velocities,
+        ): Self::SystemData,
+    ) {
+        collected.collected.clear();
+
+        // Expire running effects
+        let mut expired = Vec::new();
+        for (entity, effect, collider) in (&entities, &mut effects, &mut colliders).join() {
+            effect.remaining -= time.delta;
+            if effect.remaining <= 0.0 {
+                set_collider_width(collider, effect.base_width);
+                expired.push(entity);
+            }
+        }
+        for entity in expired {
+            effects.remove(entity);
+        }
+
+        // Drop power-ups from destroyed bricks
+        for death in &deaths.events {
+            let Some(position) = death.position else {
+                continue;
+            };
+            if !bricks.contains(death.entity) || rng.next_f32() >= self.drop_chance {
+                continue;
+            }
+            let Some(&power_type) = self.drops.try_sample(&mut rng) else {
+                continue;
+            };
+            let drop = entities.create();
+            positions
+                .insert(drop, Position::new(position.x, position.y))
+                .unwrap();
+            velocities
+                .insert(drop, Velocity::new(0.0, self.fall_speed))
+                .unwrap();
+            colliders
+                .insert(
+                    drop,
+                    Collider::new_rectangle(self.size, self.size).as_trigger(),
+                )
+                .unwrap();
+            powerups.insert(drop, PowerUp { power_type }).unwrap();
+        }
+
+        // Collect power-ups touching a paddle
+        for trigger in &collisions.triggers {
+            if !paddles.contains(trigger.other) {
+                continue;
+            }
+            let Some(powerup) = powerups.remove(trigger.entity) else {
+                continue;
+            };
+            entities.delete(trigger.entity).unwrap();
+            collected
+                .collected
+                .push((trigger.other, powerup.power_type));
+
+            let Some(factor) = self.width_factor(powerup.power_type) else {
+                continue;
+            };
+            let Some(collider) = colliders.get_mut(trigger.other) else {
+                continue;
+            };
+            let Some(current_width) = collider_width(collider) else {
+                continue;
+            };
+            // A new pickup replaces any running effect but keeps the original width
+            let base_width = effects
+                .get(trigger.other)
+                .map_or(current_width, |effect| effect.base_width);
+            set_collider_width(collider, base_width * factor);
+            effects
+                .insert(
+                    trigger.other,
+                    TimedEffect {
+                        power_type: powerup.power_type,
+                        remaining: self.duration,
+                        base_width,
+                    },
+                )
+                .unwrap();
+        }
+    }
+}
+
 /// Debug system for logging game state
 pub struct DebugSystem;
 
@@ -87,6 +653,211 @@ impl<'a> System<'a> for DebugSystem {
     }
 }
 
+/// Event emitted by a `LiveSet` when its last entity is removed
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SetEmpty;
+
+/// Resource tracking how many active entities carry the marker component `C`
+///
+/// Replaces hand-maintained `Vec`s of balls, lives and so on. Updated by `LiveSetSystem<C>`.
+pub struct LiveSet<C> {
+    alive: usize,
+    pub events: Vec<SetEmpty>,
+    _marker: PhantomData<fn() -> C>,
+}
+
+impl<C> Default for LiveSet<C> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<C> LiveSet<C> {
+    pub fn new() -> Self {
+        Self {
+            alive: 0,
+            events: Vec::new(),
+            _marker: PhantomData,
+        }
+    }
+
+    /// Number of live, active entities seen on the last update
+    pub fn count_alive(&self) -> usize {
+        self.alive
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.alive == 0
+    }
+
+    /// Record the current count, emitting `SetEmpty` when it drops to zero
+    pub fn set_count(&mut self, alive: usize) {
+        if self.alive > 0 && alive == 0 {
+            self.events.push(SetEmpty);
+        }
+        self.alive = alive;
+    }
+
+    /// Take the pending `SetEmpty` events
+    pub fn drain_events(&mut self) -> Vec<SetEmpty> {
+        std::mem::take(&mut self.events)
+    }
+}
+
+/// System that refreshes `LiveSet<C>` from the marker storage
+pub struct LiveSetSystem<C>(PhantomData<fn() -> C>);
+
+impl<C> Default for LiveSetSystem<C> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<C> LiveSetSystem<C> {
+    pub fn new() -> Self {
+        Self(PhantomData)
+    }
+}
+
+impl<'a, C: Component> System<'a> for LiveSetSystem<C> {
+    type SystemData = (
+        Entities<'a>,
+        ReadStorage<'a, C>,
+        ReadStorage<'a, Inactive>,
+        Write<'a, LiveSet<C>>,
+    );
+
+    fn run(&mut self, (entities, markers, inactive, mut set): Self::SystemData) {
+        let alive = (&entities, &markers, !&inactive).join().count();
+        set.set_count(alive);
+    }
+}
+
+/// On-screen debug HUD showing FPS, entity count and optional system timings
+#[derive(Debug, Clone)]
+pub struct DebugOverlay {
+    pub enabled: bool,
+    /// Top-left corner of the overlay in pixels
+    pub position: (i32, i32),
+    pub background: Color,
+    pub text_color: Color,
+    /// Outline colliders in `render_colliders`
+    pub show_colliders: bool,
+    pub collider_color: Color,
+    /// Per-system timings in seconds, reported by the caller
+    system_timings: Vec<(String, f32)>,
+}
+
+impl Default for DebugOverlay {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl DebugOverlay {
+    pub fn new() -> Self {
+        Self {
+            enabled: false,
+            position: (8, 8),
+            background: Color::rgba(0, 0, 0, 160),
+            text_color: Color::WHITE,
+            show_colliders: false,
+            collider_color: Color::GREEN,
+            system_timings: Vec::new(),
+        }
+    }
+
+    /// Show or hide the overlay
+    pub fn toggle(&mut self) {
+        self.enabled = !self.enabled;
+    }
+
+    /// Record how long a system took this frame. specs' dispatcher doesn't
+    /// expose timings, so callers that time their systems report them here.
+    pub fn record_timing(&mut self, system: &str, seconds: f32) {
+        match self
+            .system_timings
+            .iter_mut()
+            .find(|(name, _)| name == system)
+        {
+            Some(entry) => entry.1 = seconds,
+            None => self.system_timings.push((system.to_string(), seconds)),
+        }
+    }
+
+    /// Show or hide collider outlines
+    pub fn toggle_colliders(&mut self) {
+        self.show_colliders = !self.show_colliders;
+    }
+
+    /// Outline every collider in `world` if `show_colliders` is set; call it
+    /// after drawing the scene with the same camera
+    pub fn render_colliders(
+        &self,
+        world: &World,
+        renderer: &mut Renderer2D,
+        camera: Option<&Camera2D>,
+    ) {
+        if self.show_colliders {
+            crate::physics::draw_colliders(world, renderer, camera, self.collider_color);
+        }
+    }
+
+    /// Forget all recorded system timings
+    pub fn clear_timings(&mut self) {
+        self.system_timings.clear();
+    }
+
+    /// Build the overlay text, one entry per line
+    pub fn lines(&self, time: &Time, entity_count: usize) -> Vec<String> {
+        let mut lines = vec![
+            format!(
+                "FPS: {:.0} ({:.2}ms)",
+                time.fps,
+                time.unscaled_delta * 1000.0
+            ),
+            format!("Entities: {}", entity_count),
+        ];
+        for (name, seconds) in &self.system_timings {
+            lines.push(format!("{}: {:.2}ms", name, seconds * 1000.0));
+        }
+        lines
+    }
+
+    /// Draw the overlay for `world` if enabled
+    pub fn render(&self, world: &World, renderer: &mut Renderer2D) {
+        if !self.enabled {
+            return;
+        }
+
+        let time = world.read_resource::<Time>();
+        let entity_count = world.entities().join().count();
+        let lines = self.lines(&time, entity_count);
+
+        let padding = 4;
+        let line_height = 12;
+        let width = lines
+            .iter()
+            .map(|line| renderer.measure_text(line, 1).0)
+            .max()
+            .unwrap_or(0) as i32
+            + padding * 2;
+        let height = lines.len() as i32 * line_height + padding * 2;
+        let (x, y) = self.position;
+
+        renderer.draw_rect_blended(x, y, width, height, self.background);
+        for (i, line) in lines.iter().enumerate() {
+            renderer.draw_text(
+                line,
+                (x + padding).max(0) as usize,
+                (y + padding + i as i32 * line_height).max(0) as usize,
+                self.text_color,
+                1,
+            );
+        }
+    }
+}
+
 /// System for basic AI behavior (placeholder)
 pub struct AISystem;
 
@@ -159,12 +930,22 @@ impl<'a> System<'a> for RenderingSystem {
     type SystemData = (
         ReadStorage<'a, Position>,
         ReadStorage<'a, crate::Renderable>,
+        ReadStorage<'a, Inactive>,
+        ReadStorage<'a, ZOrder>,
         Read<'a, Time>,
     );
 
-    fn run(&mut self, (positions, renderables, _time): Self::SystemData) {
-        // Simple rendering simulation
-        for (position, renderable) in (&positions, &renderables).join() {
+    fn run(&mut self, (positions, renderables, inactive, z_orders, _time): Self::SystemData) {
+        // Simple rendering simulation, back to front
+        let mut queue: Vec<_> = (&positions, &renderables, z_orders.maybe(), !&inactive)
+            .join()
+            .map(|(position, renderable, z, _)| {
+                (z.copied().unwrap_or_default(), position, renderable)
+            })
+            .collect();
+        queue.sort_by_key(|(z, _, _)| *z);
+
+        for (_, position, renderable) in queue {
             if renderable.visible {
                 // In a real implementation, this would render the sprite
                 println!(
@@ -175,3 +956,570 @@ impl<'a> System<'a> for RenderingSystem {
         }
     }
 }
+
+/// Record every visible entity with a `Position`, `Renderable` and
+/// `rendering::Sprite` into `list` as a rect centered on its position, on the
+/// depth given by its `ZOrder` (0 when missing) within `RenderLayer::World`.
+/// An active `Flash` tints the color.
+pub fn record_entities(world: &World, list: &mut DisplayList) {
+    let positions = world.read_storage::<Position>();
+    let renderables = world.read_storage::<crate::Renderable>();
+    let sprites = world.read_storage::<crate::rendering::Sprite>();
+    let z_orders = world.read_storage::<ZOrder>();
+    let flashes = world.read_storage::<crate::visual_effects::Flash>();
+    let inactive = world.read_storage::<Inactive>();
+
+    for (position, renderable, sprite, z, flash, _) in (
+        &positions,
+        &renderables,
+        &sprites,
+        z_orders.maybe(),
+        flashes.maybe(),
+        !&inactive,
+    )
+        .join()
+    {
+        if !renderable.visible {
+            continue;
+        }
+        let size = sprite.size * renderable.scale;
+        let color = match flash {
+            Some(flash) => flash.apply(sprite.color),
+            None => sprite.color,
+        };
+        let [r, g, b, a] = color.map(|c| (c.clamp(0.0, 1.0) * 255.0).round() as u8);
+        list.rect(
+            RenderLayer::World.z(z.copied().unwrap_or_default().0),
+            (position.x - size.x / 2.0).round() as i32,
+            (position.y - size.y / 2.0).round() as i32,
+            size.x.round() as i32,
+            size.y.round() as i32,
+            Color::rgba(r, g, b, a),
+        );
+    }
+}
+
+/// Draw all renderable entities in z order (see `record_entities`)
+pub fn render_entities(world: &World, renderer: &mut Renderer2D) {
+    let mut list = DisplayList::new();
+    record_entities(world, &mut list);
+    renderer.execute(&list);
+}
+
+/// Check a dispatcher layout given as `(system name, dependency names)` in
+/// registration order. Returns every dependency that doesn't name a system
+/// registered before it (typos like "physcis" included), without duplicates.
+pub fn validate_dependencies(spec: &[(&str, &[&str])]) -> Result<(), Vec<String>> {
+    let mut registered = std::collections::HashSet::new();
+    let mut missing: Vec<String> = Vec::new();
+    for (name, dependencies) in spec {
+        for dependency in *dependencies {
+            if !registered.contains(dependency) && !missing.iter().any(|m| m == dependency) {
+                missing.push(dependency.to_string());
+            }
+        }
+        registered.insert(*name);
+    }
+    if missing.is_empty() {
+        Ok(())
+    } else {
+        Err(missing)
+    }
+}
+
+type SystemAdder = Box<dyn FnOnce(&mut specs::DispatcherBuilder<'static, 'static>)>;
+
+/// `DispatcherBuilder` wrapper that validates dependency names before building,
+/// reporting all unknown names instead of panicking on the first one.
+#[derive(Default)]
+pub struct SystemPlan {
+    names: Vec<(String, Vec<String>)>,
+    adders: Vec<SystemAdder>,
+}
+
+impl SystemPlan {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add a system, like `DispatcherBuilder::with`
+    pub fn with<S>(mut self, system: S, name: &str, dependencies: &[&str]) -> Self
+    where
+        S: for<'c> System<'c> + Send + 'static,
+    {
+        let name = name.to_string();
+        let dependencies: Vec<String> = dependencies.iter().map(|d| d.to_string()).collect();
+        self.names.push((name.clone(), dependencies.clone()));
+        self.adders.push(Box::new(move |builder| {
+            let dependencies: Vec<&str> = dependencies.iter().map(String::as_str).collect();
+            builder.add(system, &name, &dependencies);
+        }));
+        self
+    }
+
+    /// See `validate_dependencies`
+    pub fn validate(&self) -> Result<(), Vec<String>> {
+        let dependencies: Vec<Vec<&str>> = self
+            .names
+            .iter()
+            .map(|(_, deps)| deps.iter().map(String::as_str).collect())
+            .collect();
+        let spec: Vec<(&str, &[&str])> = self
+            .names
+            .iter()
+            .zip(&dependencies)
+            .map(|((name, _), deps)| (name.as_str(), deps.as_slice()))
+            .collect();
+        validate_dependencies(&spec)
+    }
+
+    /// Build the dispatcher, or return the missing dependency names
+    pub fn build(self) -> Result<specs::Dispatcher<'static, 'static>, Vec<String>> {
+        self.validate()?;
+        let mut builder = specs::DispatcherBuilder::new();
+        for add in self.adders {
+            add(&mut builder);
+        }
+        Ok(builder.build())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_zero_time_scale_freezes_physics_not_ui() {
+        use crate::visual_effects::{Easing, Tween};
+        use specs::{Builder, RunNow};
+
+        let mut world = crate::init().unwrap();
+        let ball = world
+            .create_entity()
+            .with(Position::new(10.0, 10.0))
+            .with(Velocity::new(100.0, 0.0))
+            .build();
+        let mut tween = Tween::new(0.0, 1.0, 1.0, Easing::Linear);
+        {
+            let mut time = world.write_resource::<Time>();
+            time.set_paused(true);
+            time.advance(0.25);
+        }
+
+        PhysicsSystem.run_now(&world);
+        let time = world.read_resource::<Time>().clone();
+        tween.update(time.unscaled_delta);
+
+        assert!(time.is_paused());
+        assert_eq!((time.delta, time.elapsed), (0.0, 0.0));
+        assert_eq!(world.read_storage::<Position>().get(ball).unwrap().x, 10.0);
+        assert_eq!(tween.value(), 0.25);
+    }
+
+    #[test]
+    fn test_lifetime_marks_expired_entity() {
+        use specs::{Builder, RunNow};
+
+        let mut world = crate::init().unwrap();
+        let short = world.create_entity().with(Lifetime::new(0.1)).build();
+        let long = world.create_entity().with(Lifetime::new(5.0)).build();
+
+        world.write_resource::<Time>().advance(0.2);
+        LifetimeSystem.run_now(&world);
+
+        let marked = world.read_storage::<MarkedForRemoval>();
+        assert!(marked.contains(short));
+        assert!(!marked.contains(long));
+    }
+
+    #[test]
+    fn test_cooldown_ready_again_after_duration() {
+        use specs::{Builder, RunNow};
+
+        let mut world = crate::init().unwrap();
+        let shooter = world.create_entity().with(Cooldown::new(0.25)).build();
+
+        let fire = |world: &World| {
+            let mut cooldowns = world.write_storage::<Cooldown>();
+            let cooldown = cooldowns.get_mut(shooter).unwrap();
+            let ready = cooldown.is_ready();
+            if ready {
+                cooldown.trigger();
+            }
+            ready
+        };
+        assert!(fire(&world));
+        assert!(!fire(&world));
+
+        for _ in 0..3 {
+            world.write_resource::<Time>().advance(0.0625);
+            CooldownSystem.run_now(&world);
+        }
+        assert!(!world
+            .read_storage::<Cooldown>()
+            .get(shooter)
+            .unwrap()
+            .is_ready());
+
+        world.write_resource::<Time>().advance(0.0625);
+        CooldownSystem.run_now(&world);
+        assert!(fire(&world));
+    }
+
+    #[test]
+    fn test_camera_converges_without_overshoot() {
+        use specs::{Builder, RunNow};
+
+        let mut world = crate::init().unwrap();
+        let target = world
+            .create_entity()
+            .with(Position::new(100.0, 50.0))
+            .build();
+        let camera = world
+            .create_entity()
+            .with(Camera2D {
+                position: Vec2::zeros(),
+                zoom: 1.0,
+                rotation: 0.0,
+                viewport_size: Vec2::new(800.0, 600.0),
+            })
+            .with(CameraTarget(target))
+            .build();
+
+        let mut system = CameraFollowSystem::new().with_smoothing(8.0);
+        let mut previous = 0.0;
+        for _ in 0..120 {
+            world.write_resource::<Time>().advance(1.0 / 60.0);
+            system.run_now(&world);
+            let x = world
+                .read_storage::<Camera2D>()
+                .get(camera)
+                .unwrap()
+                .position
+                .x;
+            assert!(x >= previous && x <= 100.0);
+            previous = x;
+        }
+        let position = world
+            .read_storage::<Camera2D>()
+            .get(camera)
+            .unwrap()
+            .position;
+        assert!((position - Vec2::new(100.0, 50.0)).norm() < 0.1);
+
+        // inside the dead zone the camera stays put
+        let lazy = CameraFollowSystem::new().with_dead_zone(20.0, 20.0);
+        assert_eq!(
+            lazy.follow(Vec2::zeros(), Vec2::new(15.0, -10.0), 0.1),
+            Vec2::zeros()
+        );
+        let moved = lazy.follow(Vec2::zeros(), Vec2::new(30.0, 0.0), 10.0);
+        assert!((moved.x - 10.0).abs() < 1e-3 && moved.y == 0.0);
+
+        // infinite smoothing snaps, and a paused frame never moves the camera
+        let snap = CameraFollowSystem::new().with_smoothing(f32::INFINITY);
+        let target = Vec2::new(40.0, -25.0);
+        assert_eq!(snap.follow(Vec2::zeros(), target, 1.0 / 60.0), target);
+        assert_eq!(snap.follow(Vec2::zeros(), target, 0.0), Vec2::zeros());
+        assert_eq!(system.follow(Vec2::zeros(), target, 0.0), Vec2::zeros());
+    }
+
+    #[test]
+    fn test_team_relations_and_friendly_fire() {
+        use crate::physics::CollisionEvent;
+        use specs::{Builder, RunNow};
+
+        let relations = TeamRelations::new().with(Team(1), Team(3), Relation::Neutral);
+        assert!(!relations.are_hostile(Team(1), Team(1)));
+        assert!(relations.are_hostile(Team(1), Team(2)));
+        assert!(relations.are_hostile(Team(2), Team(1)));
+        assert_eq!(relations.relation(Team(3), Team(1)), Relation::Neutral);
+
+        let mut world = crate::init().unwrap();
+        let spikes = world
+            .create_entity()
+            .with(Position::new(0.0, 0.0))
+            .with(Team(1))
+            .with(ContactDamage(10.0))
+            .build();
+        let ally = world
+            .create_entity()
+            .with(Position::new(5.0, 0.0))
+            .with(Team(1))
+            .with(Health::new(100.0))
+            .build();
+        let enemy = world
+            .create_entity()
+            .with(Position::new(50.0, 0.0))
+            .with(Team(2))
+            .with(Health::new(100.0))
+            .build();
+
+        let hit = |other| CollisionEvent {
+            entity_a: spikes,
+            entity_b: other,
+            normal: Vec2::new(1.0, 0.0),
+            relative_speed: 1.0,
+        };
+        world.write_resource::<CollisionEvents>().collisions = vec![hit(ally), hit(enemy)];
+        ContactDamageSystem.run_now(&world);
+
+        let healths = world.read_storage::<Health>();
+        assert_eq!(healths.get(ally).unwrap().current, 100.0);
+        assert_eq!(healths.get(enemy).unwrap().current, 90.0);
+        drop(healths);
+
+        // targeting skips the closer ally
+        let target = crate::enhanced_ai::nearest_hostile(&world, spikes).unwrap();
+        assert_eq!(target.0, enemy);
+        assert_eq!(target.1.distance, 50.0);
+    }
+
+    #[test]
+    fn test_bounds_clamp_and_bounce() {
+        use specs::{Builder, RunNow};
+
+        let mut world = crate::init().unwrap();
+        world.insert(WorldBounds::from_size(100.0, 50.0));
+        let paddle = world
+            .create_entity()
+            .with(Position::new(50.0, 45.0))
+            .with(Velocity::new(0.0, 200.0))
+            .with(Collider::new_rectangle(10.0, 20.0))
+            .with(ClampToBounds)
+            .build();
+        let ball = world
+            .create_entity()
+            .with(Position::new(30.0, 2.0))
+            .with(Velocity::new(40.0, -80.0))
+            .with(Collider::new_circle(5.0))
+            .with(BounceOnBounds)
+            .build();
+        let free = world
+            .create_entity()
+            .with(Position::new(-20.0, 0.0))
+            .with(Velocity::new(-10.0, 0.0))
+            .build();
+
+        BoundsSystem.run_now(&world);
+
+        let positions = world.read_storage::<Position>();
+        let velocities = world.read_storage::<Velocity>();
+        let pos = positions.get(paddle).unwrap();
+        let vel = velocities.get(paddle).unwrap();
+        assert_eq!((pos.x, pos.y), (50.0, 40.0));
+        assert_eq!((vel.x, vel.y), (0.0, 0.0));
+
+        let pos = positions.get(ball).unwrap();
+        let vel = velocities.get(ball).unwrap();
+        assert_eq!((pos.x, pos.y), (30.0, 5.0));
+        assert_eq!((vel.x, vel.y), (40.0, 80.0));
+
+        // unmarked entities are left alone
+        assert_eq!(positions.get(free).unwrap().x, -20.0);
+        assert_eq!(velocities.get(free).unwrap().x, -10.0);
+    }
+
+    #[test]
+    fn test_live_set_fires_empty_on_last_removal() {
+        use crate::Ball;
+        use specs::{Builder, RunNow};
+
+        let mut world = crate::init().unwrap();
+        let balls: Vec<_> = (0..3)
+            .map(|_| world.create_entity().with(Ball).build())
+            .collect();
+        let mut system = LiveSetSystem::<Ball>::new();
+        System::setup(&mut system, &mut world);
+        system.run_now(&world);
+        assert_eq!(world.read_resource::<LiveSet<Ball>>().count_alive(), 3);
+
+        for (i, ball) in balls.into_iter().enumerate() {
+            world.delete_entity(ball).unwrap();
+            world.maintain();
+            system.run_now(&world);
+            let mut set = world.write_resource::<LiveSet<Ball>>();
+            assert_eq!(set.count_alive(), 2 - i);
+            let events = set.drain_events();
+            if i < 2 {
+                assert!(events.is_empty());
+            } else {
+                assert_eq!(events, vec![SetEmpty]);
+            }
+        }
+    }
+
+    #[test]
+    fn test_larger_paddle_effect_reverts_after_duration() {
+        use crate::physics::TriggerEvent;
+        use specs::{Builder, RunNow};
+
+        let mut world = crate::init().unwrap();
+        let paddle = world
+            .create_entity()
+            .with(Position::new(100.0, 200.0))
+            .with(Collider::new_rectangle(80.0, 10.0))
+            .with(Paddle {
+                player_controlled: true,
+            })
+            .build();
+        let powerup = world
+            .create_entity()
+            .with(Position::new(100.0, 200.0))
+            .with(Collider::new_rectangle(12.0, 12.0).as_trigger())
+            .with(PowerUp {
+                power_type: PowerUpType::LargerPaddle,
+            })
+            .build();
+        world
+            .write_resource::<CollisionEvents>()
+            .triggers
+            .push(TriggerEvent {
+                entity: powerup,
+                other: paddle,
+            });
+        world.write_resource::<Time>().delta = 0.5;
+
+        let mut system = PowerUpSystem::new().with_duration(2.0);
+        let width = |world: &World| {
+            collider_width(world.read_storage::<Collider>().get(paddle).unwrap()).unwrap()
+        };
+
+        system.run_now(&world);
+        world.maintain();
+        assert_eq!(width(&world), 120.0);
+        assert!(!world.is_alive(powerup));
+        assert_eq!(
+            world.read_resource::<CollectedPowerUps>().collected,
+            vec![(paddle, PowerUpType::LargerPaddle)]
+        );
+
+        world.write_resource::<CollisionEvents>().triggers.clear();
+        for _ in 0..3 {
+            system.run_now(&world);
+        }
+        assert_eq!(width(&world), 120.0);
+
+        system.run_now(&world);
+        assert_eq!(width(&world), 80.0);
+        assert!(world.read_storage::<TimedEffect>().get(paddle).is_none());
+    }
+
+    #[test]
+    fn test_debug_overlay_lines() {
+        let time = Time {
+            delta: 0.016,
+            unscaled_delta: 0.016,
+            elapsed: 2.0,
+            frame_count: 120,
+            fps: 60.0,
+            ..Time::default()
+        };
+        let mut overlay = DebugOverlay::new();
+        assert_eq!(
+            overlay.lines(&time, 42),
+            vec!["FPS: 60 (16.00ms)", "Entities: 42"]
+        );
+
+        overlay.record_timing("physics", 0.0015);
+        overlay.record_timing("physics", 0.0025);
+        overlay.record_timing("rendering", 0.004);
+        assert_eq!(
+            overlay.lines(&time, 42),
+            vec![
+                "FPS: 60 (16.00ms)",
+                "Entities: 42",
+                "physics: 2.50ms",
+                "rendering: 4.00ms"
+            ]
+        );
+    }
+
+    #[test]
+    fn test_higher_z_entity_draws_on_top() {
+        use crate::ecs::GameWorldExt;
+        use crate::rendering::Sprite;
+        use crate::{Renderable, Vec2};
+
+        let mut world = crate::init().unwrap();
+        let square = |color: [f32; 4]| Sprite {
+            texture_id: "square".to_string(),
+            size: Vec2::new(10.0, 10.0),
+            color,
+        };
+        let renderable = || Renderable {
+            sprite_id: "square".to_string(),
+            layer: 0,
+            visible: true,
+            scale: 1.0,
+        };
+
+        // the red one is created first but sits on a higher layer
+        world
+            .create_entity_with_components()
+            .with(Position::new(10.0, 10.0))
+            .with(renderable())
+            .with(square([1.0, 0.0, 0.0, 1.0]))
+            .with(ZOrder(2))
+            .build();
+        world
+            .create_entity_with_components()
+            .with(Position::new(14.0, 14.0))
+            .with(renderable())
+            .with(square([0.0, 0.0, 1.0, 1.0]))
+            .build();
+
+        let mut renderer = Renderer2D::new(32, 32);
+        renderer.clear(Color::BLACK);
+        render_entities(&world, &mut renderer);
+
+        assert_eq!(renderer.get_pixel(12, 12), Some(Color::RED));
+        assert_eq!(renderer.get_pixel(17, 17), Some(Color::BLUE));
+        assert_eq!(renderer.get_pixel(6, 6), Some(Color::RED));
+    }
+
+    #[test]
+    fn test_time_advance_tracks_frames_and_fps() {
+        let mut time = Time::default();
+        time.advance(0.02);
+        time.advance(0.02);
+        assert_eq!(time.frame_count, 2);
+        assert!((time.elapsed - 0.04).abs() < 1e-6);
+        assert!((time.fps - 50.0).abs() < 1e-3);
+    }
+
+    #[test]
+    fn test_dangling_dependency_is_reported() {
+        assert_eq!(
+            validate_dependencies(&[("input", &[]), ("physics", &["input"])]),
+            Ok(())
+        );
+        // misspelled, and registered after its dependent
+        let spec: &[(&str, &[&str])] = &[
+            ("ai", &["physcis", "input"]),
+            ("physics", &[]),
+            ("input", &[]),
+            ("render", &["physcis"]),
+        ];
+        assert_eq!(
+            validate_dependencies(spec),
+            Err(vec!["physcis".to_string(), "input".to_string()])
+        );
+
+        let plan = SystemPlan::new().with(PhysicsSystem, "physics", &[]).with(
+            LifetimeSystem,
+            "lifetime",
+            &["physcis"],
+        );
+        assert_eq!(plan.build().err(), Some(vec!["physcis".to_string()]));
+
+        let mut world = crate::init().unwrap();
+        let mut dispatcher = SystemPlan::new()
+            .with(PhysicsSystem, "physics", &[])
+            .with(LifetimeSystem, "lifetime", &["physics"])
+            .build()
+            .unwrap();
+        dispatcher.dispatch(&world);
+        world.maintain();
+    }
+}
@@ -3,6 +3,8 @@
 //! A specialized system for creating dynamic trail effects behind moving objects.
 //! Perfect for balls, projectiles, particles, and other fast-moving entities.
 
+use crate::renderer_2d::{Color, Renderer2D};
+use crate::rendering::Camera2D;
 use crate::Vec2;
 use std::collections::VecDeque;
 
@@ -69,6 +71,9 @@ pub struct TrailConfig {
     pub velocity_based: bool,
     /// Minimum distance between segments
     pub min_distance: f32,
+    /// Largest allowed distance between consecutive segments; longer jumps
+    /// are filled with interpolated segments. `None` leaves gaps as they are.
+    pub max_gap: Option<f32>,
 }
 
 impl Default for TrailConfig {
@@ -85,6 +90,7 @@ impl Default for TrailConfig {
             fade_speed: 2.0,
             velocity_based: false,
             min_distance: 5.0,
+            max_gap: None,
         }
     }
 }
@@ -148,6 +154,7 @@ impl Trail {
         if self.time_since_last_segment >= self.config.segment_interval
             && distance_moved >= self.config.min_distance
         {
+            self.fill_gap(position, velocity);
             self.add_segment(position, velocity);
             self.time_since_last_segment = 0.0;
             self.last_position = position;
@@ -155,6 +162,23 @@ impl Trail {
         }
     }
 
+    /// Insert evenly spaced segments between the last sample and `position`
+    /// so no two consecutive segments are more than `max_gap` apart
+    fn fill_gap(&mut self, position: Vec2, velocity: Vec2) {
+        let Some(max_gap) = self.config.max_gap.filter(|gap| *gap > 0.0) else {
+            return;
+        };
+        if !self.has_segments() {
+            return;
+        }
+        let (from, from_velocity) = (self.last_position, self.last_velocity);
+        let steps = ((position - from).magnitude() / max_gap).ceil() as usize;
+        for i in 1..steps {
+            let t = i as f32 / steps as f32;
+            self.add_segment(from.lerp(&position, t), from_velocity.lerp(&velocity, t));
+        }
+    }
+
     /// Add a new segment to the trail
     pub fn add_segment(&mut self, position: Vec2, velocity: Vec2) {
         let mut color = self.config.base_color;
@@ -206,6 +230,25 @@ impl Trail {
     pub fn get_segments(&self) -> &VecDeque<TrailSegment> {
         &self.segments
     }
+
+    /// Draw the segments as filled circles, fading with their life.
+    /// Positions are in world space when a `camera` is given, otherwise screen space.
+    pub fn render(&self, renderer: &mut Renderer2D, camera: Option<&Camera2D>) {
+        let zoom = camera.map_or(1.0, |camera| camera.zoom);
+        for segment in &self.segments {
+            let screen = camera.map_or(segment.position, |camera| {
+                camera.world_to_screen(segment.position)
+            });
+            let mut color = segment.color;
+            color[3] *= segment.alpha();
+            renderer.draw_circle_filled(
+                screen.x as i32,
+                screen.y as i32,
+                (segment.size * zoom) as i32,
+                Color::from_f32(color),
+            );
+        }
+    }
 }
 
 /// Main trail system that manages multiple trails
@@ -318,6 +361,7 @@ pub mod presets {
             fade_speed: 3.0,
             velocity_based: true,
             min_distance: 3.0,
+            max_gap: Some(4.0),
         }
     }
 
@@ -335,6 +379,7 @@ pub mod presets {
             fade_speed: 2.0,
             velocity_based: true,
             min_distance: 5.0,
+            max_gap: Some(8.0),
         }
     }
 
@@ -352,6 +397,7 @@ pub mod presets {
             fade_speed: 4.0,
             velocity_based: true,
             min_distance: 2.0,
+            max_gap: None,
         }
     }
 
@@ -369,6 +415,7 @@ pub mod presets {
             fade_speed: 1.5,
             velocity_based: false,
             min_distance: 4.0,
+            max_gap: None,
         }
     }
 
@@ -386,6 +433,7 @@ pub mod presets {
             fade_speed: 2.0,
             velocity_based: false,
             min_distance: 8.0,
+            max_gap: None,
         }
     }
 }
@@ -452,4 +500,51 @@ mod tests {
         let fireball_config = presets::fireball_trail();
         assert_eq!(fireball_config.base_color, [1.0, 0.3, 0.0, 0.9]);
     }
+
+    #[test]
+    fn test_trail_render_uses_camera() {
+        let mut trail = Trail::new();
+        trail.segments.push_back(TrailSegment::new(
+            Vec2::new(200.0, 50.0),
+            [1.0, 1.0, 1.0, 1.0],
+            2.0,
+            1.0,
+        ));
+        let camera = Camera2D {
+            position: Vec2::new(100.0, 0.0),
+            zoom: 1.0,
+            rotation: 0.0,
+            viewport_size: Vec2::new(0.0, 0.0),
+        };
+
+        let mut renderer = Renderer2D::new(300, 100);
+        trail.render(&mut renderer, Some(&camera));
+        assert_eq!(renderer.get_pixel(100, 50), Some(Color::WHITE));
+        assert_eq!(renderer.get_pixel(200, 50), Some(Color(0)));
+
+        let mut renderer = Renderer2D::new(300, 100);
+        trail.render(&mut renderer, None);
+        assert_eq!(renderer.get_pixel(200, 50), Some(Color::WHITE));
+    }
+
+    #[test]
+    fn test_fast_trail_fills_gaps() {
+        let mut trail = Trail::new();
+        trail.config.segment_interval = 0.0;
+        trail.config.min_distance = 1.0;
+        trail.config.max_gap = Some(4.0);
+
+        trail.update(0.01, Vec2::new(10.0, 0.0), Vec2::new(1000.0, 0.0));
+        assert_eq!(trail.segment_count(), 1);
+
+        // a 30px jump in one frame is split into 8 steps of 3.75px
+        trail.update(0.01, Vec2::new(40.0, 0.0), Vec2::new(1000.0, 0.0));
+        assert_eq!(trail.segment_count(), 9);
+        let positions: Vec<f32> = trail.segments.iter().map(|s| s.position.x).collect();
+        assert_eq!(positions[0], 40.0);
+        assert_eq!(positions[8], 10.0);
+        for pair in positions.windows(2) {
+            assert!(pair[0] - pair[1] <= 4.0 + 1e-4);
+        }
+    }
 }
@@ -35,6 +35,31 @@ impl Label {
     }
 }
 
+/// Filled background rectangle; swallows clicks aimed at widgets below it
+#[derive(Debug, Clone)]
+pub struct Panel {
+    pub id: String,
+    pub position: Vec2,
+    pub size: Vec2,
+    pub color: renderer_2d::Color,
+}
+
+impl Panel {
+    pub fn new(id: &str, position: Vec2, size: Vec2, color: renderer_2d::Color) -> Self {
+        Self {
+            id: id.to_string(),
+            position,
+            size,
+            color,
+        }
+    }
+
+    fn contains_point(&self, x: i32, y: i32) -> bool {
+        let (px, py) = (self.position.x as i32, self.position.y as i32);
+        x >= px && x < px + self.size.x as i32 && y >= py && y < py + self.size.y as i32
+    }
+}
+
 /// Simple button widget
 pub struct Button {
     pub id: String,
@@ -42,6 +67,8 @@ pub struct Button {
     pub position: Vec2,
     pub size: Vec2,
     pub enabled: bool,
+    /// Text shown near the cursor after hovering for `UIManager::tooltip_delay`
+    pub tooltip: Option<String>,
     // click callback is optional and stored as FnMut
     on_click: Option<Box<dyn FnMut()>>,
     // transient UI state
@@ -57,6 +84,7 @@ impl std::fmt::Debug for Button {
             .field("position", &self.position)
             .field("size", &self.size)
             .field("enabled", &self.enabled)
+            .field("tooltip", &self.tooltip)
             .finish()
     }
 }
@@ -69,6 +97,7 @@ impl Button {
             position,
             size,
             enabled: true,
+            tooltip: None,
             on_click: None,
             hovered: false,
             pressed: false,
@@ -81,6 +110,12 @@ impl Button {
         self
     }
 
+    /// Set tooltip text shown on hover (works for disabled buttons too)
+    pub fn with_tooltip(mut self, text: &str) -> Self {
+        self.tooltip = Some(text.to_string());
+        self
+    }
+
     fn contains_point(&self, x: i32, y: i32) -> bool {
         let px = self.position.x as i32;
         let py = self.position.y as i32;
@@ -102,9 +137,10 @@ impl Clone for Button {
         Self {
             id: self.id.clone(),
             text: self.text.clone(),
-            position: self.position.clone(),
-            size: self.size.clone(),
+            position: self.position,
+            size: self.size,
             enabled: self.enabled,
+            tooltip: self.tooltip.clone(),
             on_click: None, // callbacks are not cloned
             hovered: false,
             pressed: false,
@@ -119,6 +155,7 @@ pub struct Toggle {
     pub position: Vec2,
     pub checked: bool,
     pub enabled: bool,
+    pub tooltip: Option<String>,
     on_change: Option<Box<dyn FnMut(bool)>>,
 }
 
@@ -130,6 +167,7 @@ impl std::fmt::Debug for Toggle {
             .field("position", &self.position)
             .field("checked", &self.checked)
             .field("enabled", &self.enabled)
+            .field("tooltip", &self.tooltip)
             .finish()
     }
 }
@@ -139,9 +177,10 @@ impl Clone for Toggle {
         Self {
             id: self.id.clone(),
             label: self.label.clone(),
-            position: self.position.clone(),
+            position: self.position,
             checked: self.checked,
             enabled: self.enabled,
+            tooltip: self.tooltip.clone(),
             on_change: None,
         }
     }
@@ -155,6 +194,7 @@ impl Toggle {
             position,
             checked: initial,
             enabled: true,
+            tooltip: None,
             on_change: None,
         }
     }
@@ -164,6 +204,11 @@ impl Toggle {
         self
     }
 
+    pub fn with_tooltip(mut self, text: &str) -> Self {
+        self.tooltip = Some(text.to_string());
+        self
+    }
+
     fn call_change(&mut self, value: bool) {
         self.checked = value;
         if let Some(cb) = &mut self.on_change {
@@ -181,6 +226,11 @@ pub struct Slider {
     pub max: f32,
     pub value: f32,
     pub enabled: bool,
+    pub tooltip: Option<String>,
+    /// Values snap to `min + n * step` when set
+    pub step: Option<f32>,
+    /// Minimum change since the last emitted value before `on_change` fires again
+    pub emit_threshold: f32,
     on_change: Option<Box<dyn FnMut(f32)>>,
     // transient dragging state
     dragging: bool,
@@ -198,6 +248,9 @@ impl std::fmt::Debug for Slider {
             .field("max", &self.max)
             .field("value", &self.value)
             .field("enabled", &self.enabled)
+            .field("tooltip", &self.tooltip)
+            .field("step", &self.step)
+            .field("emit_threshold", &self.emit_threshold)
             .finish()
     }
 }
@@ -206,12 +259,15 @@ impl Clone for Slider {
     fn clone(&self) -> Self {
         Self {
             id: self.id.clone(),
-            position: self.position.clone(),
-            size: self.size.clone(),
+            position: self.position,
+            size: self.size,
             min: self.min,
             max: self.max,
             value: self.value,
             enabled: self.enabled,
+            tooltip: self.tooltip.clone(),
+            step: self.step,
+            emit_threshold: self.emit_threshold,
             on_change: None,
             dragging: false,
             last_emitted: Some(self.value),
@@ -229,6 +285,9 @@ impl Slider {
             max,
             value: initial.clamp(min, max),
             enabled: true,
+            tooltip: None,
+            step: None,
+            emit_threshold: 0.01,
             on_change: None,
             dragging: false,
             last_emitted: None,
@@ -240,13 +299,38 @@ impl Slider {
         self
     }
 
+    pub fn with_tooltip(mut self, text: &str) -> Self {
+        self.tooltip = Some(text.to_string());
+        self
+    }
+
+    /// Quantize values to the nearest multiple of `step` above `min`
+    pub fn with_step(mut self, step: f32) -> Self {
+        self.step = (step > 0.0).then_some(step);
+        self.value = self.snap(self.value);
+        self
+    }
+
+    /// Set the minimum change required before `on_change` fires while dragging
+    pub fn with_emit_threshold(mut self, threshold: f32) -> Self {
+        self.emit_threshold = threshold.max(0.0);
+        self
+    }
+
+    fn snap(&self, v: f32) -> f32 {
+        let v = match self.step {
+            Some(step) => self.min + ((v - self.min) / step).round() * step,
+            None => v,
+        };
+        v.clamp(self.min, self.max)
+    }
+
     fn set_value(&mut self, v: f32) {
-        let v = v.clamp(self.min, self.max);
+        let v = self.snap(v);
         self.value = v;
         // emit only when change is significant (coarse) to reduce spam
-        let emit_delta = 0.01; // 1%
         let should_emit = match self.last_emitted {
-            Some(prev) => (prev - v).abs() >= emit_delta,
+            Some(prev) => (prev - v).abs() >= self.emit_threshold,
             None => true,
         };
         if should_emit {
@@ -257,6 +341,24 @@ impl Slider {
         }
     }
 
+    /// Amount a single Left/Right key press moves the value: the step, or
+    /// a twentieth of the range without one
+    pub fn key_increment(&self) -> f32 {
+        self.step.unwrap_or((self.max - self.min) / 20.0)
+    }
+
+    /// Set the value from a discrete input (keyboard), always emitting it
+    fn set_value_discrete(&mut self, v: f32) {
+        let previous = self.value;
+        self.set_value(v);
+        if self.value != previous && self.last_emitted != Some(self.value) {
+            if let Some(cb) = &mut self.on_change {
+                (cb)(self.value);
+            }
+            self.last_emitted = Some(self.value);
+        }
+    }
+
     fn knob_rect(&self) -> (i32, i32, i32, i32) {
         let x = self.position.x as i32;
         let y = self.position.y as i32;
@@ -268,6 +370,124 @@ impl Slider {
     }
 }
 
+/// Width of the scrollbar drawn along a `ScrollList`'s right edge
+const SCROLLBAR_WIDTH: i32 = 6;
+
+/// Vertical list that lays out other widgets (by id) in rows of
+/// `item_height` and scrolls them through a viewport of `size`.
+///
+/// Children stay regular widgets of the `UIManager`; the manager positions
+/// them, clips them to the viewport and ignores the mouse outside it.
+#[derive(Debug, Clone)]
+pub struct ScrollList {
+    pub id: String,
+    pub position: Vec2,
+    /// Viewport size
+    pub size: Vec2,
+    pub item_height: f32,
+    /// Child widget ids, top to bottom
+    pub children: Vec<String>,
+    /// Pixels scrolled from the top
+    scroll_offset: f32,
+    // dragging the scrollbar
+    dragging: bool,
+}
+
+impl ScrollList {
+    pub fn new(id: &str, position: Vec2, size: Vec2, item_height: f32) -> Self {
+        Self {
+            id: id.to_string(),
+            position,
+            size,
+            item_height,
+            children: Vec::new(),
+            scroll_offset: 0.0,
+            dragging: false,
+        }
+    }
+
+    /// Set the child widget ids (consumes and returns self for chaining)
+    pub fn with_children(mut self, ids: &[&str]) -> Self {
+        self.children = ids.iter().map(|id| id.to_string()).collect();
+        self
+    }
+
+    pub fn scroll_offset(&self) -> f32 {
+        self.scroll_offset
+    }
+
+    /// Height of all rows together
+    pub fn content_height(&self) -> f32 {
+        self.children.len() as f32 * self.item_height
+    }
+
+    /// Largest offset that still fills the viewport
+    pub fn max_offset(&self) -> f32 {
+        (self.content_height() - self.size.y).max(0.0)
+    }
+
+    /// Scroll to `offset`, clamped to the content
+    pub fn scroll_to(&mut self, offset: f32) {
+        self.scroll_offset = offset.clamp(0.0, self.max_offset());
+    }
+
+    pub fn scroll_by(&mut self, delta: f32) {
+        self.scroll_to(self.scroll_offset + delta);
+    }
+
+    /// Scroll the least amount that brings row `index` fully into view
+    pub fn ensure_visible(&mut self, index: usize) {
+        let top = index as f32 * self.item_height;
+        let bottom = top + self.item_height;
+        if top < self.scroll_offset {
+            self.scroll_to(top);
+        } else if bottom > self.scroll_offset + self.size.y {
+            self.scroll_to(bottom - self.size.y);
+        }
+    }
+
+    fn viewport(&self) -> renderer_2d::Rect {
+        renderer_2d::Rect::new(
+            self.position.x as i32,
+            self.position.y as i32,
+            self.size.x as i32,
+            self.size.y as i32,
+        )
+    }
+
+    fn contains_point(&self, x: i32, y: i32) -> bool {
+        let r = self.viewport();
+        x >= r.x && x < r.x + r.width && y >= r.y && y < r.y + r.height
+    }
+
+    fn in_scrollbar(&self, x: i32, y: i32) -> bool {
+        let r = self.viewport();
+        self.contains_point(x, y) && x >= r.x + r.width - SCROLLBAR_WIDTH
+    }
+
+    /// Scrollbar thumb as `(y, height)`, sized by the visible fraction
+    fn thumb(&self) -> (i32, i32) {
+        let r = self.viewport();
+        let content = self.content_height().max(1.0);
+        let height = ((self.size.y / content).min(1.0) * self.size.y).max(8.0);
+        let travel = self.size.y - height;
+        let t = if self.max_offset() > 0.0 {
+            self.scroll_offset / self.max_offset()
+        } else {
+            0.0
+        };
+        (r.y + (t * travel) as i32, height as i32)
+    }
+
+    /// Scroll so the thumb is centered on `y`
+    fn drag_to(&mut self, y: i32) {
+        let (_, thumb_h) = self.thumb();
+        let travel = (self.size.y - thumb_h as f32).max(1.0);
+        let t = (y as f32 - self.position.y - thumb_h as f32 / 2.0) / travel;
+        self.scroll_to(t.clamp(0.0, 1.0) * self.max_offset());
+    }
+}
+
 /// Widget enum stores possible widget types
 #[derive(Debug, Clone)]
 pub enum Widget {
@@ -275,6 +495,80 @@ pub enum Widget {
     Label(Label),
     Toggle(Toggle),
     Slider(Slider),
+    ScrollList(ScrollList),
+    Panel(Panel),
+}
+
+impl Widget {
+    /// Identifier of the wrapped widget
+    pub fn id(&self) -> &str {
+        match self {
+            Widget::Button(b) => &b.id,
+            Widget::Label(l) => &l.id,
+            Widget::Toggle(t) => &t.id,
+            Widget::Slider(s) => &s.id,
+            Widget::ScrollList(l) => &l.id,
+            Widget::Panel(p) => &p.id,
+        }
+    }
+
+    fn set_position(&mut self, position: Vec2) {
+        match self {
+            Widget::Button(b) => b.position = position,
+            Widget::Label(l) => l.position = position,
+            Widget::Toggle(t) => t.position = position,
+            Widget::Slider(s) => s.position = position,
+            Widget::ScrollList(l) => l.position = position,
+            Widget::Panel(p) => p.position = position,
+        }
+    }
+
+    fn is_focusable(&self) -> bool {
+        matches!(
+            self,
+            Widget::Button(_) | Widget::Toggle(_) | Widget::Slider(_)
+        )
+    }
+
+    /// Tooltip text for widgets that support one
+    fn tooltip(&self) -> Option<&str> {
+        match self {
+            Widget::Button(b) => b.tooltip.as_deref(),
+            Widget::Toggle(t) => t.tooltip.as_deref(),
+            Widget::Slider(s) => s.tooltip.as_deref(),
+            Widget::Label(_) | Widget::ScrollList(_) | Widget::Panel(_) => None,
+        }
+    }
+
+    /// Hit-test ignoring the enabled flag, so disabled widgets can still explain themselves
+    fn hit_test(&self, x: i32, y: i32) -> bool {
+        match self {
+            Widget::Button(b) => b.contains_point(x, y),
+            Widget::Toggle(t) => {
+                let bx = t.position.x as i32;
+                let by = t.position.y as i32;
+                x >= bx && x < bx + 200 && y >= by && y < by + 12
+            }
+            Widget::Slider(s) => {
+                let sx = s.position.x as i32;
+                let sy = s.position.y as i32;
+                let (kx, ky, kw, kh) = s.knob_rect();
+                let in_bar =
+                    x >= sx && x < sx + s.size.x as i32 && y >= sy && y < sy + s.size.y as i32;
+                let in_knob = x >= kx && x < kx + kw && y >= ky && y < ky + kh;
+                in_bar || in_knob
+            }
+            Widget::Panel(p) => p.contains_point(x, y),
+            Widget::Label(_) | Widget::ScrollList(_) => false,
+        }
+    }
+}
+
+/// Tooltip hover tracking: the widget under the cursor and how long it has been hovered
+#[derive(Debug, Clone)]
+struct TooltipHover {
+    widget_id: String,
+    elapsed: f32,
 }
 
 /// UIManager manages widgets, input handling, layout and rendering
@@ -284,31 +578,72 @@ pub struct UIManager {
     widgets: Vec<Widget>,
     // fast lookup by id -> index in widgets
     index_by_id: HashMap<String, usize>,
+    /// Layer of each widget by id; widgets are kept sorted by it, ties in
+    /// insertion order, and the last widget is drawn and hit-tested on top
+    z_by_id: HashMap<String, i32>,
     pub theme: Theme,
     /// index of focused widget (if any)
     focus_index: Option<usize>,
+    /// Seconds a widget must be hovered before its tooltip appears
+    pub tooltip_delay: f32,
+    hover: Option<TooltipHover>,
+    mouse_position: (i32, i32),
+    /// Seconds accumulated by `update`, drives the focus pulse
+    animation_time: f32,
+    /// Modal layers above the widgets, top-most last
+    modals: Vec<UIManager>,
+    /// Widget receiving all mouse input while it drags (a slider)
+    pointer_capture: Option<String>,
 }
 impl UIManager {
-    /// Bring widget with id to front (render and hit-test order)
+    /// Bring widget with id to front (render and hit-test order) by giving
+    /// it the highest z in use
     pub fn bring_to_front(&mut self, id: &str) {
         if let Some(&idx) = self.index_by_id.get(id) {
-            if idx + 1 == self.widgets.len() {
-                return; // already front
-            }
+            let top = self.z_by_id.values().copied().max().unwrap_or(0);
+            self.z_by_id.insert(id.to_string(), top);
+            // last among the widgets at the top z
             let widget = self.widgets.remove(idx);
             self.widgets.push(widget);
+            self.restack();
+        }
+    }
 
-            // rebuild index map
-            self.index_by_id.clear();
-            for (i, w) in self.widgets.iter().enumerate() {
-                let wid = match w {
-                    Widget::Button(b) => b.id.clone(),
-                    Widget::Label(l) => l.id.clone(),
-                    Widget::Toggle(t) => t.id.clone(),
-                    Widget::Slider(s) => s.id.clone(),
-                };
-                self.index_by_id.insert(wid, i);
-            }
+    /// Id of the widget capturing the pointer (a dragged slider), if any
+    pub fn pointer_capture(&self) -> Option<&str> {
+        self.pointer_capture.as_deref()
+    }
+
+    /// Layer of a widget; higher z is drawn over and hit-tested before lower z
+    pub fn z_index(&self, id: &str) -> Option<i32> {
+        self.z_by_id.get(id).copied()
+    }
+
+    /// Move a widget to layer `z`
+    pub fn set_z_index(&mut self, id: &str, z: i32) {
+        if self.index_by_id.contains_key(id) {
+            self.z_by_id.insert(id.to_string(), z);
+            self.restack();
+        }
+    }
+
+    /// Sort widgets by z (stable, so equal z keeps insertion order) and
+    /// rebuild the id lookup, keeping focus on the same widget
+    fn restack(&mut self) {
+        let focused = self
+            .focus_index
+            .and_then(|fi| self.widgets.get(fi))
+            .map(|w| w.id().to_string());
+        let z_by_id = &self.z_by_id;
+        self.widgets
+            .sort_by_key(|w| z_by_id.get(w.id()).copied().unwrap_or(0));
+
+        self.index_by_id.clear();
+        for (i, w) in self.widgets.iter().enumerate() {
+            self.index_by_id.insert(w.id().to_string(), i);
+        }
+        if let Some(id) = focused {
+            self.focus_index = self.index_by_id.get(&id).copied();
         }
     }
 }
@@ -317,6 +652,20 @@ impl UIManager {
     // ...existing methods above remain; we'll add new behavior in handle_input/render below
 }
 
+/// Nine-patch skin used for scalable widget backgrounds
+#[derive(Debug, Clone)]
+pub struct NinePatch {
+    pub sprite: renderer_2d::Sprite,
+    /// (left, top, right, bottom) insets in source pixels
+    pub insets: (u32, u32, u32, u32),
+}
+
+impl NinePatch {
+    pub fn new(sprite: renderer_2d::Sprite, insets: (u32, u32, u32, u32)) -> Self {
+        Self { sprite, insets }
+    }
+}
+
 /// Simple UI theme for colors and sizes
 #[derive(Debug, Clone)]
 pub struct Theme {
@@ -324,7 +673,23 @@ pub struct Theme {
     pub button_bg_disabled: renderer_2d::Color,
     pub button_hover: renderer_2d::Color,
     pub button_pressed: renderer_2d::Color,
+    pub tooltip_bg: renderer_2d::Color,
     pub text_color: renderer_2d::Color,
+    pub border_color: renderer_2d::Color,
+    /// Integer scale passed to the renderer for widget text
+    pub text_scale: usize,
+    /// Horizontal/vertical space kept between a button's edge and its text
+    pub button_padding: Vec2,
+    /// Optional skin drawn instead of the flat button background and border
+    pub button_nine_patch: Option<NinePatch>,
+    /// Outline drawn around the focused widget
+    pub focus_color: renderer_2d::Color,
+    /// Color the focus outline pulses towards when `focus_pulse` is on
+    pub focus_pulse_color: renderer_2d::Color,
+    /// Animate the focus outline so keyboard focus is easy to track
+    pub focus_pulse: bool,
+    /// Translucent fill drawn under each modal to dim what's behind it
+    pub modal_overlay: renderer_2d::Color,
 }
 
 impl Default for Theme {
@@ -334,7 +699,16 @@ impl Default for Theme {
             button_bg_disabled: renderer_2d::Color::rgb(60, 60, 60),
             button_hover: renderer_2d::Color::rgb(60, 60, 120),
             button_pressed: renderer_2d::Color::rgb(20, 20, 60),
+            tooltip_bg: renderer_2d::Color::rgb(30, 30, 30),
             text_color: renderer_2d::Color::WHITE,
+            border_color: renderer_2d::Color::WHITE,
+            text_scale: 1,
+            button_padding: Vec2::new(8.0, 4.0),
+            button_nine_patch: None,
+            focus_color: renderer_2d::Color::YELLOW,
+            focus_pulse_color: renderer_2d::Color::rgb(160, 120, 0),
+            focus_pulse: true,
+            modal_overlay: renderer_2d::Color::rgba(0, 0, 0, 160),
         }
     }
 }
@@ -352,49 +726,259 @@ impl UIManager {
         Self {
             widgets: Vec::new(),
             index_by_id: HashMap::new(),
+            z_by_id: HashMap::new(),
             theme: Theme::default(),
             focus_index: None,
+            tooltip_delay: 0.5,
+            hover: None,
+            mouse_position: (0, 0),
+            animation_time: 0.0,
+            modals: Vec::new(),
+            pointer_capture: None,
         }
     }
 
-    /// Add a generic widget
+    /// Add a generic widget at z 0
     pub fn add_widget(&mut self, widget: Widget) {
-        let id = match &widget {
-            Widget::Button(b) => b.id.clone(),
-            Widget::Label(l) => l.id.clone(),
-            Widget::Toggle(t) => t.id.clone(),
-            Widget::Slider(s) => s.id.clone(),
-        };
-        let idx = self.widgets.len();
+        self.add_widget_with_z(widget, 0);
+    }
+
+    /// Add a widget on layer `z`; it goes above widgets with lower z and
+    /// ones with the same z added earlier
+    pub fn add_widget_with_z(&mut self, widget: Widget, z: i32) {
+        self.z_by_id.insert(widget.id().to_string(), z);
         self.widgets.push(widget);
-        self.index_by_id.insert(id, idx);
+        self.restack();
         // If no widget focused yet, give focus to the first focusable widget
         if self.focus_index.is_none() {
-            // find first button index
-            for (i, w) in self.widgets.iter().enumerate() {
-                match w {
-                    Widget::Button(_) | Widget::Toggle(_) | Widget::Slider(_) => {
-                        self.focus_index = Some(i);
-                        break;
+            self.focus_index = self.widgets.iter().position(Widget::is_focusable);
+        }
+        self.layout_scroll_lists();
+    }
+
+    /// Immutable access to a scroll list by id
+    pub fn get_scroll_list(&self, id: &str) -> Option<&ScrollList> {
+        if let Some(&idx) = self.index_by_id.get(id) {
+            if let Widget::ScrollList(l) = &self.widgets[idx] {
+                return Some(l);
+            }
+        }
+        None
+    }
+
+    /// Mutable access to a scroll list by id; call `layout_scroll_lists`
+    /// after scrolling it directly
+    pub fn get_scroll_list_mut(&mut self, id: &str) -> Option<&mut ScrollList> {
+        if let Some(&idx) = self.index_by_id.get(id) {
+            if let Widget::ScrollList(l) = &mut self.widgets[idx] {
+                return Some(l);
+            }
+        }
+        None
+    }
+
+    /// Move every scroll list's children to their scrolled row positions
+    pub fn layout_scroll_lists(&mut self) {
+        let mut placements = Vec::new();
+        for w in &self.widgets {
+            if let Widget::ScrollList(list) = w {
+                for (row, child) in list.children.iter().enumerate() {
+                    let y = row as f32 * list.item_height - list.scroll_offset;
+                    placements.push((child.clone(), list.position + Vec2::new(0.0, y)));
+                }
+            }
+        }
+        for (child, position) in placements {
+            if let Some(&idx) = self.index_by_id.get(&child) {
+                self.widgets[idx].set_position(position);
+            }
+        }
+    }
+
+    /// Index of the scroll list holding widget `idx`, with the child's row
+    fn parent_list(&self, idx: usize) -> Option<(usize, usize)> {
+        let id = self.widgets[idx].id();
+        self.widgets.iter().enumerate().find_map(|(i, w)| match w {
+            Widget::ScrollList(list) => list
+                .children
+                .iter()
+                .position(|c| c == id)
+                .map(|row| (i, row)),
+            _ => None,
+        })
+    }
+
+    /// Whether the mouse can reach widget `idx` at `(x, y)`: children of a
+    /// scroll list only inside its viewport
+    fn reachable(&self, idx: usize, x: i32, y: i32) -> bool {
+        match self.parent_list(idx) {
+            Some((list, _)) => match &self.widgets[list] {
+                Widget::ScrollList(list) => list.contains_point(x, y),
+                _ => true,
+            },
+            None => true,
+        }
+    }
+
+    /// Scroll the focused widget's list so the widget is fully visible
+    fn scroll_focus_into_view(&mut self) {
+        let Some(fi) = self.focus_index else {
+            return;
+        };
+        if fi >= self.widgets.len() {
+            return;
+        }
+        if let Some((list, row)) = self.parent_list(fi) {
+            if let Widget::ScrollList(list) = &mut self.widgets[list] {
+                list.ensure_visible(row);
+            }
+            self.layout_scroll_lists();
+        }
+    }
+
+    /// Scroll lists: wheel over a list, dragging its scrollbar, and Up/Down
+    /// moving focus between its children
+    fn handle_scroll_input(&mut self, input: &crate::input_window::WindowInputState) {
+        let (mx, my) = input.mouse_pos();
+        let left = crate::input_window::MouseButton::Left;
+        let just_pressed = input.is_mouse_button_just_pressed(left);
+        let held = input.is_mouse_button_pressed(left);
+        let mut wheel_used = false;
+        let captured = self.pointer_capture.is_some();
+
+        for w in self.widgets.iter_mut().rev() {
+            let Widget::ScrollList(list) = w else {
+                continue;
+            };
+            if captured {
+                continue;
+            }
+            if !wheel_used && input.scroll_delta != 0.0 && list.contains_point(mx, my) {
+                list.scroll_by(-input.scroll_delta * list.item_height);
+                wheel_used = true;
+            }
+            if just_pressed && list.in_scrollbar(mx, my) {
+                list.dragging = true;
+            }
+            if !held {
+                list.dragging = false;
+            }
+            if list.dragging {
+                list.drag_to(my);
+            }
+        }
+
+        let step: isize = if input.is_key_just_pressed(Key::Down) {
+            1
+        } else if input.is_key_just_pressed(Key::Up) {
+            -1
+        } else {
+            0
+        };
+        if step != 0 {
+            if let Some((list, row)) = self
+                .focus_index
+                .filter(|&fi| fi < self.widgets.len())
+                .and_then(|fi| self.parent_list(fi))
+            {
+                if let Widget::ScrollList(list) = &self.widgets[list] {
+                    let last = list.children.len() as isize - 1;
+                    let next = (row as isize + step).clamp(0, last) as usize;
+                    if let Some(&idx) = self.index_by_id.get(&list.children[next]) {
+                        self.focus_index = Some(idx);
                     }
-                    _ => {}
                 }
+                self.scroll_focus_into_view();
             }
         }
+
+        self.layout_scroll_lists();
     }
+
+    /// Open a modal dialog made of `widgets` on top of everything else.
+    /// Until it is popped (or Escape is pressed) it receives all input and
+    /// only its widgets emit events.
+    pub fn push_modal(&mut self, widgets: Vec<Widget>) {
+        let mut layer = UIManager::new();
+        layer.theme = self.theme.clone();
+        layer.tooltip_delay = self.tooltip_delay;
+        for widget in widgets {
+            layer.add_widget(widget);
+        }
+        // whatever was hovered underneath loses its tooltip
+        match self.modals.last_mut() {
+            Some(top) => top.hover = None,
+            None => self.hover = None,
+        }
+        self.modals.push(layer);
+    }
+
+    /// Close the top-most modal; returns false when none is open
+    pub fn pop_modal(&mut self) -> bool {
+        self.modals.pop().is_some()
+    }
+
+    /// Number of open modals
+    pub fn modal_depth(&self) -> usize {
+        self.modals.len()
+    }
+
+    /// The top-most modal layer, for looking up its widgets
+    pub fn top_modal_mut(&mut self) -> Option<&mut UIManager> {
+        self.modals.last_mut()
+    }
+
     /// Handle input and emit UI events. This will also call widget callbacks
     /// for clicks (Button.on_click). Provides hover & focus management and
     /// keyboard navigation (Tab / Shift+Tab + Enter/Space to activate).
     pub fn handle_input(&mut self, input: &crate::input_window::WindowInputState) -> Vec<UiEvent> {
+        if let Some(top) = self.modals.last_mut() {
+            if input.is_key_just_pressed(Key::Escape) {
+                self.modals.pop();
+                return Vec::new();
+            }
+            return top.handle_input(input);
+        }
+
         let mut events = Vec::new();
 
         let (mx, my) = input.mouse_pos();
+        self.mouse_position = (mx, my);
+
+        self.handle_scroll_input(input);
+        // while a widget holds the pointer, nothing else can be hovered or clicked
+        let reachable: Vec<bool> = (0..self.widgets.len())
+            .map(|i| match &self.pointer_capture {
+                Some(id) => self.widgets[i].id() == id,
+                None => self.reachable(i, mx, my),
+            })
+            .collect();
+
+        // Track the top-most widget with a tooltip under the cursor; the timer
+        // restarts whenever the hovered widget changes
+        let tooltip_target = self
+            .widgets
+            .iter()
+            .enumerate()
+            .rev()
+            .find(|(i, w)| reachable[*i] && w.tooltip().is_some() && w.hit_test(mx, my))
+            .map(|(_, w)| w.id().to_string());
+        match (&self.hover, tooltip_target) {
+            (Some(h), Some(id)) if h.widget_id == id => {}
+            (_, Some(id)) => {
+                self.hover = Some(TooltipHover {
+                    widget_id: id,
+                    elapsed: 0.0,
+                })
+            }
+            (_, None) => self.hover = None,
+        }
 
         // Update hover/pressed/drag state for widgets
-        for w in &mut self.widgets {
+        for (i, w) in self.widgets.iter_mut().enumerate() {
             match w {
                 Widget::Button(btn) => {
-                    let hover = btn.enabled && btn.contains_point(mx, my);
+                    let hover = reachable[i] && btn.enabled && btn.contains_point(mx, my);
                     btn.hovered = hover;
                     // pressed state while left mouse button held
                     btn.pressed = hover
@@ -406,13 +990,16 @@ impl UIManager {
                 Widget::Toggle(_t) => {
                     // toggles are simple; no per-frame pressed state tracked here
                 }
+                Widget::ScrollList(_) | Widget::Panel(_) => {
+                    // scrolling is handled by handle_scroll_input
+                }
                 Widget::Slider(s) => {
                     // if dragging, update value from mouse while left button held
                     if s.dragging
                         && input.is_mouse_button_pressed(crate::input_window::MouseButton::Left)
                     {
-                        let x = s.position.x as f32;
-                        let w = s.size.x as f32;
+                        let x = s.position.x;
+                        let w = s.size.x;
                         let ratio = ((mx as f32) - x) / w;
                         let val = s.min + ratio.clamp(0.0, 1.0) * (s.max - s.min);
                         s.set_value(val);
@@ -424,18 +1011,19 @@ impl UIManager {
         // Mouse click handling (top-most button)
         if input.is_mouse_button_just_pressed(crate::input_window::MouseButton::Left) {
             for i in (0..self.widgets.len()).rev() {
+                if !reachable[i] {
+                    continue;
+                }
                 match &mut self.widgets[i] {
-                    Widget::Button(btn) => {
-                        if btn.enabled && btn.contains_point(mx, my) {
-                            btn.call_click();
-                            events.push(UiEvent::Click(btn.id.clone()));
-                            // set focus to clicked widget
-                            self.focus_index = Some(i);
-                            // bring to front so it's rendered on top
-                            let id = btn.id.clone();
-                            self.bring_to_front(&id);
-                            break;
-                        }
+                    Widget::Button(btn) if btn.enabled && btn.contains_point(mx, my) => {
+                        btn.call_click();
+                        events.push(UiEvent::Click(btn.id.clone()));
+                        // set focus to clicked widget
+                        self.focus_index = Some(i);
+                        // bring to front so it's rendered on top
+                        let id = btn.id.clone();
+                        self.bring_to_front(&id);
+                        break;
                     }
                     Widget::Toggle(t) => {
                         // toggle if clicked on box or label area
@@ -464,9 +1052,10 @@ impl UIManager {
                         let in_knob = mx >= kx && mx < kx + kw && my >= ky && my < ky + kh;
                         if s.enabled && (in_bar || in_knob) {
                             s.dragging = true;
+                            self.pointer_capture = Some(s.id.clone());
                             // set value immediately
-                            let x = s.position.x as f32;
-                            let w = s.size.x as f32;
+                            let x = s.position.x;
+                            let w = s.size.x;
                             let ratio = ((mx as f32) - x) / w;
                             let val = s.min + ratio.clamp(0.0, 1.0) * (s.max - s.min);
                             s.set_value(val);
@@ -477,6 +1066,8 @@ impl UIManager {
                             break;
                         }
                     }
+                    // panels hide whatever lies below them
+                    Widget::Panel(p) if p.contains_point(mx, my) => break,
                     _ => {}
                 }
             }
@@ -484,6 +1075,7 @@ impl UIManager {
 
         // If left mouse released this frame, stop dragging sliders
         if !input.is_mouse_button_pressed(crate::input_window::MouseButton::Left) {
+            self.pointer_capture = None;
             for w in &mut self.widgets {
                 if let Widget::Slider(s) = w {
                     if s.dragging {
@@ -520,14 +1112,12 @@ impl UIManager {
                     (start + 1) % len
                 };
 
-                match &self.widgets[start] {
-                    Widget::Button(_) | Widget::Toggle(_) | Widget::Slider(_) => {
-                        self.focus_index = Some(start);
-                        break;
-                    }
-                    _ => {}
+                if self.widgets[start].is_focusable() {
+                    self.focus_index = Some(start);
+                    break;
                 }
             }
+            self.scroll_focus_into_view();
         }
 
         // Activation via keyboard
@@ -535,17 +1125,13 @@ impl UIManager {
             if let Some(fi) = self.focus_index {
                 if fi < self.widgets.len() {
                     match &mut self.widgets[fi] {
-                        Widget::Button(btn) => {
-                            if btn.enabled {
-                                btn.call_click();
-                                events.push(UiEvent::Click(btn.id.clone()));
-                            }
+                        Widget::Button(btn) if btn.enabled => {
+                            btn.call_click();
+                            events.push(UiEvent::Click(btn.id.clone()));
                         }
-                        Widget::Toggle(t) => {
-                            if t.enabled {
-                                t.call_change(!t.checked);
-                                events.push(UiEvent::Click(t.id.clone()));
-                            }
+                        Widget::Toggle(t) if t.enabled => {
+                            t.call_change(!t.checked);
+                            events.push(UiEvent::Click(t.id.clone()));
                         }
                         _ => {}
                     }
@@ -553,145 +1139,329 @@ impl UIManager {
             }
         }
 
+        // Keyboard adjustment of the focused slider: Left/Right by one
+        // increment, Home/End to the ends of the range
+        if let Some(Widget::Slider(s)) = self.focus_index.and_then(|fi| self.widgets.get_mut(fi)) {
+            if s.enabled {
+                let target = if input.is_key_just_pressed(Key::Home) {
+                    Some(s.min)
+                } else if input.is_key_just_pressed(Key::End) {
+                    Some(s.max)
+                } else if input.is_key_just_pressed(Key::Left) {
+                    Some(s.value - s.key_increment())
+                } else if input.is_key_just_pressed(Key::Right) {
+                    Some(s.value + s.key_increment())
+                } else {
+                    None
+                };
+                if let Some(v) = target {
+                    s.set_value_discrete(v);
+                }
+            }
+        }
+
         events
     }
 
-    /// Update UI (animations, etc). For now it's a no-op but kept for API completeness.
-    pub fn update(&mut self, _delta_time: f32) {
-        // placeholder for transitions/animations
+    /// Update UI timers (tooltip hover duration, focus animation)
+    pub fn update(&mut self, delta_time: f32) {
+        self.animation_time += delta_time;
+        if let Some(hover) = &mut self.hover {
+            hover.elapsed += delta_time;
+        }
+        for modal in &mut self.modals {
+            modal.update(delta_time);
+        }
+    }
+
+    /// Current focus outline color; pulses once per second between the
+    /// theme's `focus_color` and `focus_pulse_color`
+    pub fn focus_color(&self) -> renderer_2d::Color {
+        if !self.theme.focus_pulse {
+            return self.theme.focus_color;
+        }
+        let phase = (self.animation_time * std::f32::consts::TAU).sin() * 0.5 + 0.5;
+        self.theme
+            .focus_color
+            .lerp(self.theme.focus_pulse_color, phase)
+    }
+
+    /// The `(widget_id, text)` of the tooltip currently shown, if the hovered
+    /// widget has been hovered for at least `tooltip_delay` seconds
+    pub fn active_tooltip(&self) -> Option<(&str, &str)> {
+        let hover = self.hover.as_ref()?;
+        if hover.elapsed < self.tooltip_delay {
+            return None;
+        }
+        let idx = *self.index_by_id.get(&hover.widget_id)?;
+        let text = self.widgets[idx].tooltip()?;
+        Some((hover.widget_id.as_str(), text))
+    }
+
+    /// Draw the active tooltip next to the cursor, flipping to the other side
+    /// of the cursor when it would leave the buffer
+    fn render_tooltip(&self, renderer: &mut renderer_2d::Renderer2D) {
+        let Some((_, text)) = self.active_tooltip() else {
+            return;
+        };
+        let padding = 4;
+        let offset = (12, 16);
+        let (text_w, text_h) = renderer.measure_text(text, 1);
+        let w = text_w as i32 + padding * 2;
+        let h = text_h as i32 + padding * 2;
+        let (buf_w, buf_h) = renderer.dimensions();
+        let (mx, my) = self.mouse_position;
+
+        let mut x = mx + offset.0;
+        if x + w > buf_w as i32 {
+            x = mx - offset.0 - w;
+        }
+        let mut y = my + offset.1;
+        if y + h > buf_h as i32 {
+            y = my - offset.1 - h;
+        }
+        let x = x.clamp(0, (buf_w as i32 - w).max(0));
+        let y = y.clamp(0, (buf_h as i32 - h).max(0));
+
+        renderer.draw_rect(x, y, w, h, self.theme.tooltip_bg);
+        renderer.draw_rect_outline(x, y, w, h, self.theme.border_color);
+        renderer.draw_text(
+            text,
+            (x + padding) as usize,
+            (y + padding) as usize,
+            self.theme.text_color,
+            1,
+        );
     }
 
     /// Render all widgets using the provided renderer
     pub fn render(&self, renderer: &mut renderer_2d::Renderer2D) {
+        let focus_color = self.focus_color();
         for (i, widget) in self.widgets.iter().enumerate() {
-            match widget {
-                Widget::Button(btn) => {
-                    let x = btn.position.x as i32;
-                    let y = btn.position.y as i32;
-                    let w = btn.size.x as i32;
-                    let h = btn.size.y as i32;
-
-                    let bg = if !btn.enabled {
-                        self.theme.button_bg_disabled
-                    } else if btn.pressed {
-                        self.theme.button_pressed
-                    } else if btn.hovered {
-                        self.theme.button_hover
-                    } else {
-                        self.theme.button_bg
-                    };
+            // scroll list children are drawn by their list, clipped to it
+            if self.parent_list(i).is_none() {
+                self.render_widget(renderer, i, widget, focus_color);
+            }
+        }
 
-                    renderer.draw_rect(x, y, w, h, bg);
-                    // border
-                    renderer.draw_rect_outline(x, y, w, h, renderer_2d::Color::WHITE);
-
-                    // focus outline if focused
-                    if let Some(fi) = self.focus_index {
-                        if fi == i {
-                            renderer.draw_rect_outline(
-                                x - 2,
-                                y - 2,
-                                w + 4,
-                                h + 4,
-                                renderer_2d::Color::YELLOW,
-                            );
-                        }
-                    }
+        // tooltip goes on top of everything but modals
+        self.render_tooltip(renderer);
 
-                    // text centered
-                    let center_x = (x + w / 2) as usize;
-                    let text_y = (y + h / 2 - 8) as usize;
-                    renderer.draw_text_centered(
-                        &btn.text,
-                        center_x,
-                        text_y,
-                        self.theme.text_color,
-                        1,
+        let (width, height) = renderer.dimensions();
+        for modal in &self.modals {
+            renderer.draw_rect_blended(0, 0, width as i32, height as i32, self.theme.modal_overlay);
+            modal.render(renderer);
+        }
+    }
+
+    fn render_widget(
+        &self,
+        renderer: &mut renderer_2d::Renderer2D,
+        i: usize,
+        widget: &Widget,
+        focus_color: renderer_2d::Color,
+    ) {
+        match widget {
+            Widget::Button(btn) => {
+                let x = btn.position.x as i32;
+                let y = btn.position.y as i32;
+                let w = btn.size.x as i32;
+                let h = btn.size.y as i32;
+
+                let bg = if !btn.enabled {
+                    self.theme.button_bg_disabled
+                } else if btn.pressed {
+                    self.theme.button_pressed
+                } else if btn.hovered {
+                    self.theme.button_hover
+                } else {
+                    self.theme.button_bg
+                };
+
+                if let Some(skin) = &self.theme.button_nine_patch {
+                    renderer.draw_nine_patch(
+                        &skin.sprite,
+                        renderer_2d::Rect::new(x, y, w, h),
+                        skin.insets,
                     );
+                } else {
+                    renderer.draw_rect(x, y, w, h, bg);
+                    // border
+                    renderer.draw_rect_outline(x, y, w, h, self.theme.border_color);
                 }
-                Widget::Label(lbl) => {
-                    let x = lbl.position.x as usize;
-                    let y = lbl.position.y as usize;
-                    renderer.draw_text(&lbl.text, x, y, self.theme.text_color, 1);
-                }
-                Widget::Toggle(t) => {
-                    // draw a box and label
-                    let box_x = t.position.x as i32;
-                    let box_y = t.position.y as i32;
-                    let box_size = 12;
-                    let bg = if !t.enabled {
-                        self.theme.button_bg_disabled
-                    } else if t.checked {
-                        self.theme.button_pressed
-                    } else {
-                        self.theme.button_bg
-                    };
-                    renderer.draw_rect(box_x, box_y, box_size, box_size, bg);
-                    renderer.draw_rect_outline(
-                        box_x,
-                        box_y,
-                        box_size,
-                        box_size,
-                        renderer_2d::Color::WHITE,
-                    );
-                    // checkmark when checked
-                    if t.checked {
-                        // simple X mark
-                        renderer.draw_text(
-                            "X",
-                            (box_x + 3) as usize,
-                            box_y as usize,
-                            renderer_2d::Color::WHITE,
-                            1,
-                        );
+
+                // focus outline if focused
+                if let Some(fi) = self.focus_index {
+                    if fi == i {
+                        renderer.draw_rect_outline(x - 2, y - 2, w + 4, h + 4, focus_color);
                     }
-                    // label text to the right
+                }
+
+                // text centered inside the padded content area; falls back to
+                // left alignment when the text is wider than the content area
+                let scale = self.theme.text_scale;
+                let (text_w, text_h) = renderer.measure_text(&btn.text, scale);
+                let pad_x = self.theme.button_padding.x as i32;
+                let pad_y = self.theme.button_padding.y as i32;
+                let content_w = (w - 2 * pad_x).max(0);
+                let content_h = (h - 2 * pad_y).max(0);
+                let text_x = x + pad_x + ((content_w - text_w as i32) / 2).max(0);
+                let text_y = y + pad_y + (content_h - text_h as i32) / 2;
+                renderer.draw_text(
+                    &btn.text,
+                    text_x.max(0) as usize,
+                    text_y.max(0) as usize,
+                    self.theme.text_color,
+                    scale,
+                );
+            }
+            Widget::Label(lbl) => {
+                let x = lbl.position.x as usize;
+                let y = lbl.position.y as usize;
+                renderer.draw_text(
+                    &lbl.text,
+                    x,
+                    y,
+                    self.theme.text_color,
+                    self.theme.text_scale,
+                );
+            }
+            Widget::Toggle(t) => {
+                // draw a box and label
+                let box_x = t.position.x as i32;
+                let box_y = t.position.y as i32;
+                let box_size = 12;
+                let bg = if !t.enabled {
+                    self.theme.button_bg_disabled
+                } else if t.checked {
+                    self.theme.button_pressed
+                } else {
+                    self.theme.button_bg
+                };
+                renderer.draw_rect(box_x, box_y, box_size, box_size, bg);
+                renderer.draw_rect_outline(
+                    box_x,
+                    box_y,
+                    box_size,
+                    box_size,
+                    self.theme.border_color,
+                );
+                // checkmark when checked
+                if t.checked {
+                    // simple X mark
                     renderer.draw_text(
-                        &t.label,
-                        (box_x + box_size + 4) as usize,
+                        "X",
+                        (box_x + 3) as usize,
                         box_y as usize,
-                        self.theme.text_color,
+                        renderer_2d::Color::WHITE,
                         1,
                     );
-                    // focus outline
-                    if let Some(fi) = self.focus_index {
-                        if fi == i {
-                            renderer.draw_rect_outline(
-                                box_x - 2,
-                                box_y - 2,
-                                box_size + 4,
-                                box_size + 4,
-                                renderer_2d::Color::YELLOW,
-                            );
-                        }
+                }
+                // label text to the right
+                renderer.draw_text(
+                    &t.label,
+                    (box_x + box_size + 4) as usize,
+                    box_y as usize,
+                    self.theme.text_color,
+                    self.theme.text_scale,
+                );
+                // focus outline
+                if let Some(fi) = self.focus_index {
+                    if fi == i {
+                        renderer.draw_rect_outline(
+                            box_x - 2,
+                            box_y - 2,
+                            box_size + 4,
+                            box_size + 4,
+                            focus_color,
+                        );
                     }
                 }
-                Widget::Slider(s) => {
-                    let x = s.position.x as i32;
-                    let y = s.position.y as i32;
-                    let w = s.size.x as i32;
-                    let h = s.size.y as i32;
-                    // track background
-                    renderer.draw_rect(x, y + h / 3, w, h / 3, self.theme.button_bg);
-                    // knob
-                    let (kx, ky, kw, kh) = s.knob_rect();
-                    renderer.draw_rect(kx, ky, kw, kh, self.theme.button_hover);
-                    renderer.draw_rect_outline(kx, ky, kw, kh, renderer_2d::Color::WHITE);
-                    // focus outline for slider
-                    if let Some(fi) = self.focus_index {
-                        if fi == i {
-                            renderer.draw_rect_outline(
-                                x - 2,
-                                y - 2,
-                                w + 4,
-                                h + 4,
-                                renderer_2d::Color::YELLOW,
-                            );
-                        }
+            }
+            Widget::Slider(s) => {
+                let x = s.position.x as i32;
+                let y = s.position.y as i32;
+                let w = s.size.x as i32;
+                let h = s.size.y as i32;
+                // track background
+                renderer.draw_rect(x, y + h / 3, w, h / 3, self.theme.button_bg);
+                // knob
+                let (kx, ky, kw, kh) = s.knob_rect();
+                renderer.draw_rect(kx, ky, kw, kh, self.theme.button_hover);
+                renderer.draw_rect_outline(kx, ky, kw, kh, self.theme.border_color);
+                // focus outline for slider
+                if let Some(fi) = self.focus_index {
+                    if fi == i {
+                        renderer.draw_rect_outline(x - 2, y - 2, w + 4, h + 4, focus_color);
                     }
                 }
             }
+            Widget::ScrollList(list) => self.render_scroll_list(renderer, list, focus_color),
+            Widget::Panel(p) => renderer.draw_rect(
+                p.position.x as i32,
+                p.position.y as i32,
+                p.size.x as i32,
+                p.size.y as i32,
+                p.color,
+            ),
+        }
+    }
+
+    fn render_scroll_list(
+        &self,
+        renderer: &mut renderer_2d::Renderer2D,
+        list: &ScrollList,
+        focus_color: renderer_2d::Color,
+    ) {
+        let view = list.viewport();
+        renderer.draw_rect(
+            view.x,
+            view.y,
+            view.width,
+            view.height,
+            self.theme.tooltip_bg,
+        );
+
+        let previous_clip = renderer.clip_rect();
+        let clip = match previous_clip {
+            Some(outer) => outer.intersect(&view),
+            None => Some(view),
+        };
+        if let Some(clip) = clip {
+            renderer.set_clip_rect(Some(clip));
+            for child in &list.children {
+                if let Some(&idx) = self.index_by_id.get(child) {
+                    self.render_widget(renderer, idx, &self.widgets[idx], focus_color);
+                }
+            }
+            renderer.set_clip_rect(previous_clip);
+        }
+
+        if list.max_offset() > 0.0 {
+            let bar_x = view.x + view.width - SCROLLBAR_WIDTH;
+            let (thumb_y, thumb_h) = list.thumb();
+            renderer.draw_rect(
+                bar_x,
+                view.y,
+                SCROLLBAR_WIDTH,
+                view.height,
+                self.theme.button_bg,
+            );
+            renderer.draw_rect(
+                bar_x,
+                thumb_y,
+                SCROLLBAR_WIDTH,
+                thumb_h,
+                self.theme.button_hover,
+            );
         }
+        renderer.draw_rect_outline(
+            view.x,
+            view.y,
+            view.width,
+            view.height,
+            self.theme.border_color,
+        );
     }
 
     /// Mutable access to a label by id
@@ -734,3 +1504,414 @@ impl UIManager {
         None
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::renderer_2d::{Color, Renderer2D};
+
+    /// Bounding box `(min_x, min_y, max_x, max_y)` of all pixels with `color`
+    fn bounding_box(renderer: &Renderer2D, color: Color) -> Option<(i32, i32, i32, i32)> {
+        let (w, h) = renderer.dimensions();
+        let mut bounds: Option<(i32, i32, i32, i32)> = None;
+        for y in 0..h as i32 {
+            for x in 0..w as i32 {
+                if renderer.get_pixel(x, y) == Some(color) {
+                    bounds = Some(match bounds {
+                        Some((x0, y0, x1, y1)) => (x0.min(x), y0.min(y), x1.max(x), y1.max(y)),
+                        None => (x, y, x, y),
+                    });
+                }
+            }
+        }
+        bounds
+    }
+
+    fn rendered_label_height(text_scale: usize) -> i32 {
+        let mut ui = UIManager::new();
+        ui.theme.text_color = Color::GREEN;
+        ui.theme.text_scale = text_scale;
+        ui.add_widget(Widget::Label(Label::new(
+            "title",
+            "TEST",
+            Vec2::new(10.0, 10.0),
+        )));
+
+        let mut renderer = Renderer2D::new(200, 100);
+        renderer.clear(Color::BLACK);
+        ui.render(&mut renderer);
+
+        let (_, y0, _, y1) = bounding_box(&renderer, Color::GREEN).expect("text was drawn");
+        y1 - y0 + 1
+    }
+
+    #[test]
+    fn test_tooltip_appears_after_hover_delay() {
+        let mut ui = UIManager::new();
+        let mut button = Button::new(
+            "locked",
+            "Locked",
+            Vec2::new(10.0, 10.0),
+            Vec2::new(80.0, 20.0),
+        )
+        .with_tooltip("Finish level 1 first");
+        button.enabled = false;
+        ui.add_widget(Widget::Button(button));
+
+        let mut input = crate::input_window::WindowInputState {
+            mouse_position: (20, 15),
+            ..Default::default()
+        };
+
+        ui.handle_input(&input);
+        ui.update(ui.tooltip_delay * 0.5);
+        assert!(ui.active_tooltip().is_none());
+
+        ui.handle_input(&input);
+        ui.update(ui.tooltip_delay);
+        assert_eq!(
+            ui.active_tooltip(),
+            Some(("locked", "Finish level 1 first"))
+        );
+
+        // moving away hides it again
+        input.mouse_position = (150, 90);
+        ui.handle_input(&input);
+        assert!(ui.active_tooltip().is_none());
+    }
+
+    #[test]
+    fn test_text_scale_doubles_text_height() {
+        let single = rendered_label_height(1);
+        let double = rendered_label_height(2);
+        assert_eq!(double, single * 2);
+    }
+
+    #[test]
+    fn test_focus_outline_pulses_over_time() {
+        let mut ui = UIManager::new();
+        ui.add_widget(Widget::Button(Button::new(
+            "play",
+            "Play",
+            Vec2::new(10.0, 10.0),
+            Vec2::new(80.0, 20.0),
+        )));
+
+        let start = ui.focus_color();
+        assert_eq!(
+            start,
+            ui.theme.focus_color.lerp(ui.theme.focus_pulse_color, 0.5)
+        );
+        ui.update(0.25);
+        let peak = ui.focus_color();
+        assert_ne!(start, peak);
+        assert_eq!(peak, ui.theme.focus_pulse_color);
+
+        let mut renderer = Renderer2D::new(120, 50);
+        renderer.clear(Color::BLACK);
+        ui.render(&mut renderer);
+        assert_eq!(renderer.get_pixel(8, 8), Some(peak));
+
+        ui.theme.focus_pulse = false;
+        assert_eq!(ui.focus_color(), ui.theme.focus_color);
+    }
+
+    #[test]
+    fn test_scroll_list_clamps_and_follows_focus() {
+        use crate::input_window::{MouseButton, WindowInputState};
+
+        let mut ui = UIManager::new();
+        let ids: Vec<String> = (0..10).map(|i| format!("score{}", i)).collect();
+        for id in &ids {
+            ui.add_widget(Widget::Button(Button::new(
+                id,
+                id,
+                Vec2::new(0.0, 0.0),
+                Vec2::new(100.0, 20.0),
+            )));
+        }
+        let children: Vec<&str> = ids.iter().map(String::as_str).collect();
+        ui.add_widget(Widget::ScrollList(
+            ScrollList::new(
+                "scores",
+                Vec2::new(10.0, 10.0),
+                Vec2::new(120.0, 60.0),
+                20.0,
+            )
+            .with_children(&children),
+        ));
+
+        // wheel far past the bottom stops at the last full page
+        let wheel = WindowInputState {
+            mouse_position: (20, 20),
+            scroll_delta: -100.0,
+            ..Default::default()
+        };
+        ui.handle_input(&wheel);
+        assert_eq!(ui.get_scroll_list("scores").unwrap().scroll_offset(), 140.0);
+
+        let wheel_up = WindowInputState {
+            scroll_delta: 100.0,
+            ..wheel
+        };
+        ui.handle_input(&wheel_up);
+        assert_eq!(ui.get_scroll_list("scores").unwrap().scroll_offset(), 0.0);
+
+        // score3 sits just below the viewport, so it can't be clicked
+        let mut click = WindowInputState {
+            mouse_position: (20, 80),
+            ..Default::default()
+        };
+        click.mouse_buttons.insert(MouseButton::Left);
+        click.mouse_buttons_just_pressed.insert(MouseButton::Left);
+        assert!(ui.handle_input(&click).is_empty());
+
+        // moving focus down to score4 scrolls it into view
+        let mut down = WindowInputState::default();
+        down.keys_just_pressed.insert(Key::Down);
+        for _ in 0..4 {
+            ui.handle_input(&down);
+        }
+        assert_eq!(ui.get_scroll_list("scores").unwrap().scroll_offset(), 40.0);
+        let focused = ui.get_button_mut("score4").unwrap();
+        assert_eq!(focused.position.y, 50.0);
+
+        // score1 now straddles the top edge and is clipped there
+        let mut renderer = Renderer2D::new(200, 100);
+        renderer.clear(Color::BLACK);
+        ui.render(&mut renderer);
+        assert_eq!(renderer.get_pixel(20, 5), Some(Color::BLACK));
+        assert_eq!(renderer.get_pixel(20, 12), Some(ui.theme.button_bg));
+        assert_eq!(renderer.clip_rect(), None);
+    }
+
+    #[test]
+    fn test_modal_captures_clicks_until_dismissed() {
+        use crate::input_window::{MouseButton, WindowInputState};
+        use std::cell::Cell;
+        use std::rc::Rc;
+
+        let quits = Rc::new(Cell::new(0));
+        let confirms = Rc::new(Cell::new(0));
+        let mut ui = UIManager::new();
+        let counter = quits.clone();
+        ui.add_widget(Widget::Button(
+            Button::new("quit", "Quit", Vec2::new(10.0, 10.0), Vec2::new(80.0, 20.0))
+                .on_click(Box::new(move || counter.set(counter.get() + 1))),
+        ));
+
+        let counter = confirms.clone();
+        ui.push_modal(vec![Widget::Button(
+            Button::new("yes", "Yes", Vec2::new(50.0, 60.0), Vec2::new(60.0, 20.0))
+                .on_click(Box::new(move || counter.set(counter.get() + 1))),
+        )]);
+        assert_eq!(ui.modal_depth(), 1);
+
+        let click_at = |x, y| {
+            let mut input = WindowInputState {
+                mouse_position: (x, y),
+                ..Default::default()
+            };
+            input.mouse_buttons.insert(MouseButton::Left);
+            input.mouse_buttons_just_pressed.insert(MouseButton::Left);
+            input
+        };
+
+        assert!(ui.handle_input(&click_at(20, 15)).is_empty());
+        assert_eq!(quits.get(), 0);
+
+        let events = ui.handle_input(&click_at(60, 65));
+        assert!(matches!(events.as_slice(), [UiEvent::Click(id)] if id == "yes"));
+        assert_eq!(confirms.get(), 1);
+
+        // the background is dimmed under the dialog
+        let mut renderer = Renderer2D::new(160, 100);
+        renderer.clear(Color::WHITE);
+        ui.render(&mut renderer);
+        let dimmed = renderer.get_pixel(150, 90).unwrap();
+        assert!(dimmed.r() < 255 && dimmed.r() > 0);
+
+        let mut escape = WindowInputState::default();
+        escape.keys_just_pressed.insert(Key::Escape);
+        assert!(ui.handle_input(&escape).is_empty());
+        assert_eq!(ui.modal_depth(), 0);
+
+        ui.handle_input(&click_at(20, 15));
+        assert_eq!(quits.get(), 1);
+    }
+
+    #[test]
+    fn test_slider_step_snaps_value_and_knob() {
+        use std::cell::Cell;
+        use std::rc::Rc;
+
+        let emitted = Rc::new(Cell::new(None));
+        let sink = emitted.clone();
+        let mut slider = Slider::new(
+            "difficulty",
+            Vec2::new(0.0, 0.0),
+            Vec2::new(100.0, 10.0),
+            0.0,
+            1.0,
+            0.0,
+        )
+        .with_step(0.25)
+        .on_change(Box::new(move |v| sink.set(Some(v))));
+
+        slider.set_value(0.3);
+        assert_eq!(slider.value, 0.25);
+        assert_eq!(emitted.get(), Some(0.25));
+        assert_eq!(slider.knob_rect().0, 25 - 6);
+
+        // below the configured threshold nothing is re-emitted
+        let sink = emitted.clone();
+        let mut coarse = Slider::new(
+            "volume",
+            Vec2::new(0.0, 0.0),
+            Vec2::new(100.0, 10.0),
+            0.0,
+            1.0,
+            0.0,
+        )
+        .with_emit_threshold(0.1)
+        .on_change(Box::new(move |v| sink.set(Some(v))));
+        coarse.set_value(0.5);
+        coarse.set_value(0.55);
+        assert_eq!(emitted.get(), Some(0.5));
+    }
+
+    #[test]
+    fn test_focused_slider_follows_arrow_keys() {
+        use crate::input_window::WindowInputState;
+        use std::cell::Cell;
+        use std::rc::Rc;
+
+        let emitted = Rc::new(Cell::new(None));
+        let sink = emitted.clone();
+        let mut ui = UIManager::new();
+        ui.add_widget(Widget::Slider(
+            Slider::new(
+                "difficulty",
+                Vec2::new(10.0, 10.0),
+                Vec2::new(100.0, 10.0),
+                0.0,
+                1.0,
+                0.25,
+            )
+            .with_step(0.25)
+            .on_change(Box::new(move |v| sink.set(Some(v)))),
+        ));
+        ui.focus_index = Some(0);
+
+        let mut right = WindowInputState::default();
+        right.keys_just_pressed.insert(Key::Right);
+        ui.handle_input(&right);
+        ui.handle_input(&right);
+        assert_eq!(ui.get_slider("difficulty").unwrap().value, 0.75);
+        assert_eq!(emitted.get(), Some(0.75));
+
+        let mut home = WindowInputState::default();
+        home.keys_just_pressed.insert(Key::Home);
+        ui.handle_input(&home);
+        assert_eq!(ui.get_slider("difficulty").unwrap().value, 0.0);
+        assert_eq!(emitted.get(), Some(0.0));
+    }
+
+    #[test]
+    fn test_z_index_orders_rendering_and_hit_testing() {
+        use crate::input_window::{MouseButton, WindowInputState};
+
+        let mut ui = UIManager::new();
+        ui.add_widget_with_z(
+            Widget::Button(Button::new(
+                "play",
+                "",
+                Vec2::new(10.0, 10.0),
+                Vec2::new(40.0, 20.0),
+            )),
+            1,
+        );
+        // a background panel added later still stays underneath
+        ui.add_widget_with_z(
+            Widget::Panel(Panel::new(
+                "background",
+                Vec2::new(0.0, 0.0),
+                Vec2::new(100.0, 50.0),
+                Color::rgb(10, 10, 10),
+            )),
+            -1,
+        );
+        ui.add_widget(Widget::Button(Button::new(
+            "behind",
+            "",
+            Vec2::new(30.0, 10.0),
+            Vec2::new(40.0, 20.0),
+        )));
+
+        let mut renderer = Renderer2D::new(100, 50);
+        ui.render(&mut renderer);
+        assert_eq!(renderer.get_pixel(20, 20), Some(ui.theme.button_bg));
+        assert_eq!(renderer.get_pixel(80, 40), Some(Color::rgb(10, 10, 10)));
+
+        // "play" overlaps "behind" and wins the click on its higher layer
+        let mut click = WindowInputState {
+            mouse_position: (40, 20),
+            ..Default::default()
+        };
+        click.mouse_buttons.insert(MouseButton::Left);
+        click.mouse_buttons_just_pressed.insert(MouseButton::Left);
+        let events = ui.handle_input(&click);
+        assert!(matches!(events.as_slice(), [UiEvent::Click(id)] if id == "play"));
+
+        ui.bring_to_front("behind");
+        assert_eq!(ui.z_index("behind"), Some(1));
+        let events = ui.handle_input(&click);
+        assert!(matches!(events.as_slice(), [UiEvent::Click(id)] if id == "behind"));
+    }
+
+    #[test]
+    fn test_dragged_slider_captures_pointer() {
+        use crate::input_window::{MouseButton, WindowInputState};
+        use std::cell::Cell;
+        use std::rc::Rc;
+
+        let clicks = Rc::new(Cell::new(0));
+        let counter = clicks.clone();
+        let mut ui = UIManager::new();
+        ui.add_widget(Widget::Slider(Slider::new(
+            "volume",
+            Vec2::new(10.0, 10.0),
+            Vec2::new(100.0, 10.0),
+            0.0,
+            1.0,
+            0.5,
+        )));
+        ui.add_widget(Widget::Button(
+            Button::new("quit", "Quit", Vec2::new(10.0, 40.0), Vec2::new(80.0, 20.0))
+                .on_click(Box::new(move || counter.set(counter.get() + 1))),
+        ));
+
+        let press_at = |x, y| {
+            let mut input = WindowInputState {
+                mouse_position: (x, y),
+                ..Default::default()
+            };
+            input.mouse_buttons.insert(MouseButton::Left);
+            input.mouse_buttons_just_pressed.insert(MouseButton::Left);
+            input
+        };
+
+        ui.handle_input(&press_at(60, 15));
+        assert_eq!(ui.pointer_capture(), Some("volume"));
+
+        // pressing over the button mid-drag still only moves the slider
+        assert!(ui.handle_input(&press_at(30, 50)).is_empty());
+        assert_eq!(clicks.get(), 0);
+        assert_eq!(ui.get_slider("volume").unwrap().value, 0.2);
+
+        ui.handle_input(&WindowInputState::default());
+        assert_eq!(ui.pointer_capture(), None);
+        ui.handle_input(&press_at(30, 50));
+        assert_eq!(clicks.get(), 1);
+    }
+}
@@ -0,0 +1,105 @@
+//! Small general-purpose helpers shared by gameplay code.
+
+use crate::RngResource;
+
+/// Items picked at random in proportion to their weights, e.g. a loot table.
+///
+/// Sampling draws from an `RngResource`, so a seeded game drops the same items.
+#[derive(Debug, Clone)]
+pub struct WeightedTable<T> {
+    /// Items with the running total of weights up to and including each one
+    entries: Vec<(T, f32)>,
+    total: f32,
+}
+
+impl<T> Default for WeightedTable<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T> WeightedTable<T> {
+    pub fn new() -> Self {
+        Self {
+            entries: Vec::new(),
+            total: 0.0,
+        }
+    }
+
+    /// Add `item` with a relative `weight`; non-positive weights are ignored
+    pub fn add(&mut self, item: T, weight: f32) {
+        if weight > 0.0 {
+            self.total += weight;
+            self.entries.push((item, self.total));
+        }
+    }
+
+    /// Builder form of `add`
+    pub fn with(mut self, item: T, weight: f32) -> Self {
+        self.add(item, weight);
+        self
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Sum of all weights
+    pub fn total_weight(&self) -> f32 {
+        self.total
+    }
+
+    /// Pick an item using cumulative weights. Panics if the table is empty.
+    pub fn sample(&self, rng: &mut RngResource) -> &T {
+        self.try_sample(rng)
+            .expect("sampled an empty WeightedTable")
+    }
+
+    /// Pick an item, or `None` if the table is empty
+    pub fn try_sample(&self, rng: &mut RngResource) -> Option<&T> {
+        let last = self.entries.last()?;
+        let roll = rng.next_f32() * self.total;
+        let index = self
+            .entries
+            .partition_point(|(_, cumulative)| *cumulative <= roll);
+        // rounding can push `roll` up to `total`; fall back to the last item
+        Some(&self.entries.get(index).unwrap_or(last).0)
+    }
+
+    /// Items in insertion order
+    pub fn items(&self) -> impl Iterator<Item = &T> {
+        self.entries.iter().map(|(item, _)| item)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_weighted_sampling_ratio_and_determinism() {
+        let table = WeightedTable::new().with("rare", 1.0).with("common", 3.0);
+        assert_eq!(table.total_weight(), 4.0);
+
+        let mut rng = RngResource::new(7);
+        let samples = 40_000;
+        let rare = (0..samples)
+            .filter(|_| *table.sample(&mut rng) == "rare")
+            .count();
+        let ratio = rare as f32 / (samples - rare) as f32;
+        assert!((ratio - 1.0 / 3.0).abs() < 0.02, "ratio {}", ratio);
+
+        let draws = |seed| {
+            let mut rng = RngResource::new(seed);
+            (0..20).map(|_| *table.sample(&mut rng)).collect::<Vec<_>>()
+        };
+        assert_eq!(draws(99), draws(99));
+
+        let empty: WeightedTable<u8> = WeightedTable::new().with(1, 0.0);
+        assert!(empty.try_sample(&mut rng).is_none());
+    }
+}
@@ -3,7 +3,9 @@
 //! Enhances the renderer_2d with advanced visual effects like glow,
 //! trails, screen shake, and post-processing effects.
 
-use crate::Vec2;
+use crate::renderer_2d::{Color, Renderer2D};
+use crate::{Time, Vec2};
+use specs::{Component, DenseVecStorage, Entities, Join, Read, System, WriteStorage};
 use std::collections::VecDeque;
 
 /// Glow effect configuration
@@ -95,6 +97,53 @@ impl TrailEffect {
     }
 }
 
+/// Draw a translucent motion-blur streak behind a moving object: a capsule of
+/// `radius` from where it was `exposure` seconds ago to `position`, fading
+/// from transparent at the tail to `color`'s alpha at the head. Faster objects
+/// get proportionally longer streaks; a stationary one draws only its disc.
+pub fn draw_motion_blur(
+    renderer: &mut Renderer2D,
+    position: Vec2,
+    velocity: Vec2,
+    exposure: f32,
+    radius: f32,
+    color: Color,
+) {
+    let tail = position - velocity * exposure;
+    let axis = position - tail;
+    let length_sq = axis.norm_squared();
+    let min = tail
+        .inf(&position)
+        .add_scalar(-radius)
+        .map(|v| v.floor() as i32);
+    let max = tail
+        .sup(&position)
+        .add_scalar(radius)
+        .map(|v| v.ceil() as i32);
+
+    for y in min.y..=max.y {
+        for x in min.x..=max.x {
+            let p = Vec2::new(x as f32, y as f32);
+            let t = if length_sq > 0.0 {
+                ((p - tail).dot(&axis) / length_sq).clamp(0.0, 1.0)
+            } else {
+                1.0
+            };
+            if (p - (tail + axis * t)).norm() > radius {
+                continue;
+            }
+            if let Some(dst) = renderer.get_pixel(x, y) {
+                let blended = dst.lerp(color, t * color.a() as f32 / 255.0);
+                renderer.set_pixel(
+                    x,
+                    y,
+                    Color::rgba(blended.r(), blended.g(), blended.b(), dst.a()),
+                );
+            }
+        }
+    }
+}
+
 /// Screen shake effect
 #[derive(Debug, Clone)]
 pub struct ScreenShake {
@@ -244,6 +293,367 @@ impl PulseEffect {
     }
 }
 
+/// Easing curves mapping linear progress `t` in `0.0..=1.0` to eased progress
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Easing {
+    Linear,
+    /// Slow start and end (cubic)
+    EaseInOut,
+    /// Overshoots past the target, then settles back
+    EaseOutBack,
+    /// Bounces against the target like a dropped ball
+    Bounce,
+}
+
+impl Easing {
+    /// Apply the curve; `t` is clamped to `0.0..=1.0`
+    pub fn apply(self, t: f32) -> f32 {
+        let t = t.clamp(0.0, 1.0);
+        match self {
+            Easing::Linear => t,
+            Easing::EaseInOut => {
+                if t < 0.5 {
+                    4.0 * t * t * t
+                } else {
+                    1.0 - (-2.0 * t + 2.0).powi(3) / 2.0
+                }
+            }
+            Easing::EaseOutBack => {
+                let c1 = 1.70158;
+                let c3 = c1 + 1.0;
+                1.0 + c3 * (t - 1.0).powi(3) + c1 * (t - 1.0).powi(2)
+            }
+            Easing::Bounce => {
+                let n1 = 7.5625;
+                let d1 = 2.75;
+                if t < 1.0 / d1 {
+                    n1 * t * t
+                } else if t < 2.0 / d1 {
+                    let t = t - 1.5 / d1;
+                    n1 * t * t + 0.75
+                } else if t < 2.5 / d1 {
+                    let t = t - 2.25 / d1;
+                    n1 * t * t + 0.9375
+                } else {
+                    let t = t - 2.625 / d1;
+                    n1 * t * t + 0.984375
+                }
+            }
+        }
+    }
+}
+
+/// Values that a `Tween` can interpolate
+pub trait Tweenable: Copy {
+    fn lerp(start: Self, end: Self, t: f32) -> Self;
+}
+
+impl Tweenable for f32 {
+    fn lerp(start: Self, end: Self, t: f32) -> Self {
+        start + (end - start) * t
+    }
+}
+
+impl Tweenable for Vec2 {
+    fn lerp(start: Self, end: Self, t: f32) -> Self {
+        start + (end - start) * t
+    }
+}
+
+/// Eased interpolation from `start` to `end` over `duration` seconds
+#[derive(Debug, Clone)]
+pub struct Tween<T: Tweenable> {
+    pub start: T,
+    pub end: T,
+    pub duration: f32,
+    pub easing: Easing,
+    pub elapsed: f32,
+}
+
+impl<T: Tweenable> Tween<T> {
+    pub fn new(start: T, end: T, duration: f32, easing: Easing) -> Self {
+        Self {
+            start,
+            end,
+            duration,
+            easing,
+            elapsed: 0.0,
+        }
+    }
+
+    /// Advance the tween by `delta_time` seconds
+    pub fn update(&mut self, delta_time: f32) {
+        self.elapsed = (self.elapsed + delta_time).min(self.duration.max(0.0));
+    }
+
+    /// Linear progress in `0.0..=1.0`
+    pub fn progress(&self) -> f32 {
+        if self.duration <= 0.0 {
+            1.0
+        } else {
+            (self.elapsed / self.duration).clamp(0.0, 1.0)
+        }
+    }
+
+    /// Current eased value
+    pub fn value(&self) -> T {
+        T::lerp(self.start, self.end, self.easing.apply(self.progress()))
+    }
+
+    pub fn is_finished(&self) -> bool {
+        self.progress() >= 1.0
+    }
+
+    /// Restart from the beginning
+    pub fn reset(&mut self) {
+        self.elapsed = 0.0;
+    }
+}
+
+/// Brief hit flash on an entity: its draw color is blended towards `color`
+/// by the remaining fraction of `duration`. Driven by `FlashSystem`.
+#[derive(Component, Debug, Clone)]
+#[storage(DenseVecStorage)]
+pub struct Flash {
+    /// Seconds remaining
+    pub timer: f32,
+    pub color: [f32; 4],
+    pub duration: f32,
+}
+
+impl Flash {
+    pub fn new(color: [f32; 4], duration: f32) -> Self {
+        Self {
+            timer: duration,
+            color,
+            duration,
+        }
+    }
+
+    /// Short white flash, e.g. for a struck brick or paddle
+    pub fn white(duration: f32) -> Self {
+        Self::new([1.0, 1.0, 1.0, 1.0], duration)
+    }
+
+    pub fn update(&mut self, delta_time: f32) {
+        self.timer = (self.timer - delta_time).max(0.0);
+    }
+
+    /// Flash strength: 1.0 when started, falling linearly to 0.0 at the end
+    pub fn intensity(&self) -> f32 {
+        if self.duration <= 0.0 {
+            0.0
+        } else {
+            (self.timer / self.duration).clamp(0.0, 1.0)
+        }
+    }
+
+    pub fn is_finished(&self) -> bool {
+        self.timer <= 0.0
+    }
+
+    /// Blend `base` towards the flash color by the current intensity
+    pub fn apply(&self, base: [f32; 4]) -> [f32; 4] {
+        let t = self.intensity();
+        let mut result = base;
+        for (i, item) in result.iter_mut().enumerate() {
+            *item = base[i] + (self.color[i] - base[i]) * t;
+        }
+        result
+    }
+}
+
+/// Counts down `Flash` timers and removes finished flashes
+pub struct FlashSystem;
+
+impl<'a> System<'a> for FlashSystem {
+    type SystemData = (Entities<'a>, WriteStorage<'a, Flash>, Read<'a, Time>);
+
+    fn run(&mut self, (entities, mut flashes, time): Self::SystemData) {
+        let mut finished = Vec::new();
+        for (entity, flash) in (&entities, &mut flashes).join() {
+            flash.update(time.delta);
+            if flash.is_finished() {
+                finished.push(entity);
+            }
+        }
+        for entity in finished {
+            flashes.remove(entity);
+        }
+    }
+}
+
+/// Slow-motion effect driving `Time::time_scale`.
+///
+/// `slow_to` eases the scale down over the first quarter of `duration`,
+/// holds it, then eases back to 1.0 over the last quarter. Advance it with
+/// the unscaled delta so the effect doesn't slow itself down.
+#[derive(Debug, Clone)]
+pub struct TimeScaleController {
+    target: f32,
+    duration: f32,
+    elapsed: f32,
+    easing: Easing,
+    scale: f32,
+}
+
+impl Default for TimeScaleController {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl TimeScaleController {
+    /// Fraction of the duration spent ramping in and again ramping out
+    const RAMP: f32 = 0.25;
+
+    pub fn new() -> Self {
+        Self {
+            target: 1.0,
+            duration: 0.0,
+            elapsed: 0.0,
+            easing: Easing::Linear,
+            scale: 1.0,
+        }
+    }
+
+    /// Start a slow-motion of `scale` lasting `duration` real seconds
+    pub fn slow_to(&mut self, scale: f32, duration: f32, ease: Easing) {
+        self.target = scale.max(0.0);
+        self.duration = duration;
+        self.elapsed = 0.0;
+        self.easing = ease;
+    }
+
+    /// Advance by `unscaled_delta` and return the current time scale
+    pub fn update(&mut self, unscaled_delta: f32) -> f32 {
+        if !self.is_active() {
+            self.scale = 1.0;
+            return self.scale;
+        }
+        self.elapsed = (self.elapsed + unscaled_delta).min(self.duration);
+
+        let t = self.elapsed / self.duration;
+        let depth = if t < Self::RAMP {
+            self.easing.apply(t / Self::RAMP)
+        } else if t > 1.0 - Self::RAMP {
+            self.easing.apply((1.0 - t) / Self::RAMP)
+        } else {
+            1.0
+        };
+        self.scale = 1.0 + (self.target - 1.0) * depth;
+        self.scale
+    }
+
+    /// Advance using `time.unscaled_delta` and write the result to
+    /// `time.time_scale`. Nothing is written while idle, so other owners of
+    /// the scale are left alone; the frame an effect ends writes 1.0.
+    pub fn apply(&mut self, time: &mut Time) {
+        let active = self.is_active();
+        let scale = self.update(time.unscaled_delta);
+        if active {
+            time.time_scale = scale;
+        }
+    }
+
+    pub fn scale(&self) -> f32 {
+        self.scale
+    }
+
+    pub fn is_active(&self) -> bool {
+        self.duration > 0.0 && self.elapsed < self.duration
+    }
+}
+
+/// A single floating label owned by `FloatingText`
+#[derive(Debug, Clone)]
+pub struct FloatingLabel {
+    pub text: String,
+    /// Center of the label's top edge
+    pub position: Vec2,
+    pub color: Color,
+    /// Pixels per second; negative y floats upwards
+    pub velocity: Vec2,
+    pub lifetime: f32,
+    pub age: f32,
+    pub scale: usize,
+}
+
+impl FloatingLabel {
+    /// Opacity: 1.0 when spawned, falling linearly to 0.0 at `lifetime`
+    pub fn alpha(&self) -> f32 {
+        if self.lifetime <= 0.0 {
+            0.0
+        } else {
+            (1.0 - self.age / self.lifetime).clamp(0.0, 1.0)
+        }
+    }
+}
+
+/// Score popups and similar text that drifts and fades out, e.g. "+30" over a broken brick
+#[derive(Debug, Clone, Default)]
+pub struct FloatingText {
+    pub labels: Vec<FloatingLabel>,
+}
+
+impl FloatingText {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn spawn(&mut self, text: &str, pos: Vec2, color: Color, lifetime: f32, velocity: Vec2) {
+        self.labels.push(FloatingLabel {
+            text: text.to_string(),
+            position: pos,
+            color,
+            velocity,
+            lifetime,
+            age: 0.0,
+            scale: 1,
+        });
+    }
+
+    /// Move and age every label, dropping those that have fully faded
+    pub fn update(&mut self, delta_time: f32) {
+        for label in &mut self.labels {
+            label.age += delta_time;
+            label.position += label.velocity * delta_time;
+        }
+        self.labels.retain(|label| label.age < label.lifetime);
+    }
+
+    pub fn render(&self, renderer: &mut Renderer2D) {
+        for label in &self.labels {
+            let (width, _) = renderer.measure_text(&label.text, label.scale);
+            let x = label.position.x - width as f32 / 2.0;
+            if x < 0.0 || label.position.y < 0.0 {
+                continue;
+            }
+            let alpha = (label.alpha() * label.color.a() as f32) as u8;
+            let color = Color::rgba(label.color.r(), label.color.g(), label.color.b(), alpha);
+            renderer.draw_text_alpha(
+                &label.text,
+                x as usize,
+                label.position.y as usize,
+                color,
+                label.scale,
+            );
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.labels.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.labels.is_empty()
+    }
+
+    pub fn clear(&mut self) {
+        self.labels.clear();
+    }
+}
+
 /// Main visual effects system
 pub struct VisualEffectsSystem {
     pub glow_effects: Vec<GlowEffect>,
@@ -481,7 +891,123 @@ mod tests {
     fn test_pulse_effect() {
         let mut pulse = PulseEffect::new(1.0, 0.2, 1.0);
         let scale = pulse.update(0.1);
-        assert!(scale >= 0.8 && scale <= 1.2); // Should vary around base scale
+        assert!((0.8..=1.2).contains(&scale)); // Should vary around base scale
+    }
+
+    #[test]
+    fn test_easing_curves() {
+        assert_eq!(Easing::EaseInOut.apply(0.5), 0.5);
+        assert_eq!(Easing::Linear.apply(0.25), 0.25);
+        for easing in [Easing::EaseInOut, Easing::EaseOutBack, Easing::Bounce] {
+            assert!(easing.apply(0.0).abs() < 1e-6);
+            assert!((easing.apply(1.0) - 1.0).abs() < 1e-6);
+        }
+
+        // EaseOutBack overshoots before settling on 1.0
+        let peak = (1..100)
+            .map(|i| Easing::EaseOutBack.apply(i as f32 / 100.0))
+            .fold(0.0, f32::max);
+        assert!(peak > 1.0);
+    }
+
+    #[test]
+    fn test_tween_vec2() {
+        let mut tween = Tween::new(
+            Vec2::new(0.0, 0.0),
+            Vec2::new(10.0, 20.0),
+            2.0,
+            Easing::Linear,
+        );
+        tween.update(1.0);
+        assert_eq!(tween.value(), Vec2::new(5.0, 10.0));
+        assert!(!tween.is_finished());
+
+        tween.update(5.0);
+        assert!(tween.is_finished());
+        assert_eq!(tween.value(), Vec2::new(10.0, 20.0));
+    }
+
+    #[test]
+    fn test_slow_motion_ramps_down_and_back() {
+        let mut controller = TimeScaleController::new();
+        assert_eq!(controller.update(0.1), 1.0);
+
+        controller.slow_to(0.2, 0.5, Easing::EaseInOut);
+        let mut time = Time::default();
+        for _ in 0..5 {
+            time.advance(0.05);
+            controller.apply(&mut time);
+        }
+        // midway through the hold
+        assert!((time.time_scale - 0.2).abs() < 1e-4);
+        assert!(controller.is_active());
+
+        for _ in 0..5 {
+            time.advance(0.05);
+            controller.apply(&mut time);
+        }
+        assert!((time.time_scale - 1.0).abs() < 1e-4);
+        controller.update(0.05);
+        assert!(!controller.is_active());
+        assert_eq!(controller.scale(), 1.0);
+
+        // idle, it leaves a scale set elsewhere alone
+        time.time_scale = 0.0;
+        time.advance(0.05);
+        controller.apply(&mut time);
+        assert_eq!(time.time_scale, 0.0);
+    }
+
+    #[test]
+    fn test_floating_text_rises_and_fades() {
+        let mut popups = FloatingText::new();
+        popups.spawn(
+            "+30",
+            Vec2::new(50.0, 40.0),
+            Color::WHITE,
+            1.0,
+            Vec2::new(0.0, -20.0),
+        );
+
+        popups.update(0.5);
+        let label = &popups.labels[0];
+        assert!((label.position.y - 30.0).abs() < 1e-5);
+        assert!((label.alpha() - 0.5).abs() < 1e-5);
+
+        let mut renderer = Renderer2D::new(100, 60);
+        renderer.clear(Color::BLACK);
+        popups.render(&mut renderer);
+        // half-faded white over black
+        let drawn = (0..60)
+            .flat_map(|y| (0..100).map(move |x| (x, y)))
+            .filter_map(|(x, y)| renderer.get_pixel(x, y))
+            .find(|pixel| *pixel != Color::BLACK)
+            .unwrap();
+        assert!(drawn.r() > 100 && drawn.r() < 155);
+
+        popups.update(0.49);
+        assert!(popups.labels[0].alpha() < 0.02);
+        popups.update(0.01);
+        assert!(popups.is_empty());
+    }
+
+    #[test]
+    fn test_flash_intensity_decays_linearly() {
+        let mut flash = Flash::white(0.2);
+        assert_eq!(flash.intensity(), 1.0);
+        assert_eq!(flash.apply([0.0, 0.0, 1.0, 1.0]), [1.0, 1.0, 1.0, 1.0]);
+
+        flash.update(0.05);
+        assert!((flash.intensity() - 0.75).abs() < 1e-6);
+        flash.update(0.05);
+        let color = flash.apply([0.0, 0.0, 1.0, 1.0]);
+        assert!((color[0] - 0.5).abs() < 1e-6);
+        assert!((color[2] - 1.0).abs() < 1e-6);
+
+        flash.update(0.2);
+        assert_eq!(flash.intensity(), 0.0);
+        assert!(flash.is_finished());
+        assert_eq!(flash.apply([0.0, 0.0, 1.0, 1.0]), [0.0, 0.0, 1.0, 1.0]);
     }
 
     #[test]
@@ -503,4 +1029,29 @@ mod tests {
         system.clear();
         assert_eq!(system.active_effects_count(), 0);
     }
+
+    #[test]
+    fn test_motion_blur_streak_grows_with_speed() {
+        let streak_pixels = |speed: f32| {
+            let mut renderer = Renderer2D::new(200, 40);
+            renderer.clear(Color::BLACK);
+            draw_motion_blur(
+                &mut renderer,
+                Vec2::new(150.0, 20.0),
+                Vec2::new(speed, 0.0),
+                1.0 / 30.0,
+                5.0,
+                Color::WHITE,
+            );
+            (0..40)
+                .flat_map(|y| (0..200).map(move |x| (x, y)))
+                .filter(|&(x, y)| renderer.get_pixel(x, y) != Some(Color::BLACK))
+                .count()
+        };
+
+        let slow = streak_pixels(60.0);
+        let fast = streak_pixels(1200.0);
+        assert!(slow > 0);
+        assert!(fast > slow * 3, "fast {} vs slow {}", fast, slow);
+    }
 }
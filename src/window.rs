@@ -3,8 +3,65 @@
 //! Provides cross-platform window creation and management.
 //! Abstracts away platform-specific window handling.
 
-use minifb::{Key, Window, WindowOptions};
+use minifb::{Key, Scale, ScaleMode, Window, WindowOptions};
 use std::collections::HashSet;
+use std::time::Duration;
+
+/// How finished frames are handed to the display
+#[derive(
+    Debug, Clone, Copy, PartialEq, Eq, Hash, Default, serde::Serialize, serde::Deserialize,
+)]
+pub enum PresentMode {
+    /// Wait for vertical blank (vsync); supported everywhere
+    #[default]
+    Fifo,
+    /// Replace the queued frame with the newest one, no tearing
+    Mailbox,
+    /// Present as soon as possible, may tear
+    Immediate,
+}
+
+impl PresentMode {
+    /// Every mode, in menu order
+    pub const ALL: [PresentMode; 3] = [
+        PresentMode::Fifo,
+        PresentMode::Mailbox,
+        PresentMode::Immediate,
+    ];
+
+    /// Whether frames wait for vertical blank (no tearing)
+    pub fn is_vsync(self) -> bool {
+        self != PresentMode::Immediate
+    }
+
+    /// Vsync on maps to `Fifo`, off to `Immediate`
+    pub fn from_vsync(vsync: bool) -> Self {
+        if vsync {
+            PresentMode::Fifo
+        } else {
+            PresentMode::Immediate
+        }
+    }
+
+    /// `requested` if it is in `supported`, otherwise `Fifo`
+    pub fn resolve(requested: PresentMode, supported: &[PresentMode]) -> PresentMode {
+        if supported.contains(&requested) {
+            requested
+        } else {
+            PresentMode::Fifo
+        }
+    }
+}
+
+impl From<PresentMode> for wgpu::PresentMode {
+    fn from(mode: PresentMode) -> Self {
+        match mode {
+            PresentMode::Fifo => wgpu::PresentMode::Fifo,
+            PresentMode::Mailbox => wgpu::PresentMode::Mailbox,
+            PresentMode::Immediate => wgpu::PresentMode::Immediate,
+        }
+    }
+}
 
 /// Window configuration
 #[derive(Debug, Clone)]
@@ -13,7 +70,10 @@ pub struct WindowConfig {
     pub width: usize,
     pub height: usize,
     pub resizable: bool,
-    pub vsync: bool,
+    /// Requested present mode; see `WindowManager::set_present_mode`
+    pub present_mode: PresentMode,
+    /// Open as a borderless window scaled to fit the screen
+    pub fullscreen: bool,
 }
 
 impl Default for WindowConfig {
@@ -23,44 +83,298 @@ impl Default for WindowConfig {
             width: 800,
             height: 600,
             resizable: true,
-            vsync: true,
+            present_mode: PresentMode::Fifo,
+            fullscreen: false,
         }
     }
 }
 
+/// Platform layer behind `WindowManager`: owns the OS window (or pretends to)
+pub trait Backend {
+    /// Whether the window is still open
+    fn is_open(&self) -> bool;
+    /// Pump platform events
+    fn update(&mut self);
+    /// Upload a full ARGB frame
+    fn present(
+        &mut self,
+        buffer: &[u32],
+        width: usize,
+        height: usize,
+    ) -> Result<(), Box<dyn std::error::Error>>;
+    /// Current client area size
+    fn size(&self) -> (usize, usize);
+    /// Keys currently held down
+    fn keys_down(&self) -> Vec<Key>;
+    fn set_title(&mut self, title: &str);
+    /// Apply a changed `config.fullscreen`, recreating the window if needed.
+    /// `config.width`/`height` are the logical resolution to keep.
+    /// Backends without a fullscreen mode keep the default, which refuses.
+    fn set_fullscreen(&mut self, _config: &WindowConfig) -> Result<(), Box<dyn std::error::Error>> {
+        Err("fullscreen is not supported by this backend".into())
+    }
+    /// Present modes this backend can honour; always includes `Fifo`
+    fn supported_present_modes(&self) -> Vec<PresentMode> {
+        vec![PresentMode::Fifo]
+    }
+    /// Switch present mode in place. Only called with a supported mode.
+    fn set_present_mode(&mut self, _mode: PresentMode) {}
+    /// The underlying minifb window, if there is one
+    fn minifb_window(&self) -> Option<&Window> {
+        None
+    }
+    fn minifb_window_mut(&mut self) -> Option<&mut Window> {
+        None
+    }
+}
+
+/// Frame interval used to emulate vsync on minifb
+const FIFO_FRAME_TIME: Duration = Duration::from_micros(16_667);
+
+/// Backend that opens a real window through minifb
+pub struct MinifbBackend {
+    window: Window,
+}
+
+impl MinifbBackend {
+    /// Open a window for `config`.
+    ///
+    /// minifb can't switch an existing window to fullscreen or report the
+    /// monitor size, so fullscreen is a borderless, topmost window created with
+    /// `Scale::FitScreen`: the buffer keeps the logical `width` x `height` and
+    /// minifb scales it up to the largest size that fits the screen.
+    pub fn new(config: &WindowConfig) -> Result<Self, Box<dyn std::error::Error>> {
+        let options = if config.fullscreen {
+            WindowOptions {
+                borderless: true,
+                title: false,
+                resize: false,
+                topmost: true,
+                scale: Scale::FitScreen,
+                scale_mode: ScaleMode::AspectRatioStretch,
+                ..WindowOptions::default()
+            }
+        } else {
+            WindowOptions {
+                resize: config.resizable,
+                ..WindowOptions::default()
+            }
+        };
+        let window = Window::new(&config.title, config.width, config.height, options)?;
+        let mut backend = Self { window };
+        let mode = PresentMode::resolve(config.present_mode, &backend.supported_present_modes());
+        backend.set_present_mode(mode);
+        Ok(backend)
+    }
+}
+
+impl Backend for MinifbBackend {
+    fn is_open(&self) -> bool {
+        self.window.is_open()
+    }
+
+    fn update(&mut self) {
+        self.window.update();
+    }
+
+    fn present(
+        &mut self,
+        buffer: &[u32],
+        width: usize,
+        height: usize,
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        self.window.update_with_buffer(buffer, width, height)?;
+        Ok(())
+    }
+
+    fn size(&self) -> (usize, usize) {
+        self.window.get_size()
+    }
+
+    fn keys_down(&self) -> Vec<Key> {
+        self.window.get_keys()
+    }
+
+    fn set_title(&mut self, title: &str) {
+        self.window.set_title(title);
+    }
+
+    fn set_fullscreen(&mut self, config: &WindowConfig) -> Result<(), Box<dyn std::error::Error>> {
+        // the old window closes when replaced
+        *self = Self::new(config)?;
+        Ok(())
+    }
+
+    /// minifb has no present queue, so `Mailbox` isn't available; `Fifo`
+    /// caps the update rate at 60 Hz and `Immediate` removes the cap.
+    fn supported_present_modes(&self) -> Vec<PresentMode> {
+        vec![PresentMode::Fifo, PresentMode::Immediate]
+    }
+
+    fn set_present_mode(&mut self, mode: PresentMode) {
+        let rate = match mode {
+            PresentMode::Immediate => None,
+            _ => Some(FIFO_FRAME_TIME),
+        };
+        self.window.limit_update_rate(rate);
+    }
+
+    fn minifb_window(&self) -> Option<&Window> {
+        Some(&self.window)
+    }
+
+    fn minifb_window_mut(&mut self) -> Option<&mut Window> {
+        Some(&mut self.window)
+    }
+}
+
+/// Backend without a window, for CI and server-side simulation.
+/// Presenting only counts frames and input is fed programmatically.
+#[derive(Debug, Clone)]
+pub struct HeadlessBackend {
+    pub width: usize,
+    pub height: usize,
+    /// Simulated monitor size used while fullscreen
+    pub screen_size: (usize, usize),
+    pub open: bool,
+    pub keys: HashSet<Key>,
+    pub frames_presented: u64,
+    /// Modes reported as supported, to simulate different adapters
+    pub supported_modes: Vec<PresentMode>,
+    /// Mode last applied through `set_present_mode`
+    pub present_mode: PresentMode,
+}
+
+impl HeadlessBackend {
+    pub fn new(width: usize, height: usize) -> Self {
+        Self {
+            width,
+            height,
+            screen_size: (1920, 1080),
+            open: true,
+            keys: HashSet::new(),
+            frames_presented: 0,
+            supported_modes: vec![
+                PresentMode::Fifo,
+                PresentMode::Mailbox,
+                PresentMode::Immediate,
+            ],
+            present_mode: PresentMode::Fifo,
+        }
+    }
+}
+
+impl Backend for HeadlessBackend {
+    fn is_open(&self) -> bool {
+        self.open
+    }
+
+    fn update(&mut self) {}
+
+    fn present(
+        &mut self,
+        _buffer: &[u32],
+        _width: usize,
+        _height: usize,
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        self.frames_presented += 1;
+        Ok(())
+    }
+
+    fn size(&self) -> (usize, usize) {
+        (self.width, self.height)
+    }
+
+    fn keys_down(&self) -> Vec<Key> {
+        self.keys.iter().copied().collect()
+    }
+
+    fn set_title(&mut self, _title: &str) {}
+
+    fn set_fullscreen(&mut self, config: &WindowConfig) -> Result<(), Box<dyn std::error::Error>> {
+        (self.width, self.height) = if config.fullscreen {
+            self.screen_size
+        } else {
+            (config.width, config.height)
+        };
+        Ok(())
+    }
+
+    fn supported_present_modes(&self) -> Vec<PresentMode> {
+        self.supported_modes.clone()
+    }
+
+    fn set_present_mode(&mut self, mode: PresentMode) {
+        self.present_mode = mode;
+    }
+}
+
 /// Window manager for handling window lifecycle
 pub struct WindowManager {
-    window: Window,
+    backend: Box<dyn Backend>,
     config: WindowConfig,
     should_close: bool,
     // Store previous key states to detect presses and releases
     previous_keys: HashSet<Key>,
+    /// Window size to restore when leaving fullscreen
+    windowed_size: (usize, usize),
 }
 
 impl WindowManager {
     /// Create a new window with the given configuration
     pub fn new(config: WindowConfig) -> Result<Self, Box<dyn std::error::Error>> {
-        let window = Window::new(
-            &config.title,
-            config.width,
-            config.height,
-            WindowOptions {
-                resize: config.resizable,
-                ..WindowOptions::default()
-            },
-        )?;
+        let backend = MinifbBackend::new(&config)?;
+        Ok(Self::with_backend(config, Box::new(backend)))
+    }
+
+    /// Create a window manager that never opens a window
+    pub fn new_headless(config: WindowConfig) -> Self {
+        let backend = HeadlessBackend::new(config.width, config.height);
+        Self::with_backend(config, Box::new(backend))
+    }
 
-        Ok(Self {
-            window,
+    /// Create a window manager on top of a custom backend
+    pub fn with_backend(config: WindowConfig, backend: Box<dyn Backend>) -> Self {
+        let present_mode = config.present_mode;
+        let mut manager = Self {
+            windowed_size: (config.width, config.height),
+            backend,
             config,
             should_close: false,
             previous_keys: HashSet::new(),
-        })
+        };
+        manager.set_present_mode(present_mode);
+        manager
     }
 
     /// Check if the window should close
     pub fn should_close(&self) -> bool {
-        !self.window.is_open() || self.should_close
+        !self.backend.is_open() || self.should_close
+    }
+
+    /// Whether there is no real window behind this manager
+    pub fn is_headless(&self) -> bool {
+        self.backend.minifb_window().is_none()
+    }
+
+    /// Upload a full frame to the window
+    pub fn present(
+        &mut self,
+        buffer: &[u32],
+        width: usize,
+        height: usize,
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        self.backend.present(buffer, width, height)
+    }
+
+    /// The platform backend
+    pub fn backend(&self) -> &dyn Backend {
+        self.backend.as_ref()
+    }
+
+    /// Mutable access to the platform backend
+    pub fn backend_mut(&mut self) -> &mut dyn Backend {
+        self.backend.as_mut()
     }
 
     /// Get the window dimensions
@@ -68,9 +382,50 @@ impl WindowManager {
         (self.config.width, self.config.height)
     }
 
+    pub fn is_fullscreen(&self) -> bool {
+        self.config.fullscreen
+    }
+
+    /// Switch between windowed and borderless fullscreen, returning the new
+    /// state. The window is reconfigured by the backend (minifb recreates it);
+    /// `dimensions` then reports the new client size.
+    pub fn toggle_fullscreen(&mut self) -> Result<bool, Box<dyn std::error::Error>> {
+        let fullscreen = !self.config.fullscreen;
+        if fullscreen {
+            self.windowed_size = (self.config.width, self.config.height);
+        }
+        let config = WindowConfig {
+            width: self.windowed_size.0,
+            height: self.windowed_size.1,
+            fullscreen,
+            ..self.config.clone()
+        };
+        self.backend.set_fullscreen(&config)?;
+
+        self.config.fullscreen = fullscreen;
+        (self.config.width, self.config.height) = self.backend.size();
+        // keys held across the switch shouldn't re-trigger presses
+        self.previous_keys = self.backend.keys_down().into_iter().collect();
+        Ok(fullscreen)
+    }
+
+    /// The present mode currently in effect
+    pub fn present_mode(&self) -> PresentMode {
+        self.config.present_mode
+    }
+
+    /// Switch present mode without recreating the window. Falls back to
+    /// `Fifo` when the backend doesn't support `mode`; returns the mode applied.
+    pub fn set_present_mode(&mut self, mode: PresentMode) -> PresentMode {
+        let mode = PresentMode::resolve(mode, &self.backend.supported_present_modes());
+        self.backend.set_present_mode(mode);
+        self.config.present_mode = mode;
+        mode
+    }
+
     /// Set the window title
     pub fn set_title(&mut self, title: &str) {
-        self.window.set_title(title);
+        self.backend.set_title(title);
     }
 
     /// Update the window and collect events (call this each frame)
@@ -79,15 +434,15 @@ impl WindowManager {
 
         // Pump the minifb event queue so input states and window events are updated.
         // Pump the minifb event queue so input states and window events are updated.
-        self.window.update();
+        self.backend.update();
 
-        if !self.window.is_open() {
+        if !self.backend.is_open() {
             self.should_close = true;
             events.push(WindowEvent::WindowClosed);
         }
 
         // Handle key presses and releases
-        let current_keys: HashSet<Key> = self.window.get_keys().into_iter().collect();
+        let current_keys: HashSet<Key> = self.backend.keys_down().into_iter().collect();
 
         for key in current_keys.difference(&self.previous_keys) {
             events.push(WindowEvent::KeyPressed(*key));
@@ -100,7 +455,7 @@ impl WindowManager {
         self.previous_keys = current_keys;
 
         // Check for window resize
-        let (current_width, current_height) = self.window.get_size();
+        let (current_width, current_height) = self.backend.size();
         if current_width != self.config.width || current_height != self.config.height {
             self.config.width = current_width;
             self.config.height = current_height;
@@ -113,14 +468,22 @@ impl WindowManager {
         events
     }
 
-    /// Get mutable reference to the underlying window
+    /// Get mutable reference to the underlying window.
+    ///
+    /// Panics for headless windows; check `is_headless` first when unsure.
     pub fn window(&mut self) -> &mut Window {
-        &mut self.window
+        self.backend
+            .minifb_window_mut()
+            .expect("headless window manager has no minifb window")
     }
 
-    /// Get immutable reference to the underlying window
+    /// Get immutable reference to the underlying window.
+    ///
+    /// Panics for headless windows; check `is_headless` first when unsure.
     pub fn window_ref(&self) -> &Window {
-        &self.window
+        self.backend
+            .minifb_window()
+            .expect("headless window manager has no minifb window")
     }
 }
 
@@ -135,3 +498,67 @@ pub enum WindowEvent {
 
 // The WindowEvents struct and its impl are no longer needed as update() now returns Vec<WindowEvent>
 // and the responsibility of iterating events is shifted to the caller.
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_present_mode_applies_or_falls_back() {
+        let mut window = WindowManager::new_headless(WindowConfig {
+            present_mode: PresentMode::Mailbox,
+            ..WindowConfig::default()
+        });
+        assert_eq!(window.present_mode(), PresentMode::Mailbox);
+
+        assert_eq!(
+            window.set_present_mode(PresentMode::Immediate),
+            PresentMode::Immediate
+        );
+
+        let mut backend = HeadlessBackend::new(800, 600);
+        backend.supported_modes = vec![PresentMode::Fifo, PresentMode::Immediate];
+        let mut window = WindowManager::with_backend(WindowConfig::default(), Box::new(backend));
+        assert_eq!(
+            window.set_present_mode(PresentMode::Mailbox),
+            PresentMode::Fifo
+        );
+        assert_eq!(window.present_mode(), PresentMode::Fifo);
+        assert_eq!(
+            wgpu::PresentMode::from(window.present_mode()),
+            wgpu::PresentMode::Fifo
+        );
+    }
+
+    #[test]
+    fn test_toggle_fullscreen_unsupported_keeps_state() {
+        struct FixedBackend;
+        impl Backend for FixedBackend {
+            fn is_open(&self) -> bool {
+                true
+            }
+            fn update(&mut self) {}
+            fn present(
+                &mut self,
+                _buffer: &[u32],
+                _width: usize,
+                _height: usize,
+            ) -> Result<(), Box<dyn std::error::Error>> {
+                Ok(())
+            }
+            fn size(&self) -> (usize, usize) {
+                (800, 600)
+            }
+            fn keys_down(&self) -> Vec<Key> {
+                Vec::new()
+            }
+            fn set_title(&mut self, _title: &str) {}
+        }
+
+        let mut window =
+            WindowManager::with_backend(WindowConfig::default(), Box::new(FixedBackend));
+        assert!(window.toggle_fullscreen().is_err());
+        assert!(!window.config.fullscreen);
+        assert_eq!((window.config.width, window.config.height), (800, 600));
+    }
+}